@@ -7,7 +7,7 @@ use risinglight::array::{
     ArrayFromDataExt, ArrayImpl, BoolArray, DecimalArray, F64Array, I32Array,
 };
 use risinglight::parser::BinaryOperator;
-use risinglight::types::DataTypeKind;
+use risinglight::types::{DataTypeKind, DataValue};
 use rust_decimal::Decimal;
 
 fn ops(c: &mut Criterion) {
@@ -108,6 +108,21 @@ fn filter(c: &mut Criterion) {
         let ArrayImpl::Bool(a2) = make_bool_array(size) else { unreachable!() };
         b.iter(|| a1.filter(a2.true_array()))
     });
+    // selection masks of `a > b` and `a > 100`, compared with `gt(i32,i32)` and `gt(i32,const)`
+    for_all_size(c, "select_gt(i32,i32)", |b, &size| {
+        let a1 = make_i32_array(size);
+        let a2 = make_i32_array(size);
+        b.iter(|| a1.select(&BinaryOperator::Gt, &a2))
+    });
+    for_all_size(c, "select_gt(i32,const)", |b, &size| {
+        let a1 = make_i32_array(size);
+        b.iter(|| a1.select_value(&BinaryOperator::Gt, &DataValue::Int32(100)))
+    });
+    for_all_size(c, "gt(i32,const)", |b, &size| {
+        let a1 = make_i32_array(size);
+        let a2: ArrayImpl = I32Array::from_iter(vec![Some(100); size]).into();
+        b.iter(|| a1.binary_op(&BinaryOperator::Gt, &a2))
+    });
 }
 
 fn make_bool_array(size: usize) -> ArrayImpl {
@@ -182,6 +182,7 @@ impl fmt::Debug for DataChunk {
 pub struct Chunk {
     data_chunks: Vec<DataChunk>,
     header: Option<Vec<String>>,
+    float_precision: Option<usize>,
 }
 
 impl Chunk {
@@ -190,6 +191,7 @@ impl Chunk {
         Chunk {
             data_chunks,
             header: None,
+            float_precision: None,
         }
     }
 
@@ -212,6 +214,35 @@ impl Chunk {
     pub fn set_header(&mut self, header: Vec<String>) {
         self.header = Some(header);
     }
+
+    /// Set the number of significant digits to display floats.
+    ///
+    /// Floats are displayed in full precision if it is `None`.
+    pub fn set_float_precision(&mut self, precision: Option<usize>) {
+        self.float_precision = precision;
+    }
+
+    /// Get the value of an array in this chunk and convert it to string.
+    fn get_to_string(&self, array: &ArrayImpl, idx: usize) -> String {
+        match array.get(idx) {
+            DataValue::Float64(v) => self.float_to_string(v.0),
+            _ => array.get_to_string(idx),
+        }
+    }
+
+    /// Convert a float to string with the display precision.
+    fn float_to_string(&self, v: f64) -> String {
+        match self.float_precision {
+            Some(digits) if v.is_finite() => {
+                // round to the significant digits, then print the shortest form of the result
+                let rounded: f64 = format!("{:.*e}", digits.saturating_sub(1), v)
+                    .parse()
+                    .unwrap();
+                rounded.to_string()
+            }
+            _ => v.to_string(),
+        }
+    }
 }
 
 /// Print the chunk as a pretty table.
@@ -228,7 +259,7 @@ impl fmt::Display for Chunk {
                 let row: Vec<_> = data_chunk
                     .arrays
                     .iter()
-                    .map(|a| a.get_to_string(i))
+                    .map(|a| self.get_to_string(a, i))
                     .collect();
                 table.add_row(row);
             }
@@ -256,7 +287,7 @@ pub fn datachunk_to_sqllogictest_string(chunk: &Chunk) -> Vec<Vec<String>> {
                     DataValue::Int16(v) => v.to_string(),
                     DataValue::Int32(v) => v.to_string(),
                     DataValue::Int64(v) => v.to_string(),
-                    DataValue::Float64(v) => v.to_string(),
+                    DataValue::Float64(v) => chunk.float_to_string(v.0),
                     DataValue::String(s) if s.is_empty() => "(empty)".to_string(),
                     DataValue::String(s) => s,
                     DataValue::Blob(s) if s.is_empty() => "(empty)".to_string(),
@@ -275,6 +306,42 @@ pub fn datachunk_to_sqllogictest_string(chunk: &Chunk) -> Vec<Vec<String>> {
     output
 }
 
+/// Convert a [`Chunk`] to JSON Lines, one JSON object per row keyed by the column names.
+///
+/// NULL is converted to `null`. Numbers except NaN and infinity are kept as JSON numbers, and
+/// other values are converted to JSON strings.
+pub fn chunk_to_json_lines(chunk: &Chunk) -> Vec<String> {
+    let json_string = |s: &str| serde_json::Value::from(s).to_string();
+    let mut output = vec![];
+    for data_chunk in chunk.data_chunks() {
+        let names: Vec<String> = match chunk.header() {
+            Some(header) => header.iter().map(|name| json_string(name)).collect(),
+            None => (1..=data_chunk.column_count())
+                .map(|i| json_string(&format!("column{i}")))
+                .collect(),
+        };
+        for row in 0..data_chunk.cardinality() {
+            let mut fields = vec![];
+            for (name, array) in names.iter().zip(data_chunk.arrays()) {
+                let value = match array.get(row) {
+                    DataValue::Null => "null".to_string(),
+                    DataValue::Bool(v) => v.to_string(),
+                    DataValue::Int16(v) => v.to_string(),
+                    DataValue::Int32(v) => v.to_string(),
+                    DataValue::Int64(v) => v.to_string(),
+                    DataValue::Float64(v) if v.0.is_finite() => chunk.float_to_string(v.0),
+                    DataValue::Decimal(v) => v.to_string(),
+                    DataValue::String(s) => json_string(&s),
+                    _ => json_string(&chunk.get_to_string(array, row)),
+                };
+                fields.push(format!("{name}:{value}"));
+            }
+            output.push(format!("{{{}}}", fields.join(",")));
+        }
+    }
+    output
+}
+
 /// Reference to a row in [`DataChunk`].
 pub struct RowRef<'a> {
     chunk: &'a DataChunk,
@@ -47,8 +47,8 @@ impl DataChunkBuilder {
 
     /// Push a row of str in the Iterator.
     ///
-    /// The row is accepted as an iterator of [`&str`], and it's required that the size of row
-    /// should be the same as the number of columns.
+    /// The row is accepted as an iterator of optional [`&str`], where `None` stands for NULL, and
+    /// it's required that the size of row should be the same as the number of columns.
     ///
     /// It will convert [`&str`] into specified type, and a [`ConvertError`] will be returned if
     /// converting fails.
@@ -56,10 +56,13 @@ impl DataChunkBuilder {
     /// A [`DataChunk`] will be returned while converting correctly and `size == capacity`.
     pub fn push_str_row<'a>(
         &mut self,
-        row: impl IntoIterator<Item = &'a str>,
+        row: impl IntoIterator<Item = Option<&'a str>>,
     ) -> Result<Option<DataChunk>, ConvertError> {
         for (builder, r) in self.array_builders.iter_mut().zip(row) {
-            builder.push_str(r)?
+            match r {
+                Some(r) => builder.push_str(r)?,
+                None => builder.push(&DataValue::Null),
+            }
         }
 
         self.size += 1;
@@ -79,6 +82,8 @@ impl DataChunkBuilder {
         let capacity = self.capacity;
         match size {
             0 => None,
+            // the cardinality can not be derived from arrays if there are no columns
+            _ if self.array_builders.is_empty() => Some(DataChunk::no_column(size)),
             _ => Some(
                 self.array_builders
                     .iter_mut()
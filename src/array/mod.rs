@@ -468,21 +468,8 @@ impl ArrayBuilderImpl {
 
     /// Appends an element in string.
     pub fn push_str(&mut self, s: &str) -> Result<(), ConvertError> {
-        let null = s.is_empty();
         match self {
             Self::Null(a) => a.push(None),
-            Self::Bool(a) if null => a.push(None),
-            Self::Int16(a) if null => a.push(None),
-            Self::Int32(a) if null => a.push(None),
-            Self::Int64(a) if null => a.push(None),
-            Self::Float64(a) if null => a.push(None),
-            Self::Utf8(a) if null => a.push(None),
-            Self::Blob(a) if null => a.push(None),
-            Self::Decimal(a) if null => a.push(None),
-            Self::Date(a) if null => a.push(None),
-            Self::Timestamp(a) if null => a.push(None),
-            Self::TimestampTz(a) if null => a.push(None),
-            Self::Interval(a) if null => a.push(None),
             Self::Bool(a) => a.push(Some(
                 &s.parse::<bool>()
                     .map_err(|e| ConvertError::ParseBool(s.to_string(), e))?,
@@ -573,6 +560,16 @@ macro_rules! impl_array {
                 }
             }
 
+            /// Returns true if the value at the given index is null.
+            pub fn is_null(&self, idx: usize) -> bool {
+                match self {
+                    Self::Null(_) => true,
+                    $(
+                        Self::$Abc(a) => a.is_null(idx),
+                    )*
+                }
+            }
+
             /// Number of items of array.
             pub fn len(&self) -> usize {
                 match self {
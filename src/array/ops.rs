@@ -3,6 +3,8 @@
 //! Array operations.
 
 use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::collections::HashSet;
 
 use num_traits::ToPrimitive;
 use regex::Regex;
@@ -19,17 +21,36 @@ use crate::types::{
 
 type A = ArrayImpl;
 
+/// The maximum length of a string produced by functions in bytes.
+const MAX_STRING_LEN: usize = 1 << 20;
+
+/// The maximum total length of strings in an array produced by functions in bytes.
+const MAX_STRINGS_LEN: usize = 1 << 26;
+
 impl ArrayImpl {
     pub fn neg(&self) -> Result<Self, ConvertError> {
         Ok(match self {
-            A::Int32(a) => A::new_int32(unary_op(a.as_ref(), |v| -v)),
-            A::Int64(a) => A::new_int64(unary_op(a.as_ref(), |v| -v)),
+            A::Int32(a) => A::new_int32(try_unary_op(a.as_ref(), |v| {
+                int_op(0, *v, "-", i32::checked_sub)
+            })?),
+            A::Int64(a) => A::new_int64(try_unary_op(a.as_ref(), |v| {
+                int_op(0, *v, "-", i64::checked_sub)
+            })?),
             A::Float64(a) => A::new_float64(unary_op(a.as_ref(), |v| -v)),
             A::Decimal(a) => A::new_decimal(unary_op(a.as_ref(), |v| -v)),
             _ => return Err(ConvertError::NoUnaryOp("-".into(), self.type_string())),
         })
     }
 
+    /// Negates integers with wrapping around at the boundary of the type.
+    pub fn wrapping_neg(&self) -> Result<Self, ConvertError> {
+        Ok(match self {
+            A::Int32(a) => A::new_int32(unary_op(a.as_ref(), |v| v.wrapping_neg())),
+            A::Int64(a) => A::new_int64(unary_op(a.as_ref(), |v| v.wrapping_neg())),
+            _ => self.neg()?,
+        })
+    }
+
     /// Perform unary operation.
     pub fn unary_op(&self, op: &UnaryOperator) -> Result<ArrayImpl, ConvertError> {
         Ok(match op {
@@ -48,43 +69,45 @@ impl ArrayImpl {
 
 /// A macro to implement arithmetic operations.
 macro_rules! arith {
-    ($name:ident, $op:tt) => {
+    ($name:ident, $op:tt, $checked:ident) => {
         pub fn $name(
             &self,
             other: &Self,
         ) -> Result<Self, ConvertError> {
         Ok(match (self, other) {
-            (A::Int16(a), A::Int16(b)) => A::new_int16(binary_op(a.as_ref(), b.as_ref(), |a, b| a $op b)),
-
-            (A::Int16(a), A::Int32(b)) => A::new_int32(binary_op(a.as_ref(), b.as_ref(), |a, b| (*a as i32) $op *b)),
-            (A::Int32(a), A::Int16(b)) => A::new_int32(binary_op(a.as_ref(), b.as_ref(), |a, b| *a $op (*b as i32))),
-            (A::Int32(a), A::Int32(b)) => A::new_int32(binary_op(a.as_ref(), b.as_ref(), |a, b| a $op b)),
-
-            (A::Int16(a), A::Int64(b)) => A::new_int64(binary_op(a.as_ref(), b.as_ref(), |a, b| (*a as i64) $op *b)),
-            (A::Int32(a), A::Int64(b)) => A::new_int64(binary_op(a.as_ref(), b.as_ref(), |a, b| (*a as i64) $op *b)),
-            (A::Int64(a), A::Int16(b)) => A::new_int64(binary_op(a.as_ref(), b.as_ref(), |a, b| *a $op (*b as i64))),
-            (A::Int64(a), A::Int32(b)) => A::new_int64(binary_op(a.as_ref(), b.as_ref(), |a, b| *a $op (*b as i64))),
-            (A::Int64(a), A::Int64(b)) => A::new_int64(binary_op(a.as_ref(), b.as_ref(), |a, b| a $op b)),
-
-            (A::Int16(a), A::Float64(b)) => A::new_float64(binary_op(a.as_ref(), b.as_ref(), |a, b| F64::from(*a as f64) $op *b)),
-            (A::Int32(a), A::Float64(b)) => A::new_float64(binary_op(a.as_ref(), b.as_ref(), |a, b| F64::from(*a as f64) $op *b)),
-            (A::Int64(a), A::Float64(b)) => A::new_float64(binary_op(a.as_ref(), b.as_ref(), |a, b| F64::from(*a as f64) $op *b)),
-            (A::Float64(a), A::Int16(b)) => A::new_float64(binary_op(a.as_ref(), b.as_ref(), |a, b| *a $op F64::from(*b as f64))),
-            (A::Float64(a), A::Int32(b)) => A::new_float64(binary_op(a.as_ref(), b.as_ref(), |a, b| *a $op F64::from(*b as f64))),
-            (A::Float64(a), A::Int64(b)) => A::new_float64(binary_op(a.as_ref(), b.as_ref(), |a, b| *a $op F64::from(*b as f64))),
-            (A::Float64(a), A::Float64(b)) => A::new_float64(binary_op(a.as_ref(), b.as_ref(), |a, b| *a $op *b)),
-
-            (A::Int16(a), A::Decimal(b)) => A::new_decimal(binary_op(a.as_ref(), b.as_ref(), |a, b| Decimal::from(*a) $op *b)),
-            (A::Int32(a), A::Decimal(b)) => A::new_decimal(binary_op(a.as_ref(), b.as_ref(), |a, b| Decimal::from(*a) $op *b)),
-            (A::Int64(a), A::Decimal(b)) => A::new_decimal(binary_op(a.as_ref(), b.as_ref(), |a, b| Decimal::from(*a) $op *b)),
-            (A::Float64(a), A::Decimal(b)) => A::new_decimal(binary_op(a.as_ref(), b.as_ref(), |a, b| Decimal::from_f64_retain(a.0).unwrap() $op *b)),
-            (A::Decimal(a), A::Int16(b)) => A::new_decimal(binary_op(a.as_ref(), b.as_ref(), |a, b| *a $op Decimal::from(*b))),
-            (A::Decimal(a), A::Int32(b)) => A::new_decimal(binary_op(a.as_ref(), b.as_ref(), |a, b| *a $op Decimal::from(*b))),
-            (A::Decimal(a), A::Int64(b)) => A::new_decimal(binary_op(a.as_ref(), b.as_ref(), |a, b| *a $op Decimal::from(*b))),
-            (A::Decimal(a), A::Float64(b)) => A::new_decimal(binary_op(a.as_ref(), b.as_ref(), |a, b| *a $op Decimal::from_f64_retain(b.0).unwrap())),
-            (A::Decimal(a), A::Decimal(b)) => A::new_decimal(binary_op(a.as_ref(), b.as_ref(), |a, b| a $op b)),
+            (A::Int16(a), A::Int16(b)) => A::new_int16(try_binary_op(a.as_ref(), b.as_ref(), |a, b| int_op(*a, *b, stringify!($op), i16::$checked))?),
+
+            (A::Int16(a), A::Int32(b)) => A::new_int32(try_binary_op(a.as_ref(), b.as_ref(), |a, b| int_op(*a as i32, *b, stringify!($op), i32::$checked))?),
+            (A::Int32(a), A::Int16(b)) => A::new_int32(try_binary_op(a.as_ref(), b.as_ref(), |a, b| int_op(*a, *b as i32, stringify!($op), i32::$checked))?),
+            (A::Int32(a), A::Int32(b)) => A::new_int32(try_binary_op(a.as_ref(), b.as_ref(), |a, b| int_op(*a, *b, stringify!($op), i32::$checked))?),
+
+            (A::Int16(a), A::Int64(b)) => A::new_int64(try_binary_op(a.as_ref(), b.as_ref(), |a, b| int_op(*a as i64, *b, stringify!($op), i64::$checked))?),
+            (A::Int32(a), A::Int64(b)) => A::new_int64(try_binary_op(a.as_ref(), b.as_ref(), |a, b| int_op(*a as i64, *b, stringify!($op), i64::$checked))?),
+            (A::Int64(a), A::Int16(b)) => A::new_int64(try_binary_op(a.as_ref(), b.as_ref(), |a, b| int_op(*a, *b as i64, stringify!($op), i64::$checked))?),
+            (A::Int64(a), A::Int32(b)) => A::new_int64(try_binary_op(a.as_ref(), b.as_ref(), |a, b| int_op(*a, *b as i64, stringify!($op), i64::$checked))?),
+            (A::Int64(a), A::Int64(b)) => A::new_int64(try_binary_op(a.as_ref(), b.as_ref(), |a, b| int_op(*a, *b, stringify!($op), i64::$checked))?),
+
+            (A::Int16(a), A::Float64(b)) => A::new_float64(try_binary_op(a.as_ref(), b.as_ref(), |a, b| float_op(F64::from(*a as f64), *b, stringify!($op), |a, b| a $op b))?),
+            (A::Int32(a), A::Float64(b)) => A::new_float64(try_binary_op(a.as_ref(), b.as_ref(), |a, b| float_op(F64::from(*a as f64), *b, stringify!($op), |a, b| a $op b))?),
+            (A::Int64(a), A::Float64(b)) => A::new_float64(try_binary_op(a.as_ref(), b.as_ref(), |a, b| float_op(F64::from(*a as f64), *b, stringify!($op), |a, b| a $op b))?),
+            (A::Float64(a), A::Int16(b)) => A::new_float64(try_binary_op(a.as_ref(), b.as_ref(), |a, b| float_op(*a, F64::from(*b as f64), stringify!($op), |a, b| a $op b))?),
+            (A::Float64(a), A::Int32(b)) => A::new_float64(try_binary_op(a.as_ref(), b.as_ref(), |a, b| float_op(*a, F64::from(*b as f64), stringify!($op), |a, b| a $op b))?),
+            (A::Float64(a), A::Int64(b)) => A::new_float64(try_binary_op(a.as_ref(), b.as_ref(), |a, b| float_op(*a, F64::from(*b as f64), stringify!($op), |a, b| a $op b))?),
+            (A::Float64(a), A::Float64(b)) => A::new_float64(try_binary_op(a.as_ref(), b.as_ref(), |a, b| float_op(*a, *b, stringify!($op), |a, b| a $op b))?),
+
+            (A::Int16(a), A::Decimal(b)) => A::new_decimal(try_binary_op(a.as_ref(), b.as_ref(), |a, b| decimal_op(Decimal::from(*a), *b, stringify!($op), Decimal::$checked))?),
+            (A::Int32(a), A::Decimal(b)) => A::new_decimal(try_binary_op(a.as_ref(), b.as_ref(), |a, b| decimal_op(Decimal::from(*a), *b, stringify!($op), Decimal::$checked))?),
+            (A::Int64(a), A::Decimal(b)) => A::new_decimal(try_binary_op(a.as_ref(), b.as_ref(), |a, b| decimal_op(Decimal::from(*a), *b, stringify!($op), Decimal::$checked))?),
+            (A::Float64(a), A::Decimal(b)) => A::new_decimal(try_binary_op(a.as_ref(), b.as_ref(), |a, b| decimal_op(float_to_decimal(*a)?, *b, stringify!($op), Decimal::$checked))?),
+            (A::Decimal(a), A::Int16(b)) => A::new_decimal(try_binary_op(a.as_ref(), b.as_ref(), |a, b| decimal_op(*a, Decimal::from(*b), stringify!($op), Decimal::$checked))?),
+            (A::Decimal(a), A::Int32(b)) => A::new_decimal(try_binary_op(a.as_ref(), b.as_ref(), |a, b| decimal_op(*a, Decimal::from(*b), stringify!($op), Decimal::$checked))?),
+            (A::Decimal(a), A::Int64(b)) => A::new_decimal(try_binary_op(a.as_ref(), b.as_ref(), |a, b| decimal_op(*a, Decimal::from(*b), stringify!($op), Decimal::$checked))?),
+            (A::Decimal(a), A::Float64(b)) => A::new_decimal(try_binary_op(a.as_ref(), b.as_ref(), |a, b| decimal_op(*a, float_to_decimal(*b)?, stringify!($op), Decimal::$checked))?),
+            (A::Decimal(a), A::Decimal(b)) => A::new_decimal(try_binary_op(a.as_ref(), b.as_ref(), |a, b| decimal_op(*a, *b, stringify!($op), Decimal::$checked))?),
 
             (A::Date(a), A::Interval(b)) => A::new_date(binary_op(a.as_ref(), b.as_ref(), |a, b| *a $op *b)),
+            // only `interval + date` is valid, which is guaranteed by type checking
+            (A::Interval(a), A::Date(b)) => A::new_date(binary_op(a.as_ref(), b.as_ref(), |a, b| *b $op *a)),
 
             _ => return Err(ConvertError::NoBinaryOp(stringify!($name).into(), self.type_string(), other.type_string())),
         })
@@ -125,16 +148,18 @@ macro_rules! cmp {
             (A::Int16(a), A::Decimal(b)) => binary_op(a.as_ref(), b.as_ref(), |a, b| Decimal::from(*a) $op *b),
             (A::Int32(a), A::Decimal(b)) => binary_op(a.as_ref(), b.as_ref(), |a, b| Decimal::from(*a) $op *b),
             (A::Int64(a), A::Decimal(b)) => binary_op(a.as_ref(), b.as_ref(), |a, b| Decimal::from(*a) $op *b),
-            (A::Float64(a), A::Decimal(b)) => binary_op(a.as_ref(), b.as_ref(), |a, b| Decimal::from_f64_retain(a.0).unwrap() $op *b),
+            (A::Float64(a), A::Decimal(b)) => binary_op(a.as_ref(), b.as_ref(), |a, b| cmp_float_decimal(*a, b) $op Ordering::Equal),
             (A::Decimal(a), A::Int16(b)) => binary_op(a.as_ref(), b.as_ref(), |a, b| *a $op Decimal::from(*b)),
             (A::Decimal(a), A::Int32(b)) => binary_op(a.as_ref(), b.as_ref(), |a, b| *a $op Decimal::from(*b)),
             (A::Decimal(a), A::Int64(b)) => binary_op(a.as_ref(), b.as_ref(), |a, b| *a $op Decimal::from(*b)),
-            (A::Decimal(a), A::Float64(b)) => binary_op(a.as_ref(), b.as_ref(), |a, b| *a $op Decimal::from_f64_retain(b.0).unwrap()),
+            (A::Decimal(a), A::Float64(b)) => binary_op(a.as_ref(), b.as_ref(), |a, b| cmp_float_decimal(*b, a).reverse() $op Ordering::Equal),
             (A::Decimal(a), A::Decimal(b)) => binary_op(a.as_ref(), b.as_ref(), |a, b| a $op b),
 
             (A::Utf8(a), A::Utf8(b)) => binary_op(a.as_ref(), b.as_ref(), |a, b| a $op b),
 
             (A::Date(a), A::Date(b)) => binary_op(a.as_ref(), b.as_ref(), |a, b| a $op b),
+            (A::Timestamp(a), A::Timestamp(b)) => binary_op(a.as_ref(), b.as_ref(), |a, b| a $op b),
+            (A::TimestampTz(a), A::TimestampTz(b)) => binary_op(a.as_ref(), b.as_ref(), |a, b| a $op b),
 
             _ => return Err(ConvertError::NoBinaryOp(stringify!($name).into(), self.type_string(), other.type_string())),
         })))
@@ -142,18 +167,145 @@ macro_rules! cmp {
     }
 }
 
+/// A macro to implement bitwise operations on integers.
+///
+/// Both sides are casted to the wider integer type.
+macro_rules! bitwise {
+    ($name:ident, $op:tt) => {
+        pub fn $name(&self, other: &Self) -> Result<Self, ConvertError> {
+            let kind = match (self, other) {
+                (A::Null(_), A::Null(_)) => return Ok(self.clone()),
+                (A::Int64(_), A::Null(_) | A::Int16(_) | A::Int32(_) | A::Int64(_))
+                | (A::Null(_) | A::Int16(_) | A::Int32(_), A::Int64(_)) => DataTypeKind::Int64,
+                (A::Int32(_), A::Null(_) | A::Int16(_) | A::Int32(_))
+                | (A::Null(_) | A::Int16(_), A::Int32(_)) => DataTypeKind::Int32,
+                (A::Int16(_), A::Null(_) | A::Int16(_)) | (A::Null(_), A::Int16(_)) => {
+                    DataTypeKind::Int16
+                }
+                _ => {
+                    return Err(ConvertError::NoBinaryOp(
+                        stringify!($name).into(),
+                        self.type_string(),
+                        other.type_string(),
+                    ))
+                }
+            };
+            Ok(match (self.cast(&kind)?, other.cast(&kind)?) {
+                (A::Int16(a), A::Int16(b)) => {
+                    A::new_int16(binary_op(a.as_ref(), b.as_ref(), |a, b| a $op b))
+                }
+                (A::Int32(a), A::Int32(b)) => {
+                    A::new_int32(binary_op(a.as_ref(), b.as_ref(), |a, b| a $op b))
+                }
+                (A::Int64(a), A::Int64(b)) => {
+                    A::new_int64(binary_op(a.as_ref(), b.as_ref(), |a, b| a $op b))
+                }
+                _ => unreachable!(),
+            })
+        }
+    };
+}
+
+/// A macro to implement bit shift operations on integers.
+///
+/// The result has the type of the left side. The shift amount is taken modulo the number of bits
+/// of the type, so shifting never overflows. Right shifts are arithmetic.
+macro_rules! shift {
+    ($name:ident, $f:ident) => {
+        pub fn $name(&self, other: &Self) -> Result<Self, ConvertError> {
+            let amount = match (self, other) {
+                (
+                    A::Null(_) | A::Int16(_) | A::Int32(_) | A::Int64(_),
+                    A::Null(_) | A::Int16(_) | A::Int32(_) | A::Int64(_),
+                ) => other.cast(&DataTypeKind::Int64)?,
+                _ => {
+                    return Err(ConvertError::NoBinaryOp(
+                        stringify!($name).into(),
+                        self.type_string(),
+                        other.type_string(),
+                    ))
+                }
+            };
+            let A::Int64(b) = amount else { unreachable!() };
+            Ok(match self {
+                A::Null(_) => self.clone(),
+                A::Int16(a) => {
+                    A::new_int16(binary_op(a.as_ref(), b.as_ref(), |a, b| a.$f(*b as u32)))
+                }
+                A::Int32(a) => {
+                    A::new_int32(binary_op(a.as_ref(), b.as_ref(), |a, b| a.$f(*b as u32)))
+                }
+                A::Int64(a) => {
+                    A::new_int64(binary_op(a.as_ref(), b.as_ref(), |a, b| a.$f(*b as u32)))
+                }
+                _ => unreachable!(),
+            })
+        }
+    };
+}
+
 impl ArrayImpl {
-    arith!(add, +);
-    arith!(sub, -);
-    arith!(mul, *);
-    arith!(div, /);
-    arith!(rem, %);
+    arith!(add, +, checked_add);
+    arith!(sub, -, checked_sub);
+    arith!(mul, *, checked_mul);
+    arith!(div, /, checked_div);
+    arith!(rem, %, checked_rem);
     cmp!(eq, ==);
     cmp!(ne, !=);
     cmp!(gt,  >);
     cmp!(lt,  <);
     cmp!(ge, >=);
     cmp!(le, <=);
+    bitwise!(bitand, &);
+    bitwise!(bitor, |);
+    bitwise!(bitxor, ^);
+    shift!(shl, wrapping_shl);
+    shift!(shr, wrapping_shr);
+
+    /// Returns whether each pair of values is distinct, treating NULLs as equal values.
+    ///
+    /// Unlike [`ne`](Self::ne), the result is never NULL.
+    pub fn is_distinct(&self, other: &Self) -> Result<Self, ConvertError> {
+        // compare the values where neither side is NULL
+        let ne = match (self, other) {
+            (A::Null(_), _) | (_, A::Null(_)) => None,
+            _ => match self.ne(other)? {
+                A::Bool(ne) => Some(ne),
+                _ => unreachable!("comparison returns bool"),
+            },
+        };
+        let (a, b) = (self.get_valid_bitmap(), other.get_valid_bitmap());
+        let distinct = (0..self.len()).map(|i| match (a[i], b[i]) {
+            (true, true) => *ne.as_ref().unwrap().get(i).unwrap(),
+            (false, false) => false,
+            _ => true,
+        });
+        Ok(A::new_bool(distinct.collect()))
+    }
+
+    /// Returns whether each value is in the given list of values.
+    ///
+    /// The values are put into a hash set, so the cost per row does not depend on the length of
+    /// the list. Like a chain of `=` and `OR`, the result is NULL if the value is NULL, or if it
+    /// is not found and the list contains NULL.
+    pub fn in_list<'a>(&self, values: impl IntoIterator<Item = &'a DataValue>) -> Self {
+        let mut has_null = false;
+        let mut set = HashSet::new();
+        for value in values {
+            match value {
+                DataValue::Null => has_null = true,
+                value => {
+                    set.insert(value);
+                }
+            }
+        }
+        let result = (0..self.len()).map(|i| match self.is_null(i) {
+            true => None,
+            false if set.contains(&self.get(i)) => Some(true),
+            false => (!has_null).then_some(false),
+        });
+        A::new_bool(result.collect())
+    }
 
     pub fn and(&self, other: &Self) -> Result<Self, ConvertError> {
         let (A::Bool(a), A::Bool(b)) = (self, other) else {
@@ -177,6 +329,25 @@ impl ArrayImpl {
         Ok(A::new_bool(c))
     }
 
+    /// Logical XOR. The result is NULL if either operand is NULL.
+    pub fn xor(&self, other: &Self) -> Result<Self, ConvertError> {
+        match (self, other) {
+            (A::Bool(a), A::Bool(b)) => {
+                Ok(A::new_bool(binary_op(a.as_ref(), b.as_ref(), |a, b| {
+                    *a != *b
+                })))
+            }
+            (A::Bool(_) | A::Null(_), A::Null(_)) | (A::Null(_), A::Bool(_)) => {
+                Ok(A::new_bool((0..self.len()).map(|_| None).collect()))
+            }
+            _ => Err(ConvertError::NoBinaryOp(
+                "xor".into(),
+                self.type_string(),
+                other.type_string(),
+            )),
+        }
+    }
+
     pub fn not(&self) -> Result<Self, ConvertError> {
         let A::Bool(a) = self else {
             return Err(ConvertError::NoUnaryOp("not".into(), self.type_string()));
@@ -187,14 +358,16 @@ impl ArrayImpl {
     pub fn like(&self, pattern: &str) -> Result<Self, ConvertError> {
         /// Converts a SQL LIKE pattern to a regex pattern.
         fn like_to_regex(pattern: &str) -> String {
-            let mut regex = String::with_capacity(pattern.len());
+            // the whole string must match, and wildcards also match newlines
+            let mut regex = String::from("(?s)^");
             for c in pattern.chars() {
                 match c {
                     '%' => regex.push_str(".*"),
                     '_' => regex.push('.'),
-                    c => regex.push(c),
+                    c => regex.push_str(&regex::escape(c.encode_utf8(&mut [0; 4]))),
                 }
             }
+            regex.push('$');
             regex
         }
         let A::Utf8(a) = self else {
@@ -234,6 +407,24 @@ impl ArrayImpl {
         })
     }
 
+    pub fn date_trunc(&self, field: DateTimeField) -> Result<Self, ConvertError> {
+        let unsupported = || ConvertError::UnsupportedTruncField(field);
+        Ok(match self {
+            A::Timestamp(a) => A::new_timestamp(try_unary_op(a.as_ref(), |t| {
+                t.truncate(field).ok_or_else(unsupported)
+            })?),
+            A::TimestampTz(a) => A::new_timestamp_tz(try_unary_op(a.as_ref(), |t| {
+                t.truncate(field).ok_or_else(unsupported)
+            })?),
+            _ => {
+                return Err(ConvertError::NoUnaryOp(
+                    "date_trunc".into(),
+                    self.type_string(),
+                ));
+            }
+        })
+    }
+
     pub fn substring(&self, start: &Self, length: &Self) -> Result<Self, ConvertError> {
         let (A::Utf8(a), A::Int32(b), A::Int32(c)) = (self, start, length) else {
             return Err(ConvertError::NoTernaryOp("substring".into(), self.type_string(), start.type_string(), length.type_string()));
@@ -283,6 +474,12 @@ impl ArrayImpl {
             LtEq => self.le(other),
             And => self.and(other),
             Or => self.or(other),
+            Xor => self.xor(other),
+            BitwiseAnd => self.bitand(other),
+            BitwiseOr => self.bitor(other),
+            BitwiseXor => self.bitxor(other),
+            PGBitwiseShiftLeft => self.shl(other),
+            PGBitwiseShiftRight => self.shr(other),
             StringConcat => self.concat(other),
             _ => Err(ConvertError::NoBinaryOp(
                 op.to_string(),
@@ -292,6 +489,87 @@ impl ArrayImpl {
         }
     }
 
+    /// Compare with another array and returns a selection mask of rows where the result is true.
+    ///
+    /// Unlike [`binary_op`](Self::binary_op), no boolean array is built and NULLs are never
+    /// selected. Returns `None` if the operator is not a comparison, or the arrays are not of
+    /// the same numeric type.
+    pub fn select(&self, op: &BinaryOperator, other: &ArrayImpl) -> Option<Vec<bool>> {
+        Some(match (self, other) {
+            (A::Int16(a), A::Int16(b)) => select_op(a.as_ref(), b.as_ref(), cmp_fn::<i16>(op)?),
+            (A::Int32(a), A::Int32(b)) => select_op(a.as_ref(), b.as_ref(), cmp_fn::<i32>(op)?),
+            (A::Int64(a), A::Int64(b)) => select_op(a.as_ref(), b.as_ref(), cmp_fn::<i64>(op)?),
+            (A::Float64(a), A::Float64(b)) => select_op(a.as_ref(), b.as_ref(), cmp_fn::<F64>(op)?),
+            _ => return None,
+        })
+    }
+
+    /// Compare with a scalar value and returns a selection mask of rows where the result is true.
+    ///
+    /// The array is the left operand. See [`select`](Self::select) for details.
+    pub fn select_value(&self, op: &BinaryOperator, value: &DataValue) -> Option<Vec<bool>> {
+        Some(match (self, value) {
+            (A::Int16(a), DataValue::Int16(v)) => {
+                let f = cmp_fn(op)?;
+                select_value_op(a.as_ref(), |a| f(a, v))
+            }
+            (A::Int32(a), DataValue::Int32(v)) => {
+                let f = cmp_fn(op)?;
+                select_value_op(a.as_ref(), |a| f(a, v))
+            }
+            (A::Int64(a), DataValue::Int64(v)) => {
+                let f = cmp_fn(op)?;
+                select_value_op(a.as_ref(), |a| f(a, v))
+            }
+            (A::Float64(a), DataValue::Float64(v)) => {
+                let f = cmp_fn(op)?;
+                select_value_op(a.as_ref(), |a| f(a, v))
+            }
+            _ => return None,
+        })
+    }
+
+    /// Perform binary operation, but integer arithmetic wraps around at the boundary of the type
+    /// instead of returning an error on overflow.
+    pub fn wrapping_binary_op(
+        &self,
+        op: &BinaryOperator,
+        other: &ArrayImpl,
+    ) -> Result<ArrayImpl, ConvertError> {
+        macro_rules! wrapping {
+            ($f:ident) => {{
+                // cast both sides to the wider type
+                let kind = match (self, other) {
+                    (A::Int64(_), A::Int16(_) | A::Int32(_) | A::Int64(_))
+                    | (A::Int16(_) | A::Int32(_), A::Int64(_)) => DataTypeKind::Int64,
+                    (A::Int32(_), A::Int16(_) | A::Int32(_)) | (A::Int16(_), A::Int32(_)) => {
+                        DataTypeKind::Int32
+                    }
+                    (A::Int16(_), A::Int16(_)) => DataTypeKind::Int16,
+                    _ => return self.binary_op(op, other),
+                };
+                match (self.cast(&kind)?, other.cast(&kind)?) {
+                    (A::Int16(a), A::Int16(b)) => {
+                        A::new_int16(binary_op(a.as_ref(), b.as_ref(), |a, b| a.$f(*b)))
+                    }
+                    (A::Int32(a), A::Int32(b)) => {
+                        A::new_int32(binary_op(a.as_ref(), b.as_ref(), |a, b| a.$f(*b)))
+                    }
+                    (A::Int64(a), A::Int64(b)) => {
+                        A::new_int64(binary_op(a.as_ref(), b.as_ref(), |a, b| a.$f(*b)))
+                    }
+                    _ => unreachable!(),
+                }
+            }};
+        }
+        Ok(match op {
+            BinaryOperator::Plus => wrapping!(wrapping_add),
+            BinaryOperator::Minus => wrapping!(wrapping_sub),
+            BinaryOperator::Multiply => wrapping!(wrapping_mul),
+            _ => return self.binary_op(op, other),
+        })
+    }
+
     /// Cast the array to another type.
     pub fn cast(&self, data_type: &DataTypeKind) -> Result<Self, ConvertError> {
         type Type = DataTypeKind;
@@ -413,9 +691,9 @@ impl ArrayImpl {
                 })?),
                 Type::Float64 => Self::Float64(a.clone()),
                 Type::String => Self::new_utf8(Utf8Array::from_iter_display(a.iter())),
-                Type::Decimal(_, _) => Self::new_decimal(unary_op(a.as_ref(), |&f| {
-                    Decimal::from_f64_retain(f.0).unwrap()
-                })),
+                Type::Decimal(_, _) => {
+                    Self::new_decimal(try_unary_op(a.as_ref(), |&f| float_to_decimal(f))?)
+                }
                 Type::Null
                 | Type::Date
                 | Type::Timestamp
@@ -427,32 +705,34 @@ impl ArrayImpl {
                 }
             },
             Self::Utf8(a) => match data_type {
-                Type::Bool => Self::new_bool(try_unary_op(a.as_ref(), |s| {
-                    s.parse::<bool>()
-                        .map_err(|e| ConvertError::ParseBool(s.to_string(), e))
-                })?),
+                Type::Bool => Self::new_bool(try_unary_op(a.as_ref(), parse_bool)?),
                 Type::Int16 => Self::new_int16(try_unary_op(a.as_ref(), |s| {
-                    s.parse::<i16>()
+                    s.trim()
+                        .parse::<i16>()
                         .map_err(|e| ConvertError::ParseInt(s.to_string(), e))
                 })?),
                 Type::Int32 => Self::new_int32(try_unary_op(a.as_ref(), |s| {
-                    s.parse::<i32>()
+                    s.trim()
+                        .parse::<i32>()
                         .map_err(|e| ConvertError::ParseInt(s.to_string(), e))
                 })?),
                 Type::Int64 => Self::new_int64(try_unary_op(a.as_ref(), |s| {
-                    s.parse::<i64>()
+                    s.trim()
+                        .parse::<i64>()
                         .map_err(|e| ConvertError::ParseInt(s.to_string(), e))
                 })?),
                 Type::Float64 => Self::new_float64(try_unary_op(a.as_ref(), |s| {
-                    s.parse::<F64>()
+                    s.trim()
+                        .parse::<F64>()
                         .map_err(|e| ConvertError::ParseFloat(s.to_string(), e))
                 })?),
                 Type::String => Self::Utf8(a.clone()),
                 Type::Decimal(_, _) => Self::new_decimal(try_unary_op(a.as_ref(), |s| {
-                    Decimal::from_str(s).map_err(|e| ConvertError::ParseDecimal(s.to_string(), e))
+                    Decimal::from_str(s.trim())
+                        .map_err(|e| ConvertError::ParseDecimal(s.to_string(), e))
                 })?),
                 Type::Date => Self::new_date(try_unary_op(a.as_ref(), |s| {
-                    Date::from_str(s).map_err(|e| ConvertError::ParseDate(s.to_string(), e))
+                    Date::from_str(s.trim()).map_err(|e| ConvertError::ParseDate(s.to_string(), e))
                 })?),
                 Type::Timestamp => Self::new_timestamp(try_unary_op(a.as_ref(), |s| {
                     Timestamp::from_str(s)
@@ -472,7 +752,11 @@ impl ArrayImpl {
                     return Err(ConvertError::NoCast("VARCHAR", data_type.clone()));
                 }
             },
-            Self::Blob(_) => todo!("cast array"),
+            Self::Blob(a) => match data_type {
+                Type::Blob => Self::Blob(a.clone()),
+                Type::String => Self::new_utf8(Utf8Array::from_iter_display(a.iter())),
+                _ => return Err(ConvertError::NoCast("BLOB", data_type.clone())),
+            },
             Self::Decimal(a) => match data_type {
                 Type::Bool => Self::new_bool(unary_op(a.as_ref(), |&d| !d.is_zero())),
                 Type::Int16 => Self::new_int16(try_unary_op(a.as_ref(), |&d| {
@@ -501,21 +785,29 @@ impl ArrayImpl {
                 | Type::TimestampTz
                 | Type::Interval
                 | Type::Struct(_) => {
-                    return Err(ConvertError::NoCast("DOUBLE", data_type.clone()));
+                    return Err(ConvertError::NoCast("DECIMAL", data_type.clone()));
                 }
             },
             Self::Date(a) => match data_type {
                 Type::Date => self.clone(),
+                Type::Timestamp => {
+                    Self::new_timestamp(unary_op(a.as_ref(), |&d| Timestamp::from_date(d)))
+                }
+                Type::TimestampTz => {
+                    Self::new_timestamp_tz(unary_op(a.as_ref(), |&d| TimestampTz::from_date(d)))
+                }
                 Type::String => Self::new_utf8(Utf8Array::from_iter_display(a.iter())),
                 _ => return Err(ConvertError::NoCast("DATE", data_type.clone())),
             },
             Self::Timestamp(a) => match data_type {
                 Type::Timestamp => self.clone(),
+                Type::Date => Self::new_date(unary_op(a.as_ref(), |t| t.date())),
                 Type::String => Self::new_utf8(Utf8Array::from_iter_display(a.iter())),
                 _ => return Err(ConvertError::NoCast("TIMESTAMP", data_type.clone())),
             },
             Self::TimestampTz(a) => match data_type {
                 Type::TimestampTz => self.clone(),
+                Type::Date => Self::new_date(unary_op(a.as_ref(), |t| t.date())),
                 Type::String => Self::new_utf8(Utf8Array::from_iter_display(a.iter())),
                 _ => {
                     return Err(ConvertError::NoCast(
@@ -532,6 +824,23 @@ impl ArrayImpl {
         })
     }
 
+    /// Cast the array to another type. Values that can not be casted become NULL.
+    pub fn try_cast(&self, data_type: &DataTypeKind) -> Self {
+        if let Ok(array) = self.cast(data_type) {
+            return array;
+        }
+        // fallback to cast values one by one
+        let mut builder =
+            ArrayBuilderImpl::with_capacity(self.len(), &data_type.clone().nullable());
+        for i in 0..self.len() {
+            let value = ArrayImpl::from(&self.get(i))
+                .cast(data_type)
+                .map_or(DataValue::Null, |a| a.get(0));
+            builder.push(&value);
+        }
+        builder.finish()
+    }
+
     /// Returns the sum of values.
     pub fn sum(&self) -> DataValue {
         match self {
@@ -556,6 +865,150 @@ impl ArrayImpl {
         };
         Ok(A::new_utf8(unary_op(a.as_ref(), |s| s.replace(from, to))))
     }
+
+    /// Returns the number of bytes in strings or blobs.
+    pub fn length(&self) -> Result<Self, ConvertError> {
+        Ok(match self {
+            A::Utf8(a) => A::new_int32(unary_op(a.as_ref(), |s| s.len() as i32)),
+            A::Blob(a) => A::new_int32(unary_op(a.as_ref(), |b| b.len() as i32)),
+            _ => return Err(ConvertError::NoUnaryOp("length".into(), self.type_string())),
+        })
+    }
+
+    /// Returns the number of characters in strings.
+    pub fn char_length(&self) -> Result<Self, ConvertError> {
+        let A::Utf8(a) = self else {
+            return Err(ConvertError::NoUnaryOp("char_length".into(), self.type_string()));
+        };
+        Ok(A::new_int32(unary_op(a.as_ref(), |s| {
+            s.chars().count() as i32
+        })))
+    }
+
+    /// Returns the code point of the first character in strings, or 0 for empty strings.
+    pub fn ascii(&self) -> Result<Self, ConvertError> {
+        let A::Utf8(a) = self else {
+            return Err(ConvertError::NoUnaryOp("ascii".into(), self.type_string()));
+        };
+        Ok(A::new_int32(unary_op(a.as_ref(), |s| {
+            s.chars().next().map_or(0, |c| c as i32)
+        })))
+    }
+
+    /// Returns the character of integer code points.
+    pub fn chr(&self) -> Result<Self, ConvertError> {
+        let A::Int64(a) = (match self {
+            A::Int16(_) | A::Int32(_) | A::Int64(_) => self.cast(&DataTypeKind::Int64)?,
+            _ => return Err(ConvertError::NoUnaryOp("chr".into(), self.type_string())),
+        }) else {
+            unreachable!()
+        };
+        Ok(A::new_utf8(try_unary_op(a.as_ref(), |&v| {
+            let c = u32::try_from(v).ok().and_then(char::from_u32);
+            c.map(String::from).ok_or(ConvertError::InvalidCodePoint(v))
+        })?))
+    }
+
+    /// Reverses the characters in strings.
+    pub fn reverse(&self) -> Result<Self, ConvertError> {
+        let A::Utf8(a) = self else {
+            return Err(ConvertError::NoUnaryOp("reverse".into(), self.type_string()));
+        };
+        Ok(A::new_utf8(unary_op(a.as_ref(), |s| {
+            s.chars().rev().collect::<String>()
+        })))
+    }
+
+    /// Repeats strings for `count` times, or returns empty strings if `count` is not positive.
+    ///
+    /// Returns an error if a result is longer than [`MAX_STRING_LEN`], or the results are longer
+    /// than [`MAX_STRINGS_LEN`] in total.
+    pub fn repeat(&self, count: &Self) -> Result<Self, ConvertError> {
+        let (A::Utf8(a), A::Int16(_) | A::Int32(_) | A::Int64(_)) = (self, count) else {
+            return Err(ConvertError::NoBinaryOp("repeat".into(), self.type_string(), count.type_string()));
+        };
+        let A::Int64(count) = count.cast(&DataTypeKind::Int64)? else { unreachable!() };
+        let total = std::cell::Cell::new(0usize);
+        Ok(A::new_utf8(try_binary_op(
+            a.as_ref(),
+            count.as_ref(),
+            |s, &n| {
+                let n = n.max(0) as usize;
+                let len = match s.len().checked_mul(n) {
+                    Some(len) if len <= MAX_STRING_LEN => len,
+                    _ => return Err(ConvertError::StringTooLong(s.len().saturating_mul(n))),
+                };
+                total.set(total.get() + len);
+                if total.get() > MAX_STRINGS_LEN {
+                    return Err(ConvertError::StringsTooLong(total.get()));
+                }
+                Ok(s.repeat(n))
+            },
+        )?))
+    }
+
+    /// Returns the sign of numbers as -1, 0 or 1 in the same type.
+    pub fn sign(&self) -> Result<Self, ConvertError> {
+        Ok(match self {
+            A::Int16(a) => A::new_int16(unary_op(a.as_ref(), |v| v.signum())),
+            A::Int32(a) => A::new_int32(unary_op(a.as_ref(), |v| v.signum())),
+            A::Int64(a) => A::new_int64(unary_op(a.as_ref(), |v| v.signum())),
+            A::Float64(a) => A::new_float64(unary_op(a.as_ref(), |v| match v.0 {
+                v if v > 0.0 => F64::from(1.0),
+                v if v < 0.0 => F64::from(-1.0),
+                v => F64::from(v),
+            })),
+            A::Decimal(a) => {
+                A::new_decimal(unary_op(a.as_ref(), |v| match v.cmp(&Decimal::ZERO) {
+                    std::cmp::Ordering::Greater => Decimal::ONE,
+                    std::cmp::Ordering::Less => Decimal::NEGATIVE_ONE,
+                    std::cmp::Ordering::Equal => Decimal::ZERO,
+                }))
+            }
+            _ => return Err(ConvertError::NoUnaryOp("sign".into(), self.type_string())),
+        })
+    }
+
+    /// Returns the natural logarithm of numbers.
+    pub fn ln(&self) -> Result<Self, ConvertError> {
+        let A::Float64(a) = self.to_float64("ln")? else { unreachable!() };
+        Ok(A::new_float64(try_unary_op(a.as_ref(), |v| ln(*v))?))
+    }
+
+    /// Returns the logarithm of `value` to the base of `self`.
+    pub fn log(&self, value: &Self) -> Result<Self, ConvertError> {
+        let A::Float64(base) = self.to_float64("log")? else { unreachable!() };
+        let A::Float64(value) = value.to_float64("log")? else { unreachable!() };
+        Ok(A::new_float64(try_binary_op(
+            base.as_ref(),
+            value.as_ref(),
+            |base, value| {
+                let base = ln(*base)?;
+                if base.0 == 0.0 {
+                    return Err(ConvertError::DivisionByZero);
+                }
+                Ok(F64::from(ln(*value)?.0 / base.0))
+            },
+        )?))
+    }
+
+    /// Returns `e` raised to the power of numbers.
+    pub fn exp(&self) -> Result<Self, ConvertError> {
+        let A::Float64(a) = self.to_float64("exp")? else { unreachable!() };
+        Ok(A::new_float64(unary_op(a.as_ref(), |v| {
+            F64::from(v.0.exp())
+        })))
+    }
+
+    /// Casts a numeric array to float64 as the argument of math function `name`.
+    fn to_float64(&self, name: &str) -> Result<Self, ConvertError> {
+        match self {
+            A::Int16(_) | A::Int32(_) | A::Int64(_) | A::Float64(_) | A::Decimal(_) => {
+                self.cast(&DataTypeKind::Float64)
+            }
+            _ => Err(ConvertError::NoUnaryOp(name.into(), self.type_string())),
+        }
+    }
 }
 
 /// Implement aggregation functions.
@@ -608,6 +1061,154 @@ where
     O::from_data(it, valid)
 }
 
+/// Returns the function of a comparison operator.
+fn cmp_fn<T: PartialOrd>(op: &BinaryOperator) -> Option<fn(&T, &T) -> bool> {
+    use BinaryOperator::*;
+    let f: fn(&T, &T) -> bool = match op {
+        Eq => T::eq,
+        NotEq => T::ne,
+        Gt => T::gt,
+        Lt => T::lt,
+        GtEq => T::ge,
+        LtEq => T::le,
+        _ => return None,
+    };
+    Some(f)
+}
+
+fn select_op<A, B, F>(a: &A, b: &B, f: F) -> Vec<bool>
+where
+    A: ArrayValidExt,
+    B: ArrayValidExt,
+    F: Fn(&A::Item, &B::Item) -> bool,
+{
+    assert_eq!(a.len(), b.len());
+    let valid = a.get_valid_bitmap().and(b.get_valid_bitmap());
+    (a.raw_iter().zip(b.raw_iter()).zip(valid))
+        .map(|((a, b), valid)| valid && f(a, b))
+        .collect()
+}
+
+fn select_value_op<A, F>(a: &A, f: F) -> Vec<bool>
+where
+    A: ArrayValidExt,
+    F: Fn(&A::Item) -> bool,
+{
+    (a.raw_iter().zip(a.get_valid_bitmap().iter()))
+        .map(|(a, valid)| *valid && f(a))
+        .collect()
+}
+
+fn try_binary_op<A, B, O, F, V, E>(a: &A, b: &B, f: F) -> Result<O, E>
+where
+    A: Array,
+    B: Array,
+    O: Array,
+    V: Borrow<O::Item>,
+    F: Fn(&A::Item, &B::Item) -> Result<V, E>,
+{
+    assert_eq!(a.len(), b.len());
+    let mut builder = O::Builder::with_capacity(a.len());
+    for (a, b) in a.iter().zip(b.iter()) {
+        if let (Some(a), Some(b)) = (a, b) {
+            builder.push(Some(f(a, b)?.borrow()));
+        } else {
+            builder.push(None);
+        }
+    }
+    Ok(builder.finish())
+}
+
+/// Performs a checked integer operation.
+///
+/// Returns an error on overflow or division by zero.
+fn int_op<T>(a: T, b: T, op: &'static str, f: fn(T, T) -> Option<T>) -> Result<T, ConvertError>
+where
+    T: Copy + Into<i64> + num_traits::Zero,
+{
+    f(a, b).ok_or_else(|| {
+        if b.is_zero() && matches!(op, "/" | "%") {
+            ConvertError::DivisionByZero
+        } else {
+            ConvertError::IntegerOverflow(a.into(), op, b.into())
+        }
+    })
+}
+
+/// Performs a float operation.
+///
+/// Returns an error on division by zero as integers and decimals do,
+/// instead of yielding infinity or NaN.
+fn float_op(a: F64, b: F64, op: &'static str, f: fn(F64, F64) -> F64) -> Result<F64, ConvertError> {
+    if b.0 == 0.0 && matches!(op, "/" | "%") {
+        return Err(ConvertError::DivisionByZero);
+    }
+    Ok(f(a, b))
+}
+
+/// Converts a float to decimal.
+///
+/// Returns an error if it is NaN or infinity, or out of the range of decimal.
+fn float_to_decimal(f: F64) -> Result<Decimal, ConvertError> {
+    Decimal::from_f64_retain(f.0).ok_or(ConvertError::ToDecimalError(DataValue::Float64(f)))
+}
+
+/// Compares a float with a decimal.
+///
+/// Floats out of the range of decimal, including infinity, are compared by their signs.
+/// NaN is greater than all decimals, as it is greater than all other floats.
+fn cmp_float_decimal(a: F64, b: &Decimal) -> Ordering {
+    match Decimal::from_f64_retain(a.0) {
+        Some(a) => a.cmp(b),
+        None if a.0.is_nan() => Ordering::Greater,
+        None if a.0 < 0.0 => Ordering::Less,
+        None => Ordering::Greater,
+    }
+}
+
+/// Performs a checked decimal operation.
+///
+/// The scale of the result is decided by [`Decimal`]: `*` adds the scales of operands,
+/// and `/` picks the minimal scale to represent the result, rounded to at most 28 digits.
+/// Returns an error on overflow or division by zero.
+fn decimal_op(
+    a: Decimal,
+    b: Decimal,
+    op: &'static str,
+    f: fn(Decimal, Decimal) -> Option<Decimal>,
+) -> Result<Decimal, ConvertError> {
+    f(a, b).ok_or_else(|| {
+        if b.is_zero() && matches!(op, "/" | "%") {
+            ConvertError::DivisionByZero
+        } else {
+            ConvertError::DecimalOverflow(a, op, b)
+        }
+    })
+}
+
+/// Parses a boolean from a string.
+///
+/// Accepts `true`/`false`, `t`/`f`, `yes`/`no`, `y`/`n`, `on`/`off` and `1`/`0`, case-insensitively
+/// and ignoring surrounding whitespace.
+fn parse_bool(s: &str) -> Result<bool, ConvertError> {
+    match s.trim().to_ascii_lowercase().as_str() {
+        "true" | "t" | "yes" | "y" | "on" | "1" => Ok(true),
+        "false" | "f" | "no" | "n" | "off" | "0" => Ok(false),
+        // the standard parser only accepts "true" and "false", so it always fails here
+        _ => s
+            .parse::<bool>()
+            .map_err(|e| ConvertError::ParseBool(s.to_string(), e)),
+    }
+}
+
+/// Returns the natural logarithm of a positive number.
+fn ln(v: F64) -> Result<F64, ConvertError> {
+    if v.0 <= 0.0 {
+        return Err(ConvertError::InvalidLogarithm(v.0));
+    }
+    Ok(F64::from(v.0.ln()))
+}
+
 fn unary_op<A, O, F, V>(a: &A, f: F) -> O
 where
     A: ArrayValidExt,
@@ -716,3 +1317,341 @@ impl BitVecExt for BitVec {
         bitvec
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decimals(values: &[&str]) -> ArrayImpl {
+        A::new_decimal(
+            values
+                .iter()
+                .map(|s| Decimal::from_str(s).unwrap())
+                .collect(),
+        )
+    }
+
+    fn to_strings(array: ArrayImpl) -> Vec<String> {
+        let A::Decimal(a) = array else {
+            panic!("not decimal array");
+        };
+        a.iter().map(|v| v.unwrap().to_string()).collect()
+    }
+
+    #[test]
+    fn decimal_mul_scale() {
+        let a = decimals(&["1.5", "0.10", "-2.000"]);
+        let b = decimals(&["2.25", "0.20", "3.0"]);
+        assert_eq!(
+            to_strings(a.mul(&b).unwrap()),
+            ["3.375", "0.0200", "-6.0000"]
+        );
+    }
+
+    #[test]
+    fn decimal_add_sub_scale() {
+        let a = decimals(&["1.5", "0.10"]);
+        let b = decimals(&["2.25", "2"]);
+        assert_eq!(to_strings(a.add(&b).unwrap()), ["3.75", "2.10"]);
+        assert_eq!(to_strings(a.sub(&b).unwrap()), ["-0.75", "-1.90"]);
+    }
+
+    #[test]
+    fn decimal_div_rem() {
+        let a = decimals(&["7.50", "1"]);
+        let b = decimals(&["2", "3"]);
+        assert_eq!(
+            to_strings(a.div(&b).unwrap()),
+            ["3.75", "0.3333333333333333333333333333"]
+        );
+        assert_eq!(to_strings(a.rem(&b).unwrap()), ["1.50", "1"]);
+    }
+
+    #[test]
+    fn decimal_overflow() {
+        let a = A::new_decimal([Decimal::MAX].into_iter().collect());
+        let b = decimals(&["2"]);
+        assert_eq!(
+            a.mul(&b),
+            Err(ConvertError::DecimalOverflow(
+                Decimal::MAX,
+                "*",
+                Decimal::from(2)
+            ))
+        );
+        assert!(a.add(&a).is_err());
+        assert_eq!(
+            b.div(&decimals(&["0.00"])),
+            Err(ConvertError::DivisionByZero)
+        );
+        assert_eq!(b.rem(&decimals(&["0"])), Err(ConvertError::DivisionByZero));
+    }
+
+    #[test]
+    fn integer_overflow() {
+        let max = A::new_int32([i32::MAX].into_iter().collect());
+        let min = A::new_int32([i32::MIN].into_iter().collect());
+        let one = A::new_int32([1].into_iter().collect());
+        assert_eq!(
+            max.add(&one),
+            Err(ConvertError::IntegerOverflow(i32::MAX as i64, "+", 1))
+        );
+        assert!(min.sub(&one).is_err());
+        assert!(max.mul(&max).is_err());
+        assert!(min.neg().is_err());
+        assert_eq!(
+            one.div(&A::new_int32([0].into_iter().collect())),
+            Err(ConvertError::DivisionByZero)
+        );
+        // the result is promoted to the wider type
+        let one = A::new_int64([1].into_iter().collect());
+        assert_eq!(
+            max.add(&one),
+            Ok(A::new_int64([i32::MAX as i64 + 1].into_iter().collect()))
+        );
+    }
+
+    #[test]
+    fn float_special_values() {
+        let floats =
+            |values: &[f64]| A::new_float64(values.iter().map(|&v| F64::from(v)).collect());
+        let one = floats(&[1.0]);
+        assert_eq!(one.div(&floats(&[0.0])), Err(ConvertError::DivisionByZero));
+        assert_eq!(one.rem(&floats(&[-0.0])), Err(ConvertError::DivisionByZero));
+        assert_eq!(
+            floats(&[0.0]).div(&A::new_int32([0].into_iter().collect())),
+            Err(ConvertError::DivisionByZero)
+        );
+
+        // NaN equals to itself and is greater than all other values
+        let a = floats(&[f64::NAN, f64::NAN, f64::INFINITY, f64::NEG_INFINITY]);
+        let b = floats(&[f64::NAN, f64::INFINITY, f64::NAN, 0.0]);
+        let bools = |values: [bool; 4]| A::new_bool(values.into_iter().collect());
+        assert_eq!(a.eq(&b).unwrap(), bools([true, false, false, false]));
+        assert_eq!(a.gt(&b).unwrap(), bools([false, true, false, false]));
+        assert_eq!(a.lt(&b).unwrap(), bools([false, false, true, true]));
+
+        // compared with decimals without panic
+        let d = decimals(&["1", "1", "1", "1"]);
+        assert_eq!(a.gt(&d).unwrap(), bools([true, true, true, false]));
+        assert_eq!(d.ge(&a).unwrap(), bools([false, false, false, true]));
+        assert!(matches!(
+            a.add(&d),
+            Err(ConvertError::ToDecimalError(DataValue::Float64(_)))
+        ));
+    }
+
+    #[test]
+    fn repeat_too_long() {
+        let a = strings(&[Some("ab")]);
+        let count = |n: i64| A::new_int64([n].into_iter().collect());
+        assert_eq!(
+            a.repeat(&count(i64::MAX)),
+            Err(ConvertError::StringTooLong(usize::MAX))
+        );
+        assert_eq!(
+            a.repeat(&count(MAX_STRING_LEN as i64)),
+            Err(ConvertError::StringTooLong(MAX_STRING_LEN * 2))
+        );
+        let a = strings(&[Some("")]);
+        assert!(a.repeat(&count(i64::MAX)).is_ok());
+
+        // every string is short enough, but they are too long in total
+        let n = MAX_STRINGS_LEN / MAX_STRING_LEN + 1;
+        let a = strings(&vec![Some("a"); n]);
+        let counts = A::new_int64(vec![MAX_STRING_LEN as i64; n].into_iter().collect());
+        let total = MAX_STRINGS_LEN + MAX_STRING_LEN;
+        assert_eq!(a.repeat(&counts), Err(ConvertError::StringsTooLong(total)));
+    }
+
+    #[test]
+    fn integer_wrapping() {
+        let max = A::new_int32([i32::MAX].into_iter().collect());
+        let min = A::new_int32([i32::MIN].into_iter().collect());
+        let one = A::new_int32([1].into_iter().collect());
+        assert_eq!(
+            max.wrapping_binary_op(&BinaryOperator::Plus, &one),
+            Ok(min.clone())
+        );
+        assert_eq!(
+            min.wrapping_binary_op(&BinaryOperator::Minus, &one),
+            Ok(max.clone())
+        );
+        assert_eq!(min.wrapping_neg(), Ok(min.clone()));
+    }
+
+    #[test]
+    fn bitwise() {
+        let ints = |v: &[i32]| A::new_int32(v.iter().cloned().collect());
+        let a = ints(&[12, -1, 0]);
+        let b = A::new_int64([10, 255, 7].into_iter().collect());
+        let longs = |v: &[i64]| A::new_int64(v.iter().cloned().collect());
+        assert_eq!(a.bitand(&b), Ok(longs(&[8, 255, 0])));
+        assert_eq!(a.bitor(&b), Ok(longs(&[14, -1, 7])));
+        assert_eq!(a.bitxor(&b), Ok(longs(&[6, -256, 7])));
+        let float = A::new_float64([F64::from(1.0); 3].into_iter().collect());
+        assert!(a.bitand(&float).is_err());
+    }
+
+    #[test]
+    fn shift() {
+        let ints = |v: &[i32]| A::new_int32(v.iter().cloned().collect());
+        let a = ints(&[1, 1, -8, i32::MIN]);
+        let b = A::new_int64([3, 33, 1, 1].into_iter().collect());
+        // the shift amount is taken modulo the number of bits
+        assert_eq!(a.shl(&b), Ok(ints(&[8, 2, -16, 0])));
+        assert_eq!(a.shr(&b), Ok(ints(&[0, 0, -4, i32::MIN / 2])));
+        assert_eq!(
+            a.wrapping_binary_op(&BinaryOperator::PGBitwiseShiftLeft, &b),
+            a.shl(&b)
+        );
+    }
+
+    #[test]
+    fn bool_xor() {
+        let bools = |v: &[Option<bool>]| A::new_bool(v.iter().cloned().collect());
+        let (t, f) = (Some(true), Some(false));
+        let a = bools(&[t, t, f, f, None, t]);
+        let b = bools(&[t, f, t, f, t, None]);
+        assert_eq!(a.xor(&b), Ok(bools(&[f, t, t, f, None, None])));
+        let null = A::new_null([None, None].into_iter().collect());
+        assert_eq!(bools(&[t, f]).xor(&null), Ok(bools(&[None, None])));
+        assert!(A::new_int32([1].into_iter().collect()).xor(&a).is_err());
+    }
+
+    fn values(array: &ArrayImpl) -> Vec<DataValue> {
+        (0..array.len()).map(|i| array.get(i)).collect()
+    }
+
+    #[test]
+    fn cast_timestamp_to_date() {
+        let ts = |s: &str| Some(Timestamp::from_str(s).unwrap());
+        let a = A::new_timestamp(
+            [ts("2023-05-17 12:34:56"), None, ts("1999-12-31 23:59:59")]
+                .into_iter()
+                .collect(),
+        );
+        let date = |s: &str| DataValue::Date(Date::from_str(s).unwrap());
+        assert_eq!(
+            values(&a.cast(&DataTypeKind::Date).unwrap()),
+            [date("2023-05-17"), DataValue::Null, date("1999-12-31")]
+        );
+    }
+
+    #[test]
+    fn cast_date_to_timestamp() {
+        let a = A::new_date(
+            [Some(Date::from_str("2023-05-17").unwrap()), None]
+                .into_iter()
+                .collect(),
+        );
+        let ts = a.cast(&DataTypeKind::Timestamp).unwrap();
+        assert_eq!(
+            values(&ts),
+            [
+                DataValue::Timestamp(Timestamp::from_str("2023-05-17 00:00:00").unwrap()),
+                DataValue::Null,
+            ]
+        );
+        // round-trip keeps both the date and the null
+        assert_eq!(values(&ts.cast(&DataTypeKind::Date).unwrap()), values(&a));
+    }
+
+    fn strings(values: &[Option<&str>]) -> ArrayImpl {
+        A::new_utf8(values.iter().copied().collect())
+    }
+
+    #[test]
+    fn cast_string_to_number() {
+        let a = strings(&[Some("42"), None, Some(" -7 ")]);
+        assert_eq!(
+            values(&a.cast(&DataTypeKind::Int32).unwrap()),
+            [DataValue::Int32(42), DataValue::Null, DataValue::Int32(-7)]
+        );
+        assert_eq!(
+            values(&a.cast(&DataTypeKind::Float64).unwrap()),
+            [
+                DataValue::Float64(42.0.into()),
+                DataValue::Null,
+                DataValue::Float64((-7.0).into())
+            ]
+        );
+        assert_eq!(
+            values(&a.cast(&DataTypeKind::Decimal(None, None)).unwrap()),
+            [
+                DataValue::Decimal(Decimal::from(42)),
+                DataValue::Null,
+                DataValue::Decimal(Decimal::from(-7))
+            ]
+        );
+    }
+
+    #[test]
+    fn cast_string_to_bool() {
+        let a = strings(&[Some("TRUE"), Some("f"), Some("yes"), Some(" 0 "), None]);
+        assert_eq!(
+            values(&a.cast(&DataTypeKind::Bool).unwrap()),
+            [
+                DataValue::Bool(true),
+                DataValue::Bool(false),
+                DataValue::Bool(true),
+                DataValue::Bool(false),
+                DataValue::Null
+            ]
+        );
+    }
+
+    #[test]
+    fn cast_number_to_string() {
+        let a = A::new_int64([Some(-1), None].into_iter().collect());
+        assert_eq!(
+            values(&a.cast(&DataTypeKind::String).unwrap()),
+            [DataValue::String("-1".into()), DataValue::Null]
+        );
+        let a = A::new_float64([Some(1.5.into())].into_iter().collect());
+        assert_eq!(
+            values(&a.cast(&DataTypeKind::String).unwrap()),
+            [DataValue::String("1.5".into())]
+        );
+    }
+
+    #[test]
+    fn cast_error() {
+        let a = strings(&[Some("1"), Some("abc")]);
+        assert!(matches!(
+            a.cast(&DataTypeKind::Int32),
+            Err(ConvertError::ParseInt(s, _)) if s == "abc"
+        ));
+        assert!(matches!(
+            a.cast(&DataTypeKind::Float64),
+            Err(ConvertError::ParseFloat(..))
+        ));
+        assert!(matches!(
+            a.cast(&DataTypeKind::Decimal(None, None)),
+            Err(ConvertError::ParseDecimal(..))
+        ));
+        assert!(matches!(
+            a.cast(&DataTypeKind::Bool),
+            Err(ConvertError::ParseBool(..))
+        ));
+        assert!(matches!(
+            strings(&[Some("2023-13-45")]).cast(&DataTypeKind::Date),
+            Err(ConvertError::ParseDate(..))
+        ));
+        assert!(matches!(
+            strings(&[Some("99999")]).cast(&DataTypeKind::Int16),
+            Err(ConvertError::ParseInt(..))
+        ));
+
+        let a = A::new_float64([Some(f64::NAN.into())].into_iter().collect());
+        assert!(matches!(
+            a.cast(&DataTypeKind::Decimal(None, None)),
+            Err(ConvertError::ToDecimalError(_))
+        ));
+        let a = A::new_blob([Some(Blob::from(&b"ab"[..]))].into_iter().collect());
+        assert!(matches!(
+            a.cast(&DataTypeKind::Int32),
+            Err(ConvertError::NoCast("BLOB", _))
+        ));
+    }
+}
@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
 use serde::{Deserialize, Serialize};
@@ -23,7 +23,10 @@ pub enum FileFormat {
         escape: Option<char>,
         /// Whether or not the file has a header line.
         header: bool,
+        /// The string that represents a NULL value.
+        null: String,
     },
+    Parquet,
 }
 
 impl std::fmt::Display for ExtSource {
@@ -64,13 +67,12 @@ impl Binder {
 
         let cols = self.bind_table_columns(&table_name, &columns)?;
 
-        let ext_source = self.egraph.add(Node::ExtSource(ExtSource {
-            path: match target {
-                CopyTarget::File { filename } => filename.into(),
-                t => todo!("unsupported copy target: {:?}", t),
-            },
-            format: FileFormat::from_options(options),
-        }));
+        let path: PathBuf = match target {
+            CopyTarget::File { filename } => filename.into(),
+            t => todo!("unsupported copy target: {:?}", t),
+        };
+        let format = FileFormat::from_options(&path, options)?;
+        let ext_source = self.egraph.add(Node::ExtSource(ExtSource { path, format }));
 
         let copy = if to {
             // COPY <source_table> TO <dest_file>
@@ -80,7 +82,19 @@ impl Binder {
                 let true_ = self.egraph.add(Node::true_());
                 self.egraph.add(Node::Scan([table, cols, true_]))
             };
-            self.egraph.add(Node::CopyTo([ext_source, scan]))
+            // header names are the bound column names
+            let mut names = vec![];
+            for id in self.node(cols).try_as_list()? {
+                let column_id = self.node(*id).try_as_column()?;
+                let column = (self.catalog.get_column(&column_id))
+                    .ok_or_else(|| BindError::InvalidColumn(column_id.to_string()))?;
+                names.push(DataValue::String(column.name().into()));
+            }
+            let names = (names.into_iter())
+                .map(|name| self.egraph.add(Node::Constant(name)))
+                .collect();
+            let names = self.egraph.add(Node::List(names));
+            self.egraph.add(Node::CopyTo([ext_source, names, scan]))
         } else {
             // COPY <dest_table> FROM <source_file>
             if is_internal {
@@ -98,28 +112,45 @@ impl Binder {
 
 impl FileFormat {
     /// Create from copy options.
-    pub fn from_options(options: &[CopyOption]) -> Self {
-        let mut delimiter = ',';
+    ///
+    /// Both `csv` and `tsv` formats are supported. They only differ in the default delimiter.
+    /// Files with `.parquet` extension are in `parquet` format unless specified otherwise.
+    pub fn from_options(path: &Path, options: &[CopyOption]) -> Result<Self> {
+        let mut parquet = path.extension().map_or(false, |ext| ext == "parquet");
+        let mut default_delimiter = ',';
+        let mut delimiter = None;
         let mut quote = '"';
         let mut escape = None;
         let mut header = false;
+        let mut null = String::new();
         for opt in options {
             match opt {
                 CopyOption::Format(fmt) => {
-                    assert_eq!(fmt.value.to_lowercase(), "csv", "only support CSV format")
+                    parquet = false;
+                    match fmt.value.to_lowercase().as_str() {
+                        "csv" => default_delimiter = ',',
+                        "tsv" => default_delimiter = '\t',
+                        "parquet" => parquet = true,
+                        _ => return Err(BindError::Todo(format!("copy format {fmt}"))),
+                    }
                 }
-                CopyOption::Delimiter(c) => delimiter = *c,
+                CopyOption::Delimiter(c) => delimiter = Some(*c),
                 CopyOption::Header(b) => header = *b,
                 CopyOption::Quote(c) => quote = *c,
                 CopyOption::Escape(c) => escape = Some(*c),
-                o => panic!("unsupported copy option: {:?}", o),
+                CopyOption::Null(s) => null = s.clone(),
+                o => return Err(BindError::Todo(format!("copy option {o}"))),
             }
         }
-        FileFormat::Csv {
-            delimiter,
+        if parquet {
+            return Ok(FileFormat::Parquet);
+        }
+        Ok(FileFormat::Csv {
+            delimiter: delimiter.unwrap_or(default_delimiter),
             quote,
             escape,
             header,
-        }
+            null,
+        })
     }
 }
@@ -0,0 +1,111 @@
+// Copyright 2023 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use std::fmt;
+use std::str::FromStr;
+
+use pretty_xmlish::helper::delegate_fmt;
+use pretty_xmlish::Pretty;
+use serde::{Deserialize, Serialize};
+
+use super::*;
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Serialize, Deserialize)]
+pub struct CreateSchema {
+    pub schema_name: String,
+    /// Do nothing if the schema already exists.
+    pub if_not_exists: bool,
+}
+
+impl fmt::Display for CreateSchema {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let explainer = Pretty::childless_record("CreateSchema", self.pretty_schema());
+        delegate_fmt(&explainer, f, String::with_capacity(1000))
+    }
+}
+
+impl CreateSchema {
+    pub fn pretty_schema<'a>(&self) -> Vec<(&'a str, Pretty<'a>)> {
+        vec![
+            ("name", Pretty::display(&self.schema_name)),
+            ("if_not_exists", Pretty::display(&self.if_not_exists)),
+        ]
+    }
+}
+
+impl FromStr for CreateSchema {
+    type Err = ();
+
+    fn from_str(_s: &str) -> std::result::Result<Self, Self::Err> {
+        Err(())
+    }
+}
+
+impl Binder {
+    pub(super) fn bind_create_schema(
+        &mut self,
+        schema_name: SchemaName,
+        if_not_exists: bool,
+    ) -> Result {
+        let SchemaName::Simple(name) = schema_name else {
+            return Err(BindError::NotSupportedTSQL);
+        };
+        let name = lower_case_name(&name);
+        let [schema_name] = name.0.as_slice() else {
+            return Err(BindError::InvalidSchema(name.to_string()));
+        };
+        let schema_name = schema_name.value.clone();
+        if self.catalog.get_schema_id_by_name(&schema_name).is_some() && !if_not_exists {
+            return Err(BindError::DuplicatedSchema(schema_name));
+        }
+        Ok(self.egraph.add(Node::CreateSchema(CreateSchema {
+            schema_name,
+            if_not_exists,
+        })))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::catalog::RootCatalog;
+    use crate::parser::parse;
+
+    #[test]
+    fn bind_create_schema() {
+        let catalog = Arc::new(RootCatalog::new());
+        catalog.add_schema("s".into()).unwrap();
+        let bind = |sql: &str| -> Result<CreateSchema> {
+            let stmt = parse(sql).unwrap().remove(0);
+            let plan = Binder::new(catalog.clone()).bind(stmt)?;
+            let [Node::CreateSchema(create)] = plan.as_ref() else {
+                panic!("not a create schema: {plan}");
+            };
+            Ok(create.clone())
+        };
+
+        assert_eq!(
+            bind("create schema S2"),
+            Ok(CreateSchema {
+                schema_name: "s2".into(),
+                if_not_exists: false,
+            })
+        );
+        assert_eq!(
+            bind("create schema s"),
+            Err(BindError::DuplicatedSchema("s".into()))
+        );
+        assert_eq!(
+            bind("create schema if not exists s"),
+            Ok(CreateSchema {
+                schema_name: "s".into(),
+                if_not_exists: true,
+            })
+        );
+        assert_eq!(
+            bind("create schema postgres"),
+            Err(BindError::DuplicatedSchema("postgres".into()))
+        );
+    }
+}
@@ -7,7 +7,7 @@ use pretty_xmlish::Pretty;
 use serde::{Deserialize, Serialize};
 
 use super::*;
-use crate::catalog::{ColumnCatalog, ColumnDesc, ColumnId, SchemaId};
+use crate::catalog::{ColumnCatalog, ColumnDesc, ColumnId, ForeignKey, SchemaId};
 use crate::types::DataType;
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Serialize, Deserialize)]
@@ -16,6 +16,8 @@ pub struct CreateTable {
     pub table_name: String,
     pub columns: Vec<ColumnCatalog>,
     pub ordered_pk_ids: Vec<ColumnId>,
+    pub unique_keys: Vec<Vec<ColumnId>>,
+    pub foreign_keys: Vec<ForeignKey>,
 }
 
 impl fmt::Display for CreateTable {
@@ -29,11 +31,30 @@ impl CreateTable {
     pub fn pretty_table<'a>(&self) -> Vec<(&'a str, Pretty<'a>)> {
         let cols = Pretty::Array(self.columns.iter().map(|c| c.desc().pretty()).collect());
         let ids = Pretty::Array(self.ordered_pk_ids.iter().map(Pretty::display).collect());
+        let unique_keys = Pretty::Array(
+            (self.unique_keys.iter())
+                .map(|key| Pretty::Array(key.iter().map(Pretty::display).collect()))
+                .collect(),
+        );
+        let foreign_keys = Pretty::Array(
+            (self.foreign_keys.iter())
+                .map(|fk| {
+                    Pretty::display(&format!(
+                        "({}) references {}({})",
+                        fk.columns.iter().join(", "),
+                        fk.ref_table,
+                        fk.ref_columns.iter().join(", "),
+                    ))
+                })
+                .collect(),
+        );
         vec![
             ("schema_id", Pretty::display(&self.schema_id)),
             ("name", Pretty::display(&self.table_name)),
             ("columns", cols),
             ("ordered_ids", ids),
+            ("unique_keys", unique_keys),
+            ("foreign_keys", foreign_keys),
         ]
     }
 }
@@ -66,7 +87,7 @@ impl Binder {
         // check duplicated column names
         let mut set = HashSet::new();
         for col in columns.iter() {
-            if !set.insert(col.name.value.to_lowercase()) {
+            if !set.insert(normalize_ident(&col.name)) {
                 return Err(BindError::DuplicatedColumn(col.name.value.clone()));
             }
         }
@@ -98,12 +119,15 @@ impl Binder {
                 .map(|name| {
                     columns
                         .iter()
-                        .position(|c| c.name.value.eq_ignore_ascii_case(name))
+                        .position(|c| normalize_ident(&c.name) == *name)
                         .unwrap() as ColumnId
                 })
                 .collect();
         }
 
+        let unique_keys = Binder::unique_keys(columns, constraints)?;
+        let foreign_keys = self.foreign_keys(columns, constraints)?;
+
         let mut columns: Vec<ColumnCatalog> = columns
             .iter()
             .enumerate()
@@ -119,15 +143,212 @@ impl Binder {
             columns[index as usize].set_nullable(false);
         }
 
+        // generated columns can only refer to the other columns
+        for column in &columns {
+            if column.generated().is_none() {
+                continue;
+            }
+            // generated columns are computed on scans and not stored, so they cannot be keys
+            let in_key = |key: &[ColumnId]| key.contains(&column.id());
+            if column.is_primary()
+                || unique_keys.iter().any(|key| in_key(key))
+                || foreign_keys.iter().any(|fk| in_key(&fk.columns))
+            {
+                return Err(BindError::GeneratedColumn(column.name().into()));
+            }
+            let values = (columns.iter())
+                .filter(|c| c.generated().is_none())
+                .map(|c| {
+                    let ty = self.egraph.add(Node::Type(c.datatype().kind()));
+                    let null = self.egraph.add(Node::null());
+                    let value = self.egraph.add(Node::Cast([ty, null]));
+                    (c.name().to_string(), value)
+                })
+                .collect_vec();
+            self.bind_generated_column(column, &values)?;
+        }
+
         let create = self.egraph.add(Node::CreateTable(CreateTable {
             schema_id: schema.id(),
             table_name: table_name.into(),
             columns,
             ordered_pk_ids,
+            unique_keys,
+            foreign_keys,
         }));
         Ok(create)
     }
 
+    /// Returns the column ids of non-primary UNIQUE constraints, from both column options and
+    /// "unique(c1, c2..)" syntax.
+    fn unique_keys(
+        columns: &[ColumnDef],
+        constraints: &[TableConstraint],
+    ) -> Result<Vec<Vec<ColumnId>>> {
+        let column_id = |name: &Ident| {
+            (columns.iter())
+                .position(|c| normalize_ident(&c.name) == normalize_ident(name))
+                .map(|idx| idx as ColumnId)
+                .ok_or_else(|| BindError::InvalidColumn(normalize_ident(name)))
+        };
+        let mut keys = vec![];
+        for (index, col_def) in columns.iter().enumerate() {
+            for option_def in &col_def.options {
+                if let ColumnOption::Unique { is_primary: false } = option_def.option {
+                    keys.push(vec![index as ColumnId]);
+                }
+            }
+        }
+        for constraint in constraints {
+            if let TableConstraint::Unique {
+                is_primary: false,
+                columns,
+                ..
+            } = constraint
+            {
+                keys.push(columns.iter().map(column_id).collect::<Result<_>>()?);
+            }
+        }
+        let mut unique_keys = vec![];
+        for key in keys {
+            if !unique_keys.contains(&key) {
+                unique_keys.push(key);
+            }
+        }
+        Ok(unique_keys)
+    }
+
+    /// Returns the FOREIGN KEY constraints, from both column options and
+    /// "foreign key(c1, c2..) references t(p1, p2..)" syntax.
+    fn foreign_keys(
+        &self,
+        columns: &[ColumnDef],
+        constraints: &[TableConstraint],
+    ) -> Result<Vec<ForeignKey>> {
+        let column_id = |name: &Ident| {
+            (columns.iter())
+                .position(|c| normalize_ident(&c.name) == normalize_ident(name))
+                .map(|idx| idx as ColumnId)
+                .ok_or_else(|| BindError::InvalidColumn(normalize_ident(name)))
+        };
+        let mut foreign_keys = vec![];
+        for (index, col_def) in columns.iter().enumerate() {
+            for option_def in &col_def.options {
+                if let ColumnOption::ForeignKey {
+                    foreign_table,
+                    referred_columns,
+                    on_delete,
+                    on_update,
+                } = &option_def.option
+                {
+                    foreign_keys.push(self.bind_foreign_key(
+                        columns,
+                        vec![index as ColumnId],
+                        foreign_table,
+                        referred_columns,
+                        [on_delete, on_update],
+                    )?);
+                }
+            }
+        }
+        for constraint in constraints {
+            if let TableConstraint::ForeignKey {
+                columns: fk_columns,
+                foreign_table,
+                referred_columns,
+                on_delete,
+                on_update,
+                ..
+            } = constraint
+            {
+                foreign_keys.push(self.bind_foreign_key(
+                    columns,
+                    fk_columns.iter().map(column_id).collect::<Result<_>>()?,
+                    foreign_table,
+                    referred_columns,
+                    [on_delete, on_update],
+                )?);
+            }
+        }
+        Ok(foreign_keys)
+    }
+
+    /// Binds a FOREIGN KEY constraint on `column_ids` of the table being created.
+    ///
+    /// The referenced columns must be the primary key or a unique key of the referenced table.
+    /// If they are omitted, the primary key is used.
+    fn bind_foreign_key(
+        &self,
+        columns: &[ColumnDef],
+        column_ids: Vec<ColumnId>,
+        foreign_table: &ObjectName,
+        referred_columns: &[Ident],
+        actions: [&Option<ReferentialAction>; 2],
+    ) -> Result<ForeignKey> {
+        // only restrict semantics are supported
+        for action in actions.into_iter().flatten() {
+            if !matches!(
+                action,
+                ReferentialAction::Restrict | ReferentialAction::NoAction
+            ) {
+                return Err(BindError::Todo(format!("foreign key action {action}")));
+            }
+        }
+        let name = lower_case_name(foreign_table);
+        let (schema_name, table_name) = split_name(&name)?;
+        let ref_table = self
+            .catalog
+            .get_table_id_by_name(schema_name, table_name)
+            .ok_or_else(|| BindError::InvalidTable(table_name.into()))?;
+        let table = self.catalog.get_table(&ref_table).unwrap();
+
+        let ref_columns = if referred_columns.is_empty() {
+            table.primary_keys()
+        } else {
+            (referred_columns.iter())
+                .map(|ident| {
+                    let name = normalize_ident(ident);
+                    table
+                        .get_column_id_by_name(&name)
+                        .ok_or(BindError::InvalidColumn(name))
+                })
+                .collect::<Result<_>>()?
+        };
+        let is_key = |key: &[ColumnId]| {
+            !key.is_empty()
+                && key.len() == ref_columns.len()
+                && key.iter().all(|id| ref_columns.contains(id))
+        };
+        if !is_key(&table.primary_keys()) && !table.unique_keys().iter().any(|key| is_key(key)) {
+            return Err(BindError::InvalidForeignKey(format!(
+                "there is no primary key or unique constraint matching the referenced columns of table {table_name}"
+            )));
+        }
+        if column_ids.len() != ref_columns.len() {
+            return Err(BindError::InvalidForeignKey(
+                "number of referencing and referenced columns do not match".into(),
+            ));
+        }
+        for (&id, &ref_id) in column_ids.iter().zip(&ref_columns) {
+            let column = ColumnCatalog::from(&columns[id as usize]);
+            let ref_column = table.get_column_by_id(ref_id).unwrap();
+            if column.datatype().kind() != ref_column.datatype().kind() {
+                return Err(BindError::InvalidForeignKey(format!(
+                    "column {} of type {} cannot reference column {} of type {}",
+                    column.name(),
+                    column.datatype(),
+                    ref_column.name(),
+                    ref_column.datatype(),
+                )));
+            }
+        }
+        Ok(ForeignKey {
+            columns: column_ids,
+            ref_table,
+            ref_columns,
+        })
+    }
+
     /// get primary keys' id in declared order。
     /// we use index in columns vector as column id
     fn ordered_pks_from_columns(columns: &[ColumnDef]) -> Vec<ColumnId> {
@@ -159,7 +380,7 @@ impl Binder {
                     columns,
                     ..
                 } if *is_primary => columns.iter().for_each(|ident| {
-                    pks_name_from_constraints.push(ident.value.to_lowercase());
+                    pks_name_from_constraints.push(normalize_ident(ident));
                 }),
                 _ => continue,
             }
@@ -173,23 +394,31 @@ impl From<&ColumnDef> for ColumnCatalog {
         let mut is_nullable = true;
         let mut is_primary_ = false;
         let mut is_required = false;
+        let mut generated = None;
         for opt in &cdef.options {
             match &opt.option {
                 ColumnOption::Null => is_nullable = true,
                 ColumnOption::NotNull => is_nullable = false,
                 ColumnOption::Unique { is_primary } => is_primary_ = *is_primary,
                 ColumnOption::Comment(comment) => is_required = comment.eq(&String::from("required")),
+                // bound separately as a table constraint
+                ColumnOption::ForeignKey { .. } => {}
+                ColumnOption::Generated {
+                    generation_expr: Some(expr),
+                    ..
+                } => generated = Some(expr.to_string()),
                 _ => todo!("column options"),
             }
         }
-        ColumnCatalog::new(
-            0,
-            ColumnDesc::new(
-                DataType::new((&cdef.data_type).into(), is_nullable),
-                cdef.name.value.to_lowercase(),
-                is_primary_,
-                is_required
-            ),
-        )
+        let mut desc = ColumnDesc::new(
+            DataType::new((&cdef.data_type).into(), is_nullable),
+            normalize_ident(&cdef.name),
+            is_primary_,
+            is_required,
+        );
+        if let Some(expr) = generated {
+            desc.set_generated(expr);
+        }
+        ColumnCatalog::new(0, desc)
     }
 }
@@ -18,9 +18,19 @@ impl Binder {
         }
         let cols = self.bind_table_name(name, None, true)?;
         let true_ = self.egraph.add(Node::true_());
-        let scan = self.egraph.add(Node::Scan([table_id, cols, true_]));
+        let scan = self.plan_scan(table_id, cols, true_)?;
         let cond = self.bind_where(selection)?;
         let filter = self.egraph.add(Node::Filter([cond, scan]));
         Ok(self.egraph.add(Node::Delete([table_id, filter])))
     }
+
+    pub(super) fn bind_truncate(&mut self, name: ObjectName) -> Result {
+        let (table_id, is_internal) = self.bind_table_id(&name)?;
+        if is_internal {
+            return Err(BindError::NotSupportedOnInternalTable);
+        }
+        let table_ref_id = self.node(table_id).as_table();
+        self.check_not_referenced(&table_ref_id, &[table_ref_id])?;
+        Ok(self.egraph.add(Node::Truncate(table_id)))
+    }
 }
@@ -7,9 +7,10 @@ use serde::{Deserialize, Serialize};
 
 use super::*;
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Serialize, Deserialize)]
 pub struct BoundDrop {
-    pub object: Object,
+    /// Objects to drop. Objects that don't exist are omitted if `if_exists` is set.
+    pub objects: Vec<Object>,
     pub if_exists: bool,
     pub cascade: bool,
 }
@@ -29,7 +30,10 @@ impl std::fmt::Display for BoundDrop {
 impl BoundDrop {
     pub fn pretty_table<'a>(&self) -> Vec<(&'a str, Pretty<'a>)> {
         vec![
-            ("object", Pretty::display(&self.object)),
+            (
+                "objects",
+                Pretty::Array(self.objects.iter().map(Pretty::display).collect()),
+            ),
             ("if_exists", Pretty::display(&self.if_exists)),
             ("cascade", Pretty::display(&self.cascade)),
         ]
@@ -62,15 +66,23 @@ impl Binder {
     ) -> Result {
         match object_type {
             ObjectType::Table => {
-                let name = lower_case_name(&names[0]);
-                let (schema_name, table_name) = split_name(&name)?;
-                let table_ref_id = self
-                    .catalog
-                    .get_table_id_by_name(schema_name, table_name)
-                    .ok_or_else(|| BindError::InvalidTable(table_name.into()))?;
-
+                let mut objects = vec![];
+                for name in names {
+                    let name = lower_case_name(&name);
+                    let (schema_name, table_name) = split_name(&name)?;
+                    match self.catalog.get_table_id_by_name(schema_name, table_name) {
+                        Some(table_ref_id) => objects.push(Object::Table(table_ref_id)),
+                        None if if_exists => {}
+                        None => return Err(BindError::InvalidTable(table_name.into())),
+                    }
+                }
+                // tables dropped together may reference each other
+                let dropped = (objects.iter()).map(|Object::Table(id)| *id).collect_vec();
+                for table_id in &dropped {
+                    self.check_not_referenced(table_id, &dropped)?;
+                }
                 Ok(self.egraph.add(Node::Drop(BoundDrop {
-                    object: Object::Table(table_ref_id),
+                    objects,
                     if_exists,
                     cascade,
                 })))
@@ -78,12 +90,35 @@ impl Binder {
             _ => todo!(),
         }
     }
+
+    /// Returns an error if the table is referenced by a FOREIGN KEY constraint of any table
+    /// other than the `excluded` ones.
+    ///
+    /// Only restrict semantics are supported, so a referenced table can not be dropped or
+    /// truncated.
+    pub(super) fn check_not_referenced(
+        &self,
+        table_id: &TableRefId,
+        excluded: &[TableRefId],
+    ) -> Result<()> {
+        for (referencing, _) in self.catalog.get_referencing_foreign_keys(table_id) {
+            if !excluded.contains(&referencing) {
+                let name = |id| self.catalog.get_table(id).unwrap().name();
+                return Err(BindError::ReferencedTable(
+                    name(table_id),
+                    name(&referencing),
+                ));
+            }
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
 
+    use super::*;
     use crate::catalog::RootCatalog;
     use crate::parser::parse;
 
@@ -91,10 +126,45 @@ mod tests {
     fn bind_drop_table() {
         let catalog = Arc::new(RootCatalog::new());
         catalog
-            .add_table(0, "mytable".into(), vec![], false, vec![])
+            .add_table(0, "mytable".into(), vec![], false, vec![], vec![], vec![])
             .unwrap();
 
         let stmts = parse("drop table mytable").unwrap();
         println!("{:?}", stmts)
     }
+
+    #[test]
+    fn bind_drop_if_exists() {
+        let catalog = Arc::new(RootCatalog::new());
+        let t1 = catalog
+            .add_table(0, "t1".into(), vec![], false, vec![], vec![], vec![])
+            .unwrap();
+        let t2 = catalog
+            .add_table(0, "t2".into(), vec![], false, vec![], vec![], vec![])
+            .unwrap();
+        let table = |table_id| Object::Table(TableRefId::new(0, table_id));
+        let bind = |sql: &str| -> Result<Vec<Object>> {
+            let stmt = parse(sql).unwrap().remove(0);
+            let plan = Binder::new(catalog.clone()).bind(stmt)?;
+            let [Node::Drop(drop)] = plan.as_ref() else {
+                panic!("not a drop: {plan}");
+            };
+            Ok(drop.objects.clone())
+        };
+
+        assert_eq!(bind("drop table t1, t2"), Ok(vec![table(t1), table(t2)]));
+        assert_eq!(
+            bind("drop table missing"),
+            Err(BindError::InvalidTable("missing".into()))
+        );
+        assert_eq!(
+            bind("drop table t1, missing"),
+            Err(BindError::InvalidTable("missing".into()))
+        );
+        assert_eq!(bind("drop table if exists missing"), Ok(vec![]));
+        assert_eq!(
+            bind("drop table if exists missing, t2"),
+            Ok(vec![table(t2)])
+        );
+    }
 }
@@ -1,31 +1,39 @@
 // Copyright 2023 RisingLight Project Authors. Licensed under Apache-2.0.
 
+use std::ops::RangeInclusive;
+
 use rust_decimal::Decimal;
 
 use super::*;
 use crate::parser::{
     self, BinaryOperator, DataType, DateTimeField, Expr, Function, FunctionArg, FunctionArgExpr,
-    UnaryOperator, Value,
+    ObjectName, UnaryOperator, Value,
 };
-use crate::types::{DataTypeKind, DataValue, Interval};
+use crate::types::{DataTypeKind, DataValue, Interval, ParamIndex};
 
 impl Binder {
     /// Bind an expression.
     pub fn bind_expr(&mut self, expr: Expr) -> Result {
         let id = match expr {
-            Expr::Value(v) => Ok(self.egraph.add(Node::Constant(v.into()))),
+            Expr::Value(Value::Placeholder(name)) => self.bind_placeholder(&name),
+            // double-quoted strings are identifiers in standard SQL, not string literals as in
+            // MySQL. use single quotes for strings.
+            Expr::Value(Value::DoubleQuotedString(name)) => {
+                self.bind_ident([Ident::with_quote('"', name)])
+            }
+            Expr::Value(v) => Ok(self.egraph.add(Node::Constant(v.try_into()?))),
             Expr::Identifier(ident) => self.bind_ident([ident]),
             Expr::CompoundIdentifier(idents) => self.bind_ident(idents),
             Expr::BinaryOp { left, op, right } => self.bind_binary_op(*left, op, *right),
             Expr::UnaryOp { op, expr } => self.bind_unary_op(op, *expr),
             Expr::Nested(expr) => self.bind_expr(*expr),
-            Expr::Cast { expr, data_type } => self.bind_cast(*expr, data_type),
+            Expr::Cast { expr, data_type } => self.bind_cast(*expr, data_type, false),
+            Expr::TryCast { expr, data_type } => self.bind_cast(*expr, data_type, true),
             Expr::Function(func) => self.bind_function(func),
-            Expr::IsNull(expr) => self.bind_is_null(*expr),
-            Expr::IsNotNull(expr) => {
-                let isnull = self.bind_is_null(*expr)?;
-                Ok(self.egraph.add(Node::Not(isnull)))
-            }
+            Expr::IsNull(expr) => self.bind_is_null(*expr, false),
+            Expr::IsNotNull(expr) => self.bind_is_null(*expr, true),
+            Expr::IsDistinctFrom(a, b) => self.bind_is_distinct(*a, *b, false),
+            Expr::IsNotDistinctFrom(a, b) => self.bind_is_distinct(*a, *b, true),
             Expr::TypedString { data_type, value } => self.bind_typed_string(data_type, value),
             Expr::Like {
                 negated,
@@ -46,6 +54,17 @@ impl Binder {
                 substring_from,
                 substring_for,
             } => self.bind_substring(*expr, substring_from, substring_for),
+            Expr::Exists { subquery, negated } => self.bind_exists(*subquery, negated),
+            Expr::InSubquery {
+                expr,
+                subquery,
+                negated,
+            } => self.bind_in_subquery(*expr, *subquery, negated),
+            Expr::InList {
+                expr,
+                list,
+                negated,
+            } => self.bind_in_list(*expr, list, negated),
             _ => todo!("bind expression: {:?}", expr),
         }?;
         self.check_type(id)?;
@@ -64,27 +83,70 @@ impl Binder {
     fn bind_ident(&mut self, idents: impl IntoIterator<Item = Ident>) -> Result {
         let idents = idents
             .into_iter()
-            .map(|ident| Ident::new(ident.value.to_lowercase()))
+            .map(|ident| Ident::new(normalize_ident(&ident)))
             .collect_vec();
-        let (_schema_name, table_name, column_name) = match idents.as_slice() {
+        let (schema_name, table_name, column_name) = match idents.as_slice() {
             [column] => (None, None, &column.value),
             [table, column] => (None, Some(&table.value), &column.value),
             [schema, table, column] => (Some(&schema.value), Some(&table.value), &column.value),
             _ => return Err(BindError::InvalidTableName(idents)),
         };
-        let map = self
-            .current_ctx()
-            .aliases
-            .get(column_name)
-            .ok_or_else(|| BindError::InvalidColumn(column_name.into()))?;
-        if let Some(table_name) = table_name {
-            map.get(table_name)
-                .cloned()
-                .ok_or_else(|| BindError::InvalidTable(table_name.clone()))
-        } else if map.len() == 1 {
-            Ok(*map.values().next().unwrap())
-        } else {
-            Err(BindError::AmbiguousColumn(column_name.into()))
+        let schema_id = match schema_name {
+            Some(name) => Some(
+                (self.catalog.get_schema_id_by_name(name))
+                    .ok_or_else(|| BindError::InvalidSchema(name.clone()))?,
+            ),
+            None => None,
+        };
+        // find the column from the current query,
+        // then from the outer query for correlated subquery.
+        let mut column_found = false;
+        let mut resolved = None;
+        for (depth, ctx) in self.contexts.iter().rev().enumerate() {
+            let Some(map) = ctx.aliases.get(column_name) else {
+                continue;
+            };
+            column_found = true;
+            let id = if let Some(table_name) = table_name {
+                let Some(id) = map.get(table_name) else {
+                    continue;
+                };
+                // a schema-qualified name must refer to a table in that schema
+                if let Some(schema_id) = schema_id {
+                    match self.node(*id) {
+                        Node::Column(column) if column.schema_id == schema_id => {}
+                        _ => continue,
+                    }
+                }
+                *id
+            } else if map.len() == 1 {
+                *map.values().next().unwrap()
+            } else {
+                // a column of the input shadows an alias in the select list
+                let mut columns = map.iter().filter(|(table, _)| !table.is_empty());
+                match (columns.next(), columns.next()) {
+                    (Some((_, id)), None) => *id,
+                    _ => return Err(BindError::AmbiguousColumn(column_name.into())),
+                }
+            };
+            resolved = Some((depth, id));
+            break;
+        }
+        match resolved {
+            Some((0, id)) => Ok(id),
+            Some((1, id)) => {
+                self.current_ctx_mut().outer_refs.insert(id);
+                Ok(id)
+            }
+            Some(_) => Err(BindError::UnsupportedCorrelation(format!(
+                "{column_name} refers to a query more than one level up"
+            ))),
+            None => match table_name {
+                Some(table_name) if column_found => {
+                    Err(BindError::InvalidTable(table_name.clone()))
+                }
+                _ => Err(BindError::InvalidColumn(column_name.into())),
+            },
         }
     }
 
@@ -93,6 +155,14 @@ impl Binder {
 
         let l = self.bind_expr(left)?;
         let r = self.bind_expr(right)?;
+        self.infer_param_type(l, r);
+        self.infer_param_type(r, l);
+        let (l, r) = match op {
+            Plus | Minus | Multiply | Divide | Modulo | Gt | Lt | GtEq | LtEq | Eq | NotEq => {
+                self.coerce_numeric(l, r)
+            }
+            _ => (l, r),
+        };
         let node = match op {
             Plus => Node::Add([l, r]),
             Minus => Node::Sub([l, r]),
@@ -109,14 +179,60 @@ impl Binder {
             And => Node::And([l, r]),
             Or => Node::Or([l, r]),
             Xor => Node::Xor([l, r]),
+            BitwiseAnd => Node::BitAnd([l, r]),
+            BitwiseOr => Node::BitOr([l, r]),
+            BitwiseXor | PGBitwiseXor => Node::BitXor([l, r]),
+            PGBitwiseShiftLeft => Node::Shl([l, r]),
+            PGBitwiseShiftRight => Node::Shr([l, r]),
             _ => todo!("bind binary op: {:?}", op),
         };
         Ok(self.egraph.add(node))
     }
 
+    /// Binds a parameter `?` or `$n` in prepared statements.
+    fn bind_placeholder(&mut self, name: &str) -> Result {
+        let index = match name {
+            "?" => self.params.len() + 1,
+            _ => (name.strip_prefix('$'))
+                .and_then(|n| n.parse().ok())
+                .filter(|n| *n > 0)
+                .ok_or_else(|| {
+                    BindError::InvalidExpression(format!("invalid parameter: {name}"))
+                })?,
+        };
+        if self.params.len() < index {
+            self.params.resize(index, None);
+        }
+        Ok(self.egraph.add(Node::Placeholder(ParamIndex(index as u32))))
+    }
+
+    /// Infers the type of parameter `param` from the other operand.
+    fn infer_param_type(&mut self, param: Id, other: Id) {
+        let Node::Placeholder(index) = self.node(param) else {
+            return;
+        };
+        let index = index.0 as usize - 1;
+        if let Ok(ty) = self.check_type(other) {
+            if !ty.kind.is_null() {
+                self.params[index] = Some(ty.kind);
+            }
+        }
+    }
+
     fn bind_unary_op(&mut self, op: UnaryOperator, expr: Expr) -> Result {
         use UnaryOperator::*;
-        let expr = self.bind_expr(expr)?;
+        let expr = match (&op, unnest(expr)) {
+            // NOT (x IN (subquery)) => x NOT IN (subquery)
+            (
+                Not,
+                Expr::InSubquery {
+                    expr,
+                    subquery,
+                    negated,
+                },
+            ) => return self.bind_in_subquery(*expr, *subquery, !negated),
+            (_, expr) => self.bind_expr(expr)?,
+        };
         Ok(match op {
             Plus => expr,
             Minus => self.egraph.add(Node::Neg(expr)),
@@ -125,7 +241,44 @@ impl Binder {
         })
     }
 
-    fn bind_cast(&mut self, expr: Expr, mut ty: DataType) -> Result {
+    /// Casts the numeric operands of a binary operator to a common type.
+    ///
+    /// Integers are promoted along `SMALLINT -> INT -> BIGINT`, and are casted to `DOUBLE` or
+    /// `DECIMAL` when mixed with them. A `DECIMAL` mixed with a `DOUBLE` is casted to `DOUBLE`.
+    /// Other operands are returned as they are.
+    fn coerce_numeric(&mut self, l: Id, r: Id) -> (Id, Id) {
+        let (Ok(lty), Ok(rty)) = (self.check_type(l), self.check_type(r)) else {
+            return (l, r);
+        };
+        let (lty, rty) = (lty.kind, rty.kind);
+        if !lty.is_number() || !rty.is_number() || lty == rty {
+            return (l, r);
+        }
+        let mut cast = |id, ty: DataTypeKind| {
+            let ty = match ty {
+                // the precision of decimal depends on the value being casted
+                DataTypeKind::Decimal(_, _) => DataTypeKind::Decimal(None, None),
+                ty => ty,
+            };
+            let ty = self.egraph.add(Node::Type(ty));
+            self.egraph.add(Node::Cast([ty, id]))
+        };
+        match (lty, rty) {
+            (DataTypeKind::Decimal(_, _), DataTypeKind::Decimal(_, _)) => (l, r),
+            // decimals are casted to floats, since NaN and infinity are not valid decimals
+            (DataTypeKind::Decimal(_, _), DataTypeKind::Float64) => {
+                (cast(l, DataTypeKind::Float64), r)
+            }
+            (DataTypeKind::Float64, DataTypeKind::Decimal(_, _)) => {
+                (l, cast(r, DataTypeKind::Float64))
+            }
+            (lty, rty) if lty < rty => (cast(l, rty), r),
+            (lty, _) => (l, cast(r, lty)),
+        }
+    }
+
+    /// Binds `CAST` or `TRY_CAST` if `try_cast` is true.
+    fn bind_cast(&mut self, expr: Expr, mut ty: DataType, try_cast: bool) -> Result {
         let expr = self.bind_expr(expr)?;
         // workaround for 'BLOB'
         if let DataType::Custom(name, _modifiers) = &ty {
@@ -134,12 +287,48 @@ impl Binder {
             }
         }
         let ty = self.egraph.add(Node::Type((&ty).into()));
-        Ok(self.egraph.add(Node::Cast([ty, expr])))
+        Ok(self.egraph.add(match try_cast {
+            true => Node::TryCast([ty, expr]),
+            false => Node::Cast([ty, expr]),
+        }))
     }
 
-    fn bind_is_null(&mut self, expr: Expr) -> Result {
+    fn bind_is_null(&mut self, expr: Expr, negated: bool) -> Result {
         let expr = self.bind_expr(expr)?;
-        Ok(self.egraph.add(Node::IsNull(expr)))
+        Ok(self.egraph.add(match negated {
+            false => Node::IsNull(expr),
+            true => Node::IsNotNull(expr),
+        }))
+    }
+
+    /// Binds `t.*` in `COUNT(t.*)` as an expression that is NULL if all columns of `t` are NULL,
+    /// so that rows with no match from `t` in an outer join are not counted.
+    fn bind_row_exists(&mut self, name: ObjectName) -> Result {
+        let table_name = normalize_ident(name.0.last().unwrap());
+        let mut columns = (self.current_ctx().aliases.values())
+            .filter_map(|tables| tables.get(&table_name).cloned())
+            .collect_vec();
+        columns.sort();
+        let Some((&first, rest)) = columns.split_first() else {
+            return Err(BindError::InvalidTable(table_name));
+        };
+        let mut all_null = self.egraph.add(Node::IsNull(first));
+        for &column in rest {
+            let is_null = self.egraph.add(Node::IsNull(column));
+            all_null = self.egraph.add(Node::And([all_null, is_null]));
+        }
+        let null = self.egraph.add(Node::null());
+        let true_ = self.egraph.add(Node::true_());
+        Ok(self.egraph.add(Node::If([all_null, null, true_])))
+    }
+
+    fn bind_is_distinct(&mut self, a: Expr, b: Expr, negated: bool) -> Result {
+        let a = self.bind_expr(a)?;
+        let b = self.bind_expr(b)?;
+        Ok(self.egraph.add(match negated {
+            false => Node::IsDistinct([a, b]),
+            true => Node::IsNotDistinct([a, b]),
+        }))
     }
 
     fn bind_typed_string(&mut self, data_type: DataType, value: String) -> Result {
@@ -169,8 +358,10 @@ impl Binder {
         let expr = self.bind_expr(expr)?;
         let low = self.bind_expr(low)?;
         let high = self.bind_expr(high)?;
-        let left = self.egraph.add(Node::GtEq([expr, low]));
-        let right = self.egraph.add(Node::LtEq([expr, high]));
+        let (lexpr, low) = self.coerce_numeric(expr, low);
+        let (rexpr, high) = self.coerce_numeric(expr, high);
+        let left = self.egraph.add(Node::GtEq([lexpr, low]));
+        let right = self.egraph.add(Node::LtEq([rexpr, high]));
         let between = self.egraph.add(Node::And([left, right]));
         if negated {
             Ok(self.egraph.add(Node::Not(between)))
@@ -180,14 +371,26 @@ impl Binder {
     }
 
     fn bind_interval(&mut self, interval: parser::Interval) -> Result {
-        let Expr::Value(Value::Number(v, _) | Value::SingleQuotedString(v)) = *interval.value else {
-            panic!("interval value must be number or string");
+        let v = match *interval.value {
+            Expr::Value(Value::Number(v, _) | Value::SingleQuotedString(v)) => v,
+            value => {
+                return Err(BindError::InvalidExpression(format!(
+                    "interval value must be number or string: {value}"
+                )))
+            }
+        };
+        let parse_num = || {
+            v.parse()
+                .map_err(|_| BindError::InvalidExpression(format!("invalid interval: {v}")))
         };
-        let num = v.parse().expect("interval value is not a number");
         let value = DataValue::Interval(match interval.leading_field {
-            Some(DateTimeField::Day) => Interval::from_days(num),
-            Some(DateTimeField::Month) => Interval::from_months(num),
-            Some(DateTimeField::Year) => Interval::from_years(num),
+            Some(DateTimeField::Day) => Interval::from_days(parse_num()?),
+            Some(DateTimeField::Month) => Interval::from_months(parse_num()?),
+            Some(DateTimeField::Year) => Interval::from_years(parse_num()?),
+            // e.g. INTERVAL '1 year 2 months'
+            None => v
+                .parse()
+                .map_err(|e| BindError::InvalidExpression(format!("invalid interval: {e}")))?,
             f => todo!("Support interval with leading field: {f:?}"),
         });
         Ok(self.egraph.add(Node::Constant(value)))
@@ -217,6 +420,96 @@ impl Binder {
         Ok(self.egraph.add(Node::Substring([expr, from, for_])))
     }
 
+    /// Binds `[NOT] EXISTS (subquery)`.
+    ///
+    /// An uncorrelated subquery is limited to one row,
+    /// so that its execution stops as soon as a row is found.
+    fn bind_exists(&mut self, subquery: Query, negated: bool) -> Result {
+        let (mut id, ctx) = self.bind_query(subquery)?;
+        if ctx.outer_refs.is_empty() {
+            let one = self.egraph.add(Node::Constant(DataValue::Int32(1)));
+            let zero = self.egraph.add(Node::zero());
+            id = self.egraph.add(Node::Limit([one, zero, id]));
+        }
+        let exists = self.egraph.add(Node::Exists(id));
+        if negated {
+            Ok(self.egraph.add(Node::Not(exists)))
+        } else {
+            Ok(exists)
+        }
+    }
+
+    /// Binds `expr [NOT] IN (subquery)` as an EXISTS subquery.
+    ///
+    /// `x IN (SELECT y ...)` is rewritten to `EXISTS (SELECT ... WHERE x = y)`.
+    /// Since an EXISTS is decorrelated into a semi join, which outputs each left row at most once,
+    /// duplicated values in the subquery output don't matter.
+    ///
+    /// `x NOT IN (SELECT y ...)` is true only if the subquery is empty,
+    /// or neither `x` nor any `y` is NULL and no `y` equals to `x`. Otherwise it is false or NULL.
+    /// So it is rewritten to `NOT EXISTS (SELECT ... WHERE x = y OR y IS NULL OR x IS NULL)`.
+    ///
+    /// Note that the rewrite is only valid in WHERE clause, where NULL is equivalent to false.
+    fn bind_in_subquery(&mut self, expr: Expr, subquery: Query, negated: bool) -> Result {
+        let expr = self.bind_expr(expr)?;
+        let (subquery, _) = self.bind_query(subquery)?;
+        let &[column] = self.schema(subquery).as_slice() else {
+            return Err(BindError::InvalidExpression(
+                "subquery in IN must return only one column".into(),
+            ));
+        };
+        let mut cond = self.egraph.add(Node::Eq([expr, column]));
+        self.check_type(cond)?;
+        if negated {
+            let expr_is_null = self.egraph.add(Node::IsNull(expr));
+            let column_is_null = self.egraph.add(Node::IsNull(column));
+            let is_null = self.egraph.add(Node::Or([column_is_null, expr_is_null]));
+            cond = self.egraph.add(Node::Or([cond, is_null]));
+        }
+        let filter = self.egraph.add(Node::Filter([cond, subquery]));
+        let exists = self.egraph.add(Node::Exists(filter));
+        if negated {
+            Ok(self.egraph.add(Node::Not(exists)))
+        } else {
+            Ok(exists)
+        }
+    }
+
+    /// Binds `expr [NOT] IN (value..)`.
+    ///
+    /// The expression and the values are casted to a common type,
+    /// so that they can be compared by their values when evaluating.
+    fn bind_in_list(&mut self, expr: Expr, list: Vec<Expr>, negated: bool) -> Result {
+        let mut expr = self.bind_expr(expr)?;
+        let mut values: Vec<Id> = (list.into_iter())
+            .map(|value| self.bind_expr(value))
+            .try_collect()?;
+        for &value in &values {
+            (expr, _) = self.coerce_numeric(expr, value);
+        }
+        let ty = self.check_type(expr)?.kind;
+        for value in &mut values {
+            (_, *value) = self.coerce_numeric(expr, *value);
+            let value_ty = self.check_type(*value)?.kind;
+            let same_type = value_ty == ty
+                || matches!(
+                    (&value_ty, &ty),
+                    (DataTypeKind::Decimal(_, _), DataTypeKind::Decimal(_, _))
+                );
+            if !same_type && value_ty != DataTypeKind::Null && ty != DataTypeKind::Null {
+                let ty = self.egraph.add(Node::Type(ty.clone()));
+                *value = self.egraph.add(Node::Cast([ty, *value]));
+            }
+        }
+        let list = self.egraph.add(Node::List(values.into()));
+        let in_list = self.egraph.add(Node::In([expr, list]));
+        if negated {
+            Ok(self.egraph.add(Node::Not(in_list)))
+        } else {
+            Ok(in_list)
+        }
+    }
+
     fn bind_function(&mut self, func: Function) -> Result {
         let mut args = vec![];
         for arg in func.args {
@@ -232,29 +525,94 @@ impl Binder {
                     args.clear();
                     break;
                 }
-                FunctionArgExpr::QualifiedWildcard(_) => todo!("support qualified wildcard"),
+                FunctionArgExpr::QualifiedWildcard(name)
+                    if func.name.to_string().eq_ignore_ascii_case("count") =>
+                {
+                    args.push(self.bind_row_exists(name)?);
+                }
+                FunctionArgExpr::QualifiedWildcard(name) => {
+                    return Err(BindError::Todo(format!("{name}.* in {}", func.name)));
+                }
             }
         }
-        let node = match func.name.to_string().to_lowercase().as_str() {
+        let name = func.name.to_string().to_lowercase();
+        if let Some(arity) = arity(&name) {
+            if !arity.contains(&args.len()) {
+                return Err(BindError::InvalidArgumentCount(name, args.len()));
+            }
+        }
+        let node = match name.as_str() {
             "count" if args.is_empty() => Node::RowCount,
             "count" => Node::Count(args[0]),
             "max" => Node::Max(args[0]),
             "min" => Node::Min(args[0]),
             "sum" => Node::Sum(args[0]),
             "avg" => {
-                let sum = self.egraph.add(Node::Sum(args[0]));
+                // rewrite here so that sum and count are collected as aggregations
+                let mut sum = self.egraph.add(Node::Sum(args[0]));
                 let count = self.egraph.add(Node::Count(args[0]));
+                // the average of integers is fractional
+                if self.check_type(args[0])?.kind.is_integer() {
+                    let ty = self.egraph.add(Node::Type(DataTypeKind::Float64));
+                    sum = self.egraph.add(Node::Cast([ty, sum]));
+                }
                 Node::Div([sum, count])
             }
             "first" => Node::First(args[0]),
             "last" => Node::Last(args[0]),
+            "string_agg" => {
+                if !matches!(self.node(args[1]), Node::Constant(DataValue::String(_))) {
+                    return Err(BindError::InvalidExpression(
+                        "string_agg separator must be a string constant".into(),
+                    ));
+                }
+                Node::StringAgg([args[0], args[1]])
+            }
+            "group_concat" => {
+                let sep = self
+                    .egraph
+                    .add(Node::Constant(DataValue::String(",".into())));
+                Node::StringAgg([args[0], sep])
+            }
             "replace" => Node::Replace([args[0], args[1], args[2]]),
+            "length" | "octet_length" => Node::Length(args[0]),
+            "char_length" | "character_length" => Node::CharLength(args[0]),
+            "ascii" => Node::Ascii(args[0]),
+            "chr" => Node::Chr(args[0]),
+            "reverse" => Node::Reverse(args[0]),
+            "repeat" => Node::Repeat([args[0], args[1]]),
+            "sign" => Node::Sign(args[0]),
+            "ln" => Node::Ln(args[0]),
+            "log" if args.len() == 1 => {
+                let base = self.egraph.add(Node::Constant(DataValue::Int32(10)));
+                Node::Log([base, args[0]])
+            }
+            "log" => Node::Log([args[0], args[1]]),
+            "exp" => Node::Exp(args[0]),
+            "date_trunc" => {
+                let field = match self.node(args[0]) {
+                    Node::Constant(DataValue::String(s)) => s.to_uppercase().parse().ok(),
+                    _ => None,
+                };
+                let Some(field) = field else {
+                    return Err(BindError::InvalidExpression(
+                        "date_trunc field must be a string constant of date part".into(),
+                    ));
+                };
+                let field = self.egraph.add(Node::Field(field));
+                Node::DateTrunc([field, args[1]])
+            }
             "row_number" => Node::RowNumber,
+            "rank" => Node::Rank,
+            "dense_rank" => Node::DenseRank,
+            "now" | "current_timestamp" => Node::Now,
             name => todo!("Unsupported function: {}", name),
         };
         let mut id = self.egraph.add(node);
         if let Some(window) = func.over {
             id = self.bind_window_function(id, window)?;
+        } else if self.node(id).is_pure_window_function() {
+            return Err(BindError::WindowWithoutOver(self.node(id).to_string()));
         }
         Ok(id)
     }
@@ -271,7 +629,13 @@ impl Binder {
             return Err(BindError::NestedWindow);
         }
         let partitionby = self.bind_exprs(window.partition_by)?;
-        let orderby = self.bind_orderby(window.order_by)?;
+        let orderby = self.bind_orderby(window.order_by, None)?;
+        // aggregations under the over node are not extracted into the agg plan
+        let mut children = self.node(func).children().to_vec();
+        children.extend([partitionby, orderby]);
+        if children.iter().any(|id| !self.aggs(*id).is_empty()) {
+            return Err(BindError::Todo("aggregate in window function".into()));
+        }
         if window.window_frame.is_some() {
             todo!("support window frame");
         }
@@ -279,9 +643,11 @@ impl Binder {
     }
 }
 
-impl From<Value> for DataValue {
-    fn from(v: Value) -> Self {
-        match v {
+impl TryFrom<Value> for DataValue {
+    type Error = BindError;
+
+    fn try_from(v: Value) -> Result<Self> {
+        Ok(match v {
             Value::Number(n, _) => {
                 if let Ok(int) = n.parse::<i32>() {
                     Self::Int32(int)
@@ -290,14 +656,36 @@ impl From<Value> for DataValue {
                 } else if let Ok(decimal) = n.parse::<Decimal>() {
                     Self::Decimal(decimal)
                 } else {
-                    panic!("invalid digit: {}", n);
+                    return Err(BindError::InvalidExpression(format!("invalid number: {n}")));
                 }
             }
             Value::SingleQuotedString(s) => Self::String(s),
-            Value::DoubleQuotedString(s) => Self::String(s),
             Value::Boolean(b) => Self::Bool(b),
             Value::Null => Self::Null,
-            _ => todo!("parse value: {:?}", v),
-        }
+            _ => return Err(BindError::Todo(format!("parse value: {v}"))),
+        })
+    }
+}
+
+/// Returns the allowed numbers of arguments of a function.
+fn arity(name: &str) -> Option<RangeInclusive<usize>> {
+    Some(match name {
+        "row_number" | "rank" | "dense_rank" | "now" | "current_timestamp" => 0..=0,
+        "count" => 0..=1,
+        "max" | "min" | "sum" | "avg" | "first" | "last" | "group_concat" | "length"
+        | "octet_length" | "char_length" | "character_length" | "ascii" | "chr" | "reverse"
+        | "sign" | "ln" | "exp" => 1..=1,
+        "log" => 1..=2,
+        "string_agg" | "repeat" | "date_trunc" => 2..=2,
+        "replace" => 3..=3,
+        _ => return None,
+    })
+}
+
+/// Removes the parentheses around an expression.
+fn unnest(expr: Expr) -> Expr {
+    match expr {
+        Expr::Nested(expr) => unnest(*expr),
+        _ => expr,
     }
 }
@@ -10,18 +10,23 @@ use itertools::Itertools;
 
 use crate::catalog::{RootCatalog, TableRefId, DEFAULT_SCHEMA_NAME};
 use crate::parser::*;
-use crate::planner::{Expr as Node, RecExpr, TypeError, TypeSchemaAnalysis};
+use crate::planner::{
+    Expr as Node, ExprAnalysis, RecExpr, TypeError, TypeSchemaAnalysis, UnexpectedNode,
+};
 use crate::types::{DataTypeKind, DataValue};
 
 pub mod copy;
+mod create_schema;
 mod create_table;
 mod delete;
 mod drop;
 mod expr;
 mod insert;
 mod select;
+mod show;
 mod table;
 
+pub use self::create_schema::*;
 pub use self::create_table::*;
 pub use self::delete::*;
 pub use self::drop::*;
@@ -43,12 +48,16 @@ pub enum BindError {
     InvalidTable(String),
     #[error("invalid column {0}")]
     InvalidColumn(String),
+    #[error("duplicated schema {0}")]
+    DuplicatedSchema(String),
     #[error("duplicated table {0}")]
     DuplicatedTable(String),
     #[error("duplicated column {0}")]
     DuplicatedColumn(String),
     #[error("duplicated alias {0}")]
     DuplicatedAlias(String),
+    #[error("table {0} has {1} columns available but {2} columns specified")]
+    TooManyColumnAliases(String, usize, usize),
     #[error("invalid expression: {0}")]
     InvalidExpression(String),
     #[error("not nullable column: {0}")]
@@ -67,14 +76,26 @@ pub enum BindError {
     BindFunctionError(String),
     #[error("type error: {0}")]
     TypeError(#[from] TypeError),
+    #[error("invalid plan: {0}")]
+    UnexpectedNode(#[from] UnexpectedNode),
     #[error("aggregate function calls cannot be nested")]
     NestedAgg,
     #[error("WHERE clause cannot contain aggregates")]
     AggInWhere,
     #[error("GROUP BY clause cannot contain aggregates")]
     AggInGroupBy,
+    #[error("{0} position {1} is not in select list")]
+    InvalidOrdinal(&'static str, String),
+    #[error("aggregate functions are not allowed in {0}")]
+    AggNotAllowed(&'static str),
+    #[error("window functions are not allowed in {0}")]
+    WindowNotAllowed(&'static str),
+    #[error("aggregate function calls cannot contain window function calls")]
+    WindowInAgg,
     #[error("window function calls cannot be nested")]
     NestedWindow,
+    #[error("window function {0} requires an OVER clause")]
+    WindowWithoutOver(String),
     #[error("WHERE clause cannot contain window functions")]
     WindowInWhere,
     #[error("HAVING clause cannot contain window functions")]
@@ -87,8 +108,22 @@ pub enum BindError {
     NotSupportedOnInternalTable,
     #[error("{0} is not an aggregate function")]
     NotAgg(String),
+    #[error("unsupported correlated subquery: {0}")]
+    UnsupportedCorrelation(String),
     #[error("not supported yet: {0}")]
     Todo(String),
+    #[error("{0} must be a non-negative integer: {1}")]
+    InvalidLimit(&'static str, String),
+    #[error("WITH TIES cannot be specified without ORDER BY clause")]
+    WithTiesWithoutOrderBy,
+    #[error("invalid foreign key: {0}")]
+    InvalidForeignKey(String),
+    #[error("table {0} is referenced by a foreign key constraint of table {1}")]
+    ReferencedTable(String, String),
+    #[error("column {0} is a generated column")]
+    GeneratedColumn(String),
+    #[error("function {0} does not take {1} arguments")]
+    InvalidArgumentCount(String, usize),
 }
 
 /// The binder resolves all expressions referring to schema objects such as
@@ -97,6 +132,12 @@ pub struct Binder {
     egraph: egg::EGraph<Node, TypeSchemaAnalysis>,
     catalog: Arc<RootCatalog>,
     contexts: Vec<Context>,
+    /// The number of references to each table so far.
+    table_occurrences: HashMap<TableRefId, u32>,
+    /// Output column names of the bound query.
+    output_names: Vec<String>,
+    /// The types of parameters in prepared statements. `None` if it is unknown.
+    params: Vec<Option<DataTypeKind>>,
 }
 
 /// The context of binder execution.
@@ -107,9 +148,19 @@ struct Context {
     /// Column names that can be accessed from the current query.
     /// column_name -> (table_name -> id)
     aliases: HashMap<String, HashMap<String, Id>>,
+    /// The first name bound to each column, used to name the columns of `*`.
+    /// id -> column_name
+    column_names: HashMap<Id, String>,
     /// Column names that can be accessed from the outside query.
     /// column_name -> id
     output_aliases: HashMap<String, Id>,
+    /// Columns of the outer query that are referenced in the current query.
+    outer_refs: HashSet<Id>,
+    /// Names of the select list.
+    output_names: Vec<String>,
+    /// Whether it is a LATERAL subquery, which is evaluated for each row of the preceding
+    /// FROM items, so that they can be referred to anywhere in the query.
+    lateral: bool,
 }
 
 impl Binder {
@@ -119,9 +170,25 @@ impl Binder {
             catalog: catalog.clone(),
             egraph: egg::EGraph::new(TypeSchemaAnalysis { catalog }),
             contexts: vec![Context::default()],
+            table_occurrences: HashMap::new(),
+            output_names: vec![],
+            params: vec![],
         }
     }
 
+    /// Returns the types of parameters in the bound statement.
+    ///
+    /// The type of a parameter is inferred from the other operand of a binary operator. It is
+    /// `None` if the type can not be inferred.
+    pub fn params(&self) -> &[Option<DataTypeKind>] {
+        &self.params
+    }
+
+    /// Returns the output column names if the bound statement is a query.
+    pub fn output_names(&self) -> &[String] {
+        &self.output_names
+    }
+
     /// Bind a statement.
     pub fn bind(&mut self, stmt: Statement) -> Result<RecExpr> {
         let id = self.bind_stmt(stmt)?;
@@ -132,6 +199,10 @@ impl Binder {
 
     fn bind_stmt(&mut self, stmt: Statement) -> Result {
         match stmt {
+            Statement::CreateSchema {
+                schema_name,
+                if_not_exists,
+            } => self.bind_create_schema(schema_name, if_not_exists),
             Statement::CreateTable {
                 name,
                 columns,
@@ -154,6 +225,7 @@ impl Binder {
             Statement::Delete {
                 from, selection, ..
             } => self.bind_delete(from, selection),
+            Statement::Truncate { table_name, .. } => self.bind_truncate(table_name),
             Statement::Copy {
                 source,
                 to,
@@ -161,11 +233,26 @@ impl Binder {
                 options,
                 ..
             } => self.bind_copy(source, to, target, &options),
-            Statement::Query(query) => self.bind_query(*query).map(|(id, _)| id),
-            Statement::Explain { statement, .. } => self.bind_explain(*statement),
-            Statement::ShowVariable { .. }
-            | Statement::ShowCreate { .. }
-            | Statement::ShowColumns { .. } => Err(BindError::NotSupportedTSQL),
+            Statement::Query(query) => {
+                let (id, ctx) = self.bind_query(*query)?;
+                self.output_names = ctx.output_names;
+                Ok(id)
+            }
+            Statement::Explain {
+                statement,
+                analyze,
+                verbose,
+                ..
+            } => self.bind_explain(*statement, analyze, verbose),
+            Statement::ShowTables {
+                db_name, filter, ..
+            } => self.bind_show_tables(db_name, filter),
+            Statement::ShowColumns {
+                table_name, filter, ..
+            } => self.bind_show_columns(table_name, filter),
+            Statement::ShowVariable { .. } | Statement::ShowCreate { .. } => {
+                Err(BindError::NotSupportedTSQL)
+            }
             _ => Err(BindError::InvalidSQL),
         }
     }
@@ -181,6 +268,9 @@ impl Binder {
     /// Add an alias to the current context.
     fn add_alias(&mut self, column_name: String, table_name: String, id: Id) {
         let context = self.contexts.last_mut().unwrap();
+        (context.column_names)
+            .entry(id)
+            .or_insert_with(|| column_name.clone());
         context
             .aliases
             .entry(column_name)
@@ -209,15 +299,20 @@ impl Binder {
         &self.egraph[id].nodes[0]
     }
 
-    fn bind_explain(&mut self, query: Statement) -> Result {
+    /// Binds `EXPLAIN [ANALYZE] [VERBOSE]`. With VERBOSE, physical properties are shown.
+    fn bind_explain(&mut self, query: Statement, analyze: bool, verbose: bool) -> Result {
         let id = self.bind_stmt(query)?;
-        let id = self.egraph.add(Node::Explain(id));
+        let verbose = self.egraph.add(Node::Constant(DataValue::Bool(verbose)));
+        let id = match analyze {
+            true => self.egraph.add(Node::Analyze([verbose, id])),
+            false => self.egraph.add(Node::Explain([verbose, id])),
+        };
         Ok(id)
     }
 }
 
 /// Split an object name into `(schema name, table name)`.
-fn split_name(name: &ObjectName) -> Result<(&str, &str)> {
+pub(crate) fn split_name(name: &ObjectName) -> Result<(&str, &str)> {
     Ok(match name.0.as_slice() {
         [table] => (DEFAULT_SCHEMA_NAME, &table.value),
         [schema, table] => (&schema.value, &table.value),
@@ -225,12 +320,23 @@ fn split_name(name: &ObjectName) -> Result<(&str, &str)> {
     })
 }
 
-/// Convert an object name into lower case
-fn lower_case_name(name: &ObjectName) -> ObjectName {
+/// Normalize an identifier.
+///
+/// Unquoted identifiers are case-insensitive and converted into lower case,
+/// while quoted identifiers keep their case.
+fn normalize_ident(ident: &Ident) -> String {
+    match ident.quote_style {
+        Some(_) => ident.value.clone(),
+        None => ident.value.to_lowercase(),
+    }
+}
+
+/// Normalize each part of an object name. See [`normalize_ident`].
+pub(crate) fn lower_case_name(name: &ObjectName) -> ObjectName {
     ObjectName(
         name.0
             .iter()
-            .map(|ident| Ident::new(ident.value.to_lowercase()))
+            .map(|ident| Ident::new(normalize_ident(ident)))
             .collect::<Vec<_>>(),
     )
 }
@@ -11,46 +11,145 @@ impl Binder {
         ret.map(|id| (id, ctx))
     }
 
+    /// Binds a LATERAL subquery, which can refer to the preceding FROM items.
+    pub(super) fn bind_lateral_query(&mut self, query: Query) -> Result<(Id, Context)> {
+        self.contexts.push(Context {
+            lateral: true,
+            ..Default::default()
+        });
+        let ret = self.bind_query_internal(query);
+        let ctx = self.contexts.pop().unwrap();
+        ret.map(|id| (id, ctx))
+    }
+
     pub(super) fn bind_query_internal(&mut self, query: Query) -> Result {
         let child = match *query.body {
             SetExpr::Select(select) => self.bind_select(*select, query.order_by)?,
             SetExpr::Values(values) => self.bind_values(values)?,
             _ => todo!("handle query ???"),
         };
-        let limit = match query.limit {
-            Some(expr) => self.bind_expr(expr)?,
-            None => self.egraph.add(Node::null()),
+        if !self.current_ctx().outer_refs.is_empty()
+            && !self.current_ctx().lateral
+            && (query.limit.is_some() || query.offset.is_some() || query.fetch.is_some())
+        {
+            return Err(BindError::UnsupportedCorrelation(
+                "LIMIT, OFFSET or FETCH in correlated subquery".into(),
+            ));
+        }
+        if query.limit.is_some() && query.fetch.is_some() {
+            return Err(BindError::InvalidSQL);
+        }
+        let with_ties = query.fetch.as_ref().is_some_and(|fetch| fetch.with_ties);
+        let limit = match (query.limit, query.fetch) {
+            (Some(expr), _) => self.bind_limit_value(expr, "LIMIT")?,
+            (None, Some(fetch)) => self.bind_fetch(fetch)?,
+            (None, None) => self.egraph.add(Node::null()),
         };
         let offset = match query.offset {
-            Some(offset) => self.bind_expr(offset.value)?,
+            Some(offset) => self.bind_limit_value(offset.value, "OFFSET")?,
             None => self.egraph.add(Node::zero()),
         };
+        if with_ties {
+            return self.plan_with_ties(limit, offset, child);
+        }
         Ok(self.egraph.add(Node::Limit([limit, offset, child])))
     }
 
+    /// Binds the number of rows in FETCH clause.
+    fn bind_fetch(&mut self, fetch: Fetch) -> Result {
+        if fetch.percent {
+            return Err(BindError::Todo("FETCH ... PERCENT".into()));
+        }
+        match fetch.quantity {
+            Some(expr) => self.bind_limit_value(expr, "FETCH"),
+            // `FETCH FIRST ROW ONLY`
+            None => Ok(self.egraph.add(Node::Constant(DataValue::Int32(1)))),
+        }
+    }
+
+    /// Plans `FETCH FIRST n ROWS WITH TIES` into a top-n on the ORDER BY keys.
+    ///
+    /// The plan of SELECT always has the order right below the projection, whatever is below the
+    /// order, e.g. an aggregation, the filter of HAVING or a window. So the top-n takes the place
+    /// of the order. Other queries are not supported.
+    fn plan_with_ties(&mut self, limit: Id, offset: Id, child: Id) -> Result {
+        let unsupported = || BindError::Todo("FETCH ... WITH TIES without SELECT".into());
+        let &Node::Proj([projection, order]) = self.node(child) else {
+            return Err(unsupported());
+        };
+        let &Node::Order([keys, plan]) = self.node(order) else {
+            return Err(unsupported());
+        };
+        if self.node(keys).try_as_list()?.is_empty() {
+            return Err(BindError::WithTiesWithoutOrderBy);
+        }
+        let with_ties = self.egraph.add(Node::true_());
+        let topn = Node::TopN([limit, offset, keys, with_ties, plan]);
+        let topn = self.egraph.add(topn);
+        Ok(self.egraph.add(Node::Proj([projection, topn])))
+    }
+
+    /// Binds the value of LIMIT, OFFSET or FETCH and evaluates it to a constant.
+    ///
+    /// The executor expects a non-negative integer, or NULL for no limit.
+    fn bind_limit_value(&mut self, expr: Expr, clause: &'static str) -> Result {
+        let invalid = || BindError::InvalidLimit(clause, expr.to_string());
+        let id = self.bind_expr(expr.clone())?;
+        let (_, best) = egg::Extractor::new(&self.egraph, egg::AstSize).find_best(id);
+        let mut egraph = egg::EGraph::new(ExprAnalysis {
+            catalog: self.catalog.clone(),
+            ..Default::default()
+        });
+        let root = egraph.add_expr(&best);
+        let value = egraph[root].data.constant.clone().ok_or_else(invalid)?;
+        match value.as_usize() {
+            Ok(Some(_)) => {}
+            Ok(None) if clause == "LIMIT" => {}
+            _ => return Err(invalid()),
+        }
+        Ok(self.egraph.add(Node::Constant(value)))
+    }
+
     fn bind_select(&mut self, select: Select, order_by: Vec<OrderByExpr>) -> Result {
         let from = self.bind_from(select.from)?;
         let projection = self.bind_projection(select.projection, from)?;
         let where_ = self.bind_where(select.selection)?;
         let groupby = match select.group_by {
             group_by if group_by.is_empty() => None,
-            group_by => Some(self.bind_groupby(group_by)?),
+            group_by => Some(self.bind_groupby(group_by, projection)?),
         };
+        let has_having = select.having.is_some();
         let having = self.bind_having(select.having)?;
-        let orderby = self.bind_orderby(order_by)?;
+        let orderby = self.bind_orderby(order_by, Some(projection))?;
+        let distinct_on = matches!(select.distinct, Some(Distinct::On(_)));
         let distinct = match select.distinct {
             None => self.egraph.add(Node::List([].into())),
             Some(Distinct::Distinct) => projection,
             Some(Distinct::On(exprs)) => self.bind_exprs(exprs)?,
         };
+        self.check_exists(where_, [projection, having, orderby, distinct])?;
+        self.check_correlation(where_, groupby, [projection, having, orderby, distinct])?;
 
         let mut plan = self.egraph.add(Node::Filter([where_, from]));
         let mut to_rewrite = [projection, distinct, having, orderby];
+        let ungrouped = plan;
         plan = self.plan_agg(&mut to_rewrite, groupby, plan)?;
-        let [mut projection, distinct, having, orderby] = to_rewrite;
+        if has_having && plan == ungrouped {
+            // HAVING groups all rows into one even without aggregations,
+            // so columns can not be referred to directly.
+            for id in to_rewrite {
+                self.rewrite_agg_in_expr(id, &[])?;
+            }
+            let aggs = self.egraph.add(Node::List([].into()));
+            plan = self.egraph.add(Node::Agg([aggs, plan]));
+        }
+        let [mut projection, distinct, having, mut orderby] = to_rewrite;
         plan = self.egraph.add(Node::Filter([having, plan]));
         plan = self.plan_window(projection, distinct, orderby, plan)?;
-        plan = self.plan_distinct(distinct, orderby, &mut projection, plan)?;
+        plan = match distinct_on {
+            true => self.plan_distinct_on(distinct, orderby, plan)?,
+            false => self.plan_distinct(distinct, &mut orderby, &mut projection, plan)?,
+        };
         plan = self.egraph.add(Node::Order([orderby, plan]));
         plan = self.egraph.add(Node::Proj([projection, plan]));
         Ok(plan)
@@ -59,14 +158,16 @@ impl Binder {
     /// Binds the select list. Returns a list of expressions.
     fn bind_projection(&mut self, projection: Vec<SelectItem>, from: Id) -> Result {
         let mut select_list = vec![];
+        let mut names = vec![];
         for item in projection {
             match item {
                 SelectItem::UnnamedExpr(expr) => {
-                    let ident = if let Expr::Identifier(ident) = &expr {
-                        Some(ident.value.to_lowercase())
-                    } else {
-                        None
+                    let ident = match &expr {
+                        Expr::Identifier(ident) => Some(normalize_ident(ident)),
+                        Expr::Value(Value::DoubleQuotedString(name)) => Some(name.clone()),
+                        _ => None,
                     };
+                    names.push(expr_name(&expr));
                     let id = self.bind_expr(expr)?;
                     if let Some(ident) = ident {
                         self.current_ctx_mut().output_aliases.insert(ident, id);
@@ -75,20 +176,33 @@ impl Binder {
                 }
                 SelectItem::ExprWithAlias { expr, alias } => {
                     let id = self.bind_expr(expr)?;
-                    let name = alias.value.to_lowercase();
+                    let name = normalize_ident(&alias);
                     self.add_alias(name.clone(), "".into(), id);
-                    self.current_ctx_mut().output_aliases.insert(name, id);
+                    self.current_ctx_mut()
+                        .output_aliases
+                        .insert(name.clone(), id);
                     select_list.push(id);
+                    names.push(name);
                 }
                 SelectItem::Wildcard(_) => {
-                    select_list.append(&mut self.schema(from));
+                    for id in self.schema(from) {
+                        names.push(self.column_name(id));
+                        select_list.push(id);
+                    }
                 }
                 _ => todo!("bind select list"),
             }
         }
+        self.current_ctx_mut().output_names = names;
         Ok(self.egraph.add(Node::List(select_list.into())))
     }
 
+    /// Returns the name of a column in the FROM clause.
+    fn column_name(&self, id: Id) -> String {
+        (self.current_ctx().column_names.get(&id))
+            .map_or_else(|| "?column?".into(), |name| name.clone())
+    }
+
     /// Binds the WHERE clause. Returns an expression for condition.
     ///
     /// There should be no aggregation in the expression, otherwise an error will be returned.
@@ -112,6 +226,19 @@ impl Binder {
         Ok(id)
     }
 
+    /// Checks that there is no aggregation or window function in the expression of `clause`.
+    ///
+    /// They can only be evaluated in the select list, HAVING and ORDER BY clause.
+    pub(super) fn check_no_agg(&self, id: Id, clause: &'static str) -> Result<()> {
+        if !self.aggs(id).is_empty() {
+            return Err(BindError::AggNotAllowed(clause));
+        }
+        if !self.overs(id).is_empty() {
+            return Err(BindError::WindowNotAllowed(clause));
+        }
+        Ok(())
+    }
+
     /// Binds a selection. Returns a `true` node if no selection.
     fn bind_selection(&mut self, selection: Option<Expr>) -> Result {
         Ok(match selection {
@@ -122,20 +249,62 @@ impl Binder {
 
     /// Binds the GROUP BY clause. Returns a list of expressions.
     ///
+    /// An integer constant refers to an item in the select list by its position.
     /// There should be no aggregation in the expressions, otherwise an error will be returned.
-    fn bind_groupby(&mut self, group_by: Vec<Expr>) -> Result {
-        let id = self.bind_exprs(group_by)?;
+    fn bind_groupby(&mut self, group_by: Vec<Expr>, projection: Id) -> Result {
+        let mut keys = Vec::with_capacity(group_by.len());
+        for expr in group_by {
+            let key = match self.bind_ordinal(&expr, projection, "GROUP BY")? {
+                Some(id) => id,
+                None => self.bind_expr(expr)?,
+            };
+            keys.push(key);
+        }
+        let id = self.egraph.add(Node::List(keys.into()));
         if !self.aggs(id).is_empty() {
             return Err(BindError::AggInGroupBy);
         }
         Ok(id)
     }
 
+    /// Resolves an ordinal in `clause` to the item at that position (1-based) in the select list.
+    ///
+    /// Returns `None` if the expression is not an integer constant.
+    fn bind_ordinal(
+        &self,
+        expr: &Expr,
+        projection: Id,
+        clause: &'static str,
+    ) -> Result<Option<Id>> {
+        let Expr::Value(Value::Number(n, _)) = expr else {
+            return Ok(None);
+        };
+        let Ok(ordinal) = n.parse::<usize>() else {
+            return Ok(None);
+        };
+        let items = self.node(projection).try_as_list()?;
+        match ordinal.checked_sub(1).and_then(|i| items.get(i)) {
+            Some(id) => Ok(Some(*id)),
+            None => Err(BindError::InvalidOrdinal(clause, n.clone())),
+        }
+    }
+
     /// Binds the ORDER BY clause. Returns a list of expressions.
-    pub(super) fn bind_orderby(&mut self, order_by: Vec<OrderByExpr>) -> Result {
+    ///
+    /// If the `projection` of the query is given, keys are resolved by [`bind_order_key`].
+    ///
+    /// [`bind_order_key`]: Self::bind_order_key
+    pub(super) fn bind_orderby(
+        &mut self,
+        order_by: Vec<OrderByExpr>,
+        projection: Option<Id>,
+    ) -> Result {
         let mut orderby = Vec::with_capacity(order_by.len());
         for e in order_by {
-            let expr = self.bind_expr(e.expr)?;
+            let expr = match projection {
+                Some(projection) => self.bind_order_key(e.expr, projection)?,
+                None => self.bind_expr(e.expr)?,
+            };
             let key = match e.asc {
                 Some(true) | None => expr,
                 Some(false) => self.egraph.add(Node::Desc(expr)),
@@ -145,7 +314,120 @@ impl Binder {
         Ok(self.egraph.add(Node::List(orderby.into())))
     }
 
+    /// Binds an ORDER BY key of the query.
+    ///
+    /// An integer constant refers to an item in the select list by its position. A bare name
+    /// refers to an output column of the select list, and then to a column of the input.
+    fn bind_order_key(&mut self, expr: Expr, projection: Id) -> Result {
+        if let Some(id) = self.bind_ordinal(&expr, projection, "ORDER BY")? {
+            return Ok(id);
+        }
+        if let Expr::Identifier(ident) = &expr {
+            let name = normalize_ident(ident);
+            let items = self.node(projection).try_as_list()?;
+            let ids = (self.current_ctx().output_names.iter().zip(items))
+                .filter(|(output_name, _)| **output_name == name)
+                .map(|(_, id)| *id)
+                .unique()
+                .collect_vec();
+            match ids[..] {
+                [] => {}
+                [id] => return Ok(id),
+                _ => return Err(BindError::AmbiguousColumn(name)),
+            }
+        }
+        self.bind_expr(expr)
+    }
+
+    /// Checks that EXISTS only appears in the conjunctions of WHERE clause,
+    /// so that it can be rewritten to a semi or anti join by the optimizer.
+    fn check_exists(&self, where_: Id, others: [Id; 4]) -> Result<()> {
+        let unsupported = || Err(BindError::Todo("EXISTS outside WHERE conjunctions".into()));
+        if others.iter().any(|id| self.contains_exists(*id)) {
+            return unsupported();
+        }
+        let mut conds = vec![];
+        self.split_conjunction(where_, &mut conds);
+        for cond in conds {
+            let cond = match self.node(cond) {
+                Node::Not(a) => *a,
+                _ => cond,
+            };
+            if !matches!(self.node(cond), Node::Exists(_)) && self.contains_exists(cond) {
+                return unsupported();
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns true if the expression contains an EXISTS subquery.
+    fn contains_exists(&self, id: Id) -> bool {
+        let node = self.node(id);
+        matches!(node, Node::Exists(_))
+            || (node.children().iter()).any(|child| self.contains_exists(*child))
+    }
+
+    /// Checks whether the correlated subquery can be decorrelated by the optimizer.
+    ///
+    /// Columns from the outer query can only appear in equality predicates of the WHERE clause,
+    /// and the subquery must not be aggregated, deduplicated or ordered, since the predicates
+    /// are pulled up above it.
+    fn check_correlation(&self, where_: Id, groupby: Option<Id>, others: [Id; 4]) -> Result<()> {
+        let outer_refs = &self.current_ctx().outer_refs;
+        // a LATERAL subquery is evaluated for each row of the outer query
+        if outer_refs.is_empty() || self.current_ctx().lateral {
+            return Ok(());
+        }
+        let unsupported = |msg: &str| Err(BindError::UnsupportedCorrelation(msg.into()));
+        if groupby.is_some() || others.iter().any(|id| !self.aggs(*id).is_empty()) {
+            return unsupported("aggregation in correlated subquery");
+        }
+        let [_, _, orderby, distinct] = others;
+        if !self.node(distinct).try_as_list()?.is_empty() {
+            return unsupported("DISTINCT in correlated subquery");
+        }
+        if !self.node(orderby).try_as_list()?.is_empty() {
+            return unsupported("ORDER BY in correlated subquery");
+        }
+        if others.iter().any(|id| self.refers_to(*id, outer_refs)) {
+            return unsupported("outer column can only be used in WHERE clause");
+        }
+        let mut conds = vec![];
+        self.split_conjunction(where_, &mut conds);
+        for cond in conds {
+            if !self.refers_to(cond, outer_refs) {
+                continue;
+            }
+            let Node::Eq([a, b]) = self.node(cond) else {
+                return unsupported("only equality predicates can refer to outer columns");
+            };
+            if self.refers_to(*a, outer_refs) == self.refers_to(*b, outer_refs) {
+                return unsupported("outer columns must be on one side of the equality");
+            }
+        }
+        Ok(())
+    }
+
+    /// Splits a conjunction into a list of predicates.
+    fn split_conjunction(&self, id: Id, conds: &mut Vec<Id>) {
+        if let Node::And([a, b]) = self.node(id) {
+            self.split_conjunction(*a, conds);
+            self.split_conjunction(*b, conds);
+        } else {
+            conds.push(id);
+        }
+    }
+
+    /// Returns true if the expression refers to any of the `columns`.
+    fn refers_to(&self, id: Id, columns: &HashSet<Id>) -> bool {
+        columns.contains(&id)
+            || (self.node(id).children().iter()).any(|child| self.refers_to(*child, columns))
+    }
+
     /// Binds the VALUES clause. Returns a [`Values`](Node::Values) plan.
+    ///
+    /// Each column of the values becomes a [`ValuesColumn`](Node::ValuesColumn),
+    /// which is named `column1`, `column2`, ... in the output.
     fn bind_values(&mut self, values: Values) -> Result {
         let values = values.rows;
         let mut bound_values = Vec::with_capacity(values.len());
@@ -160,11 +442,30 @@ impl Binder {
                     "VALUES lists must all be the same length".into(),
                 ));
             }
-            bound_values.push(self.bind_exprs(row)?);
+            let row = self.bind_exprs(row)?;
+            self.check_no_agg(row, "VALUES")?;
+            bound_values.push(self.node(row).try_as_list()?.to_vec());
         }
-        let id = self.egraph.add(Node::Values(bound_values.into()));
-        self.check_type(id)?;
-        Ok(id)
+        if !self.current_ctx().outer_refs.is_empty() && !self.current_ctx().lateral {
+            return Err(BindError::UnsupportedCorrelation(
+                "VALUES in correlated subquery".into(),
+            ));
+        }
+        let mut columns = Vec::with_capacity(column_len);
+        let mut names = Vec::with_capacity(column_len);
+        for i in 0..column_len {
+            let values = bound_values.iter().map(|row| row[i]).collect();
+            let column = self.egraph.add(Node::ValuesColumn(values));
+            self.check_type(column)?;
+            let name = format!("column{}", i + 1);
+            self.current_ctx_mut()
+                .output_aliases
+                .insert(name.clone(), column);
+            columns.push(column);
+            names.push(name);
+        }
+        self.current_ctx_mut().output_names = names;
+        Ok(self.egraph.add(Node::Values(columns.into())))
     }
 
     /// Extracts all aggregations from `exprs` and generates an [`Agg`](Node::Agg) plan.
@@ -175,11 +476,14 @@ impl Binder {
         if aggs.is_empty() && groupby.is_none() {
             return Ok(plan);
         }
-        // check nested agg
+        // check nested agg and window function
         for child in aggs.iter().flat_map(|agg| agg.children()) {
             if !self.aggs(*child).is_empty() {
                 return Err(BindError::NestedAgg);
             }
+            if !self.overs(*child).is_empty() {
+                return Err(BindError::WindowInAgg);
+            }
         }
         let mut list: Vec<_> = aggs.into_iter().map(|agg| self.egraph.add(agg)).collect();
         // make sure the order of the aggs is deterministic
@@ -236,40 +540,50 @@ impl Binder {
     ///
     /// If `distinct` is an empty list, returns the original `plan`.
     ///
+    /// The `orderby` list must start with items in `distinct` list.
+    /// If there are more items after them, `plan` will be sorted by `orderby` before aggregation,
+    /// and the extra items will be removed from `orderby`.
+    ///
     /// # Example
     /// ```ignore
     /// distinct=(list a b)
     /// projection=(list b c)
     /// output=(hashagg (list b (first c)) (list a b) plan)
+    ///
+    /// distinct=(list a)
+    /// projection=(list a b)
+    /// orderby=(list a b) -> (list a)
+    /// output=(hashagg (list (first b)) (list a) (order (list a b) plan))
     /// ```
     fn plan_distinct(
         &mut self,
         distinct: Id,
-        orderby: Id,
+        orderby: &mut Id,
         projection: &mut Id,
-        plan: Id,
+        mut plan: Id,
     ) -> Result {
-        let distinct_on = self.node(distinct).as_list().to_vec();
+        let distinct_on = self.node(distinct).try_as_list()?.to_vec();
         if distinct_on.is_empty() {
             return Ok(plan);
         }
-        // make sure all ORDER BY items are in DISTINCT list.
-        for id in self.node(orderby).as_list() {
-            // id = key or (desc key)
-            let key = match self.node(*id) {
-                Node::Desc(id) => id,
-                _ => id,
-            };
-            if !distinct_on.contains(key) {
-                return Err(BindError::OrderKeyNotInDistinct);
-            }
+        let distinct_set: HashSet<Id> = distinct_on.iter().cloned().collect();
+        let orderby_list = self.node(*orderby).try_as_list()?.to_vec();
+        let (prefix_len, _) = self.distinct_order_prefix(&distinct_set, &orderby_list)?;
+        if prefix_len < orderby_list.len() {
+            // sort rows before aggregation so that the first row is kept.
+            plan = self.egraph.add(Node::Order([*orderby, plan]));
+            // rows are unique on DISTINCT items after aggregation,
+            // so the remaining ORDER BY items are useless.
+            *orderby = self
+                .egraph
+                .add(Node::List(orderby_list[..prefix_len].into()));
         }
         // for all projection items that are not in DISTINCT list,
         // wrap them with first() aggregation.
         let mut aggs = vec![];
-        let mut projs = self.node(*projection).as_list().to_vec();
+        let mut projs = self.node(*projection).try_as_list()?.to_vec();
         for id in &mut projs {
-            if !distinct_on.contains(id) {
+            if !distinct_set.contains(id) {
                 *id = self.egraph.add(Node::First(*id));
                 aggs.push(*id);
             }
@@ -279,9 +593,84 @@ impl Binder {
         Ok(self.egraph.add(Node::HashAgg([aggs, distinct, plan])))
     }
 
+    /// Generate a [`Window`](Node::Window) plan for DISTINCT ON,
+    /// which keeps the first row of each partition on the `distinct` list.
+    ///
+    /// The `orderby` list must start with items in `distinct` list.
+    /// Rows are sorted by `distinct` items and then the remaining items in `orderby`,
+    /// and numbered by `row_number()` in each partition.
+    ///
+    /// # Example
+    /// ```ignore
+    /// distinct=(list a)
+    /// orderby=(list a (desc b))
+    /// output=(filter (= over 1)
+    ///            (window (list over:(over row_number (list a) (list (desc b))))
+    ///                (order (list a (desc b)) plan)))
+    /// ```
+    fn plan_distinct_on(&mut self, distinct: Id, orderby: Id, plan: Id) -> Result {
+        let distinct_on = self.node(distinct).try_as_list()?.to_vec();
+        let distinct_set: HashSet<Id> = distinct_on.iter().cloned().collect();
+        let orderby_list = self.node(orderby).try_as_list()?.to_vec();
+        let (prefix_len, covered) = self.distinct_order_prefix(&distinct_set, &orderby_list)?;
+
+        // sort by all DISTINCT items, then the remaining ORDER BY items
+        let mut sort_keys = orderby_list.clone();
+        sort_keys.extend(distinct_on.iter().filter(|id| !covered.contains(*id)));
+        let sort_keys = self.egraph.add(Node::List(sort_keys.into()));
+        let plan = self.egraph.add(Node::Order([sort_keys, plan]));
+
+        // number the rows in each partition and keep the first one
+        let row_number = self.egraph.add(Node::RowNumber);
+        let remaining = self
+            .egraph
+            .add(Node::List(orderby_list[prefix_len..].into()));
+        let over = self
+            .egraph
+            .add(Node::Over([row_number, distinct, remaining]));
+        let overs = self.egraph.add(Node::List([over].into()));
+        let plan = self.egraph.add(Node::Window([overs, plan]));
+        let one = self.egraph.add(Node::Constant(DataValue::Int32(1)));
+        let cond = self.egraph.add(Node::Eq([over, one]));
+        Ok(self.egraph.add(Node::Filter([cond, plan])))
+    }
+
+    /// Makes sure ORDER BY items start with DISTINCT items.
+    /// The remaining items decide which row is kept for each distinct group.
+    /// e.g. `DISTINCT ON (a, b) ... ORDER BY b, a, c`
+    ///
+    /// Returns the number of leading ORDER BY items on DISTINCT items, and the set of them.
+    fn distinct_order_prefix(
+        &self,
+        distinct_set: &HashSet<Id>,
+        orderby_list: &[Id],
+    ) -> Result<(usize, HashSet<Id>)> {
+        let mut covered = HashSet::new();
+        let mut prefix_len = 0;
+        for id in orderby_list {
+            if covered.len() == distinct_set.len() {
+                break;
+            }
+            // id = key or (desc key)
+            let key = match self.node(*id) {
+                Node::Desc(id) => *id,
+                _ => *id,
+            };
+            if !distinct_set.contains(&key) {
+                return Err(BindError::OrderKeyNotInDistinct);
+            }
+            covered.insert(key);
+            prefix_len += 1;
+        }
+        Ok((prefix_len, covered))
+    }
+
     /// Extracts all over nodes from `projection`, `distinct` and `orderby`.
-    /// Generates an [`Window`](Node::Window) plan if any over node is found.
+    /// Generates [`Window`](Node::Window) plans if any over node is found.
     /// Otherwise returns the original `plan`.
+    ///
+    /// Window functions with the same partition and order keys are computed by one window plan,
+    /// over the input sorted by the partition keys and then the order keys.
     fn plan_window(&mut self, projection: Id, distinct: Id, orderby: Id, plan: Id) -> Result {
         let mut overs = vec![];
         overs.extend_from_slice(self.overs(projection));
@@ -297,7 +686,384 @@ impl Binder {
             .collect();
         list.sort();
         list.dedup();
-        let overs = self.egraph.add(Node::List(list.into()));
-        Ok(self.egraph.add(Node::Window([overs, plan])))
+
+        // group window functions by (partition keys, order keys)
+        let mut groups: Vec<([Id; 2], Vec<Id>)> = vec![];
+        for over in list {
+            let Node::Over([_, partitionby, orderby]) = *self.node(over) else {
+                unreachable!("not an over node");
+            };
+            let keys = [partitionby, orderby];
+            match groups.iter_mut().find(|(k, _)| *k == keys) {
+                Some((_, overs)) => overs.push(over),
+                None => groups.push((keys, vec![over])),
+            }
+        }
+
+        let mut plan = plan;
+        for ([partitionby, orderby], overs) in groups {
+            let mut keys = self.node(partitionby).try_as_list()?.to_vec();
+            keys.extend_from_slice(self.node(orderby).try_as_list()?);
+            if !keys.is_empty() {
+                let keys = self.egraph.add(Node::List(keys.into()));
+                plan = self.egraph.add(Node::Order([keys, plan]));
+            }
+            let overs = self.egraph.add(Node::List(overs.into()));
+            plan = self.egraph.add(Node::Window([overs, plan]));
+        }
+        Ok(plan)
+    }
+}
+
+/// Returns the output name of an unnamed expression in the select list.
+fn expr_name(expr: &Expr) -> String {
+    match expr {
+        Expr::Identifier(ident) => normalize_ident(ident),
+        Expr::CompoundIdentifier(idents) => normalize_ident(idents.last().unwrap()),
+        Expr::Value(Value::DoubleQuotedString(name)) => name.clone(),
+        Expr::Function(func) => func.name.to_string().to_lowercase(),
+        Expr::Nested(expr) => expr_name(expr),
+        _ => "?column?".into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::catalog::{ColumnCatalog, RootCatalog};
+    use crate::parser::parse;
+
+    fn bind(sql: &str) -> Result<RecExpr> {
+        let catalog = Arc::new(RootCatalog::new());
+        let columns = ["a", "b"]
+            .iter()
+            .enumerate()
+            .map(|(i, name)| {
+                let desc = DataTypeKind::Int32
+                    .not_null()
+                    .to_column(name.to_string(), false);
+                ColumnCatalog::new(i as u32, desc)
+            })
+            .collect();
+        catalog
+            .add_table(0, "t".into(), columns, false, vec![], vec![], vec![])
+            .unwrap();
+        let stmt = parse(sql).unwrap().remove(0);
+        Binder::new(catalog).bind(stmt)
+    }
+
+    #[test]
+    fn bind_distinct_on() -> Result<()> {
+        let plan = bind("select distinct on (a) a, b from t order by a, b desc")?;
+        let nodes = plan.as_ref();
+        let node = |id: &Id| &nodes[usize::from(*id)];
+        let list = |id: &Id| -> Result<Vec<&Node>> {
+            Ok(node(id).try_as_list()?.iter().map(node).collect())
+        };
+
+        // (filter (= over 1) (window (list over) (order (list a (desc b)) ...)))
+        // where over = (over row_number (list a) (list (desc b)))
+        let Some((cond, window)) = nodes.iter().find_map(|n| match n {
+            Node::Filter([cond, child]) if matches!(node(child), Node::Window(_)) => {
+                Some((cond, child))
+            }
+            _ => None,
+        }) else {
+            panic!("no filter over window: {}", plan.pretty(80));
+        };
+        let Node::Window([overs, child]) = node(window) else {
+            unreachable!()
+        };
+        let [over] = node(overs).try_as_list()? else {
+            panic!("expect one window function: {}", plan.pretty(80));
+        };
+        let Node::Eq([lhs, rhs]) = node(cond) else {
+            panic!("not an equality: {}", plan.pretty(80));
+        };
+        assert_eq!(lhs, over);
+        assert_eq!(node(rhs), &Node::Constant(DataValue::Int32(1)));
+        let Node::Over([f, partitionby, orderby]) = node(over) else {
+            panic!("not a window function: {}", plan.pretty(80));
+        };
+        assert_eq!(node(f), &Node::RowNumber);
+        assert!(matches!(list(partitionby)?[..], [Node::Column(_)]));
+        assert!(matches!(list(orderby)?[..], [Node::Desc(_)]));
+        let Node::Order([keys, _]) = node(child) else {
+            panic!("no order under window: {}", plan.pretty(80));
+        };
+        assert!(matches!(list(keys)?[..], [Node::Column(_), Node::Desc(_)]));
+        Ok(())
+    }
+
+    #[test]
+    fn bind_is_not_null() {
+        let plan = bind("select a from t where a is not null").unwrap();
+        let nodes = plan.as_ref();
+        let node = |id: &Id| &nodes[usize::from(*id)];
+        let Some(Node::IsNotNull(a)) = nodes.iter().find(|n| matches!(n, Node::IsNotNull(_)))
+        else {
+            panic!("no isnotnull: {}", plan.pretty(80));
+        };
+        assert!(matches!(node(a), Node::Column(_)));
+        assert!(!nodes.iter().any(|n| matches!(n, Node::Not(_))));
+    }
+
+    #[test]
+    fn bind_window_sorts_input() -> Result<()> {
+        let plan = bind("select sum(a) over (partition by b order by a) from t")?;
+        let nodes = plan.as_ref();
+        let node = |id: &Id| &nodes[usize::from(*id)];
+        let Some(Node::Window([_, child])) = nodes.iter().find(|n| matches!(n, Node::Window(_)))
+        else {
+            panic!("no window: {}", plan.pretty(80));
+        };
+        let Node::Order([keys, _]) = node(child) else {
+            panic!("no order under window: {}", plan.pretty(80));
+        };
+        // partition keys, then order keys
+        let keys = node(keys).try_as_list()?.iter().map(node).collect_vec();
+        let Node::Column(b) = keys[0] else {
+            panic!("not a column: {}", plan.pretty(80));
+        };
+        let Node::Column(a) = keys[1] else {
+            panic!("not a column: {}", plan.pretty(80));
+        };
+        assert_eq!((b.column_id, a.column_id), (1, 0));
+        Ok(())
+    }
+
+    #[test]
+    fn bind_distinct_on_incompatible_order() {
+        let error = bind("select distinct on (a) a, b from t order by b").unwrap_err();
+        assert_eq!(error, BindError::OrderKeyNotInDistinct);
+    }
+
+    #[test]
+    fn bind_uncorrelated_exists() {
+        let limit_one = |plan: &RecExpr| {
+            let nodes = plan.as_ref();
+            nodes.iter().any(|n| match n {
+                Node::Exists(e) => matches!(
+                    nodes[usize::from(*e)],
+                    Node::Limit([limit, _, _])
+                        if nodes[usize::from(limit)] == Node::Constant(DataValue::Int32(1))
+                ),
+                _ => false,
+            })
+        };
+        let plan = bind("select a from t where exists (select b from t where b > 1)").unwrap();
+        assert!(limit_one(&plan), "{}", plan.pretty(80));
+
+        // correlated subquery is decorrelated into a join instead
+        let plan =
+            bind("select a from t as x where exists (select b from t where b = x.a)").unwrap();
+        assert!(!limit_one(&plan), "{}", plan.pretty(80));
+    }
+
+    #[test]
+    fn bind_numeric_coercion() {
+        let plan = bind("select a from t where a > 1.5").unwrap();
+        let nodes = plan.as_ref();
+        let node = |id: &Id| &nodes[usize::from(*id)];
+        let Some(Node::Gt([l, r])) = nodes.iter().find(|n| matches!(n, Node::Gt(_))) else {
+            panic!("no comparison: {}", plan.pretty(80));
+        };
+        // the int column is promoted to decimal
+        let Node::Cast([ty, a]) = node(l) else {
+            panic!("column is not casted: {}", plan.pretty(80));
+        };
+        assert_eq!(node(ty), &Node::Type(DataTypeKind::Decimal(None, None)));
+        assert!(matches!(node(a), Node::Column(_)));
+        assert!(matches!(node(r), Node::Constant(DataValue::Decimal(_))));
+
+        // operands of the same type are left as they are
+        let plan = bind("select a + b from t where a > 1").unwrap();
+        assert!(
+            !plan.as_ref().iter().any(|n| matches!(n, Node::Cast(_))),
+            "{}",
+            plan.pretty(80)
+        );
+    }
+
+    #[test]
+    fn bind_invalid_limit() {
+        assert_eq!(
+            bind("select a from t limit a").unwrap_err(),
+            BindError::InvalidLimit("LIMIT", "a".into())
+        );
+        assert_eq!(
+            bind("select a from t limit -1").unwrap_err(),
+            BindError::InvalidLimit("LIMIT", "-1".into())
+        );
+        assert_eq!(
+            bind("select a from t offset null").unwrap_err(),
+            BindError::InvalidLimit("OFFSET", "NULL".into())
+        );
+
+        // constant expressions are evaluated
+        let plan = bind("select a from t limit 1 + 1").unwrap();
+        let nodes = plan.as_ref();
+        let Some(Node::Limit([limit, offset, _])) =
+            nodes.iter().find(|n| matches!(n, Node::Limit(_)))
+        else {
+            panic!("no limit: {}", plan.pretty(80));
+        };
+        assert_eq!(
+            nodes[usize::from(*limit)],
+            Node::Constant(DataValue::Int32(2))
+        );
+        assert_eq!(
+            nodes[usize::from(*offset)],
+            Node::Constant(DataValue::Int32(0))
+        );
+    }
+
+    #[test]
+    fn bind_count() -> Result<()> {
+        let aggs = |sql: &str| -> Result<Vec<Node>> {
+            let plan = bind(sql)?;
+            let nodes = plan.as_ref();
+            let Some(Node::Agg([aggs, _])) = nodes.iter().find(|n| matches!(n, Node::Agg(_)))
+            else {
+                panic!("no agg: {}", plan.pretty(80));
+            };
+            Ok((nodes[usize::from(*aggs)].try_as_list()?.iter())
+                .map(|id| nodes[usize::from(*id)].clone())
+                .collect())
+        };
+        assert!(matches!(
+            aggs("select count(*) from t")?[..],
+            [Node::RowCount]
+        ));
+        assert!(matches!(
+            aggs("select count(a) from t")?[..],
+            [Node::Count(_)]
+        ));
+        assert!(matches!(
+            aggs("select count(*), count(b) from t")?[..],
+            [Node::RowCount, Node::Count(_)]
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn bind_invalid_agg() {
+        assert_eq!(
+            bind("select a, sum(b) from t").unwrap_err(),
+            BindError::ColumnNotInAgg("a".into())
+        );
+        assert_eq!(
+            bind("select sum(a) from t group by b order by a").unwrap_err(),
+            BindError::ColumnNotInAgg("a".into())
+        );
+        assert_eq!(
+            bind("select * from t as x join t as y on sum(x.a) = y.a").unwrap_err(),
+            BindError::AggNotAllowed("JOIN conditions")
+        );
+        assert_eq!(
+            bind("values (count(*))").unwrap_err(),
+            BindError::AggNotAllowed("VALUES")
+        );
+        assert_eq!(
+            bind("select sum(row_number() over ()) from t").unwrap_err(),
+            BindError::WindowInAgg
+        );
+        assert!(bind("select b, sum(a) from t group by b").is_ok());
+    }
+
+    #[test]
+    fn bind_groupby_ordinal() {
+        assert!(bind("select b, sum(a) from t group by 1").is_ok());
+        assert_eq!(
+            bind("select b, sum(a) from t group by 3").unwrap_err(),
+            BindError::InvalidOrdinal("GROUP BY", "3".into())
+        );
+        assert_eq!(
+            bind("select b, sum(a) from t group by 0").unwrap_err(),
+            BindError::InvalidOrdinal("GROUP BY", "0".into())
+        );
+    }
+
+    #[test]
+    fn bind_orderby_output_name() {
+        assert!(bind("select a, b from t order by 2").is_ok());
+        assert!(bind("select b as a from t order by a").is_ok());
+        assert!(bind("select b as a from t order by a + 1").is_ok());
+        assert_eq!(
+            bind("select a from t order by 2").unwrap_err(),
+            BindError::InvalidOrdinal("ORDER BY", "2".into())
+        );
+        assert_eq!(
+            bind("select a, b as a from t order by a").unwrap_err(),
+            BindError::AmbiguousColumn("a".into())
+        );
+    }
+
+    #[test]
+    fn bind_having() {
+        assert!(bind("select b from t group by b having sum(a) > 10").is_ok());
+        assert!(bind("select sum(a) from t having count(*) > 1").is_ok());
+        assert_eq!(
+            bind("select b from t group by b having a > 10").unwrap_err(),
+            BindError::ColumnNotInAgg("a".into())
+        );
+        assert_eq!(
+            bind("select 1 from t having a > 10").unwrap_err(),
+            BindError::ColumnNotInAgg("a".into())
+        );
+    }
+
+    #[test]
+    fn bind_window_without_over() {
+        assert_eq!(
+            bind("select rank() from t").unwrap_err(),
+            BindError::WindowWithoutOver("rank".into())
+        );
+        assert_eq!(
+            bind("select row_number() from t").unwrap_err(),
+            BindError::WindowWithoutOver("row_number".into())
+        );
+        assert!(bind("select rank() over (order by a) from t").is_ok());
+    }
+
+    #[test]
+    fn bind_values() {
+        assert!(bind("values (1, 2), (3, 4)").is_ok());
+        assert!(bind("select column1 + column2 from (values (1, 2), (3, 4))").is_ok());
+        assert!(bind("select y from (values (1, null), (null, 'a')) as v(x, y)").is_ok());
+
+        // each column is distinct even if the first row has equal values
+        let plan = bind("values (null, null), (1, 'a')").unwrap();
+        let nodes = plan.as_ref();
+        let columns = (nodes.iter())
+            .find_map(|node| match node {
+                Node::Values(columns) => Some(columns),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(columns.len(), 2);
+        assert_ne!(columns[0], columns[1]);
+
+        assert_eq!(
+            bind("values (1), (2, 3)").unwrap_err(),
+            BindError::InvalidExpression("VALUES lists must all be the same length".into())
+        );
+    }
+
+    #[test]
+    fn bind_invalid_ordinal() {
+        for (sql, clause, n) in [
+            ("select a from t order by 2", "ORDER BY", "2"),
+            ("select a from t order by 0", "ORDER BY", "0"),
+            ("select a, count(*) from t group by 3", "GROUP BY", "3"),
+        ] {
+            assert_eq!(
+                bind(sql).unwrap_err(),
+                BindError::InvalidOrdinal(clause, n.into()),
+                "{sql}"
+            );
+        }
     }
 }
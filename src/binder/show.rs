@@ -0,0 +1,181 @@
+// Copyright 2023 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use super::*;
+use crate::catalog::{
+    COLUMNS_TABLE_NAME, INFORMATION_SCHEMA_NAME, INTERNAL_SCHEMA_NAME, TABLES_TABLE_NAME,
+};
+
+impl Binder {
+    /// Binds `SHOW TABLES [FROM schema] [LIKE pattern]` as a query on `pg_catalog.tables`.
+    ///
+    /// Internal tables are listed only if the schema is specified.
+    pub(super) fn bind_show_tables(
+        &mut self,
+        schema: Option<Ident>,
+        filter: Option<ShowStatementFilter>,
+    ) -> Result {
+        let mut conditions = vec![];
+        match schema {
+            Some(schema) => {
+                let schema = normalize_ident(&schema);
+                if self.catalog.get_schema_by_name(&schema).is_none() {
+                    return Err(BindError::InvalidSchema(schema));
+                }
+                conditions.push(eq("table_schema", &schema));
+            }
+            None => conditions.push(Expr::InList {
+                expr: Box::new(column("table_schema")),
+                list: vec![
+                    string(INTERNAL_SCHEMA_NAME),
+                    string(INFORMATION_SCHEMA_NAME),
+                ],
+                negated: true,
+            }),
+        }
+        conditions.extend(bind_show_filter("table_name", filter)?);
+        self.bind_show(
+            &format!(
+                "SELECT table_schema, table_name FROM {INTERNAL_SCHEMA_NAME}.{TABLES_TABLE_NAME}"
+            ),
+            conditions,
+        )
+    }
+
+    /// Binds `SHOW COLUMNS FROM table [LIKE pattern]` as a query on `pg_catalog.columns`.
+    pub(super) fn bind_show_columns(
+        &mut self,
+        table_name: ObjectName,
+        filter: Option<ShowStatementFilter>,
+    ) -> Result {
+        let name = lower_case_name(&table_name);
+        let (schema_name, table_name) = split_name(&name)?;
+        if (self.catalog)
+            .get_table_id_by_name(schema_name, table_name)
+            .is_none()
+        {
+            return Err(BindError::InvalidTable(table_name.into()));
+        }
+        let mut conditions = vec![
+            eq("table_schema", schema_name),
+            eq("table_name", table_name),
+        ];
+        conditions.extend(bind_show_filter("column_name", filter)?);
+        self.bind_show(
+            &format!("SELECT column_name, data_type, is_nullable, is_primary FROM {INTERNAL_SCHEMA_NAME}.{COLUMNS_TABLE_NAME}"),
+            conditions,
+        )
+    }
+
+    /// Binds the query of a `SHOW` statement with the conditions as its `WHERE` clause.
+    ///
+    /// The conditions are built as expressions rather than SQL text, so that user input such as
+    /// the `LIKE` pattern is never parsed.
+    fn bind_show(&mut self, sql: &str, conditions: Vec<Expr>) -> Result {
+        let mut stmt = parse(sql).map_err(|_| BindError::InvalidSQL)?.remove(0);
+        let Statement::Query(query) = &mut stmt else {
+            return Err(BindError::InvalidSQL);
+        };
+        let SetExpr::Select(select) = &mut *query.body else {
+            return Err(BindError::InvalidSQL);
+        };
+        select.selection = conditions.into_iter().reduce(|left, right| Expr::BinaryOp {
+            left: Box::new(left),
+            op: BinaryOperator::And,
+            right: Box::new(right),
+        });
+        self.bind_stmt(stmt)
+    }
+}
+
+/// Returns the condition of `LIKE pattern` on the column.
+fn bind_show_filter(
+    column_name: &str,
+    filter: Option<ShowStatementFilter>,
+) -> Result<Option<Expr>> {
+    match filter {
+        None => Ok(None),
+        Some(ShowStatementFilter::Like(pattern)) => Ok(Some(Expr::Like {
+            negated: false,
+            expr: Box::new(column(column_name)),
+            pattern: Box::new(string(&pattern)),
+            escape_char: None,
+        })),
+        Some(_) => Err(BindError::NotSupportedTSQL),
+    }
+}
+
+/// Returns the condition `column = 'value'`.
+fn eq(column_name: &str, value: &str) -> Expr {
+    Expr::BinaryOp {
+        left: Box::new(column(column_name)),
+        op: BinaryOperator::Eq,
+        right: Box::new(string(value)),
+    }
+}
+
+fn column(name: &str) -> Expr {
+    Expr::Identifier(Ident::new(name))
+}
+
+fn string(s: &str) -> Expr {
+    Expr::Value(Value::SingleQuotedString(s.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::catalog::{ColumnCatalog, RootCatalog};
+    use crate::parser::parse;
+
+    #[test]
+    fn bind_show() {
+        let catalog = Arc::new(RootCatalog::new());
+        let col_desc = DataTypeKind::Int32.not_null().to_column("a".into(), false);
+        let col_catalog = ColumnCatalog::new(0, col_desc);
+        catalog
+            .add_table(
+                0,
+                "t".into(),
+                vec![col_catalog],
+                false,
+                vec![],
+                vec![],
+                vec![],
+            )
+            .unwrap();
+
+        let mut binder = Binder::new(catalog.clone());
+        let stmt = parse("show tables").unwrap().remove(0);
+        binder.bind(stmt).unwrap();
+        assert_eq!(binder.output_names(), ["table_schema", "table_name"]);
+
+        let mut binder = Binder::new(catalog.clone());
+        let stmt = parse("show columns from t").unwrap().remove(0);
+        binder.bind(stmt).unwrap();
+        assert_eq!(
+            binder.output_names(),
+            ["column_name", "data_type", "is_nullable", "is_primary"]
+        );
+
+        let mut binder = Binder::new(catalog.clone());
+        let stmt = parse("show columns from u").unwrap().remove(0);
+        assert_eq!(
+            binder.bind(stmt).unwrap_err(),
+            BindError::InvalidTable("u".into())
+        );
+
+        // the pattern is not parsed as SQL
+        let mut binder = Binder::new(catalog.clone());
+        let stmt = parse(r"show tables like 'a\\'").unwrap().remove(0);
+        binder.bind(stmt).unwrap();
+
+        let mut binder = Binder::new(catalog);
+        let stmt = parse("show tables from s").unwrap().remove(0);
+        assert_eq!(
+            binder.bind(stmt).unwrap_err(),
+            BindError::InvalidSchema("s".into())
+        );
+    }
+}
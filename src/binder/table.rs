@@ -3,7 +3,7 @@
 use std::vec::Vec;
 
 use super::*;
-use crate::catalog::{ColumnRefId, INTERNAL_SCHEMA_NAME};
+use crate::catalog::{is_internal_schema, ColumnCatalog, ColumnRefId};
 
 impl Binder {
     /// Binds the FROM clause. Returns a nested [`Join`](Node::Join) plan of tables.
@@ -12,11 +12,15 @@ impl Binder {
     pub(super) fn bind_from(&mut self, tables: Vec<TableWithJoins>) -> Result {
         let mut node = None;
         for table in tables {
+            let lateral = is_lateral(&table.relation);
             let table_node = self.bind_table_with_joins(table)?;
             node = Some(if let Some(node) = node {
                 let ty = self.egraph.add(Node::Inner);
                 let expr = self.egraph.add(Node::true_());
-                self.egraph.add(Node::Join([ty, expr, node, table_node]))
+                match lateral {
+                    true => self.egraph.add(Node::Apply([ty, expr, node, table_node])),
+                    false => self.egraph.add(Node::Join([ty, expr, node, table_node])),
+                }
             } else {
                 table_node
             });
@@ -25,8 +29,8 @@ impl Binder {
             Ok(node)
         } else {
             let zero = self.egraph.add(Node::zero());
-            let row = self.egraph.add(Node::List([zero].into()));
-            Ok(self.egraph.add(Node::Values([row].into())))
+            let column = self.egraph.add(Node::ValuesColumn([zero].into()));
+            Ok(self.egraph.add(Node::Values([column].into())))
         }
     }
 
@@ -45,13 +49,29 @@ impl Binder {
     fn bind_table_with_joins(&mut self, tables: TableWithJoins) -> Result {
         let mut node = self.bind_table_factor(tables.relation)?;
         for join in tables.joins {
+            let lateral = is_lateral(&join.relation);
             let table = self.bind_table_factor(join.relation)?;
             let (ty, condition) = self.bind_join_op(join.join_operator)?;
-            node = self.egraph.add(Node::Join([ty, condition, node, table]));
+            node = match lateral {
+                true => self.plan_apply(ty, condition, node, table)?,
+                false => self.egraph.add(Node::Join([ty, condition, node, table])),
+            };
         }
         Ok(node)
     }
 
+    /// Returns an [`Apply`](Node::Apply) plan that evaluates the LATERAL subquery `right`
+    /// for each row of `left`.
+    fn plan_apply(&mut self, ty: Id, condition: Id, left: Id, right: Id) -> Result {
+        if !matches!(self.node(ty), Node::Inner | Node::LeftOuter) {
+            return Err(BindError::Todo(format!(
+                "{} join with LATERAL subquery",
+                self.node(ty)
+            )));
+        }
+        Ok(self.egraph.add(Node::Apply([ty, condition, left, right])))
+    }
+
     /// Returns a `Scan` plan of table or a plan of subquery.
     ///
     /// # Example
@@ -66,19 +86,52 @@ impl Binder {
                     self.egraph.add(Node::Internal([table_id, cols]))
                 } else {
                     let null = self.egraph.add(Node::null());
-                    self.egraph.add(Node::Scan([table_id, cols, null]))
+                    self.plan_scan(table_id, cols, null)?
                 };
                 Ok(id)
             }
             TableFactor::Derived {
-                subquery, alias, ..
+                lateral,
+                subquery,
+                alias,
             } => {
-                let (id, ctx) = self.bind_query(*subquery)?;
+                let (id, ctx) = match lateral {
+                    true => self.bind_lateral_query(*subquery)?,
+                    false => self.bind_query(*subquery)?,
+                };
+                if !ctx.outer_refs.is_empty() && !lateral {
+                    return Err(BindError::UnsupportedCorrelation(
+                        "subquery in FROM clause".into(),
+                    ));
+                }
+                let (table_name, columns) = match alias {
+                    Some(alias) => (normalize_ident(&alias.name), alias.columns),
+                    None => ("".into(), vec![]),
+                };
+                let schema = self.schema(id);
+                if columns.len() > schema.len() {
+                    return Err(BindError::TooManyColumnAliases(
+                        table_name,
+                        schema.len(),
+                        columns.len(),
+                    ));
+                }
+                // the leading output columns are renamed by column aliases
+                let renamed = &schema[..columns.len()];
+                let mut aliases = (ctx.output_aliases.into_iter())
+                    .filter(|(_, id)| !renamed.contains(id))
+                    .collect_vec();
+                // in the order of the select list, so that the first name of a column is determined
+                let position = |name: &String| ctx.output_names.iter().position(|n| n == name);
+                aliases.sort_by_key(|(name, _)| (position(name), name.clone()));
+                aliases.extend((columns.iter().map(normalize_ident)).zip(renamed.iter().copied()));
                 // move `output_aliases` to current context
-                let table_name = alias.map_or("".into(), |alias| alias.name.value);
-                for (name, mut id) in ctx.output_aliases {
+                for (name, mut id) in aliases {
                     // wrap with `Ref` if the node is not a column unit.
-                    if !matches!(self.node(id), Node::Column(_) | Node::Ref(_)) {
+                    if !matches!(
+                        self.node(id),
+                        Node::Column(_) | Node::Ref(_) | Node::ValuesColumn(_)
+                    ) {
                         id = self.egraph.add(Node::Ref(id));
                     }
                     self.add_alias(name, table_name.clone(), id);
@@ -123,7 +176,11 @@ impl Binder {
 
     fn bind_join_constraint(&mut self, constraint: JoinConstraint) -> Result {
         match constraint {
-            JoinConstraint::On(expr) => self.bind_expr(expr),
+            JoinConstraint::On(expr) => {
+                let id = self.bind_expr(expr)?;
+                self.check_no_agg(id, "JOIN conditions")?;
+                Ok(id)
+            }
             JoinConstraint::None => Ok(self.egraph.add(Node::true_())),
             _ => todo!("Support more join constraints"),
         }
@@ -132,6 +189,7 @@ impl Binder {
     /// Returns a list of all columns in the table.
     ///
     /// This function defines the table name so that it can be referred later.
+    /// Generated columns are returned as their expressions on the other columns.
     ///
     /// # Example
     /// - `bind_table_name(t)` => `(list $1.1 $1.2)`
@@ -149,38 +207,116 @@ impl Binder {
             .ok_or_else(|| BindError::InvalidTable(table_name.into()))?;
 
         let table_alias = match &alias {
-            Some(alias) => &alias.name.value,
-            None => table_name,
+            Some(alias) => normalize_ident(&alias.name),
+            None => table_name.into(),
         };
         if !self
             .current_ctx_mut()
             .table_aliases
-            .insert(table_alias.into())
+            .insert(table_alias.clone())
         {
-            return Err(BindError::DuplicatedTable(table_alias.into()));
+            return Err(BindError::DuplicatedTable(table_alias));
         }
 
+        // columns of each reference to the table are distinct
+        let occurrence = self.table_occurrences.entry(ref_id).or_default();
+        let table_occurrence = *occurrence;
+        *occurrence += 1;
+
         let table = self.catalog.get_table(&ref_id).unwrap();
-        let mut ids = vec![];
-        for (cid, column) in if with_rowid {
+        let columns = if with_rowid {
             table.all_columns_with_rowid()
         } else {
             table.all_columns()
-        } {
-            let column_ref_id = ColumnRefId::from_table(ref_id, cid);
-            let id = self.egraph.add(Node::Column(column_ref_id));
-            // TODO: handle column aliases
-            self.add_alias(column.name().into(), table_alias.into(), id);
-            ids.push(id);
+        };
+        let mut ids = (columns.keys())
+            .map(|cid| {
+                let column_ref_id = ColumnRefId::from_table(ref_id, *cid);
+                let column_ref_id = column_ref_id.with_occurrence(table_occurrence);
+                self.egraph.add(Node::Column(column_ref_id))
+            })
+            .collect_vec();
+        // generated columns are computed from the other columns
+        let values = (columns.values().zip(&ids))
+            .filter(|(column, _)| column.generated().is_none())
+            .map(|(column, id)| (column.name().to_string(), *id))
+            .collect_vec();
+        for (column, id) in columns.values().zip(&mut ids) {
+            if column.generated().is_some() {
+                *id = self.bind_generated_column(column, &values)?;
+            }
+        }
+        // the leading columns are renamed by column aliases
+        let aliases = alias.map_or(vec![], |alias| alias.columns);
+        if aliases.len() > columns.len() {
+            return Err(BindError::TooManyColumnAliases(
+                table_alias,
+                columns.len(),
+                aliases.len(),
+            ));
+        }
+        for (i, (column, id)) in columns.values().zip(&ids).enumerate() {
+            let name = match aliases.get(i) {
+                Some(alias) => normalize_ident(alias),
+                None => column.name().into(),
+            };
+            self.add_alias(name, table_alias.clone(), *id);
         }
         let id = self.egraph.add(Node::List(ids.into()));
         Ok(id)
     }
 
+    /// Binds the expression of a generated column.
+    ///
+    /// `columns` are the names and values of the columns that the expression can refer to.
+    pub(super) fn bind_generated_column(
+        &mut self,
+        column: &ColumnCatalog,
+        columns: &[(String, Id)],
+    ) -> Result {
+        let expr = parse_expr(column.generated().unwrap())
+            .map_err(|e| BindError::InvalidExpression(e.to_string()))?;
+        let mut context = Context::default();
+        for (name, id) in columns {
+            let aliases = context.aliases.entry(name.clone()).or_default();
+            aliases.insert("".into(), *id);
+        }
+        self.contexts.push(context);
+        let ret = self.bind_expr(expr);
+        self.contexts.pop();
+        let id = ret?;
+        if !self.aggs(id).is_empty() || !self.overs(id).is_empty() {
+            return Err(BindError::InvalidExpression(
+                "generation expression cannot contain aggregate or window functions".into(),
+            ));
+        }
+        let ty = self.egraph.add(Node::Type(column.datatype().kind()));
+        let id = self.egraph.add(Node::Cast([ty, id]));
+        self.check_type(id)?;
+        Ok(id)
+    }
+
+    /// Returns a [`Scan`](Node::Scan) plan of the table.
+    ///
+    /// Generated columns in `cols` are computed by a projection over the scan.
+    pub(super) fn plan_scan(&mut self, table: Id, cols: Id, filter: Id) -> Result {
+        let list = self.node(cols).try_as_list()?.to_vec();
+        let columns = (list.iter().copied())
+            .filter(|id| matches!(self.node(*id), Node::Column(_)))
+            .collect_vec();
+        if columns.len() == list.len() {
+            return Ok(self.egraph.add(Node::Scan([table, cols, filter])));
+        }
+        let columns = self.egraph.add(Node::List(columns.into()));
+        let scan = self.egraph.add(Node::Scan([table, columns, filter]));
+        Ok(self.egraph.add(Node::Proj([cols, scan])))
+    }
+
     /// Returns a list of given columns in the table.
     ///
-    /// If `columns` is empty, returns all columns in the table.
+    /// If `columns` is empty, returns all columns in the table except generated columns.
     /// If `table_name` is undefined or any column name is not exist, returns an error.
+    /// Generated columns can not be given, as their values are computed.
     /// (note: )
     ///
     /// # Example
@@ -202,14 +338,20 @@ impl Binder {
         let table = self.catalog.get_table(&table_ref_id).unwrap();
 
         let column_ids = if columns.is_empty() {
-            table.all_columns().keys().cloned().collect_vec()
+            (table.all_columns().values())
+                .filter(|col| col.generated().is_none())
+                .map(|col| col.id())
+                .collect_vec()
         } else {
             let mut ids = vec![];
             for col in columns.iter() {
-                let col_name = col.value.to_lowercase();
+                let col_name = normalize_ident(col);
                 let col = table
                     .get_column_by_name(&col_name)
                     .ok_or_else(|| BindError::InvalidColumn(col_name.clone()))?;
+                if col.generated().is_some() {
+                    return Err(BindError::GeneratedColumn(col_name));
+                }
                 ids.push(col.id());
             }
             ids
@@ -233,15 +375,22 @@ impl Binder {
         let name = lower_case_name(table_name);
         let (schema_name, table_name) = split_name(&name)?;
 
-        let table_ref_id = self
-            .catalog
-            .get_table_id_by_name(schema_name, table_name)
+        let schema = (self.catalog.get_schema_by_name(schema_name))
+            .ok_or_else(|| BindError::InvalidSchema(schema_name.into()))?;
+        let table_id = schema
+            .get_table_id_by_name(table_name)
             .ok_or_else(|| BindError::InvalidTable(table_name.into()))?;
+        let table_ref_id = TableRefId::new(schema.id(), table_id);
         let id = self.egraph.add(Node::Table(table_ref_id));
-        Ok((id, schema_name == INTERNAL_SCHEMA_NAME))
+        Ok((id, is_internal_schema(schema_name)))
     }
 }
 
+/// Returns true if the table is a LATERAL subquery.
+fn is_lateral(table: &TableFactor) -> bool {
+    matches!(table, TableFactor::Derived { lateral: true, .. })
+}
+
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
@@ -256,7 +405,15 @@ mod tests {
         let col_desc = DataTypeKind::Int32.not_null().to_column("a".into(), false);
         let col_catalog = ColumnCatalog::new(0, col_desc);
         catalog
-            .add_table(0, "t".into(), vec![col_catalog], false, vec![])
+            .add_table(
+                0,
+                "t".into(),
+                vec![col_catalog],
+                false,
+                vec![],
+                vec![],
+                vec![],
+            )
             .unwrap();
 
         let stmts = parse("select x.b from (select a as b from t) as x").unwrap();
@@ -266,4 +423,108 @@ mod tests {
             println!("{}", plan.pretty(10));
         }
     }
+
+    #[test]
+    fn bind_alias() {
+        let catalog = Arc::new(RootCatalog::new());
+        let col_desc = DataTypeKind::Int32.not_null().to_column("a".into(), false);
+        let col_catalog = ColumnCatalog::new(0, col_desc);
+        catalog
+            .add_table(
+                0,
+                "t".into(),
+                vec![col_catalog],
+                false,
+                vec![],
+                vec![],
+                vec![],
+            )
+            .unwrap();
+        let bind = |sql: &str| {
+            let stmt = parse(sql).unwrap().remove(0);
+            let mut binder = Binder::new(catalog.clone());
+            binder.bind(stmt).map(|_| binder.output_names().to_vec())
+        };
+
+        assert_eq!(bind("select tt.a as x from t as tt").unwrap(), ["x"]);
+        assert_eq!(
+            bind("select t.a from t as tt").unwrap_err(),
+            BindError::InvalidTable("t".into())
+        );
+        // column aliases
+        assert_eq!(bind("select tt.x from t as tt(x)").unwrap(), ["x"]);
+        assert_eq!(
+            bind("select a from t as tt(x)").unwrap_err(),
+            BindError::InvalidColumn("a".into())
+        );
+        assert_eq!(
+            bind("select * from t as tt(x, y)").unwrap_err(),
+            BindError::TooManyColumnAliases("tt".into(), 1, 2)
+        );
+        assert_eq!(
+            bind("select v from (select a from t) as s(v)").unwrap(),
+            ["v"]
+        );
+        // self-join
+        assert!(bind("select x.a, y.a from t as x, t as y").is_ok());
+        let stmt = parse("select x.a, y.a from t as x, t as y")
+            .unwrap()
+            .remove(0);
+        let plan = Binder::new(catalog.clone()).bind(stmt).unwrap();
+        let columns = (plan.as_ref().iter())
+            .filter_map(|node| match node {
+                Node::Column(column) => Some(column.table_occurrence),
+                _ => None,
+            })
+            .sorted()
+            .collect_vec();
+        assert_eq!(columns, [0, 1]);
+        assert_eq!(
+            bind("select a from t as x, t as y").unwrap_err(),
+            BindError::AmbiguousColumn("a".into())
+        );
+        assert_eq!(
+            bind("select * from t, t").unwrap_err(),
+            BindError::DuplicatedTable("t".into())
+        );
+        // columns of `*` are named by the first name bound to them
+        for _ in 0..10 {
+            assert_eq!(bind("select a as z, * from t").unwrap(), ["z", "a"]);
+        }
+    }
+
+    #[test]
+    fn bind_lateral() {
+        let catalog = Arc::new(RootCatalog::new());
+        let col_desc = DataTypeKind::Int32.not_null().to_column("a".into(), false);
+        let col_catalog = ColumnCatalog::new(0, col_desc);
+        catalog
+            .add_table(
+                0,
+                "t".into(),
+                vec![col_catalog],
+                false,
+                vec![],
+                vec![],
+                vec![],
+            )
+            .unwrap();
+        let bind = |sql: &str| {
+            let stmt = parse(sql).unwrap().remove(0);
+            Binder::new(catalog.clone()).bind(stmt)
+        };
+
+        let plan =
+            bind("select * from t as x, lateral (select x.a + y.a from t as y) as z").unwrap();
+        assert!(plan.as_ref().iter().any(|n| matches!(n, Node::Apply(_))));
+        assert!(bind("select * from t as x cross join lateral (select x.a) as z").is_ok());
+        assert!(matches!(
+            bind("select * from t as x, (select x.a) as z"),
+            Err(BindError::UnsupportedCorrelation(_))
+        ));
+        assert!(matches!(
+            bind("select * from t as x right join lateral (select x.a) as z on true"),
+            Err(BindError::Todo(_))
+        ));
+    }
 }
@@ -13,6 +13,11 @@ pub struct ColumnDesc {
     name: String,
     is_primary: bool,
     is_required: bool,
+    /// The expression computing a generated column, in SQL.
+    ///
+    /// Generated columns are computed from the other columns when they are read.
+    #[serde(default)]
+    generated: Option<String>,
 }
 
 impl ColumnDesc {
@@ -22,6 +27,7 @@ impl ColumnDesc {
             name,
             is_primary,
             is_required,
+            generated: None,
         }
     }
 
@@ -37,6 +43,14 @@ impl ColumnDesc {
         self.is_primary = is_primary;
     }
 
+    pub fn set_generated(&mut self, expr: String) {
+        self.generated = Some(expr);
+    }
+
+    pub fn generated(&self) -> Option<&str> {
+        self.generated.as_deref()
+    }
+
     pub fn is_primary(&self) -> bool {
         self.is_primary
     }
@@ -71,6 +85,9 @@ impl ColumnDesc {
         if self.is_required {
             fields.push(("required", Pretty::display(&self.is_required)));
         }
+        if let Some(expr) = &self.generated {
+            fields.push(("generated", Pretty::display(expr)));
+        }
         Pretty::childless_record("Column", fields)
     }
 }
@@ -140,20 +157,26 @@ impl ColumnCatalog {
     pub fn is_required(&self) -> bool {
         self.desc.is_required()
     }
-}
 
-/// Find the id of the sort key among column catalogs
-pub fn find_sort_key_id(column_infos: &[ColumnCatalog]) -> Option<usize> {
-    let mut key = None;
-    for (id, column_info) in column_infos.iter().enumerate() {
-        if column_info.is_primary() {
-            if key.is_some() {
-                panic!("only one primary key is supported");
-            }
-            key = Some(id);
-        }
+    pub fn generated(&self) -> Option<&str> {
+        self.desc.generated()
     }
-    key
+}
+
+/// Find the indexes of sort key columns among column catalogs.
+///
+/// The rows are sorted by primary key columns in the order of `ordered_pk_ids`.
+/// Returns an empty list if there is no primary key.
+pub fn find_sort_key_id(column_infos: &[ColumnCatalog], ordered_pk_ids: &[ColumnId]) -> Vec<usize> {
+    ordered_pk_ids
+        .iter()
+        .map(|pk| {
+            column_infos
+                .iter()
+                .position(|column_info| column_info.id() == *pk)
+                .expect("primary key not found in columns")
+        })
+        .collect()
 }
 
 #[cfg(test)]
@@ -172,4 +195,18 @@ mod tests {
         col_catalog.set_primary(true);
         assert!(col_catalog.is_primary());
     }
+
+    #[test]
+    fn test_find_sort_key_id() {
+        let columns = ["a", "b", "c"]
+            .iter()
+            .enumerate()
+            .map(|(id, name)| {
+                let desc = DataTypeKind::Int32.not_null().to_column(name.to_string(), false);
+                ColumnCatalog::new(id as ColumnId, desc)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(find_sort_key_id(&columns, &[]), Vec::<usize>::new());
+        assert_eq!(find_sort_key_id(&columns, &[2, 0]), vec![2, 0]);
+    }
 }
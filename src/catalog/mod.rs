@@ -13,9 +13,22 @@ use crate::types::*;
 
 pub static DEFAULT_SCHEMA_NAME: &str = "postgres";
 pub static INTERNAL_SCHEMA_NAME: &str = "pg_catalog";
+pub const INTERNAL_SCHEMA_ID: SchemaId = 1;
+pub static INFORMATION_SCHEMA_NAME: &str = "information_schema";
+pub const INFORMATION_SCHEMA_ID: SchemaId = 2;
 
 static CONTRIBUTORS_TABLE_NAME: &str = "contributors";
 pub const CONTRIBUTORS_TABLE_ID: TableId = 0;
+pub static TABLES_TABLE_NAME: &str = "tables";
+pub const TABLES_TABLE_ID: TableId = 1;
+pub static COLUMNS_TABLE_NAME: &str = "columns";
+pub const COLUMNS_TABLE_ID: TableId = 2;
+pub const INFORMATION_SCHEMA_COLUMNS_TABLE_ID: TableId = 0;
+
+/// Returns true if the schema only contains internal tables.
+pub fn is_internal_schema(name: &str) -> bool {
+    name == INTERNAL_SCHEMA_NAME || name == INFORMATION_SCHEMA_NAME
+}
 
 mod column;
 mod root;
@@ -88,6 +101,8 @@ impl TableRefId {
 pub struct ColumnRefId {
     pub schema_id: SchemaId,
     pub table_id: TableId,
+    /// Distinguishes the references to the same table in a query, e.g. in a self-join.
+    pub table_occurrence: u32,
     pub column_id: ColumnId,
 }
 
@@ -96,6 +111,7 @@ impl ColumnRefId {
         ColumnRefId {
             schema_id: table.schema_id,
             table_id: table.table_id,
+            table_occurrence: 0,
             column_id,
         }
     }
@@ -104,10 +120,19 @@ impl ColumnRefId {
         ColumnRefId {
             schema_id,
             table_id,
+            table_occurrence: 0,
             column_id,
         }
     }
 
+    /// Returns the column of the given reference to the table.
+    pub const fn with_occurrence(self, table_occurrence: u32) -> Self {
+        ColumnRefId {
+            table_occurrence,
+            ..self
+        }
+    }
+
     pub const fn table(&self) -> TableRefId {
         TableRefId {
             schema_id: self.schema_id,
@@ -119,7 +144,11 @@ impl ColumnRefId {
 impl std::fmt::Debug for ColumnRefId {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         // TODO: now ignore database and schema
-        write!(f, "${}.{}", self.table_id, self.column_id)
+        write!(f, "${}.{}", self.table_id, self.column_id)?;
+        if self.table_occurrence != 0 {
+            write!(f, "#{}", self.table_occurrence)?;
+        }
+        Ok(())
     }
 }
 
@@ -147,6 +176,10 @@ impl FromStr for ColumnRefId {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let body = s.strip_prefix('$').ok_or(Self::Err::NoLeadingDollar)?;
+        let (body, table_occurrence) = match body.split_once('#') {
+            Some((body, occurrence)) => (body, occurrence.parse()?),
+            None => (body, 0),
+        };
         let mut parts = body.rsplit('.');
         let column_id = parts.next().ok_or(Self::Err::InvalidColumn)?.parse()?;
         let table_id = parts.next().ok_or(Self::Err::InvalidTable)?.parse()?;
@@ -154,6 +187,7 @@ impl FromStr for ColumnRefId {
         Ok(ColumnRefId {
             schema_id,
             table_id,
+            table_occurrence,
             column_id,
         })
     }
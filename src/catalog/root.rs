@@ -15,6 +15,9 @@ struct Inner {
     schema_idxs: HashMap<String, SchemaId>,
     schemas: HashMap<SchemaId, SchemaCatalog>,
     next_schema_id: SchemaId,
+    /// Table ids are allocated across all user schemas, so that a [`TableId`] alone identifies
+    /// a user table in the storage.
+    next_table_id: TableId,
 }
 
 impl Default for RootCatalog {
@@ -54,14 +57,18 @@ impl RootCatalog {
         inner.schemas.get(id).cloned()
     }
 
+    pub fn add_schema(&self, name: String) -> Result<SchemaId, CatalogError> {
+        let mut inner = self.inner.lock().unwrap();
+        inner.add_schema(name)
+    }
+
     pub fn get_table(&self, table_ref_id: &TableRefId) -> Option<Arc<TableCatalog>> {
         let schema = self.get_schema_by_id(table_ref_id.schema_id)?;
         schema.get_table_by_id(table_ref_id.table_id)
     }
 
     pub fn get_table_by_name(&self, name: &str) -> Option<Arc<TableCatalog>> {
-        let name = name.to_lowercase();
-        let (schema_name, table_name) = split_name(&name)?;
+        let (schema_name, table_name) = split_name(name)?;
         let ref_id = self.get_table_id_by_name(schema_name, table_name)?;
         self.get_table(&ref_id)
     }
@@ -78,10 +85,47 @@ impl RootCatalog {
         columns: Vec<ColumnCatalog>,
         is_materialized_view: bool,
         ordered_pk_ids: Vec<ColumnId>,
+        unique_keys: Vec<Vec<ColumnId>>,
+        foreign_keys: Vec<ForeignKey>,
     ) -> Result<TableId, CatalogError> {
         let mut inner = self.inner.lock().unwrap();
+        let table_id = inner.next_table_id;
         let schema = inner.schemas.get_mut(&schema_id).unwrap();
-        schema.add_table(name, columns, is_materialized_view, ordered_pk_ids)
+        schema.add_table(
+            table_id,
+            name,
+            columns,
+            is_materialized_view,
+            ordered_pk_ids,
+            unique_keys,
+            foreign_keys,
+        )?;
+        inner.next_table_id += 1;
+        Ok(table_id)
+    }
+
+    /// Returns the FOREIGN KEY constraints referencing the table, along with the tables they
+    /// belong to.
+    pub fn get_referencing_foreign_keys(
+        &self,
+        table_ref_id: &TableRefId,
+    ) -> Vec<(TableRefId, ForeignKey)> {
+        let inner = self.inner.lock().unwrap();
+        let mut foreign_keys = vec![];
+        for (schema_id, schema) in &inner.schemas {
+            for (table_id, table) in schema.all_tables() {
+                for fk in table.foreign_keys() {
+                    if fk.ref_table == *table_ref_id {
+                        let table_ref_id = TableRefId {
+                            schema_id: *schema_id,
+                            table_id,
+                        };
+                        foreign_keys.push((table_ref_id, fk.clone()));
+                    }
+                }
+            }
+        }
+        foreign_keys
     }
 
     pub fn drop_table(&self, table_ref_id: TableRefId) {
@@ -115,24 +159,83 @@ impl Inner {
     }
 
     fn add_internals(&mut self) {
-        let schema_id = self.add_schema(INTERNAL_SCHEMA_NAME.into()).unwrap();
-        let table_id = self
-            .schemas
-            .get_mut(&schema_id)
-            .unwrap()
-            .add_table(
-                CONTRIBUTORS_TABLE_NAME.to_string(),
-                vec![ColumnCatalog::new(
-                    0,
-                    DataTypeKind::String
-                        .not_null()
-                        .to_column("github_id".into(), false),
+        let string = |id, name: &str| {
+            let desc = DataTypeKind::String
+                .not_null()
+                .to_column(name.into(), false);
+            ColumnCatalog::new(id, desc)
+        };
+        let int = |id, name: &str| {
+            let desc = DataTypeKind::Int32.not_null().to_column(name.into(), false);
+            ColumnCatalog::new(id, desc)
+        };
+        let bool = |id, name: &str| {
+            let desc = DataTypeKind::Bool.not_null().to_column(name.into(), false);
+            ColumnCatalog::new(id, desc)
+        };
+        let internals = [
+            (
+                INTERNAL_SCHEMA_ID,
+                INTERNAL_SCHEMA_NAME,
+                vec![
+                    (
+                        CONTRIBUTORS_TABLE_ID,
+                        CONTRIBUTORS_TABLE_NAME,
+                        vec![string(0, "github_id")],
+                    ),
+                    (
+                        TABLES_TABLE_ID,
+                        TABLES_TABLE_NAME,
+                        vec![string(0, "table_schema"), string(1, "table_name")],
+                    ),
+                    (
+                        COLUMNS_TABLE_ID,
+                        COLUMNS_TABLE_NAME,
+                        vec![
+                            string(0, "table_schema"),
+                            string(1, "table_name"),
+                            string(2, "column_name"),
+                            string(3, "data_type"),
+                            bool(4, "is_nullable"),
+                            bool(5, "is_primary"),
+                        ],
+                    ),
+                ],
+            ),
+            (
+                INFORMATION_SCHEMA_ID,
+                INFORMATION_SCHEMA_NAME,
+                vec![(
+                    INFORMATION_SCHEMA_COLUMNS_TABLE_ID,
+                    COLUMNS_TABLE_NAME,
+                    vec![
+                        string(0, "table_schema"),
+                        string(1, "table_name"),
+                        string(2, "column_name"),
+                        int(3, "ordinal_position"),
+                        string(4, "is_nullable"),
+                        string(5, "data_type"),
+                    ],
                 )],
-                false,
-                vec![],
-            )
-            .unwrap();
-        assert_eq!(table_id, CONTRIBUTORS_TABLE_ID);
+            ),
+        ];
+        for (schema_id, schema_name, tables) in internals {
+            assert_eq!(self.add_schema(schema_name.into()).unwrap(), schema_id);
+            let schema = self.schemas.get_mut(&schema_id).unwrap();
+            for (table_id, table_name, columns) in tables {
+                schema
+                    .add_table(
+                        table_id,
+                        table_name.into(),
+                        columns,
+                        false,
+                        vec![],
+                        vec![],
+                        vec![],
+                    )
+                    .unwrap();
+            }
+        }
     }
 }
 
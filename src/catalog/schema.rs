@@ -12,7 +12,6 @@ pub struct SchemaCatalog {
     name: String,
     table_idxs: HashMap<String, TableId>,
     tables: HashMap<TableId, Arc<TableCatalog>>,
-    next_table_id: TableId,
 }
 
 impl SchemaCatalog {
@@ -22,28 +21,30 @@ impl SchemaCatalog {
             name,
             table_idxs: HashMap::new(),
             tables: HashMap::new(),
-            next_table_id: 0,
         }
     }
 
     pub(super) fn add_table(
         &mut self,
+        table_id: TableId,
         name: String,
         columns: Vec<ColumnCatalog>,
         is_materialized_view: bool,
         ordered_pk_ids: Vec<ColumnId>,
+        unique_keys: Vec<Vec<ColumnId>>,
+        foreign_keys: Vec<ForeignKey>,
     ) -> Result<TableId, CatalogError> {
         if self.table_idxs.contains_key(&name) {
             return Err(CatalogError::Duplicated("table", name));
         }
-        let table_id = self.next_table_id;
-        self.next_table_id += 1;
         let table_catalog = Arc::new(TableCatalog::new(
             table_id,
             name.clone(),
             columns,
             is_materialized_view,
             ordered_pk_ids,
+            unique_keys,
+            foreign_keys,
         ));
         self.table_idxs.insert(name, table_id);
         self.tables.insert(table_id, table_catalog);
@@ -17,6 +17,21 @@ pub struct TableCatalog {
     next_column_id: ColumnId,
     #[allow(dead_code)]
     ordered_pk_ids: Vec<ColumnId>,
+    /// Sets of columns with a UNIQUE constraint.
+    unique_keys: Vec<Vec<ColumnId>>,
+    /// FOREIGN KEY constraints referencing other tables.
+    foreign_keys: Vec<ForeignKey>,
+}
+
+/// A FOREIGN KEY constraint.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Serialize, Deserialize)]
+pub struct ForeignKey {
+    /// Columns of the referencing table.
+    pub columns: Vec<ColumnId>,
+    /// The referenced table.
+    pub ref_table: TableRefId,
+    /// Columns of the referenced table, in the same order as `columns`.
+    pub ref_columns: Vec<ColumnId>,
 }
 
 impl TableCatalog {
@@ -26,6 +41,8 @@ impl TableCatalog {
         columns: Vec<ColumnCatalog>,
         is_materialized_view: bool,
         ordered_pk_ids: Vec<ColumnId>,
+        unique_keys: Vec<Vec<ColumnId>>,
+        foreign_keys: Vec<ForeignKey>,
     ) -> TableCatalog {
         let mut table_catalog = TableCatalog {
             id,
@@ -35,6 +52,8 @@ impl TableCatalog {
             is_materialized_view,
             next_column_id: 0,
             ordered_pk_ids,
+            unique_keys,
+            foreign_keys,
         };
         table_catalog
             .add_column(ColumnCatalog::new(
@@ -104,6 +123,14 @@ impl TableCatalog {
     pub fn primary_keys(&self) -> Vec<ColumnId> {
         self.ordered_pk_ids.clone()
     }
+
+    pub fn unique_keys(&self) -> &[Vec<ColumnId>] {
+        &self.unique_keys
+    }
+
+    pub fn foreign_keys(&self) -> &[ForeignKey] {
+        &self.foreign_keys
+    }
 }
 
 #[cfg(test)]
@@ -116,7 +143,7 @@ mod tests {
         let col1 = ColumnCatalog::new(1, DataTypeKind::Bool.not_null().to_column("b".into(), false));
 
         let col_catalogs = vec![col0, col1];
-        let table_catalog = TableCatalog::new(0, "t".into(), col_catalogs, false, vec![]);
+        let table_catalog = TableCatalog::new(0, "t".into(), col_catalogs, false, vec![], vec![], vec![]);
 
         assert!(!table_catalog.contains_column("c"));
         assert!(table_catalog.contains_column("a"));
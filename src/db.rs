@@ -1,25 +1,33 @@
 // Copyright 2023 RisingLight Project Authors. Licensed under Apache-2.0.
 
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
-use futures::TryStreamExt;
+use futures::{Stream, TryStreamExt};
+use itertools::Itertools;
 use risinglight_proto::rowset::block_statistics::BlockStatisticsType;
 
 use crate::array::{
     ArrayBuilder, ArrayBuilderImpl, Chunk, DataChunk, I32ArrayBuilder, Utf8ArrayBuilder,
 };
+use crate::binder::{lower_case_name, split_name, BindError};
 use crate::catalog::RootCatalogRef;
-use crate::parser::{parse, ParserError};
-use crate::planner::Explain;
+use crate::executor::BoxedExecutor;
+use crate::parser::{
+    parse, parse_backup, parse_object_name, BackupStatement, ObjectName, ParserError, Statement,
+    Value,
+};
+use crate::planner::{Config, Explain, Expr, Optimizer, RecExpr, Statistic};
 use crate::storage::{
     InMemoryStorage, SecondaryStorage, SecondaryStorageOptions, Storage, StorageColumnRef,
     StorageImpl, Table,
 };
+use crate::types::{DataTypeKind, DataValue};
 
 /// The database instance.
 pub struct Database {
     catalog: RootCatalogRef,
     storage: StorageImpl,
+    config: Mutex<Config>,
 }
 
 impl Database {
@@ -29,6 +37,7 @@ impl Database {
         Database {
             catalog: storage.catalog().clone(),
             storage: StorageImpl::InMemoryStorage(Arc::new(storage)),
+            config: Default::default(),
         }
     }
 
@@ -39,9 +48,15 @@ impl Database {
         Database {
             catalog: storage.catalog().clone(),
             storage: StorageImpl::SecondaryStorage(storage),
+            config: Default::default(),
         }
     }
 
+    /// Update the configuration for the following queries.
+    pub fn update_config(&self, f: impl FnOnce(&mut Config)) {
+        f(&mut self.config.lock().unwrap());
+    }
+
     pub async fn shutdown(&self) -> Result<(), Error> {
         if let StorageImpl::SecondaryStorage(storage) = &self.storage {
             storage.shutdown().await?;
@@ -55,7 +70,11 @@ impl Database {
         let mut column_type = Utf8ArrayBuilder::new();
         let mut column_is_null = Utf8ArrayBuilder::new();
         let mut column_is_primary = Utf8ArrayBuilder::new();
-        let table_catalog = self.catalog.get_table_by_name(table_name).unwrap();
+        let (schema_name, table_name) = split_table_name(&parse_object_name(table_name)?)?;
+        let table_catalog = (self.catalog)
+            .get_table_id_by_name(&schema_name, &table_name)
+            .and_then(|id| self.catalog.get_table(&id))
+            .ok_or(BindError::InvalidTable(table_name))?;
 
         let all_columns = table_catalog.all_columns();
         for (id, column) in &all_columns {
@@ -186,43 +205,356 @@ impl Database {
         }
     }
 
+    /// Backup a table to a file, or restore a table from it.
+    async fn run_backup(&self, stmt: BackupStatement) -> Result<Vec<Chunk>, Error> {
+        let StorageImpl::SecondaryStorage(storage) = &self.storage else {
+            return Err(Error::Internal(
+                "this storage engine doesn't support backup".to_string(),
+            ));
+        };
+        match stmt {
+            BackupStatement::Backup { table, path } => {
+                let (schema_name, table_name) = split_table_name(&table)?;
+                let table_id = (self.catalog)
+                    .get_table_id_by_name(&schema_name, &table_name)
+                    .ok_or(BindError::InvalidTable(table_name))?;
+                storage.backup_table(table_id, path).await?;
+            }
+            BackupStatement::Restore {
+                table,
+                path,
+                replace,
+            } => {
+                let (schema_name, table_name) = split_table_name(&table)?;
+                let schema_id = (self.catalog)
+                    .get_schema_id_by_name(&schema_name)
+                    .ok_or(BindError::InvalidSchema(schema_name))?;
+                (storage.restore_table(schema_id, &table_name, path, replace)).await?;
+            }
+        }
+        Ok(vec![])
+    }
+
+    /// Reads the row count of tables scanned by the plan from the storage.
+    ///
+    /// Only the secondary storage provides statistics.
+    /// Tables without any rowset are left unknown.
+    async fn get_statistic(&self, plan: &RecExpr) -> Result<Statistic, Error> {
+        let mut stat = Statistic::default();
+        let StorageImpl::SecondaryStorage(storage) = &self.storage else {
+            return Ok(stat);
+        };
+        for node in plan.as_ref() {
+            let Expr::Scan([table, _, _]) = node else {
+                continue;
+            };
+            let table_id = plan[*table].as_table();
+            let txn = storage.get_table(table_id)?.read().await?;
+            let row_count = txn.aggreagate_block_stat(&[(
+                BlockStatisticsType::RowCount,
+                StorageColumnRef::Idx(0),
+            )]);
+            match row_count[0].as_usize() {
+                Ok(Some(0)) | Ok(None) | Err(_) => {}
+                Ok(Some(rows)) => stat.add_row_count(table_id, rows as u32),
+            }
+        }
+        Ok(stat)
+    }
+
     /// Run SQL queries and return the outputs.
     pub async fn run(&self, sql: &str) -> Result<Vec<Chunk>, Error> {
         if let Some(cmdline) = sql.trim().strip_prefix('\\') {
             return self.run_internal(cmdline).await;
         }
+        if let Some(stmt) = parse_backup(sql)? {
+            return self.run_backup(stmt).await;
+        }
 
-        let optimizer = crate::planner::Optimizer::new(
-            self.catalog.clone(),
-            crate::planner::Config {
-                enable_range_filter_scan: self.storage.support_range_filter_scan(),
-                table_is_sorted_by_primary_key: self.storage.table_is_sorted_by_primary_key(),
-            },
-        );
-
+        let mut config = self.query_config();
         let stmts = parse(sql)?;
         let mut outputs: Vec<Chunk> = vec![];
         for stmt in stmts {
-            let mut binder = crate::binder::Binder::new(self.catalog.clone());
-            let bound = binder.bind(stmt)?;
-            println!("===========\nbefore optimze binder:\n{}",Explain::of(&bound).to_string());
-            let optimized = optimizer.optimize(&bound);
-            println!("===========\noptimized res:\n{}\n===========",Explain::of(&optimized).to_string());
-            let executor = match self.storage.clone() {
-                StorageImpl::InMemoryStorage(s) => {
-                    crate::executor::build(self.catalog.clone(), s, &optimized)
-                }
-                StorageImpl::SecondaryStorage(s) => {
-                    crate::executor::build(self.catalog.clone(), s, &optimized)
-                }
-            };
+            if let Statement::SetVariable {
+                variable, value, ..
+            } = &stmt
+            {
+                self.set_config(variable, value)?;
+                config = self.query_config();
+                outputs.push(Chunk::new(vec![]));
+                continue;
+            }
+            let (executor, names) = self.build_executor(stmt, &config).await?;
             let output = executor.try_collect().await?;
-            let chunk = Chunk::new(output);
-            // TODO: set name
+            let mut chunk = Chunk::new(output);
+            if !names.is_empty() {
+                chunk.set_header(names);
+            }
+            chunk.set_float_precision(config.float_precision);
             outputs.push(chunk);
         }
         Ok(outputs)
     }
+
+    /// Run SQL queries and stream the outputs, yielding at most `fetch_size` rows at a time
+    /// regardless of the chunk size of executors.
+    ///
+    /// A `fetch_size` of 0 yields all remaining rows of each query at once.
+    pub fn run_stream<'a>(
+        &'a self,
+        sql: &'a str,
+        fetch_size: usize,
+    ) -> impl Stream<Item = Result<DataChunk, Error>> + 'a {
+        async_stream::try_stream! {
+            let mut config = self.query_config();
+            for stmt in parse(sql)? {
+                if let Statement::SetVariable { variable, value, .. } = &stmt {
+                    self.set_config(variable, value)?;
+                    config = self.query_config();
+                    continue;
+                }
+                let (executor, _) = self.build_executor(stmt, &config).await?;
+                if fetch_size == 0 {
+                    // concatenate all chunks at once
+                    let chunks: Vec<DataChunk> = executor
+                        .try_filter(|chunk| futures::future::ready(chunk.cardinality() != 0))
+                        .try_collect()
+                        .await?;
+                    if !chunks.is_empty() {
+                        yield concat_chunks(&chunks);
+                    }
+                    continue;
+                }
+                let mut buffer: Option<DataChunk> = None;
+                for await chunk in executor {
+                    let chunk = match buffer.take() {
+                        Some(buffer) => concat_chunks(&[buffer, chunk?]),
+                        None => chunk?,
+                    };
+                    let mut offset = 0;
+                    while chunk.cardinality() - offset >= fetch_size {
+                        yield chunk.slice(offset..offset + fetch_size);
+                        offset += fetch_size;
+                    }
+                    if offset < chunk.cardinality() {
+                        buffer = Some(chunk.slice(offset..));
+                    }
+                }
+                if let Some(chunk) = buffer {
+                    yield chunk;
+                }
+            }
+        }
+    }
+
+    /// Returns the configuration for the following queries.
+    fn query_config(&self) -> Config {
+        let mut config = self.config.lock().unwrap().clone();
+        config.enable_range_filter_scan = self.storage.support_range_filter_scan();
+        config.table_is_sorted_by_primary_key = self.storage.table_is_sorted_by_primary_key();
+        config
+    }
+
+    /// Sets a configuration option for the following queries by `SET name = value`.
+    ///
+    /// Options of the storage can not be set.
+    fn set_config(
+        &self,
+        variable: &ObjectName,
+        value: &[crate::parser::Expr],
+    ) -> Result<(), Error> {
+        let name = variable.to_string().to_ascii_lowercase();
+        let value = match value {
+            [crate::parser::Expr::Value(Value::SingleQuotedString(s))] => s.to_ascii_lowercase(),
+            _ => value.iter().join(", ").to_ascii_lowercase(),
+        };
+        let invalid = || Error::InvalidConfigValue {
+            name: name.clone(),
+            value: value.clone(),
+        };
+        let parse_bool = || match value.as_str() {
+            "true" | "on" => Ok(true),
+            "false" | "off" => Ok(false),
+            _ => Err(invalid()),
+        };
+        let parse_usize = || value.parse::<usize>().map_err(|_| invalid());
+        // NULL means unlimited or unset
+        let parse_optional = || match value.as_str() {
+            "null" => Ok(None),
+            _ => parse_usize().map(Some),
+        };
+        let mut config = self.config.lock().unwrap();
+        match name.as_str() {
+            "wrapping_arithmetic" => config.wrapping_arithmetic = parse_bool()?,
+            "strict_casts" => config.strict_casts = parse_bool()?,
+            "enable_eager_aggregation" => config.enable_eager_aggregation = parse_bool()?,
+            "enable_bushy_join" => config.enable_bushy_join = parse_bool()?,
+            "scan_parallelism" => config.scan_parallelism = parse_usize()?.max(1),
+            "hash_agg_memory_budget" => config.hash_agg_memory_budget = parse_optional()?,
+            "hash_join_memory_budget" => config.hash_join_memory_budget = parse_optional()?,
+            "pushdown_rounds" => config.pushdown_rounds = parse_usize()?,
+            "egraph_iter_limit" => config.egraph_iter_limit = parse_usize()?,
+            "egraph_node_limit" => config.egraph_node_limit = parse_usize()?,
+            "join_reorder_limit" => config.join_reorder_limit = parse_usize()?,
+            "float_precision" => config.float_precision = parse_optional()?,
+            _ => return Err(Error::UnknownConfig(name.clone())),
+        }
+        Ok(())
+    }
+
+    /// Plans a statement and builds its executor.
+    ///
+    /// Returns the executor and the output column names if the statement is a query.
+    async fn build_executor(
+        &self,
+        stmt: Statement,
+        config: &Config,
+    ) -> Result<(BoxedExecutor, Vec<String>), Error> {
+        let mut binder = crate::binder::Binder::new(self.catalog.clone());
+        let bound = binder.bind(stmt)?;
+        if !binder.params().is_empty() {
+            return Err(Error::ParameterCount {
+                expected: binder.params().len(),
+                actual: 0,
+            });
+        }
+        let names = binder.output_names().to_vec();
+        let stat = self.get_statistic(&bound).await?;
+        let optimizer = Optimizer::new(self.catalog.clone(), stat.clone(), config.clone());
+        let optimized = optimizer.optimize(&bound);
+        Ok((self.build_plan(&optimized, stat, config), names))
+    }
+
+    /// Builds the executor of an optimized plan.
+    ///
+    /// The statistic is the one used to optimize the plan, so that EXPLAIN shows the same costs.
+    fn build_plan(&self, plan: &RecExpr, stat: Statistic, config: &Config) -> BoxedExecutor {
+        let catalog = self.catalog.clone();
+        match self.storage.clone() {
+            StorageImpl::InMemoryStorage(s) => {
+                crate::executor::build(config.clone(), stat, catalog, s, plan)
+            }
+            StorageImpl::SecondaryStorage(s) => {
+                crate::executor::build(config.clone(), stat, catalog, s, plan)
+            }
+        }
+    }
+
+    /// Prepares a statement with parameters `?` or `$n`.
+    ///
+    /// The plan is optimized once with parameters and can be executed many times by
+    /// [`execute`](Self::execute).
+    pub async fn prepare(&self, sql: &str) -> Result<PreparedStatement, Error> {
+        let mut stmts = parse(sql)?;
+        if stmts.len() != 1 {
+            return Err(Error::Internal(format!(
+                "expect exactly 1 statement to prepare, but got {}",
+                stmts.len()
+            )));
+        }
+        let mut binder = crate::binder::Binder::new(self.catalog.clone());
+        let bound = binder.bind(stmts.remove(0))?;
+        let stat = self.get_statistic(&bound).await?;
+        let optimizer = Optimizer::new(self.catalog.clone(), stat.clone(), self.query_config());
+        Ok(PreparedStatement {
+            plan: optimizer.optimize(&bound),
+            stat,
+            params: binder.params().to_vec(),
+            names: binder.output_names().to_vec(),
+        })
+    }
+
+    /// Executes a prepared statement with parameter values.
+    pub async fn execute(
+        &self,
+        stmt: &PreparedStatement,
+        params: &[DataValue],
+    ) -> Result<Chunk, Error> {
+        let plan = stmt.bind_params(params)?;
+        let config = self.query_config();
+        let executor = self.build_plan(&plan, stmt.stat.clone(), &config);
+        let mut chunk = Chunk::new(executor.try_collect().await?);
+        if !stmt.names.is_empty() {
+            chunk.set_header(stmt.names.clone());
+        }
+        chunk.set_float_precision(config.float_precision);
+        Ok(chunk)
+    }
+}
+
+/// A statement prepared by [`Database::prepare`].
+#[derive(Debug, Clone)]
+pub struct PreparedStatement {
+    /// The optimized plan with placeholders.
+    plan: RecExpr,
+    /// The statistic used to optimize the plan.
+    stat: Statistic,
+    /// The types of parameters. `None` if it is unknown.
+    params: Vec<Option<DataTypeKind>>,
+    /// The output column names.
+    names: Vec<String>,
+}
+
+impl PreparedStatement {
+    /// Returns the number of parameters.
+    pub fn param_count(&self) -> usize {
+        self.params.len()
+    }
+
+    /// Returns the plan with placeholders replaced by parameter values.
+    ///
+    /// Values are casted to the inferred types of parameters.
+    fn bind_params(&self, params: &[DataValue]) -> Result<RecExpr, Error> {
+        if params.len() != self.params.len() {
+            return Err(Error::ParameterCount {
+                expected: self.params.len(),
+                actual: params.len(),
+            });
+        }
+        let mut values = vec![];
+        for (i, (value, ty)) in params.iter().zip(&self.params).enumerate() {
+            values.push(match ty {
+                Some(ty) if !value.is_null() => {
+                    value.cast(ty).map_err(|_| Error::ParameterType {
+                        index: i + 1,
+                        expected: ty.clone(),
+                        value: value.clone(),
+                    })?
+                }
+                _ => value.clone(),
+            });
+        }
+        let nodes = (self.plan.as_ref().iter())
+            .map(|node| match node {
+                Expr::Placeholder(i) => Expr::Constant(values[i.0 as usize - 1].clone()),
+                node => node.clone(),
+            })
+            .collect::<Vec<_>>();
+        Ok(RecExpr::from(nodes))
+    }
+}
+
+/// Concatenates a non-empty list of chunks with the same schema in rows.
+fn concat_chunks(chunks: &[DataChunk]) -> DataChunk {
+    if chunks[0].column_count() == 0 {
+        return DataChunk::no_column(chunks.iter().map(|c| c.cardinality()).sum());
+    }
+    (0..chunks[0].column_count())
+        .map(|i| {
+            let mut builder = ArrayBuilderImpl::from_type_of_array(chunks[0].array_at(i));
+            for chunk in chunks {
+                builder.append(chunk.array_at(i));
+            }
+            builder.finish()
+        })
+        .collect()
+}
+
+/// Split a table name into the normalized schema name and table name.
+fn split_table_name(name: &ObjectName) -> Result<(String, String), BindError> {
+    let name = lower_case_name(name);
+    let (schema_name, table_name) = split_name(&name)?;
+    Ok((schema_name.into(), table_name.into()))
 }
 
 /// The error type of database operations.
@@ -253,6 +585,535 @@ pub enum Error {
         #[backtrace]
         crate::storage::TracedStorageError,
     ),
+    #[error("expected {expected} parameters, but got {actual}")]
+    ParameterCount { expected: usize, actual: usize },
+    #[error("parameter ${index} expects type {expected}, but got {value}")]
+    ParameterType {
+        index: usize,
+        expected: DataTypeKind,
+        value: DataValue,
+    },
+    #[error("unrecognized configuration parameter \"{0}\"")]
+    UnknownConfig(String),
+    #[error("invalid value for parameter \"{name}\": {value}")]
+    InvalidConfigValue { name: String, value: String },
     #[error("Internal error: {0}")]
     Internal(String),
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use egg::Id;
+    use itertools::Itertools;
+
+    use super::*;
+    use crate::array::{chunk_to_json_lines, datachunk_to_sqllogictest_string};
+    use crate::catalog::{TableRefId, DEFAULT_SCHEMA_NAME};
+    use crate::planner::{CostProvider, TypeSchemaAnalysis};
+    use crate::storage::{ScanOptions, StorageResult, Transaction, TxnIterator};
+    use crate::types::DataValue;
+
+    /// Creates an in-memory database and runs the statements to set it up.
+    async fn in_memory(sql: &str) -> Database {
+        let db = Database::new_in_memory();
+        db.run(sql).await.unwrap();
+        db
+    }
+
+    /// Creates an on-disk database and runs the statements to set it up.
+    async fn on_disk(sql: &str) -> Database {
+        let db = Database::new_on_disk(SecondaryStorageOptions::default_for_test()).await;
+        db.run(sql).await.unwrap();
+        db
+    }
+
+    /// Binds a statement in the database.
+    fn bind(db: &Database, sql: &str) -> RecExpr {
+        let stmt = parse(sql).unwrap().remove(0);
+        crate::binder::Binder::new(db.catalog.clone())
+            .bind(stmt)
+            .unwrap()
+    }
+
+    /// Returns an optimizer of the database with the default statistics.
+    fn optimizer(db: &Database, config: Config) -> Optimizer {
+        Optimizer::new(db.catalog.clone(), Statistic::default(), config)
+    }
+
+    /// Returns true if any node in the plan satisfies `f`.
+    fn has_node(plan: &RecExpr, f: impl Fn(&Expr) -> bool) -> bool {
+        plan.as_ref().iter().any(f)
+    }
+
+    /// Returns the number of rows in the output.
+    fn row_count(chunk: &Chunk) -> usize {
+        chunk.data_chunks().iter().map(|c| c.cardinality()).sum()
+    }
+
+    #[tokio::test]
+    async fn deterministic_explain() {
+        let db = in_memory("create table t(a int, b int); create table u(c int, d int);").await;
+
+        let sql = "select a, sum(d) from t, u
+            where a = c and 10 > b and d <> 1 and (b = 1 or 2 = d)
+            group by a order by a";
+        let explain = || {
+            let optimizer = optimizer(&db, Config::default());
+            let plan = optimizer.optimize(&bind(&db, sql));
+            let costs = optimizer.costs(&plan);
+            Explain::of(&plan)
+                .with_costs(&costs)
+                .with_catalog(&db.catalog)
+                .to_string()
+        };
+        let first = explain();
+        for _ in 0..5 {
+            assert_eq!(explain(), first);
+        }
+    }
+
+    #[tokio::test]
+    async fn custom_cost_provider() {
+        let db = in_memory("create table t(a int primary key comment 'required', b int);").await;
+
+        /// A storage where seeking is much more expensive than scanning.
+        struct ExpensiveSeek;
+        impl CostProvider for ExpensiveSeek {
+            fn seek_cost(&self, _table: TableRefId) -> f32 {
+                1e12
+            }
+        }
+
+        let config = Config {
+            enable_range_filter_scan: true,
+            ..Default::default()
+        };
+        let plan = bind(&db, "select * from t where a = 1");
+        let has_filter = |plan: &RecExpr| has_node(plan, |e| matches!(e, Expr::Filter(_)));
+
+        // seek by the primary key
+        let seek = optimizer(&db, config.clone()).optimize(&plan);
+        assert!(!has_filter(&seek), "{seek}");
+
+        // scan the table and filter
+        let scan = optimizer(&db, config)
+            .with_cost_provider(Arc::new(ExpensiveSeek))
+            .optimize(&plan);
+        assert!(has_filter(&scan), "{scan}");
+    }
+
+    #[tokio::test]
+    async fn optimizer_time_limit() {
+        let tables = 8;
+        let create = (0..tables)
+            .map(|i| format!("create table t{i}(a int, b int);"))
+            .join("\n");
+        let db = in_memory(&create).await;
+        // a join of many tables with a deeply nested predicate
+        let from = (0..tables).map(|i| format!("t{i}")).join(", ");
+        let join = (1..tables)
+            .map(|i| format!("t{}.b = t{i}.a", i - 1))
+            .join(" and ");
+        let nested = (0..50).fold("t0.a".to_string(), |e, i| format!("({e} + {i})"));
+        let sql = format!("select count(*) from {from} where {join} and {nested} > 0");
+
+        let limit = Duration::from_millis(100);
+        db.update_config(|config| config.optimizer_time_limit = limit);
+        let start = Instant::now();
+        let outputs = db.run(&sql).await.unwrap();
+        // each run of the e-graph stops at the first iteration past the limit
+        assert!(start.elapsed() < limit * 30, "{:?}", start.elapsed());
+        assert_eq!(
+            outputs[0].get_first_data_chunk().row(0).get(0),
+            DataValue::Int32(0)
+        );
+    }
+
+    #[tokio::test]
+    async fn greedy_join_order() {
+        let tables = 6;
+        let create = (0..tables)
+            .map(|i| {
+                format!(
+                    "create table t{i}(a int, b int); insert into t{i} values ({i}, {});",
+                    i + 1
+                )
+            })
+            .join("\n");
+        let db = in_memory(&create).await;
+        let mut stat = Statistic::default();
+        let mut table_ids = vec![];
+        for i in 0..tables {
+            let table_id = (db.catalog)
+                .get_table_id_by_name(DEFAULT_SCHEMA_NAME, &format!("t{i}"))
+                .unwrap();
+            // the later tables are smaller
+            stat.add_row_count(table_id, 1000 * (tables - i) as u32);
+            table_ids.push(table_id);
+        }
+        // a chain of joins: t0 - t1 - ... - t5
+        let from = (0..tables).map(|i| format!("t{i}")).join(", ");
+        let join = (1..tables)
+            .map(|i| format!("t{}.b = t{i}.a", i - 1))
+            .join(" and ");
+        let sql = format!("select * from {from} where {join}");
+
+        let config = Config {
+            join_reorder_limit: 4,
+            ..Default::default()
+        };
+        let optimizer = Optimizer::new(db.catalog.clone(), stat, config);
+        let plan = optimizer.optimize(&bind(&db, &sql));
+
+        // no cross joins
+        assert!(!has_node(&plan, |e| matches!(e, Expr::Join(_))), "{plan}");
+        // a left-deep tree, which is not reordered by rules
+        let nodes = plan.as_ref();
+        let is_join = |id: Id| match nodes[usize::from(id)] {
+            Expr::HashJoin(_) => true,
+            Expr::Proj([_, child]) => matches!(nodes[usize::from(child)], Expr::HashJoin(_)),
+            _ => false,
+        };
+        assert!(
+            !has_node(
+                &plan,
+                |e| matches!(e, Expr::HashJoin([.., right]) if is_join(*right))
+            ),
+            "{plan}"
+        );
+        // joined from the smallest table along the chain
+        let scanned = (plan.as_ref().iter())
+            .filter_map(|e| match e {
+                Expr::Table(id) => Some(*id),
+                _ => None,
+            })
+            .collect_vec();
+        assert_eq!(scanned, table_ids.iter().rev().cloned().collect_vec());
+
+        // the output columns are kept in order
+        db.update_config(|config| config.join_reorder_limit = 4);
+        let outputs = db.run(&sql).await.unwrap();
+        let expected = (0..tables)
+            .flat_map(|i| [i.to_string(), (i + 1).to_string()])
+            .collect_vec();
+        assert_eq!(datachunk_to_sqllogictest_string(&outputs[0]), [expected]);
+    }
+
+    #[tokio::test]
+    async fn prepared_statement() {
+        let db = in_memory(
+            "create table t(id int, v varchar);
+            insert into t values (1, 'a'), (2, 'b'), (3, 'c');",
+        )
+        .await;
+
+        let stmt = db.prepare("select * from t where id = ?").await.unwrap();
+        assert_eq!(stmt.param_count(), 1);
+        let chunk = db.execute(&stmt, &[DataValue::Int32(2)]).await.unwrap();
+        assert_eq!(datachunk_to_sqllogictest_string(&chunk), [["2", "b"]]);
+        // parameters are casted to the inferred type
+        let chunk = db.execute(&stmt, &[DataValue::Int64(3)]).await.unwrap();
+        assert_eq!(datachunk_to_sqllogictest_string(&chunk), [["3", "c"]]);
+
+        assert!(matches!(
+            db.execute(&stmt, &[]).await,
+            Err(Error::ParameterCount {
+                expected: 1,
+                actual: 0
+            })
+        ));
+        assert!(matches!(
+            db.execute(&stmt, &[DataValue::String("x".into())]).await,
+            Err(Error::ParameterType { index: 1, .. })
+        ));
+        assert!(matches!(
+            db.run("select * from t where id = ?").await,
+            Err(Error::ParameterCount { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn json_lines_output() {
+        let db = in_memory(
+            "create table t(a int, b varchar, c double, d date);
+            insert into t values (1, 'x\"y', 1.5, date '2023-01-01'), (2, null, null, null);",
+        )
+        .await;
+
+        let outputs = db
+            .run("select a, b as name, c, t.d, a + 1 from t order by a;")
+            .await
+            .unwrap();
+        assert_eq!(
+            chunk_to_json_lines(&outputs[0]),
+            [
+                r#"{"a":1,"name":"x\"y","c":1.5,"d":"2023-01-01","?column?":2}"#,
+                r#"{"a":2,"name":null,"c":null,"d":null,"?column?":3}"#,
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn explain_analyze() {
+        let db = in_memory(
+            "create table t(v int);
+            insert into t values (1), (2), (3), (4), (5);",
+        )
+        .await;
+
+        let outputs = db
+            .run("explain analyze select v from t where v > 3;")
+            .await
+            .unwrap();
+        let explain = outputs[0].get_first_data_chunk().row(0).get(0).to_string();
+        assert!(explain.contains("Scan"), "{explain}");
+        assert!(explain.contains("actual_rows: 5"), "{explain}");
+        assert!(explain.contains("Filter"), "{explain}");
+        assert!(explain.contains("actual_rows: 2"), "{explain}");
+        assert!(explain.contains("time: "), "{explain}");
+
+        let outputs = db.run("explain analyze delete from t;").await.unwrap();
+        let explain = outputs[0].get_first_data_chunk().row(0).get(0).to_string();
+        assert!(explain.contains("Delete"), "{explain}");
+    }
+
+    #[tokio::test]
+    async fn run_stream_with_fetch_size() {
+        // insert in separate statements so that the scan yields multiple chunks
+        let inserts = (0..3)
+            .map(|i| {
+                let values = (0..4).map(|j| format!("({})", i * 4 + j)).join(", ");
+                format!("insert into t values {values};")
+            })
+            .join("\n");
+        let db = in_memory(&format!("create table t(v int);\n{inserts}")).await;
+
+        let batches: Vec<DataChunk> = db
+            .run_stream("select v from t", 5)
+            .try_collect()
+            .await
+            .unwrap();
+        let sizes = batches.iter().map(|c| c.cardinality()).collect_vec();
+        assert_eq!(sizes, [5, 5, 2]);
+        let values = (batches.iter())
+            .flat_map(|c| c.rows().map(|row| row.get(0)).collect_vec())
+            .collect_vec();
+        assert_eq!(values, (0..12).map(DataValue::Int32).collect_vec());
+
+        // 0 means all remaining rows
+        let batches: Vec<DataChunk> = db
+            .run_stream("select v from t", 0)
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].cardinality(), 12);
+    }
+
+    #[tokio::test]
+    async fn empty_relation_reports_columns() {
+        let db =
+            in_memory("create table t(a int, b varchar); insert into t values (1, 'x');").await;
+        for (sql, columns) in [
+            ("select a, b from t where false", vec!["a", "b"]),
+            ("select b, a + 1 as c from t limit 0", vec!["b", "c"]),
+            (
+                "select s.b, t.a from (select a, b from t where false) as s join t on s.a = t.a",
+                vec!["b", "a"],
+            ),
+        ] {
+            let outputs = db.run(sql).await.unwrap();
+            assert_eq!(outputs[0].header().unwrap(), columns, "{sql}");
+            assert_eq!(row_count(&outputs[0]), 0, "{sql}");
+
+            // the empty node has the schema and types of its child
+            let stmt = db.prepare(sql).await.unwrap();
+            let mut egraph = egg::EGraph::new(TypeSchemaAnalysis {
+                catalog: db.catalog.clone(),
+            });
+            let root = egraph.add_expr(&stmt.plan);
+            let Expr::Empty(children) = &egraph[root].nodes[0] else {
+                panic!("not empty: {}", stmt.plan);
+            };
+            let child = children[0];
+            assert_eq!(egraph[root].data.schema.len(), columns.len(), "{sql}");
+            assert_eq!(egraph[root].data.schema, egraph[child].data.schema, "{sql}");
+            assert_eq!(
+                egraph[root].data.type_.as_ref().unwrap(),
+                egraph[child].data.type_.as_ref().unwrap(),
+                "{sql}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn concurrent_unique_inserts() {
+        let db = on_disk("create table t(a int primary key, b int, unique (a, b))").await;
+        let results =
+            futures::future::join_all((0..8).map(|_| db.run("insert into t values (1, 1)"))).await;
+        assert_eq!(results.iter().filter(|r| r.is_ok()).count(), 1);
+        let count = db.run("select count(*) from t").await.unwrap();
+        assert_eq!(
+            count[0].get_first_data_chunk().row(0).get(0),
+            DataValue::Int32(1)
+        );
+    }
+
+    #[tokio::test]
+    async fn backup_and_restore() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("t.bak");
+        let backup = format!("backup table t to '{}'", path.display());
+        let restore = format!("restore table t from '{}'", path.display());
+        let query = "select * from t order by a";
+
+        let db = on_disk(
+            "create table t(a int primary key, b varchar);
+            insert into t values (3, 'c'), (1, 'a'), (2, null);
+            insert into t values (5, 'e'), (4, 'd');
+            delete from t where a = 5;",
+        )
+        .await;
+        let expected = db.run(query).await.unwrap();
+        db.run(&backup).await.unwrap();
+
+        // restore into a fresh catalog
+        let db = on_disk(&restore).await;
+        assert_eq!(db.run(query).await.unwrap(), expected);
+
+        // restoring into an existing table requires `REPLACE`
+        db.run("insert into t values (6, 'f')").await.unwrap();
+        db.run(&restore).await.unwrap_err();
+        db.run(&format!("{restore} replace")).await.unwrap();
+        assert_eq!(db.run(query).await.unwrap(), expected);
+
+        // a table referenced by a foreign key can not be replaced
+        db.run("create table r(a int references t)").await.unwrap();
+        db.run(&format!("{restore} replace")).await.unwrap_err();
+        assert_eq!(db.run(query).await.unwrap(), expected);
+
+        // a truncated backup is rejected before the table is created
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::write(&path, &bytes[..bytes.len() - 1]).unwrap();
+        let db = on_disk("").await;
+        db.run(&restore).await.unwrap_err();
+        db.run(query).await.unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn eager_aggregation() {
+        let db = in_memory(
+            "create table f(k int, v int); insert into f values (1, 10), (1, 20), (2, 30), (3, 40);
+            create table d(k int, name int); insert into d values (1, 100), (2, 100), (2, 200);",
+        )
+        .await;
+        let table = |name: &str| {
+            let id = db.catalog.get_table_id_by_name("postgres", name).unwrap();
+            format!("${}.{}", id.schema_id, id.table_id)
+        };
+        let (f, d) = (table("f"), table("d"));
+
+        // SELECT d.name, sum(f.v), count(f.v) FROM f JOIN d ON f.k = d.k GROUP BY d.name
+        // with a partial aggregation on `f` below the join
+        let plan: RecExpr = format!(
+            "
+            (hashagg (list (sum {f}.1) (count {f}.1)) (list {d}.1)
+            (hashjoin inner (list {f}.0) (list {d}.0)
+                (hashagg (list (partial (sum {f}.1)) (partial (count {f}.1))) (list {f}.0)
+                    (scan {f} (list {f}.0 {f}.1) null)
+                )
+                (scan {d} (list {d}.0 {d}.1) null)
+            ))"
+        )
+        .parse()
+        .unwrap();
+        let StorageImpl::InMemoryStorage(storage) = db.storage.clone() else {
+            unreachable!()
+        };
+        let chunks: Vec<DataChunk> = crate::executor::build(
+            Config::default(),
+            Statistic::default(),
+            db.catalog.clone(),
+            storage,
+            &plan,
+        )
+        .try_collect()
+        .await
+        .unwrap();
+        let rows = chunks
+            .iter()
+            .flat_map(|chunk| chunk.rows().map(|row| row.values().collect_vec()))
+            .sorted_by_key(|row| row[2].clone())
+            .collect_vec();
+        use DataValue::Int32 as I;
+        assert_eq!(
+            rows,
+            [[I(60), I(3), I(100)], [I(30), I(1), I(200)]].map(Vec::from)
+        );
+    }
+
+    #[tokio::test]
+    async fn parallel_scan() {
+        // partitions of a table are disjoint and can be read concurrently
+        let inserts = (0..8)
+            .map(|i| format!("insert into t values ({i});"))
+            .join("\n");
+        let db = in_memory(&format!("create table t(a int);\n{inserts}")).await;
+        let table_id = db.catalog.get_table_id_by_name("postgres", "t").unwrap();
+        let table = db
+            .storage
+            .as_in_memory_storage()
+            .get_table(table_id)
+            .unwrap();
+        let txn = table.read().await.unwrap();
+        let mut iters = vec![];
+        for index in 0..4 {
+            let options = ScanOptions::default().with_partition(index, 4);
+            iters.push(
+                txn.scan(&[StorageColumnRef::Idx(0)], options)
+                    .await
+                    .unwrap(),
+            );
+        }
+        // every partition reads its first batch before any of them continues
+        let barrier = tokio::sync::Barrier::new(iters.len());
+        let partitions = futures::future::try_join_all(iters.into_iter().map(|mut it| {
+            let barrier = &barrier;
+            async move {
+                let mut values = vec![];
+                let mut first = true;
+                while let Some(chunk) = it.next_batch(None).await? {
+                    values.extend(chunk.rows().map(|row| row.get(0)));
+                    if std::mem::take(&mut first) {
+                        barrier.wait().await;
+                    }
+                }
+                StorageResult::Ok(values)
+            }
+        }))
+        .await
+        .unwrap();
+        assert!(partitions.iter().all(|p| p.len() == 2), "{partitions:?}");
+        let values = partitions.into_iter().flatten().sorted().collect_vec();
+        assert_eq!(values, (0..8).map(DataValue::Int32).collect_vec());
+    }
+
+    #[tokio::test]
+    async fn float_precision() {
+        let db =
+            in_memory("create table t(v double); insert into t values (1), (123456.789);").await;
+        let query = "select v / 3 as a, v * 2 as b from t";
+
+        let full = db.run(query).await.unwrap();
+        db.update_config(|config| config.float_precision = Some(3));
+        let rounded = db.run(query).await.unwrap();
+        assert_eq!(
+            chunk_to_json_lines(&rounded[0]),
+            [r#"{"a":0.333,"b":2}"#, r#"{"a":41200,"b":247000}"#]
+        );
+        // only the rendered output is rounded
+        assert_eq!(rounded[0].data_chunks(), full[0].data_chunks());
+    }
+}
@@ -0,0 +1,106 @@
+// Copyright 2023 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+
+use super::explain::explain_chunk;
+use super::*;
+use crate::planner::{Explain, Metrics, Optimizer};
+
+/// The executor of `explain analyze` statement.
+///
+/// It runs the plan to completion, then explains it with the actual metrics.
+pub struct AnalyzeExecutor {
+    pub plan: RecExpr,
+    /// The id in the e-graph of each node in `plan`.
+    pub ids: Vec<Id>,
+    /// Whether to show the physical properties of each node.
+    pub verbose: bool,
+    pub profiler: Arc<Profiler>,
+    /// The optimizer to compute the costs of the plan.
+    pub optimizer: Optimizer,
+    pub catalog: RootCatalogRef,
+}
+
+impl AnalyzeExecutor {
+    #[try_stream(boxed, ok = DataChunk, error = ExecutorError)]
+    pub async fn execute(self, child: BoxedExecutor) {
+        #[for_await]
+        for chunk in child {
+            chunk?;
+        }
+        let costs = self.optimizer.costs(&self.plan);
+        let properties = (self.verbose).then(|| self.optimizer.properties(&self.plan));
+        let metrics = (self.ids.iter())
+            .map(|id| self.profiler.metrics(*id))
+            .collect_vec();
+        let mut explain = Explain::of(&self.plan)
+            .with_costs(&costs)
+            .with_metrics(&metrics)
+            .with_catalog(&self.catalog);
+        if let Some(properties) = &properties {
+            explain = explain.with_properties(properties);
+        }
+        yield explain_chunk(&explain);
+    }
+}
+
+/// Collects the actual metrics of executors.
+#[derive(Default)]
+pub struct Profiler {
+    nodes: Mutex<HashMap<Id, Arc<Counters>>>,
+}
+
+#[derive(Default)]
+struct Counters {
+    rows: AtomicU64,
+    nanos: AtomicU64,
+}
+
+impl Profiler {
+    /// Wraps the executor of plan node `id` to count its output rows and time.
+    pub fn instrument(&self, id: Id, stream: BoxedExecutor) -> BoxedExecutor {
+        let counters = self.nodes.lock().entry(id).or_default().clone();
+        Box::pin(InstrumentedStream { stream, counters })
+    }
+
+    /// Returns the metrics of plan node `id`.
+    pub fn metrics(&self, id: Id) -> Metrics {
+        let Some(counters) = self.nodes.lock().get(&id).cloned() else {
+            return Metrics::default();
+        };
+        Metrics {
+            rows: counters.rows.load(Ordering::Relaxed),
+            time: Duration::from_nanos(counters.nanos.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+/// A stream counting the rows produced by the inner executor and the time spent polling it.
+///
+/// Children are spawned in their own tasks, so their time is not included.
+struct InstrumentedStream {
+    stream: BoxedExecutor,
+    counters: Arc<Counters>,
+}
+
+impl Stream for InstrumentedStream {
+    type Item = Result<DataChunk, ExecutorError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let start = Instant::now();
+        let poll = self.stream.poll_next_unpin(cx);
+        let nanos = start.elapsed().as_nanos() as u64;
+        self.counters.nanos.fetch_add(nanos, Ordering::Relaxed);
+        if let Poll::Ready(Some(Ok(chunk))) = &poll {
+            let rows = chunk.cardinality() as u64;
+            self.counters.rows.fetch_add(rows, Ordering::Relaxed);
+        }
+        poll
+    }
+}
@@ -0,0 +1,43 @@
+// Copyright 2023 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use super::*;
+use crate::array::{DataChunk, DataChunkBuilder};
+use crate::types::{DataType, DataValue};
+
+/// The executor of apply, which evaluates the right side for each row of the left side,
+/// and joins the row with the result.
+pub struct ApplyExecutor {
+    pub op: Expr,
+    pub condition: RecExpr,
+    pub left_types: Vec<DataType>,
+    pub right_types: Vec<DataType>,
+    /// Builds the executor of the right side with the values of a left row.
+    pub build_right: Box<dyn Fn(&[DataValue]) -> BoxedExecutor + Send + Sync>,
+}
+
+impl ApplyExecutor {
+    #[try_stream(boxed, ok = DataChunk, error = ExecutorError)]
+    pub async fn execute(self, left: BoxedExecutor) {
+        #[for_await]
+        for chunk in left {
+            let chunk = chunk?;
+            for row in chunk.rows() {
+                let values = row.values().collect_vec();
+                let right = (self.build_right)(&values);
+                let mut builder = DataChunkBuilder::new(&self.left_types, 1);
+                let left_row = builder.push_row(values).unwrap();
+                let join = NestedLoopJoinExecutor {
+                    op: self.op.clone(),
+                    condition: self.condition.clone(),
+                    left_types: self.left_types.clone(),
+                    right_types: self.right_types.clone(),
+                };
+                let left = futures::stream::once(async { Ok(left_row) }).boxed();
+                #[for_await]
+                for chunk in join.execute(left, right) {
+                    yield chunk?;
+                }
+            }
+        }
+    }
+}
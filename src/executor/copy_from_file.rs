@@ -4,14 +4,19 @@ use std::fs::File;
 use std::io::BufReader;
 
 use indicatif::{ProgressBar, ProgressStyle};
+use parquet::file::reader::{FileReader, SerializedFileReader};
+use parquet::record::Field;
 use tokio::sync::mpsc::Sender;
 
 use super::*;
 use crate::array::{ArrayImpl, DataChunkBuilder};
 use crate::binder::copy::{ExtSource, FileFormat};
-use crate::types::DataTypeKind;
+use crate::types::{Blob, DataTypeKind, Date, Timestamp};
 
 /// The executor of loading file data.
+///
+/// Records are parsed in a streaming fashion and sent out in chunks, so the file is never loaded
+/// into memory as a whole. A malformed record fails with the line number where it starts.
 pub struct CopyFromFileExecutor {
     pub source: ExtSource,
     pub types: Vec<DataType>,
@@ -47,21 +52,25 @@ impl CopyFromFileExecutor {
     ///
     /// The read data chunks will be sent through `tx`.
     fn read_file_blocking(self, tx: Sender<DataChunk>) -> Result<(), ExecutorError> {
-        let file = File::open(self.source.path)?;
+        let file = File::open(&self.source.path)?;
         let file_size = file.metadata()?.len();
-        let mut buf_reader = BufReader::new(file);
-        let mut reader = match self.source.format {
+        let (mut reader, null) = match self.source.format {
             FileFormat::Csv {
                 delimiter,
                 quote,
                 escape,
                 header,
-            } => csv::ReaderBuilder::new()
-                .delimiter(delimiter as u8)
-                .quote(quote as u8)
-                .escape(escape.map(|c| c as u8))
-                .has_headers(header)
-                .from_reader(&mut buf_reader),
+                null,
+            } => {
+                let reader = csv::ReaderBuilder::new()
+                    .delimiter(delimiter as u8)
+                    .quote(quote as u8)
+                    .escape(escape.map(|c| c as u8))
+                    .has_headers(header)
+                    .from_reader(BufReader::new(file));
+                (reader, null)
+            }
+            FileFormat::Parquet => return self.read_parquet_blocking(file, tx),
         };
 
         let bar = if file_size < IMPORT_PROGRESS_BAR_LIMIT {
@@ -87,25 +96,35 @@ impl CopyFromFileExecutor {
         for record in reader.records() {
             // read records and push raw str rows into data chunk builder
             let record = record?;
+            let line = record.position().map_or(0, |p| p.line());
+            let malformed = |e: ExecutorError| ExecutorError::MalformedRecord {
+                line,
+                source: Box::new(e),
+            };
 
             if !(record.len() == column_count
                 || record.len() == column_count + 1 && record.get(column_count) == Some(""))
             {
-                return Err(ExecutorError::LengthMismatch {
+                return Err(malformed(ExecutorError::LengthMismatch {
                     expected: column_count,
                     actual: record.len(),
-                });
+                }));
             }
 
-            for (v, ty) in record.iter().zip(&self.types) {
-                if !ty.nullable && v.is_empty() {
-                    return Err(ExecutorError::NotNullable);
+            // only fields equal to the NULL string are parsed as NULL
+            let fields = record.iter().map(|v| (v != null).then_some(v));
+            for (v, ty) in fields.clone().zip(&self.types) {
+                if !ty.nullable && v.is_none() {
+                    return Err(malformed(ExecutorError::NotNullable));
                 }
             }
             size_count += record.as_slice().as_bytes().len();
 
             // push a raw str row and send it if necessary
-            if let Some(chunk) = chunk_builder.push_str_row(record.iter())? {
+            let chunk = chunk_builder
+                .push_str_row(fields)
+                .map_err(|e| malformed(e.into()))?;
+            if let Some(chunk) = chunk {
                 bar.set_position(size_count as u64);
                 tx.blocking_send(chunk).map_err(|_| ExecutorError::Abort)?;
             }
@@ -117,6 +136,74 @@ impl CopyFromFileExecutor {
         bar.finish();
         Ok(())
     }
+
+    /// Read rows from a parquet file using blocking IO.
+    ///
+    /// Columns in the file are mapped to the target columns by position.
+    fn read_parquet_blocking(self, file: File, tx: Sender<DataChunk>) -> Result<(), ExecutorError> {
+        let reader = SerializedFileReader::new(file)?;
+        let schema = reader.metadata().file_metadata().schema_descr();
+        let column_count = schema.root_schema().get_fields().len();
+        if column_count != self.types.len() {
+            return Err(ExecutorError::LengthMismatch {
+                expected: self.types.len(),
+                actual: column_count,
+            });
+        }
+
+        let mut chunk_builder = DataChunkBuilder::new(&self.types, PROCESSING_WINDOW_SIZE);
+        for row in reader.get_row_iter(None)? {
+            let row = row?;
+            let mut values = Vec::with_capacity(column_count);
+            for ((_, field), ty) in row.get_column_iter().zip(&self.types) {
+                let value = field_to_value(field, &ty.kind())?;
+                if value.is_null() {
+                    if !ty.nullable {
+                        return Err(ExecutorError::NotNullable);
+                    }
+                    values.push(value);
+                } else {
+                    values.push(value.cast(&ty.kind())?);
+                }
+            }
+            if let Some(chunk) = chunk_builder.push_row(values) {
+                tx.blocking_send(chunk).map_err(|_| ExecutorError::Abort)?;
+            }
+        }
+        if let Some(chunk) = chunk_builder.take() {
+            tx.blocking_send(chunk).map_err(|_| ExecutorError::Abort)?;
+        }
+        Ok(())
+    }
+}
+
+/// Converts a parquet field to a value.
+///
+/// Timestamps are converted according to whether the target type has a time zone.
+fn field_to_value(field: &Field, kind: &DataTypeKind) -> Result<DataValue, ExecutorError> {
+    let timestamp = |micros: i64| match kind {
+        DataTypeKind::TimestampTz => DataValue::TimestampTz(TimestampTz::from_unix_micros(micros)),
+        _ => DataValue::Timestamp(Timestamp::from_unix_micros(micros)),
+    };
+    Ok(match field {
+        Field::Null => DataValue::Null,
+        Field::Bool(v) => DataValue::Bool(*v),
+        Field::Byte(v) => DataValue::Int16(*v as i16),
+        Field::Short(v) => DataValue::Int16(*v),
+        Field::Int(v) => DataValue::Int32(*v),
+        Field::Long(v) => DataValue::Int64(*v),
+        Field::UByte(v) => DataValue::Int16(*v as i16),
+        Field::UShort(v) => DataValue::Int32(*v as i32),
+        Field::UInt(v) => DataValue::Int64(*v as i64),
+        Field::Float(v) => DataValue::Float64((*v as f64).into()),
+        Field::Double(v) => DataValue::Float64((*v).into()),
+        Field::Str(v) => DataValue::String(v.clone()),
+        Field::Bytes(v) => DataValue::Blob(Blob::from(v.data().to_vec())),
+        Field::Date(v) => DataValue::Date(Date::new(*v)),
+        Field::TimestampMillis(v) => timestamp(*v * 1000),
+        Field::TimestampMicros(v) => timestamp(*v),
+        field => return Err(ExecutorError::UnsupportedParquetType(format!("{field:?}"))),
+    })
 }
 
 #[cfg(test)]
@@ -125,7 +212,7 @@ mod tests {
 
     use super::*;
     use crate::array::ArrayImpl;
-    use crate::types::DataTypeKind;
+    use crate::types::{DataTypeKind, F64};
 
     #[tokio::test]
     async fn read_csv() {
@@ -142,6 +229,7 @@ mod tests {
                     quote: '"',
                     escape: None,
                     header: false,
+                    null: String::new(),
                 },
             },
             types: vec![
@@ -161,4 +249,105 @@ mod tests {
         .collect();
         assert_eq!(actual, expected);
     }
+
+    #[tokio::test]
+    async fn read_csv_with_header() {
+        let csv = "id|name|score\n1|\"a|b\"|\n2|'c'|2.5\n";
+
+        let mut file = tempfile::NamedTempFile::new().expect("failed to create temp file");
+        write!(file, "{}", csv).expect("failed to write file");
+
+        let executor = CopyFromFileExecutor {
+            source: ExtSource {
+                path: file.path().into(),
+                format: FileFormat::Csv {
+                    delimiter: '|',
+                    quote: '"',
+                    escape: None,
+                    header: true,
+                    null: String::new(),
+                },
+            },
+            types: vec![
+                DataTypeKind::Int64.not_null(),
+                DataTypeKind::String.not_null(),
+                DataTypeKind::Float64.nullable(),
+            ],
+        };
+        let actual = executor.execute().next().await.unwrap().unwrap();
+
+        let expected: DataChunk = [
+            ArrayImpl::new_int64([1, 2].into_iter().collect()),
+            ArrayImpl::new_utf8(["a|b", "'c'"].iter().map(Some).collect()),
+            ArrayImpl::new_float64([None, Some(F64::from(2.5))].into_iter().collect()),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(actual, expected);
+    }
+
+    #[tokio::test]
+    async fn read_csv_with_null_string() {
+        let csv = "1,\n2,N/A\n";
+
+        let mut file = tempfile::NamedTempFile::new().expect("failed to create temp file");
+        write!(file, "{}", csv).expect("failed to write file");
+
+        let executor = CopyFromFileExecutor {
+            source: ExtSource {
+                path: file.path().into(),
+                format: FileFormat::Csv {
+                    delimiter: ',',
+                    quote: '"',
+                    escape: None,
+                    header: false,
+                    null: "N/A".into(),
+                },
+            },
+            types: vec![
+                DataTypeKind::Int32.not_null(),
+                DataTypeKind::String.nullable(),
+            ],
+        };
+        let actual = executor.execute().next().await.unwrap().unwrap();
+
+        let expected: DataChunk = [
+            ArrayImpl::new_int32([1, 2].into_iter().collect()),
+            ArrayImpl::new_utf8([Some(""), None].into_iter().collect()),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(actual, expected);
+    }
+
+    #[tokio::test]
+    async fn read_malformed_csv() {
+        let csv = "a,b\n1,2\n3,x\n";
+
+        let mut file = tempfile::NamedTempFile::new().expect("failed to create temp file");
+        write!(file, "{}", csv).expect("failed to write file");
+
+        let executor = CopyFromFileExecutor {
+            source: ExtSource {
+                path: file.path().into(),
+                format: FileFormat::Csv {
+                    delimiter: ',',
+                    quote: '"',
+                    escape: None,
+                    header: true,
+                    null: String::new(),
+                },
+            },
+            types: vec![
+                DataTypeKind::Int32.not_null(),
+                DataTypeKind::Int32.not_null(),
+            ],
+        };
+        let error = executor.execute().next().await.unwrap().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "malformed record at line 3: conversion error: \
+             failed to convert string \"x\" to int: invalid digit found in string"
+        );
+    }
 }
@@ -1,16 +1,25 @@
 // Copyright 2023 RisingLight Project Authors. Licensed under Apache-2.0.
 
 use std::fs::File;
-use std::path::PathBuf;
 
+use parquet::basic::{LogicalType, MicroSeconds, Repetition, TimeUnit, Type as PhysicalType};
+use parquet::data_type::{BoolType, ByteArray, ByteArrayType, DoubleType, Int32Type, Int64Type};
+use parquet::file::properties::WriterProperties;
+use parquet::file::writer::{SerializedColumnWriter, SerializedFileWriter};
+use parquet::schema::types::Type;
 use tokio::sync::mpsc;
 
 use super::*;
+use crate::array::ArrayImpl;
 use crate::binder::copy::{ExtSource, FileFormat};
+use crate::types::DataTypeKind;
 
 /// The executor of saving data to file.
 pub struct CopyToFileExecutor {
     pub source: ExtSource,
+    /// Column names written in the header line or the parquet schema.
+    pub columns: Vec<String>,
+    pub types: Vec<DataType>,
 }
 
 impl CopyToFileExecutor {
@@ -21,7 +30,7 @@ impl CopyToFileExecutor {
         // When this stream is dropped, the `sender` is dropped, the `recver` will return
         // `None` in the spawned task, then the task will finish.
         let writer = tokio::task::spawn_blocking(move || {
-            Self::write_file_blocking(self.source.path, self.source.format, recver)
+            Self::write_file_blocking(self.source, self.columns, self.types, recver)
         });
         #[for_await]
         for batch in child {
@@ -37,23 +46,33 @@ impl CopyToFileExecutor {
     }
 
     fn write_file_blocking(
-        path: PathBuf,
-        format: FileFormat,
+        source: ExtSource,
+        columns: Vec<String>,
+        types: Vec<DataType>,
         mut recver: mpsc::Receiver<DataChunk>,
     ) -> Result<usize, ExecutorError> {
-        let file = File::create(path)?;
-        let mut writer = match format {
+        let file = File::create(source.path)?;
+        let (mut writer, null) = match source.format {
             FileFormat::Csv {
                 delimiter,
                 quote,
                 escape,
                 header,
-            } => csv::WriterBuilder::new()
-                .delimiter(delimiter as u8)
-                .quote(quote as u8)
-                .escape(escape.unwrap_or(quote) as u8)
-                .has_headers(header)
-                .from_writer(file),
+                null,
+            } => {
+                let mut writer = csv::WriterBuilder::new()
+                    .delimiter(delimiter as u8)
+                    .quote(quote as u8)
+                    .escape(escape.unwrap_or(quote) as u8)
+                    .from_writer(file);
+                if header {
+                    writer.write_record(&columns)?;
+                }
+                (writer, null)
+            }
+            FileFormat::Parquet => {
+                return Self::write_parquet_blocking(file, columns, types, recver);
+            }
         };
 
         let mut rows = 0;
@@ -61,7 +80,10 @@ impl CopyToFileExecutor {
         while let Some(chunk) = recver.blocking_recv() {
             for i in 0..chunk.cardinality() {
                 // TODO(wrj): avoid dynamic memory allocation (String)
-                let row = chunk.arrays().iter().map(|a| a.get_to_string(i));
+                let row = chunk.arrays().iter().map(|a| match a.is_null(i) {
+                    true => null.clone(),
+                    false => a.get_to_string(i),
+                });
                 writer.write_record(row)?;
             }
             writer.flush()?;
@@ -72,12 +94,126 @@ impl CopyToFileExecutor {
 
         Ok(rows)
     }
+
+    /// Write chunks to a parquet file. Each chunk is written as a row group.
+    fn write_parquet_blocking(
+        file: File,
+        columns: Vec<String>,
+        types: Vec<DataType>,
+        mut recver: mpsc::Receiver<DataChunk>,
+    ) -> Result<usize, ExecutorError> {
+        let timestamp = |utc| LogicalType::Timestamp {
+            is_adjusted_to_u_t_c: utc,
+            unit: TimeUnit::MICROS(MicroSeconds {}),
+        };
+        let mut fields = vec![];
+        for (name, ty) in columns.iter().zip(&types) {
+            let (physical_type, logical_type) = match ty.kind() {
+                DataTypeKind::Bool => (PhysicalType::BOOLEAN, None),
+                DataTypeKind::Int16 => (
+                    PhysicalType::INT32,
+                    Some(LogicalType::Integer {
+                        bit_width: 16,
+                        is_signed: true,
+                    }),
+                ),
+                DataTypeKind::Int32 => (PhysicalType::INT32, None),
+                DataTypeKind::Int64 => (PhysicalType::INT64, None),
+                DataTypeKind::Float64 => (PhysicalType::DOUBLE, None),
+                DataTypeKind::String => (PhysicalType::BYTE_ARRAY, Some(LogicalType::String)),
+                DataTypeKind::Blob => (PhysicalType::BYTE_ARRAY, None),
+                DataTypeKind::Date => (PhysicalType::INT32, Some(LogicalType::Date)),
+                DataTypeKind::Timestamp => (PhysicalType::INT64, Some(timestamp(false))),
+                DataTypeKind::TimestampTz => (PhysicalType::INT64, Some(timestamp(true))),
+                kind => return Err(ExecutorError::UnsupportedParquetType(kind.to_string())),
+            };
+            let field = Type::primitive_type_builder(name, physical_type)
+                .with_repetition(Repetition::OPTIONAL)
+                .with_logical_type(logical_type)
+                .build()?;
+            fields.push(Arc::new(field));
+        }
+        let schema = Arc::new(
+            Type::group_type_builder("schema")
+                .with_fields(fields)
+                .build()?,
+        );
+        let props = Arc::new(WriterProperties::builder().build());
+        let mut writer = SerializedFileWriter::new(file, schema, props)?;
+
+        let mut rows = 0;
+        while let Some(chunk) = recver.blocking_recv() {
+            let mut row_group = writer.next_row_group()?;
+            for array in chunk.arrays() {
+                let mut column = row_group.next_column()?.expect("missing column");
+                write_array(&mut column, array)?;
+                column.close()?;
+            }
+            row_group.close()?;
+            rows += chunk.cardinality();
+        }
+        writer.close()?;
+        Ok(rows)
+    }
+}
+
+/// Write an array to a parquet column.
+fn write_array(
+    column: &mut SerializedColumnWriter<'_>,
+    array: &ArrayImpl,
+) -> Result<(), ExecutorError> {
+    match array {
+        ArrayImpl::Bool(a) => write_values::<BoolType>(column, a.iter().map(|v| v.copied())),
+        ArrayImpl::Int16(a) => {
+            write_values::<Int32Type>(column, a.iter().map(|v| v.map(|v| *v as i32)))
+        }
+        ArrayImpl::Int32(a) => write_values::<Int32Type>(column, a.iter().map(|v| v.copied())),
+        ArrayImpl::Int64(a) => write_values::<Int64Type>(column, a.iter().map(|v| v.copied())),
+        ArrayImpl::Float64(a) => {
+            write_values::<DoubleType>(column, a.iter().map(|v| v.map(|v| v.0)))
+        }
+        ArrayImpl::Utf8(a) => {
+            write_values::<ByteArrayType>(column, a.iter().map(|v| v.map(ByteArray::from)))
+        }
+        ArrayImpl::Blob(a) => write_values::<ByteArrayType>(
+            column,
+            a.iter().map(|v| v.map(|v| ByteArray::from(v.to_vec()))),
+        ),
+        ArrayImpl::Date(a) => {
+            write_values::<Int32Type>(column, a.iter().map(|v| v.map(|v| v.get_inner())))
+        }
+        ArrayImpl::Timestamp(a) => {
+            write_values::<Int64Type>(column, a.iter().map(|v| v.map(|v| v.unix_micros())))
+        }
+        ArrayImpl::TimestampTz(a) => {
+            write_values::<Int64Type>(column, a.iter().map(|v| v.map(|v| v.unix_micros())))
+        }
+        ArrayImpl::Null(_) | ArrayImpl::Decimal(_) | ArrayImpl::Interval(_) => {
+            unreachable!("unsupported types are rejected when creating schema")
+        }
+    }
+}
+
+/// Write optional values to a parquet column. NULLs are encoded in definition levels.
+fn write_values<T: parquet::data_type::DataType>(
+    column: &mut SerializedColumnWriter<'_>,
+    values: impl Iterator<Item = Option<T::T>>,
+) -> Result<(), ExecutorError> {
+    let mut def_levels = vec![];
+    let mut non_null_values = vec![];
+    for value in values {
+        def_levels.push(value.is_some() as i16);
+        non_null_values.extend(value);
+    }
+    column
+        .typed::<T>()
+        .write_batch(&non_null_values, Some(&def_levels), None)?;
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::array::ArrayImpl;
 
     #[tokio::test]
     async fn write_csv() {
@@ -91,8 +227,11 @@ mod tests {
                     quote: '"',
                     escape: None,
                     header: false,
+                    null: String::new(),
                 },
             },
+            columns: vec![],
+            types: vec![],
         };
         let child = async_stream::try_stream! {
             yield [
@@ -110,4 +249,78 @@ mod tests {
         let expected = "1,1.5,one\n2,2.5,two\n";
         assert_eq!(actual, expected);
     }
+
+    #[tokio::test]
+    async fn write_tsv_with_header() {
+        let file = tempfile::NamedTempFile::new().expect("failed to create temp file");
+
+        let executor = CopyToFileExecutor {
+            source: ExtSource {
+                path: file.path().into(),
+                format: FileFormat::Csv {
+                    delimiter: '\t',
+                    quote: '"',
+                    escape: None,
+                    header: true,
+                    null: "\\N".into(),
+                },
+            },
+            columns: vec!["a".into(), "b".into()],
+            types: vec![],
+        };
+        let child = async_stream::try_stream! {
+            yield [
+                ArrayImpl::new_int32([Some(1), None].into_iter().collect()),
+                ArrayImpl::new_utf8([None, Some("a\tb")].into_iter().collect()),
+            ]
+            .into_iter()
+            .collect();
+        }
+        .boxed();
+        executor.execute(child).next().await.unwrap().unwrap();
+
+        let actual = std::fs::read_to_string(file.path()).unwrap();
+        let expected = "a\tb\n1\t\\N\n\\N\t\"a\tb\"\n";
+        assert_eq!(actual, expected);
+    }
+
+    #[tokio::test]
+    async fn write_parquet_timestamp() {
+        use parquet::file::reader::{FileReader, SerializedFileReader};
+        use parquet::record::Field;
+
+        use crate::types::Timestamp;
+
+        let file = tempfile::NamedTempFile::new().expect("failed to create temp file");
+
+        let ts: Timestamp = "2023-05-17 12:34:56".parse().unwrap();
+        let executor = CopyToFileExecutor {
+            source: ExtSource {
+                path: file.path().into(),
+                format: FileFormat::Parquet,
+            },
+            // names are not restricted to identifiers
+            columns: vec!["(+ $1.1 1)".into()],
+            types: vec![DataTypeKind::Timestamp.nullable()],
+        };
+        let child = async_stream::try_stream! {
+            yield [ArrayImpl::new_timestamp([Some(ts), None].into_iter().collect())]
+                .into_iter()
+                .collect();
+        }
+        .boxed();
+        executor.execute(child).next().await.unwrap().unwrap();
+
+        let reader = SerializedFileReader::new(file.reopen().unwrap()).unwrap();
+        let schema = reader.metadata().file_metadata().schema_descr();
+        assert_eq!(schema.column(0).name(), "(+ $1.1 1)");
+        let values = (reader.get_row_iter(None).unwrap())
+            .map(|row| row.unwrap().get_column_iter().next().unwrap().1.clone())
+            .collect::<Vec<_>>();
+        // timestamps are stored as microseconds since the unix epoch
+        assert_eq!(
+            values,
+            [Field::TimestampMicros(1_684_326_896_000_000), Field::Null]
+        );
+    }
 }
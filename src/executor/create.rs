@@ -3,9 +3,30 @@
 use std::sync::Arc;
 
 use super::*;
-use crate::binder::CreateTable;
+use crate::binder::{CreateSchema, CreateTable};
+use crate::catalog::RootCatalogRef;
 use crate::storage::Storage;
 
+/// The executor of `create schema` statement.
+pub struct CreateSchemaExecutor<S: Storage> {
+    pub plan: CreateSchema,
+    pub catalog: RootCatalogRef,
+    pub storage: Arc<S>,
+}
+
+impl<S: Storage> CreateSchemaExecutor<S> {
+    #[try_stream(boxed, ok = DataChunk, error = ExecutorError)]
+    pub async fn execute(self) {
+        let exists = (self.catalog)
+            .get_schema_id_by_name(&self.plan.schema_name)
+            .is_some();
+        if !(exists && self.plan.if_not_exists) {
+            self.storage.create_schema(&self.plan.schema_name).await?;
+        }
+        yield DataChunk::single(1);
+    }
+}
+
 /// The executor of `create table` statement.
 pub struct CreateTableExecutor<S: Storage> {
     pub plan: CreateTable,
@@ -21,6 +42,8 @@ impl<S: Storage> CreateTableExecutor<S> {
                 &self.plan.table_name,
                 &self.plan.columns,
                 &self.plan.ordered_pk_ids,
+                &self.plan.unique_keys,
+                &self.plan.foreign_keys,
             )
             .await?;
 
@@ -4,7 +4,7 @@ use std::sync::Arc;
 
 use super::*;
 use crate::array::DataChunk;
-use crate::catalog::TableRefId;
+use crate::catalog::{ColumnId, TableRefId};
 use crate::storage::{RowHandler, Storage, Table, Transaction};
 
 /// The executor of `delete` statement.
@@ -12,18 +12,57 @@ use crate::storage::{RowHandler, Storage, Table, Transaction};
 /// The last column of the input data chunk should be `_row_id_`.
 pub struct DeleteExecutor<S: Storage> {
     pub table_id: TableRefId,
+    /// FOREIGN KEY constraints referencing the table.
+    pub references: Vec<Reference>,
     pub storage: Arc<S>,
 }
 
+/// A FOREIGN KEY constraint of another table referencing the table being deleted from.
+pub struct Reference {
+    /// The referencing table.
+    pub table_id: TableRefId,
+    pub table_name: String,
+    /// Columns of the referencing table.
+    pub columns: Vec<ColumnId>,
+    /// Indexes of the referenced columns in the input chunk.
+    pub ref_indexes: Vec<usize>,
+    /// Names of the referenced columns.
+    pub ref_names: Vec<String>,
+}
+
 impl<S: Storage> DeleteExecutor<S> {
     #[try_stream(boxed, ok = DataChunk, error = ExecutorError)]
     pub async fn execute(self, child: BoxedExecutor) {
         let table = self.storage.get_table(self.table_id)?;
+        // keys that are still referenced by other tables
+        let mut referenced_keys = vec![];
+        for reference in &self.references {
+            let txn = self.storage.get_table(reference.table_id)?.read().await?;
+            let keys = scan_keys(&txn, std::slice::from_ref(&reference.columns)).await?;
+            referenced_keys.extend(keys);
+        }
+
         let mut txn = table.update().await?;
         let mut cnt = 0;
         #[for_await]
         for chunk in child {
             let chunk = chunk?;
+            // restrict deleting rows that are referenced
+            for row in chunk.rows() {
+                for (reference, referenced) in self.references.iter().zip(&referenced_keys) {
+                    let values = row.get_by_indexes(&reference.ref_indexes);
+                    if referenced.contains(&values) {
+                        Err(ExecutorError::ForeignKeyReferenced(
+                            format!(
+                                "({})=({})",
+                                reference.ref_names.iter().join(", "),
+                                values.iter().join(", "),
+                            ),
+                            reference.table_name.clone(),
+                        ))?;
+                    }
+                }
+            }
             let row_handlers = chunk.array_at(chunk.column_count() - 1);
             for row_handler_idx in 0..row_handlers.len() {
                 let row_handler = <S::Transaction as Transaction>::RowHandlerType::from_column(
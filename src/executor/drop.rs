@@ -15,8 +15,10 @@ pub struct DropExecutor<S: Storage> {
 impl<S: Storage> DropExecutor<S> {
     #[try_stream(boxed, ok = DataChunk, error = ExecutorError)]
     pub async fn execute(self) {
-        match self.plan.object {
-            Object::Table(id) => self.storage.drop_table(id).await?,
+        for object in self.plan.objects {
+            match object {
+                Object::Table(id) => self.storage.drop_table(id).await?,
+            }
         }
         yield DataChunk::single(1);
     }
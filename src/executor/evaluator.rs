@@ -5,8 +5,10 @@
 use std::fmt;
 
 use egg::{Id, Language};
+use itertools::Itertools;
 
 use crate::array::*;
+use crate::parser::{BinaryOperator, UnaryOperator};
 use crate::planner::{Expr, RecExpr};
 use crate::types::{ConvertError, DataValue};
 
@@ -65,7 +67,16 @@ impl<'a> Evaluator<'a> {
             }
             Cast([ty, a]) => {
                 let array = self.next(*a).eval(chunk)?;
-                array.cast(self.next(*ty).node().as_type())
+                let ty = self.next(*ty).node().as_type();
+                match super::config().strict_casts {
+                    true => array.cast(ty),
+                    false => Ok(array.try_cast(ty)),
+                }
+            }
+            TryCast([ty, a]) => {
+                let array = self.next(*a).eval(chunk)?;
+                let ty = self.next(*ty).node().as_type();
+                Ok(array.try_cast(ty))
             }
             IsNull(a) => {
                 let array = self.next(*a).eval(chunk)?;
@@ -73,6 +84,46 @@ impl<'a> Evaluator<'a> {
                     array.get_valid_bitmap().iter().map(|v| !v).collect(),
                 ))
             }
+            IsNotNull(a) => {
+                let array = self.next(*a).eval(chunk)?;
+                Ok(ArrayImpl::new_bool(
+                    array.get_valid_bitmap().iter().map(|v| *v).collect(),
+                ))
+            }
+            IsDistinct([a, b]) => {
+                let a = self.next(*a).eval(chunk)?;
+                let b = self.next(*b).eval(chunk)?;
+                a.is_distinct(&b)
+            }
+            IsNotDistinct([a, b]) => {
+                let a = self.next(*a).eval(chunk)?;
+                let b = self.next(*b).eval(chunk)?;
+                a.is_distinct(&b)?.not()
+            }
+            In([a, list]) => {
+                let a = self.next(*a).eval(chunk)?;
+                let list = self.next(*list).node().as_list();
+                let constants: Option<Vec<&DataValue>> = (list.iter())
+                    .map(|id| match self.next(*id).node() {
+                        Constant(v) => Some(v),
+                        _ => None,
+                    })
+                    .collect();
+                if let Some(values) = constants {
+                    return Ok(a.in_list(values));
+                }
+                // compare with each value otherwise
+                let mut result: Option<ArrayImpl> = None;
+                for id in list {
+                    let value = self.next(*id).eval(chunk)?;
+                    let eq = a.binary_op(&BinaryOperator::Eq, &value)?;
+                    result = Some(match result {
+                        Some(result) => result.or(&eq)?,
+                        None => eq,
+                    });
+                }
+                Ok(result.expect("empty IN list"))
+            }
             Like([a, b]) => match self.next(*b).node() {
                 Expr::Constant(DataValue::String(pattern)) => {
                     let a = self.next(*a).eval(chunk)?;
@@ -85,18 +136,44 @@ impl<'a> Evaluator<'a> {
                 let Expr::Field(field) = self.expr[*field] else { panic!("not a field") };
                 a.extract(field)
             }
+            DateTrunc([field, a]) => {
+                let a = self.next(*a).eval(chunk)?;
+                let Expr::Field(field) = self.expr[*field] else { panic!("not a field") };
+                a.date_trunc(field)
+            }
             Substring([str, start, length]) => {
                 let str = self.next(*str).eval(chunk)?;
                 let start = self.next(*start).eval(chunk)?;
                 let length = self.next(*length).eval(chunk)?;
                 str.substring(&start, &length)
             }
+            Length(a) => self.next(*a).eval(chunk)?.length(),
+            CharLength(a) => self.next(*a).eval(chunk)?.char_length(),
+            Ascii(a) => self.next(*a).eval(chunk)?.ascii(),
+            Chr(a) => self.next(*a).eval(chunk)?.chr(),
+            Reverse(a) => self.next(*a).eval(chunk)?.reverse(),
+            Repeat([a, n]) => {
+                let a = self.next(*a).eval(chunk)?;
+                let n = self.next(*n).eval(chunk)?;
+                a.repeat(&n)
+            }
+            Sign(a) => self.next(*a).eval(chunk)?.sign(),
+            Ln(a) => self.next(*a).eval(chunk)?.ln(),
+            Exp(a) => self.next(*a).eval(chunk)?.exp(),
+            Log([base, a]) => {
+                let base = self.next(*base).eval(chunk)?;
+                let a = self.next(*a).eval(chunk)?;
+                base.log(&a)
+            }
             Desc(a) | Ref(a) => self.next(*a).eval(chunk),
             // for aggs, evaluate its children
-            RowCount => Ok(ArrayImpl::new_null(
+            Over([window, _, _]) => self.next(*window).eval(chunk),
+            RowCount | RowNumber | Rank | DenseRank => Ok(ArrayImpl::new_null(
                 (0..chunk.cardinality()).map(|_| ()).collect(),
             )),
-            Count(a) | Sum(a) | Min(a) | Max(a) | First(a) | Last(a) => self.next(*a).eval(chunk),
+            Count(a) | Sum(a) | Min(a) | Max(a) | First(a) | Last(a) | StringAgg([a, _]) => {
+                self.next(*a).eval(chunk)
+            }
             Replace([a, from, to]) => {
                 let a = self.next(*a).eval(chunk)?;
                 let from = self.next(*from);
@@ -111,14 +188,23 @@ impl<'a> Evaluator<'a> {
                 };
                 a.replace(from, to)
             }
+            And([a, b]) => self.eval_short_circuit(chunk, *a, *b, false),
+            Or([a, b]) => self.eval_short_circuit(chunk, *a, *b, true),
             e => {
+                let wrapping = super::config().wrapping_arithmetic;
                 if let Some((op, a, b)) = e.binary_op() {
                     let left = self.next(a).eval(chunk)?;
                     let right = self.next(b).eval(chunk)?;
-                    left.binary_op(&op, &right)
+                    match wrapping {
+                        true => left.wrapping_binary_op(&op, &right),
+                        false => left.binary_op(&op, &right),
+                    }
                 } else if let Some((op, a)) = e.unary_op() {
                     let array = self.next(a).eval(chunk)?;
-                    array.unary_op(&op)
+                    match (op, wrapping) {
+                        (UnaryOperator::Minus, true) => array.wrapping_neg(),
+                        (op, _) => array.unary_op(&op),
+                    }
                 } else {
                     panic!("can not evaluate expression: {self}");
                 }
@@ -126,6 +212,131 @@ impl<'a> Evaluator<'a> {
         }
     }
 
+    /// Evaluate `a AND b` if `decisive` is false, or `a OR b` if `decisive` is true.
+    ///
+    /// The right operand is only evaluated on rows where the left one is not `decisive`,
+    /// so that it won't fail on rows whose result has been decided.
+    fn eval_short_circuit(
+        &self,
+        chunk: &DataChunk,
+        a: Id,
+        b: Id,
+        decisive: bool,
+    ) -> Result<ArrayImpl, ConvertError> {
+        let combine = |a: &ArrayImpl, b: &ArrayImpl| if decisive { a.or(b) } else { a.and(b) };
+        let left = self.next(a).eval(chunk)?;
+        let ArrayImpl::Bool(bools) = &left else {
+            let right = self.next(b).eval(chunk)?;
+            return combine(&left, &right);
+        };
+        let visibility = bools.iter().map(|v| v != Some(&decisive)).collect_vec();
+        if visibility.iter().all(|v| *v) {
+            let right = self.next(b).eval(chunk)?;
+            return combine(&left, &right);
+        }
+        // evaluate the right operand on undecided rows, and fill others with `decisive`
+        let right = self.next(b).eval(&chunk.filter(&visibility))?;
+        let ArrayImpl::Bool(bools) = &right else {
+            return combine(&left, &right);
+        };
+        let mut iter = bools.iter();
+        let right: BoolArray = (visibility.iter())
+            .map(|visible| match visible {
+                true => iter.next().unwrap().cloned(),
+                false => Some(decisive),
+            })
+            .collect();
+        combine(&left, &ArrayImpl::new_bool(right))
+    }
+
+    /// Evaluate the given predicate as a selection mask of rows where it is true.
+    ///
+    /// Numeric comparisons between columns and constants are evaluated on the raw values, and
+    /// `AND`/`OR` combine the masks directly, without building boolean arrays in between.
+    /// Other predicates fall back to [`eval`](Self::eval).
+    pub fn eval_selection(&self, chunk: &DataChunk) -> Result<Vec<bool>, ConvertError> {
+        use Expr::*;
+        match self.node() {
+            And([a, b]) => {
+                let mut mask = self.next(*a).eval_selection(chunk)?;
+                self.next(*b).refine_selection(chunk, &mut mask, false)?;
+                Ok(mask)
+            }
+            Or([a, b]) => {
+                let mut mask = self.next(*a).eval_selection(chunk)?;
+                self.next(*b).refine_selection(chunk, &mut mask, true)?;
+                Ok(mask)
+            }
+            Constant(DataValue::Bool(v)) => Ok(vec![*v; chunk.cardinality()]),
+            e => {
+                if let Some((op, a, b)) = e.binary_op() {
+                    if let Some(mask) = self.select(chunk, &op, a, b) {
+                        return Ok(mask);
+                    }
+                }
+                match self.eval(chunk)? {
+                    ArrayImpl::Bool(a) => Ok(a.iter().map(|v| v == Some(&true)).collect()),
+                    ArrayImpl::Null(a) => Ok(vec![false; a.len()]),
+                    _ => panic!("predicates can only be evaluated to bool array"),
+                }
+            }
+        }
+    }
+
+    /// Refine the selection `mask` of `a` to that of `a AND self` if `decisive` is false,
+    /// or `a OR self` if `decisive` is true.
+    ///
+    /// `self` is only evaluated on rows that are not `decisive` in the mask. Compared with
+    /// [`eval_short_circuit`](Self::eval_short_circuit), rows where `a` is NULL are skipped for
+    /// `AND` as well, since they can never be selected.
+    fn refine_selection(
+        &self,
+        chunk: &DataChunk,
+        mask: &mut [bool],
+        decisive: bool,
+    ) -> Result<(), ConvertError> {
+        let undecided = mask.iter().filter(|v| **v != decisive).count();
+        if undecided == 0 {
+            return Ok(());
+        }
+        if undecided == mask.len() {
+            mask.copy_from_slice(&self.eval_selection(chunk)?);
+            return Ok(());
+        }
+        let visibility = mask.iter().map(|v| *v != decisive).collect_vec();
+        let mut iter = self.eval_selection(&chunk.filter(&visibility))?.into_iter();
+        for v in mask.iter_mut().filter(|v| **v != decisive) {
+            *v = iter.next().unwrap();
+        }
+        Ok(())
+    }
+
+    /// Evaluate a comparison on columns and constants as a selection mask.
+    ///
+    /// Returns `None` if it is not supported by [`ArrayImpl::select`].
+    fn select(&self, chunk: &DataChunk, op: &BinaryOperator, a: Id, b: Id) -> Option<Vec<bool>> {
+        use Expr::*;
+        match (&self.expr[a], &self.expr[b]) {
+            (ColumnIndex(a), ColumnIndex(b)) => {
+                let a = chunk.array_at(a.0 as _);
+                a.select(op, chunk.array_at(b.0 as _))
+            }
+            (ColumnIndex(a), Constant(v)) => chunk.array_at(a.0 as _).select_value(op, v),
+            (Constant(v), ColumnIndex(b)) => {
+                // `v < b` is `b > v`
+                let op = match op {
+                    BinaryOperator::Gt => BinaryOperator::Lt,
+                    BinaryOperator::Lt => BinaryOperator::Gt,
+                    BinaryOperator::GtEq => BinaryOperator::LtEq,
+                    BinaryOperator::LtEq => BinaryOperator::GtEq,
+                    op => op.clone(),
+                };
+                chunk.array_at(b.0 as _).select_value(&op, v)
+            }
+            _ => None,
+        }
+    }
+
     /// Returns the initial aggregation states.
     pub fn init_agg_states<B: FromIterator<DataValue>>(&self) -> B {
         (self.node().as_list().iter())
@@ -138,12 +349,58 @@ impl<'a> Evaluator<'a> {
         use Expr::*;
         match self.node() {
             Over([window, _, _]) => self.next(*window).init_agg_state(),
-            RowCount | RowNumber | Count(_) => DataValue::Int32(0),
-            Sum(_) | Min(_) | Max(_) | First(_) | Last(_) => DataValue::Null,
+            RowCount | RowNumber | Rank | DenseRank | Count(_) => DataValue::Int32(0),
+            Sum(_) | Min(_) | Max(_) | First(_) | Last(_) | StringAgg(_) => DataValue::Null,
             t => panic!("not aggregation: {t}"),
         }
     }
 
+    /// Evaluate the partition keys of a list of window functions.
+    ///
+    /// Returns a chunk of keys for each window function.
+    pub fn eval_partition_keys(&self, chunk: &DataChunk) -> Result<Vec<DataChunk>, ConvertError> {
+        (self.node().as_list().iter())
+            .map(|id| {
+                let Expr::Over([_, partitionby, _]) = self.expr[*id] else {
+                    panic!("not a window function: {}", self.expr[*id]);
+                };
+                self.next(partitionby).eval_list(chunk)
+            })
+            .collect()
+    }
+
+    /// Evaluate the order keys of a list of window functions.
+    ///
+    /// Returns a chunk of keys for each window function.
+    pub fn eval_order_keys(&self, chunk: &DataChunk) -> Result<Vec<DataChunk>, ConvertError> {
+        (self.node().as_list().iter())
+            .map(|id| {
+                let Expr::Over([_, _, orderby]) = self.expr[*id] else {
+                    panic!("not a window function: {}", self.expr[*id]);
+                };
+                self.next(orderby).eval_list(chunk)
+            })
+            .collect()
+    }
+
+    /// Update the states of ranking functions in a list of window functions.
+    ///
+    /// `rows` is the number of rows in the partition up to the current row, and `peers` tells
+    /// whether the current row has the same order keys as the previous one in the partition.
+    pub fn rank_list_update(&self, states: &mut [DataValue], rows: &[usize], peers: &[bool]) {
+        let list = self.node().as_list();
+        for (((state, id), rows), peer) in states.iter_mut().zip(list).zip(rows).zip(peers) {
+            let Expr::Over([window, _, _]) = self.expr[*id] else {
+                panic!("not a window function: {}", self.expr[*id]);
+            };
+            match (&self.expr[window], state) {
+                (Expr::Rank, state) if !peer => *state = DataValue::Int32(*rows as _),
+                (Expr::DenseRank, DataValue::Int32(rank)) if !peer => *rank += 1,
+                _ => {}
+            }
+        }
+    }
+
     /// Evaluate a list of aggregations.
     pub fn eval_agg_list(
         &self,
@@ -152,7 +409,9 @@ impl<'a> Evaluator<'a> {
     ) -> Result<(), ConvertError> {
         let list = self.node().as_list();
         for (state, id) in states.iter_mut().zip(list) {
-            *state = self.next(*id).eval_agg(state.clone(), chunk)?;
+            *state = self
+                .next(*id)
+                .eval_agg(std::mem::replace(state, DataValue::Null), chunk)?;
         }
         Ok(())
     }
@@ -165,7 +424,9 @@ impl<'a> Evaluator<'a> {
     ) {
         let list = self.node().as_list();
         for ((state, id), value) in states.iter_mut().zip(list).zip(values) {
-            *state = self.next(*id).agg_append(state.clone(), value);
+            *state = self
+                .next(*id)
+                .agg_append(std::mem::replace(state, DataValue::Null), value);
         }
     }
 
@@ -196,6 +457,10 @@ impl<'a> Evaluator<'a> {
             Max(a) => Ok(state.max(self.next(*a).eval(chunk)?.max_())),
             First(a) => Ok(state.or(self.next(*a).eval(chunk)?.first())),
             Last(a) => Ok(self.next(*a).eval(chunk)?.last().or(state)),
+            StringAgg([a, _]) => {
+                let array = self.next(*a).eval(chunk)?;
+                Ok((0..array.len()).fold(state, |state, i| self.agg_append(state, array.get(i))))
+            }
             t => panic!("not aggregation: {t}"),
         }
     }
@@ -206,12 +471,27 @@ impl<'a> Evaluator<'a> {
         match self.node() {
             Over([window, _, _]) => self.next(*window).agg_append(state, value),
             RowCount | RowNumber => state.add(DataValue::Int32(1)),
+            // updated by `rank_list_update`
+            Rank | DenseRank => state,
             Count(_) => state.add(DataValue::Int32(!value.is_null() as _)),
             Sum(_) => state.add(value),
             Min(_) => state.min(value),
             Max(_) => state.max(value),
             First(_) => state.or(value),
             Last(_) => value,
+            StringAgg([_, sep]) => match (state, value) {
+                (state, DataValue::Null) => state,
+                (DataValue::Null, value) => value,
+                (DataValue::String(mut state), DataValue::String(value)) => {
+                    let Expr::Constant(DataValue::String(sep)) = self.next(*sep).node() else {
+                        panic!("string_agg separator must be a string constant");
+                    };
+                    state.push_str(sep);
+                    state.push_str(&value);
+                    DataValue::String(state)
+                }
+                (state, value) => panic!("invalid string_agg state {state} and value {value}"),
+            },
             t => panic!("not aggregation: {t}"),
         }
     }
@@ -225,3 +505,227 @@ impl<'a> Evaluator<'a> {
             .collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use rust_decimal::Decimal;
+
+    use super::*;
+    use crate::planner::Config;
+    use crate::types::{ColumnIndex, F64};
+
+    #[test]
+    fn short_circuit() {
+        // 1 / 0 fails on the first row
+        let chunk: DataChunk = [ArrayImpl::new_decimal(
+            [Decimal::from(0), Decimal::from(2)].into_iter().collect(),
+        )]
+        .into_iter()
+        .collect();
+        let expr = "(and (<> #0 0) (> (/ 1 #0) 0))".parse().unwrap();
+        let result = Evaluator::new(&expr).eval(&chunk).unwrap();
+        assert_eq!(
+            result,
+            ArrayImpl::new_bool([false, true].into_iter().collect())
+        );
+
+        let expr = "(or (= #0 0) (> (/ 1 #0) 0))".parse().unwrap();
+        let result = Evaluator::new(&expr).eval(&chunk).unwrap();
+        assert_eq!(
+            result,
+            ArrayImpl::new_bool([true, true].into_iter().collect())
+        );
+
+        // without short-circuit
+        let expr = "(and (>= #0 0) (> (/ 1 #0) 0))".parse().unwrap();
+        assert!(Evaluator::new(&expr).eval(&chunk).is_err());
+    }
+
+    #[test]
+    fn is_distinct() {
+        let chunk: DataChunk = [
+            ArrayImpl::new_int32([Some(1), Some(1), None, None].into_iter().collect()),
+            ArrayImpl::new_int32([Some(1), Some(2), Some(1), None].into_iter().collect()),
+        ]
+        .into_iter()
+        .collect();
+        let expr = "(is_distinct #0 #1)".parse().unwrap();
+        let result = Evaluator::new(&expr).eval(&chunk).unwrap();
+        assert_eq!(
+            result,
+            ArrayImpl::new_bool([false, true, true, false].into_iter().collect())
+        );
+
+        let expr = "(is_not_distinct #0 #1)".parse().unwrap();
+        let result = Evaluator::new(&expr).eval(&chunk).unwrap();
+        assert_eq!(
+            result,
+            ArrayImpl::new_bool([true, false, false, true].into_iter().collect())
+        );
+
+        // compare with a NULL constant
+        let expr = "(is_distinct #0 null)".parse().unwrap();
+        let result = Evaluator::new(&expr).eval(&chunk).unwrap();
+        assert_eq!(
+            result,
+            ArrayImpl::new_bool([true, true, false, false].into_iter().collect())
+        );
+    }
+
+    #[test]
+    fn in_list() {
+        let chunk: DataChunk = [ArrayImpl::new_int32(
+            (0..1000).map(|i| (i % 7 != 0).then_some(i)).collect(),
+        )]
+        .into_iter()
+        .collect();
+
+        // compare with the chain of `=` and `OR`
+        let mut expr = RecExpr::default();
+        let mut naive = RecExpr::default();
+        let a = expr.add(Expr::ColumnIndex(ColumnIndex(0)));
+        let naive_a = naive.add(Expr::ColumnIndex(ColumnIndex(0)));
+        let mut values = vec![];
+        let mut cond = None;
+        for i in 0..500 {
+            let value = DataValue::Int32(i * 3);
+            values.push(expr.add(Expr::Constant(value.clone())));
+            let value = naive.add(Expr::Constant(value));
+            let eq = naive.add(Expr::Eq([naive_a, value]));
+            cond = Some(match cond {
+                Some(cond) => naive.add(Expr::Or([cond, eq])),
+                None => eq,
+            });
+        }
+        let list = expr.add(Expr::List(values.into()));
+        expr.add(Expr::In([a, list]));
+        let result = Evaluator::new(&expr).eval(&chunk).unwrap();
+        assert_eq!(result, Evaluator::new(&naive).eval(&chunk).unwrap());
+
+        // NULL if not found in a list with NULL
+        let expr = "(in #0 (list 1 2 null))".parse().unwrap();
+        let result = Evaluator::new(&expr).eval(&chunk.slice(0..4)).unwrap();
+        assert_eq!(
+            result,
+            ArrayImpl::new_bool([None, Some(true), Some(true), None].into_iter().collect())
+        );
+
+        // non-constant values are compared one by one
+        let expr = "(in #0 (list 2 (- #0 1)))".parse().unwrap();
+        let result = Evaluator::new(&expr).eval(&chunk.slice(0..4)).unwrap();
+        assert_eq!(
+            result,
+            ArrayImpl::new_bool(
+                [None, Some(false), Some(true), Some(false)]
+                    .into_iter()
+                    .collect()
+            )
+        );
+    }
+
+    #[test]
+    fn selection() {
+        // pseudo-random values in 0..8, where 0 is NULL in nullable columns
+        let mut seed = 1u64;
+        let mut rand = move || {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1);
+            (seed >> 33) as i32 % 8
+        };
+        let nullable = |v: i32| (v != 0).then_some(v);
+        let n = 1000;
+        let chunk: DataChunk = [
+            ArrayImpl::new_int32((0..n).map(|_| nullable(rand())).collect()),
+            ArrayImpl::new_int32((0..n).map(|_| Some(rand())).collect()),
+            ArrayImpl::new_int64((0..n).map(|_| Some(rand() as i64)).collect()),
+            ArrayImpl::new_float64(
+                (0..n)
+                    .map(|_| nullable(rand()).map(|v| F64::from(v as f64)))
+                    .collect(),
+            ),
+            ArrayImpl::new_float64((0..n).map(|_| Some(F64::from(rand() as f64))).collect()),
+        ]
+        .into_iter()
+        .collect();
+
+        for expr in [
+            "(> #0 3)",
+            "(< 3 #0)",
+            "(<= 3 #1)",
+            "(= #0 #1)",
+            "(<> #0 #1)",
+            "(>= #3 #4)",
+            // fall back to the scalar path
+            "(> #0 #2)",
+            "(= #2 5)",
+            "(isnull #0)",
+            "(not (> #0 3))",
+            "true",
+            "false",
+            // combinations
+            "(and (> #0 2) (< #1 5))",
+            "(or (= #0 1) (isnull #0))",
+            "(or (< #3 #4) (> #1 6))",
+            "(and (or (> #0 5) (< #1 2)) (<> #2 3))",
+            "(or (and (> #0 5) (< #1 2)) (and (isnull #3) (= #1 #0)))",
+            "(and (> #0 8) (< #1 5))",
+            "(or (>= #1 0) (< #0 5))",
+        ] {
+            let expr = expr.parse().unwrap();
+            let evaluator = Evaluator::new(&expr);
+            let ArrayImpl::Bool(expected) = evaluator.eval(&chunk).unwrap() else {
+                panic!("not bool array");
+            };
+            let expected = expected.iter().map(|v| v == Some(&true)).collect_vec();
+            assert_eq!(
+                evaluator.eval_selection(&chunk).unwrap(),
+                expected,
+                "{evaluator}"
+            );
+        }
+    }
+
+    #[test]
+    fn integer_overflow() {
+        let chunk: DataChunk = [ArrayImpl::new_int32([i32::MAX].into_iter().collect())]
+            .into_iter()
+            .collect();
+        let expr = "(+ #0 1)".parse().unwrap();
+        assert_eq!(
+            Evaluator::new(&expr).eval(&chunk),
+            Err(ConvertError::IntegerOverflow(i32::MAX as i64, "+", 1))
+        );
+
+        let config = Config {
+            wrapping_arithmetic: true,
+            ..Default::default()
+        };
+        let result = super::super::CONFIG.sync_scope(config, || Evaluator::new(&expr).eval(&chunk));
+        assert_eq!(
+            result,
+            Ok(ArrayImpl::new_int32([i32::MIN].into_iter().collect()))
+        );
+    }
+
+    #[test]
+    fn cast_strictness() {
+        let chunk: DataChunk = [ArrayImpl::new_utf8(
+            [Some("1"), Some("x"), None].into_iter().collect(),
+        )]
+        .into_iter()
+        .collect();
+        let expr = "(cast INT #0)".parse().unwrap();
+        assert!(Evaluator::new(&expr).eval(&chunk).is_err());
+
+        let config = Config {
+            strict_casts: false,
+            ..Default::default()
+        };
+        let result = super::super::CONFIG.sync_scope(config, || Evaluator::new(&expr).eval(&chunk));
+        assert_eq!(
+            result,
+            Ok(ArrayImpl::new_int32(
+                [Some(1), None, None].into_iter().collect()
+            ))
+        );
+    }
+}
@@ -10,25 +10,37 @@ use crate::planner::{Explain, Optimizer};
 /// The executor of `explain` statement.
 pub struct ExplainExecutor {
     pub plan: RecExpr,
+    /// Whether to show the physical properties of each node.
+    pub verbose: bool,
+    /// The optimizer to compute the costs of the plan.
+    pub optimizer: Optimizer,
     pub catalog: RootCatalogRef,
 }
 
 impl ExplainExecutor {
     pub fn execute(self) -> BoxedExecutor {
-        let costs = Optimizer::new(self.catalog.clone(), Default::default()).costs(&self.plan);
-        let explain_obj = Explain::of(&self.plan)
+        let costs = self.optimizer.costs(&self.plan);
+        let properties = (self.verbose).then(|| self.optimizer.properties(&self.plan));
+        let mut explain_obj = Explain::of(&self.plan)
             .with_costs(&costs)
             .with_catalog(&self.catalog);
-        let explainer = explain_obj.pretty();
-        let mut explain = String::with_capacity(4096);
-        let mut config = PrettyConfig {
-            need_boundaries: false,
-            ..PrettyConfig::default()
-        };
-        config.unicode(&mut explain, &explainer);
-        let chunk =
-            DataChunk::from_iter([ArrayImpl::new_utf8(Utf8Array::from_iter([Some(explain)]))]);
+        if let Some(properties) = &properties {
+            explain_obj = explain_obj.with_properties(properties);
+        }
+        let chunk = explain_chunk(&explain_obj);
 
         stream::once(future::ok(chunk)).boxed()
     }
 }
+
+/// Renders the explain into a chunk of a single string.
+pub fn explain_chunk(explain_obj: &Explain) -> DataChunk {
+    let explainer = explain_obj.pretty();
+    let mut explain = String::with_capacity(4096);
+    let mut config = PrettyConfig {
+        need_boundaries: false,
+        ..PrettyConfig::default()
+    };
+    config.unicode(&mut explain, &explainer);
+    DataChunk::from_iter([ArrayImpl::new_utf8(Utf8Array::from_iter([Some(explain)]))])
+}
@@ -1,10 +1,12 @@
 // Copyright 2023 RisingLight Project Authors. Licensed under Apache-2.0.
 
+use std::collections::hash_map::Entry;
 use std::collections::HashMap;
 
 use iter_chunks::IterChunks;
 use smallvec::SmallVec;
 
+use super::spill::{partition_of, SpillFile, SPILL_PARTITIONS};
 use super::*;
 use crate::array::DataChunkBuilder;
 use crate::types::DataValue;
@@ -14,14 +16,24 @@ pub struct HashAggExecutor {
     pub aggs: RecExpr,
     pub group_keys: RecExpr,
     pub types: Vec<DataType>,
+    /// The memory budget of aggregation states in bytes.
+    /// Groups are spilled to temporary files if it is exceeded. `None` means unlimited.
+    pub memory_budget: Option<usize>,
 }
 
 pub type GroupKeys = SmallVec<[DataValue; 4]>;
 pub type AggValue = SmallVec<[DataValue; 16]>;
 
 impl HashAggExecutor {
+    pub fn execute(self, child: BoxedExecutor) -> BoxedExecutor {
+        match self.memory_budget {
+            Some(budget) => self.execute_spill(child, budget),
+            None => self.execute_in_memory(child),
+        }
+    }
+
     #[try_stream(boxed, ok = DataChunk, error = ExecutorError)]
-    pub async fn execute(self, child: BoxedExecutor) {
+    async fn execute_in_memory(self, child: BoxedExecutor) {
         let mut states = HashMap::<GroupKeys, AggValue>::new();
 
         #[for_await]
@@ -52,4 +64,183 @@ impl HashAggExecutor {
             }
         }
     }
+
+    /// Aggregate with a memory budget.
+    ///
+    /// Groups are divided into partitions by the hash of keys. When the budget is exceeded, the
+    /// largest partition in memory is spilled: its states are written to a temporary file, and
+    /// subsequent rows of the partition are appended to the file instead of being aggregated.
+    /// After the input is exhausted, spilled partitions are restored and aggregated one at a
+    /// time. Note that a spilled partition is still aggregated in memory as a whole.
+    #[try_stream(boxed, ok = DataChunk, error = ExecutorError)]
+    async fn execute_spill(self, child: BoxedExecutor, budget: usize) {
+        let evaluator = Evaluator::new(&self.aggs);
+        let mut partitions = (0..SPILL_PARTITIONS)
+            .map(|_| Partition::default())
+            .collect_vec();
+        let mut memory_usage = 0;
+
+        #[for_await]
+        for chunk in child {
+            let chunk = chunk?;
+            let keys_chunk = Evaluator::new(&self.group_keys).eval_list(&chunk)?;
+            let args_chunk = evaluator.eval_list(&chunk)?;
+
+            for i in 0..chunk.cardinality() {
+                let keys: GroupKeys = keys_chunk.row(i).values().collect();
+                let partition = &mut partitions[partition_of(&keys)];
+                if let Some(file) = &mut partition.file {
+                    file.write_row(keys.into_iter().chain(args_chunk.row(i).values()))
+                        .await?;
+                    continue;
+                }
+                let mut size = partition.size;
+                // the states of a new group are counted in full
+                let (states, old_states_size) = match partition.states.entry(keys) {
+                    Entry::Occupied(e) => {
+                        let states = e.into_mut();
+                        let states_size = estimated_size(states);
+                        (states, states_size)
+                    }
+                    Entry::Vacant(e) => {
+                        size += estimated_size(e.key());
+                        (e.insert(evaluator.init_agg_states()), 0)
+                    }
+                };
+                evaluator.agg_list_append(states, args_chunk.row(i).values());
+                size = size + estimated_size(states) - old_states_size;
+                memory_usage = memory_usage + size - partition.size;
+                partition.size = size;
+
+                while memory_usage > budget {
+                    let Some(partition) = partitions
+                        .iter_mut()
+                        .filter(|p| p.file.is_none() && !p.states.is_empty())
+                        .max_by_key(|p| p.size)
+                    else {
+                        break;
+                    };
+                    memory_usage -= partition.size;
+                    partition.spill().await?;
+                }
+            }
+        }
+
+        // output partitions in memory first to release their states
+        let mut builder = DataChunkBuilder::new(&self.types, PROCESSING_WINDOW_SIZE);
+        let mut spilled = vec![];
+        for partition in partitions {
+            let Some(file) = partition.file else {
+                for (key, aggs) in partition.states {
+                    if let Some(chunk) = builder.push_row(aggs.into_iter().chain(key)) {
+                        yield chunk;
+                    }
+                }
+                continue;
+            };
+            spilled.push((file, partition.spilled_groups));
+        }
+        let num_keys = self.types.len() - evaluator.init_agg_states::<AggValue>().len();
+        for (file, spilled_groups) in spilled {
+            let states = restore(&evaluator, file, spilled_groups, num_keys).await?;
+            for (key, aggs) in states {
+                if let Some(chunk) = builder.push_row(aggs.into_iter().chain(key)) {
+                    yield chunk;
+                }
+            }
+        }
+        if let Some(chunk) = builder.take() {
+            yield chunk;
+        }
+    }
+}
+
+/// Restore the states of a spilled partition, and aggregate the rows in it.
+async fn restore(
+    evaluator: &Evaluator<'_>,
+    file: SpillFile,
+    spilled_groups: usize,
+    num_keys: usize,
+) -> Result<HashMap<GroupKeys, AggValue>, ExecutorError> {
+    let mut reader = file.into_reader().await?;
+    let mut states = HashMap::<GroupKeys, AggValue>::new();
+    for _ in 0..spilled_groups {
+        let row = reader.read_row().await?.ok_or_else(|| {
+            std::io::Error::new(std::io::ErrorKind::UnexpectedEof, "missing spilled states")
+        })?;
+        let mut values = row.into_iter();
+        let keys = values.by_ref().take(num_keys).collect();
+        states.insert(keys, values.collect());
+    }
+    while let Some(row) = reader.read_row().await? {
+        let mut values = row.into_iter();
+        let keys = values.by_ref().take(num_keys).collect();
+        let states = states
+            .entry(keys)
+            .or_insert_with(|| evaluator.init_agg_states());
+        evaluator.agg_list_append(states, values);
+    }
+    Ok(states)
+}
+
+/// A partition of groups in spilling hash aggregation.
+#[derive(Default)]
+struct Partition {
+    /// Aggregation states of groups in memory.
+    states: HashMap<GroupKeys, AggValue>,
+    /// The estimated memory size of `states` in bytes.
+    size: usize,
+    /// The file that the partition is spilled to.
+    ///
+    /// It starts with `spilled_groups` rows of keys and states, followed by input rows of keys
+    /// and aggregation arguments after spilling.
+    file: Option<SpillFile>,
+    spilled_groups: usize,
+}
+
+impl Partition {
+    /// Spill all states to a new file.
+    async fn spill(&mut self) -> Result<(), ExecutorError> {
+        let mut file = SpillFile::create().await?;
+        self.spilled_groups = self.states.len();
+        for (keys, states) in self.states.drain() {
+            file.write_row(keys.into_iter().chain(states)).await?;
+        }
+        self.states = HashMap::new();
+        self.size = 0;
+        self.file = Some(file);
+        Ok(())
+    }
+}
+
+/// Returns the estimated memory size of values in bytes.
+fn estimated_size(values: &[DataValue]) -> usize {
+    let heap_size = |value: &DataValue| match value {
+        DataValue::String(s) => s.capacity(),
+        DataValue::Blob(b) => b.len(),
+        _ => 0,
+    };
+    values
+        .iter()
+        .map(|v| std::mem::size_of::<DataValue>() + heap_size(v))
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn restore_missing_states() {
+        let aggs: RecExpr = "(list (sum #1))".parse().unwrap();
+        let evaluator = Evaluator::new(&aggs);
+        let mut file = SpillFile::create().await.unwrap();
+        file.write_row([DataValue::Int32(1), DataValue::Int32(10)])
+            .await
+            .unwrap();
+
+        // the file has 1 group of states but 2 are expected
+        let result = restore(&evaluator, file, 2, 1).await;
+        assert!(matches!(result, Err(ExecutorError::Io(_))));
+    }
 }
@@ -6,16 +6,22 @@ use std::vec::Vec;
 use futures::TryStreamExt;
 use smallvec::SmallVec;
 
+use super::spill::{partition_of, SpillFile, SPILL_PARTITIONS};
 use super::*;
 use crate::array::{DataChunk, DataChunkBuilder, RowRef};
 use crate::types::{DataType, DataValue};
 
 /// The executor for hash join
+#[derive(Clone)]
 pub struct HashJoinExecutor<const T: JoinType> {
     pub left_keys: RecExpr,
     pub right_keys: RecExpr,
     pub left_types: Vec<DataType>,
     pub right_types: Vec<DataType>,
+    /// The memory budget of the build side in bytes.
+    /// Both sides are partitioned and spilled to temporary files if it is exceeded.
+    /// `None` means unlimited.
+    pub memory_budget: Option<usize>,
 }
 
 /// Join types for generating join code during the compilation.
@@ -25,49 +31,59 @@ pub enum JoinType {
     LeftOuter,
     RightOuter,
     FullOuter,
+    Semi,
+    Anti,
 }
 
 pub type JoinKeys = SmallVec<[DataValue; 2]>;
 
 impl<const T: JoinType> HashJoinExecutor<T> {
+    pub fn execute(self, left: BoxedExecutor, right: BoxedExecutor) -> BoxedExecutor {
+        match self.memory_budget {
+            Some(budget) => self.execute_grace(left, right, budget),
+            None => self.execute_in_memory(left, right),
+        }
+    }
+
     #[try_stream(boxed, ok = DataChunk, error = ExecutorError)]
-    pub async fn execute(self, left: BoxedExecutor, right: BoxedExecutor) {
-        // collect all chunks from children
-        let (left_chunks, right_chunks) = async {
-            tokio::try_join!(
-                left.try_collect::<Vec<DataChunk>>(),
-                right.try_collect::<Vec<DataChunk>>(),
-            )
+    async fn execute_in_memory(self, left: BoxedExecutor, right: BoxedExecutor) {
+        if T == JoinType::Semi || T == JoinType::Anti {
+            #[for_await]
+            for chunk in self.semi_join(left, right) {
+                yield chunk?;
+            }
+            return Ok(());
         }
-        .await?;
 
         // build
-        let mut hash_map: HashMap<JoinKeys, SmallVec<[RowRef<'_>; 1]>> = HashMap::new();
+        // each key of the build side has a flag of whether it is matched by the probe side,
+        // so that the memory for outer joins is bounded by the build side.
+        let left_chunks = left.try_collect::<Vec<DataChunk>>().await?;
+        let mut hash_map: HashMap<JoinKeys, (SmallVec<[RowRef<'_>; 1]>, bool)> = HashMap::new();
         for chunk in &left_chunks {
             let keys_chunk = Evaluator::new(&self.left_keys).eval_list(chunk)?;
             for i in 0..chunk.cardinality() {
                 let keys = keys_chunk.row(i).values().collect();
                 let row = chunk.row(i);
-                hash_map.entry(keys).or_insert_with(SmallVec::new).push(row);
+                hash_map.entry(keys).or_default().0.push(row);
                 tokio::task::consume_budget().await;
             }
         }
 
         let data_types = self.left_types.iter().chain(self.right_types.iter());
         let mut builder = DataChunkBuilder::new(data_types, PROCESSING_WINDOW_SIZE);
-        let mut right_keys = HashSet::new();
 
-        // probe
-        for chunk in &right_chunks {
-            let keys_chunk = Evaluator::new(&self.right_keys).eval_list(chunk)?;
+        // probe the right side chunk by chunk, without materializing it
+        #[for_await]
+        for chunk in right {
+            let chunk = chunk?;
+            let keys_chunk = Evaluator::new(&self.right_keys).eval_list(&chunk)?;
             for i in 0..chunk.cardinality() {
                 let right_row = chunk.row(i);
                 let keys: JoinKeys = keys_chunk.row(i).values().collect();
-                if T == JoinType::LeftOuter || T == JoinType::FullOuter {
-                    right_keys.insert(keys.clone());
-                }
-                if let Some(left_rows) = hash_map.get(&keys) {
-                    for left_row in left_rows {
+                if let Some((left_rows, matched)) = hash_map.get_mut(&keys) {
+                    *matched = true;
+                    for left_row in left_rows.iter() {
                         let values = left_row.values().chain(right_row.values());
                         if let Some(chunk) = builder.push_row(values) {
                             yield chunk;
@@ -83,6 +99,10 @@ impl<const T: JoinType> HashJoinExecutor<T> {
                 }
                 tokio::task::consume_budget().await;
             }
+            // emit the matched rows of this chunk before waiting for the next one
+            if let Some(chunk) = builder.take() {
+                yield chunk;
+            }
         }
 
         // append rows for left outer join
@@ -92,7 +112,7 @@ impl<const T: JoinType> HashJoinExecutor<T> {
                 for i in 0..chunk.cardinality() {
                     let keys: JoinKeys = keys_chunk.row(i).values().collect();
                     let row = chunk.row(i);
-                    if right_keys.contains(&keys) {
+                    if hash_map[&keys].1 {
                         continue;
                     }
                     // append row: (left, NULL)
@@ -110,4 +130,256 @@ impl<const T: JoinType> HashJoinExecutor<T> {
             yield chunk;
         }
     }
+
+    /// Grace hash join with a memory budget of the build side.
+    ///
+    /// The build side is buffered in memory until it exceeds the budget. If it never does, the
+    /// join is done in memory. Otherwise, rows of both sides are partitioned by the hash of join
+    /// keys and spilled to temporary files, so that rows with equal keys are in the same pair of
+    /// partitions. Then each pair of partitions is joined in memory one at a time.
+    ///
+    /// The build side is the right side for semi and anti joins, and the left side otherwise.
+    #[try_stream(boxed, ok = DataChunk, error = ExecutorError)]
+    async fn execute_grace(self, left: BoxedExecutor, right: BoxedExecutor, budget: usize) {
+        let executor = HashJoinExecutor::<T> {
+            memory_budget: None,
+            ..self
+        };
+        let build_right = T == JoinType::Semi || T == JoinType::Anti;
+        let (mut build, probe) = if build_right {
+            (right, left)
+        } else {
+            (left, right)
+        };
+
+        let mut build_chunks = vec![];
+        let mut size = 0;
+        while size <= budget {
+            let Some(chunk) = build.try_next().await? else {
+                let build = futures::stream::iter(build_chunks.into_iter().map(Ok)).boxed();
+                let (left, right) = if build_right {
+                    (probe, build)
+                } else {
+                    (build, probe)
+                };
+                #[for_await]
+                for chunk in executor.execute(left, right) {
+                    yield chunk?;
+                }
+                return Ok(());
+            };
+            size += chunk.estimated_size();
+            build_chunks.push(chunk);
+        }
+
+        let buffered = futures::stream::iter(build_chunks.into_iter().map(Ok));
+        let build = buffered.chain(build).boxed();
+        let (left, right) = if build_right {
+            (probe, build)
+        } else {
+            (build, probe)
+        };
+        let left_files = partition(left, &executor.left_keys).await?;
+        let right_files = partition(right, &executor.right_keys).await?;
+
+        for (left_file, right_file) in left_files.into_iter().zip(right_files) {
+            let left = left_file.into_chunks(executor.left_types.clone());
+            let right = right_file.into_chunks(executor.right_types.clone());
+            #[for_await]
+            for chunk in executor.clone().execute(left, right) {
+                yield chunk?;
+            }
+        }
+    }
+
+    /// Semi join outputs left rows that have a match in the right side.
+    /// Anti join outputs left rows that have no match.
+    ///
+    /// NULL keys never match anything.
+    #[try_stream(boxed, ok = DataChunk, error = ExecutorError)]
+    async fn semi_join(self, left: BoxedExecutor, right: BoxedExecutor) {
+        // build
+        let mut right_keys = HashSet::new();
+        #[for_await]
+        for chunk in right {
+            let chunk = chunk?;
+            let keys_chunk = Evaluator::new(&self.right_keys).eval_list(&chunk)?;
+            for i in 0..chunk.cardinality() {
+                let keys: JoinKeys = keys_chunk.row(i).values().collect();
+                if !keys.iter().any(|v| v.is_null()) {
+                    right_keys.insert(keys);
+                }
+            }
+        }
+
+        // probe the left side chunk by chunk
+        let mut builder = DataChunkBuilder::new(&self.left_types, PROCESSING_WINDOW_SIZE);
+        #[for_await]
+        for chunk in left {
+            let chunk = chunk?;
+            let keys_chunk = Evaluator::new(&self.left_keys).eval_list(&chunk)?;
+            for i in 0..chunk.cardinality() {
+                let keys: JoinKeys = keys_chunk.row(i).values().collect();
+                let matched = right_keys.contains(&keys);
+                if matched == (T == JoinType::Semi) {
+                    if let Some(output) = builder.push_row(chunk.row(i).values()) {
+                        yield output;
+                    }
+                }
+            }
+            if let Some(chunk) = builder.take() {
+                yield chunk;
+            }
+        }
+    }
+}
+
+/// Partition rows by the hash of keys into spill files.
+async fn partition(
+    mut input: BoxedExecutor,
+    keys: &RecExpr,
+) -> Result<Vec<SpillFile>, ExecutorError> {
+    let mut files = vec![];
+    for _ in 0..SPILL_PARTITIONS {
+        files.push(SpillFile::create().await?);
+    }
+    while let Some(chunk) = input.try_next().await? {
+        let keys_chunk = Evaluator::new(keys).eval_list(&chunk)?;
+        for i in 0..chunk.cardinality() {
+            let keys: JoinKeys = keys_chunk.row(i).values().collect();
+            files[partition_of(&keys)]
+                .write_row(chunk.row(i).values())
+                .await?;
+        }
+    }
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::array::ArrayImpl;
+    use crate::types::DataTypeKind;
+
+    fn int32(values: &[i32]) -> DataChunk {
+        [ArrayImpl::new_int32(values.iter().cloned().collect())]
+            .into_iter()
+            .collect()
+    }
+
+    fn executor<const T: JoinType>() -> HashJoinExecutor<T> {
+        HashJoinExecutor {
+            left_keys: "(list #0)".parse().unwrap(),
+            right_keys: "(list #0)".parse().unwrap(),
+            left_types: vec![DataTypeKind::Int32.not_null()],
+            right_types: vec![DataTypeKind::Int32.not_null()],
+            memory_budget: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn probe_incrementally() {
+        let left = futures::stream::iter([Ok(int32(&[1, 2]))]).boxed();
+        // the probe side never ends
+        let right = futures::stream::iter([Ok(int32(&[1, 3]))])
+            .chain(futures::stream::pending())
+            .boxed();
+        let mut output = executor::<{ JoinType::Inner }>().execute(left, right);
+        let chunk = tokio::time::timeout(Duration::from_secs(1), output.next())
+            .await
+            .expect("matched rows should be emitted before the probe side ends")
+            .unwrap()
+            .unwrap();
+        let expected: DataChunk = [
+            ArrayImpl::new_int32([1].into_iter().collect()),
+            ArrayImpl::new_int32([1].into_iter().collect()),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(chunk, expected);
+    }
+
+    #[tokio::test]
+    async fn left_outer_emits_unmatched_build_rows_last() {
+        let left = futures::stream::iter([Ok(int32(&[1, 2]))]).boxed();
+        let right = futures::stream::iter([Ok(int32(&[1])), Ok(int32(&[3]))]).boxed();
+        let output = executor::<{ JoinType::LeftOuter }>()
+            .execute(left, right)
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        let rows = output
+            .iter()
+            .flat_map(|chunk| chunk.rows().map(|row| row.values().collect_vec()))
+            .collect_vec();
+        assert_eq!(
+            rows,
+            vec![
+                vec![DataValue::Int32(1), DataValue::Int32(1)],
+                vec![DataValue::Int32(2), DataValue::Null],
+            ]
+        );
+    }
+
+    /// Chunks of `(key, id)`, where keys have duplicates and NULLs.
+    fn input(modulo: i32) -> Vec<DataChunk> {
+        (0..4)
+            .map(|c| {
+                let ids = (c * 100..(c + 1) * 100).collect_vec();
+                let keys = ids
+                    .iter()
+                    .map(|id| (id % modulo != 0).then_some(id % modulo));
+                [
+                    ArrayImpl::new_int32(keys.collect()),
+                    ArrayImpl::new_int32(ids.iter().cloned().collect()),
+                ]
+                .into_iter()
+                .collect()
+            })
+            .collect()
+    }
+
+    /// Returns the sorted rows of the join.
+    async fn join<const T: JoinType>(memory_budget: Option<usize>) -> Vec<Vec<DataValue>> {
+        let types = vec![DataTypeKind::Int32.nullable(); 2];
+        let executor = HashJoinExecutor::<T> {
+            left_keys: "(list #0)".parse().unwrap(),
+            right_keys: "(list #0)".parse().unwrap(),
+            left_types: types.clone(),
+            right_types: types,
+            memory_budget,
+        };
+        let left = futures::stream::iter(input(37).into_iter().map(Ok)).boxed();
+        let right = futures::stream::iter(input(23).into_iter().map(Ok)).boxed();
+        let output = executor
+            .execute(left, right)
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        output
+            .iter()
+            .flat_map(|chunk| chunk.rows().map(|row| row.values().collect_vec()))
+            .sorted()
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn grace_join() {
+        async fn check<const T: JoinType>() {
+            let expected = join::<T>(None).await;
+            assert!(!expected.is_empty());
+            // the build side is spilled with a budget of 0, and never spilled with the maximum
+            for budget in [0, 4096, usize::MAX] {
+                assert_eq!(join::<T>(Some(budget)).await, expected, "budget: {budget}");
+            }
+        }
+        check::<{ JoinType::Inner }>().await;
+        check::<{ JoinType::LeftOuter }>().await;
+        check::<{ JoinType::RightOuter }>().await;
+        check::<{ JoinType::FullOuter }>().await;
+        check::<{ JoinType::Semi }>().await;
+        check::<{ JoinType::Anti }>().await;
+    }
 }
@@ -1,17 +1,31 @@
 // Copyright 2023 RisingLight Project Authors. Licensed under Apache-2.0.
 
+use std::collections::HashSet;
+use std::ops::Bound;
 use std::sync::Arc;
 
 use super::*;
 use crate::array::DataChunk;
-use crate::catalog::{ColumnId, TableRefId};
-use crate::storage::{Storage, Table, Transaction};
+use crate::catalog::{ColumnId, ForeignKey, TableRefId};
+use crate::storage::{
+    KeyRange, ScanOptions, Storage, StorageColumnRef, Table, Transaction, TxnIterator,
+};
 use crate::types::ColumnIndex;
 
 /// The executor of `insert` statement.
 pub struct InsertExecutor<S: Storage> {
     pub table_id: TableRefId,
     pub column_ids: Vec<ColumnId>,
+    /// Sets of columns with a UNIQUE constraint.
+    pub unique_keys: Vec<Vec<ColumnId>>,
+    /// For each unique key, the position of the column that the table can seek to by a range
+    /// scan. If `None`, the table is scanned entirely.
+    pub unique_seek_columns: Vec<Option<usize>>,
+    /// FOREIGN KEY constraints of the table.
+    pub foreign_keys: Vec<ForeignKey>,
+    /// For each foreign key, the position of the referenced column that the referenced table
+    /// can seek to by a range scan. If `None`, the referenced table is scanned entirely.
+    pub seek_columns: Vec<Option<usize>>,
     pub storage: Arc<S>,
 }
 
@@ -38,11 +52,72 @@ impl<S: Storage> InsertExecutor<S> {
             .collect();
         expr.add(Expr::List(list));
 
-        let mut txn = table.write().await?;
+        // positions of unique keys and foreign keys in the inserted chunk
+        let position = |key: &[ColumnId]| {
+            (key.iter())
+                .map(|id| columns.iter().position(|c| c.id() == *id).unwrap())
+                .collect_vec()
+        };
+        let unique_keys = (self.unique_keys.iter())
+            .map(|key| position(key))
+            .collect_vec();
+        let foreign_keys = (self.foreign_keys.iter())
+            .map(|fk| position(&fk.columns))
+            .collect_vec();
+        // unique keys are checked in the transaction that appends the rows. it holds the lock
+        // of the table, so that no other transaction can insert the same key concurrently.
+        let mut txn = if unique_keys.is_empty() {
+            table.write().await?
+        } else {
+            table.update().await?
+        };
+        let mut existing_keys = vec![];
+        for (key, seek_column) in self.unique_keys.iter().zip(&self.unique_seek_columns) {
+            existing_keys.push(KeySet::new(&txn, key, *seek_column).await?);
+        }
+        let mut referenced_keys = vec![];
+        for (fk, seek_column) in self.foreign_keys.iter().zip(&self.seek_columns) {
+            let ref_table = self.storage.get_table(fk.ref_table)?;
+            let ref_txn = ref_table.read().await?;
+            let keys = KeySet::new(&ref_txn, &fk.ref_columns, *seek_column).await?;
+            referenced_keys.push((ref_txn, keys));
+        }
+
         let mut cnt = 0;
         #[for_await]
         for chunk in child {
             let chunk = Evaluator::new(&expr).eval_list(&chunk?)?;
+            for row in chunk.rows() {
+                for (key, existing) in unique_keys.iter().zip(&mut existing_keys) {
+                    let values = row.get_by_indexes(key);
+                    // NULLs are never equal to each other
+                    if values.iter().any(|v| v.is_null()) {
+                        continue;
+                    }
+                    if existing.contains(&txn, values.clone()).await? {
+                        let columns = key.iter().map(|i| columns[*i].name()).join(", ");
+                        Err(ExecutorError::UniqueViolation(
+                            format!("({})", values.iter().join(", ")),
+                            columns,
+                        ))?;
+                    }
+                    existing.insert(values);
+                }
+                for (key, (ref_txn, referenced)) in foreign_keys.iter().zip(&mut referenced_keys) {
+                    let values = row.get_by_indexes(key);
+                    // a key with NULL doesn't reference any row
+                    if values.iter().any(|v| v.is_null()) {
+                        continue;
+                    }
+                    if !referenced.contains(&*ref_txn, values.clone()).await? {
+                        Err(ExecutorError::ForeignKeyViolation(format!(
+                            "({})=({})",
+                            key.iter().map(|i| columns[*i].name()).join(", "),
+                            values.iter().join(", "),
+                        )))?;
+                    }
+                }
+            }
             cnt += chunk.cardinality();
             txn.append(chunk).await?;
         }
@@ -52,6 +127,112 @@ impl<S: Storage> InsertExecutor<S> {
     }
 }
 
+/// The keys of a table, checked by a UNIQUE or FOREIGN KEY constraint.
+struct KeySet {
+    /// The columns of the key.
+    columns: Vec<ColumnId>,
+    /// The position of the column in `columns` to seek by a range scan.
+    /// If `None`, all keys of the table are in `keys`.
+    seek_column: Option<usize>,
+    /// The keys known to exist in the table.
+    keys: HashSet<Vec<DataValue>>,
+}
+
+impl KeySet {
+    /// Creates a set of keys in the table read by `txn`.
+    ///
+    /// If the table can not seek to a key, all keys are scanned in advance.
+    async fn new(
+        txn: &impl Transaction,
+        columns: &[ColumnId],
+        seek_column: Option<usize>,
+    ) -> Result<Self, ExecutorError> {
+        let keys = match seek_column {
+            // keys are looked up when inserted
+            Some(_) => HashSet::new(),
+            None => scan_keys(txn, &[columns.to_vec()]).await?.remove(0),
+        };
+        Ok(KeySet {
+            columns: columns.to_vec(),
+            seek_column,
+            keys,
+        })
+    }
+
+    /// Returns true if the key exists in the table.
+    async fn contains(
+        &mut self,
+        txn: &impl Transaction,
+        key: Vec<DataValue>,
+    ) -> Result<bool, ExecutorError> {
+        if self.keys.contains(&key) {
+            return Ok(true);
+        }
+        let Some(seek_column) = self.seek_column else {
+            return Ok(false);
+        };
+        let value = key[seek_column].clone();
+        let range = KeyRange {
+            start: Bound::Included(value.clone()),
+            end: Bound::Included(value),
+        };
+        let col_idx = (self.columns.iter())
+            .map(|id| StorageColumnRef::Idx(*id))
+            .collect_vec();
+        let options = ScanOptions::default().with_filter_opt(Some(range));
+        let mut it = txn.scan(&col_idx, options).await?;
+        while let Some(chunk) = it.next_batch(None).await? {
+            if chunk.rows().any(|row| row.values().eq(key.iter().cloned())) {
+                self.keys.insert(key);
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Adds a key to the set.
+    fn insert(&mut self, key: Vec<DataValue>) {
+        self.keys.insert(key);
+    }
+}
+
+/// Returns the values of each key in the table.
+///
+/// Keys containing NULL are skipped.
+pub(super) async fn scan_keys(
+    txn: &impl Transaction,
+    keys: &[Vec<ColumnId>],
+) -> Result<Vec<HashSet<Vec<DataValue>>>, ExecutorError> {
+    let mut sets = vec![HashSet::new(); keys.len()];
+    if keys.is_empty() {
+        return Ok(sets);
+    }
+    let col_ids = keys.iter().flatten().copied().unique().collect_vec();
+    let col_idx = (col_ids.iter())
+        .map(|id| StorageColumnRef::Idx(*id))
+        .collect_vec();
+    let keys = (keys.iter())
+        .map(|key| {
+            (key.iter())
+                .map(|id| col_ids.iter().position(|c| c == id).unwrap())
+                .collect_vec()
+        })
+        .collect_vec();
+
+    let mut it = txn.scan(&col_idx, ScanOptions::default()).await?;
+    while let Some(chunk) = it.next_batch(None).await? {
+        for row in chunk.rows() {
+            for (key, set) in keys.iter().zip(&mut sets) {
+                let values = row.get_by_indexes(key);
+                if !values.iter().any(|v| v.is_null()) {
+                    set.insert(values);
+                }
+            }
+        }
+    }
+    Ok(sets)
+}
+
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
@@ -68,6 +249,10 @@ mod tests {
         let executor = InsertExecutor {
             table_id: TableRefId::new(0, 0),
             column_ids: vec![0, 1],
+            unique_keys: vec![],
+            unique_seek_columns: vec![],
+            foreign_keys: vec![],
+            seek_columns: vec![],
             storage: storage.as_in_memory_storage(),
         };
         let source = async_stream::try_stream! {
@@ -90,10 +275,18 @@ mod tests {
                 0,
                 "t",
                 &[
-                    ColumnCatalog::new(0, DataTypeKind::Int32.not_null().to_column("v1".into(), false)),
-                    ColumnCatalog::new(1, DataTypeKind::Int32.not_null().to_column("v2".into(), false)),
+                    ColumnCatalog::new(
+                        0,
+                        DataTypeKind::Int32.not_null().to_column("v1".into(), false),
+                    ),
+                    ColumnCatalog::new(
+                        1,
+                        DataTypeKind::Int32.not_null().to_column("v2".into(), false),
+                    ),
                 ],
                 &[],
+                &[],
+                &[],
             )
             .await
             .unwrap();
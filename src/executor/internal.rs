@@ -1,19 +1,36 @@
 // Copyright 2023 RisingLight Project Authors. Licensed under Apache-2.0.
 use super::*;
-use crate::array::{ArrayImpl, Utf8Array};
-use crate::catalog::{TableRefId, CONTRIBUTORS_TABLE_ID};
+use crate::array::{
+    ArrayBuilder, ArrayBuilderImpl, ArrayImpl, BoolArrayBuilder, I32ArrayBuilder, Utf8Array,
+    Utf8ArrayBuilder,
+};
+use crate::catalog::{
+    RootCatalog, TableCatalog, TableRefId, COLUMNS_TABLE_ID, CONTRIBUTORS_TABLE_ID,
+    INFORMATION_SCHEMA_COLUMNS_TABLE_ID, INFORMATION_SCHEMA_ID, INTERNAL_SCHEMA_ID,
+    TABLES_TABLE_ID,
+};
 /// The executor of internal tables.
 pub struct InternalTableExecutor {
     pub table_id: TableRefId,
+    pub catalog: RootCatalogRef,
 }
 
 impl InternalTableExecutor {
     #[try_stream(boxed, ok = DataChunk, error = ExecutorError)]
     pub async fn execute(self) {
-        match self.table_id.table_id {
-            CONTRIBUTORS_TABLE_ID => {
+        match (self.table_id.schema_id, self.table_id.table_id) {
+            (INTERNAL_SCHEMA_ID, CONTRIBUTORS_TABLE_ID) => {
                 yield contributors();
             }
+            (INTERNAL_SCHEMA_ID, TABLES_TABLE_ID) => {
+                yield tables(&self.catalog);
+            }
+            (INTERNAL_SCHEMA_ID, COLUMNS_TABLE_ID) => {
+                yield columns(&self.catalog);
+            }
+            (INFORMATION_SCHEMA_ID, INFORMATION_SCHEMA_COLUMNS_TABLE_ID) => {
+                yield information_schema_columns(&self.catalog);
+            }
             _ => {
                 panic!(
                     "InternalTableExecutor::execute: unknown table ref id: {}",
@@ -24,6 +41,90 @@ impl InternalTableExecutor {
     }
 }
 
+/// Returns `(schema name, table)` of all tables, ordered by name.
+fn all_tables(catalog: &RootCatalog) -> Vec<(String, Arc<TableCatalog>)> {
+    let mut tables = vec![];
+    for schema in catalog.all_schemas().into_values() {
+        for table in schema.all_tables().into_values() {
+            tables.push((schema.name(), table));
+        }
+    }
+    tables.sort_by_key(|(schema, table)| (schema.clone(), table.name()));
+    tables
+}
+
+/// Returns the rows of `pg_catalog.tables`.
+fn tables(catalog: &RootCatalog) -> DataChunk {
+    let mut schema_names = Utf8ArrayBuilder::new();
+    let mut table_names = Utf8ArrayBuilder::new();
+    for (schema, table) in all_tables(catalog) {
+        schema_names.push(Some(&schema));
+        table_names.push(Some(&table.name()));
+    }
+    let arrays: [ArrayBuilderImpl; 2] = [schema_names.into(), table_names.into()];
+    arrays.into_iter().collect()
+}
+
+/// Returns the rows of `pg_catalog.columns`.
+fn columns(catalog: &RootCatalog) -> DataChunk {
+    let mut schema_names = Utf8ArrayBuilder::new();
+    let mut table_names = Utf8ArrayBuilder::new();
+    let mut column_names = Utf8ArrayBuilder::new();
+    let mut data_types = Utf8ArrayBuilder::new();
+    let mut is_nullable = BoolArrayBuilder::new();
+    let mut is_primary = BoolArrayBuilder::new();
+    for (schema, table) in all_tables(catalog) {
+        for column in table.all_columns().into_values() {
+            let data_type = column.datatype().kind().to_string().to_ascii_lowercase();
+            schema_names.push(Some(&schema));
+            table_names.push(Some(&table.name()));
+            column_names.push(Some(column.name()));
+            data_types.push(Some(&data_type));
+            is_nullable.push(Some(&column.is_nullable()));
+            is_primary.push(Some(&column.is_primary()));
+        }
+    }
+    let arrays: [ArrayBuilderImpl; 6] = [
+        schema_names.into(),
+        table_names.into(),
+        column_names.into(),
+        data_types.into(),
+        is_nullable.into(),
+        is_primary.into(),
+    ];
+    arrays.into_iter().collect()
+}
+
+/// Returns the rows of `information_schema.columns`.
+fn information_schema_columns(catalog: &RootCatalog) -> DataChunk {
+    let mut schema_names = Utf8ArrayBuilder::new();
+    let mut table_names = Utf8ArrayBuilder::new();
+    let mut column_names = Utf8ArrayBuilder::new();
+    let mut ordinal_positions = I32ArrayBuilder::new();
+    let mut is_nullable = Utf8ArrayBuilder::new();
+    let mut data_types = Utf8ArrayBuilder::new();
+    for (schema, table) in all_tables(catalog) {
+        for (i, column) in table.all_columns().into_values().enumerate() {
+            let data_type = column.datatype().kind().to_string().to_ascii_lowercase();
+            schema_names.push(Some(&schema));
+            table_names.push(Some(&table.name()));
+            column_names.push(Some(column.name()));
+            ordinal_positions.push(Some(&(i as i32 + 1)));
+            is_nullable.push(Some(if column.is_nullable() { "YES" } else { "NO" }));
+            data_types.push(Some(&data_type));
+        }
+    }
+    let arrays: [ArrayBuilderImpl; 6] = [
+        schema_names.into(),
+        table_names.into(),
+        column_names.into(),
+        ordinal_positions.into(),
+        is_nullable.into(),
+        data_types.into(),
+    ];
+    arrays.into_iter().collect()
+}
+
 // TODO: find a better way to maintain the contributors list instead of hard-coding, and get total
 // contributors when contributors is more than 100. (per_page max is 100)
 // update this funciton with `curl https://api.github.com/repos/risinglightdb/risinglight/contributors?per_page=100 | jq ".[].login"`
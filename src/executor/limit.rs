@@ -6,7 +6,8 @@ use crate::array::DataChunk;
 /// The executor of a limit operation.
 pub struct LimitExecutor {
     pub offset: usize,
-    pub limit: usize,
+    /// The maximum number of rows to output. `None` means unbounded.
+    pub limit: Option<usize>,
 }
 
 impl LimitExecutor {
@@ -14,16 +15,21 @@ impl LimitExecutor {
     pub async fn execute(self, child: BoxedExecutor) {
         // the number of rows have been processed
         let mut processed = 0;
+        // the end position of output rows
+        let end_position = self.limit.map(|limit| self.offset.saturating_add(limit));
 
         #[for_await]
         for batch in child {
-            if self.limit == 0 {
+            if self.limit == Some(0) {
                 break;
             }
             let batch = batch?;
             let cardinality = batch.cardinality();
             let start = processed.max(self.offset) - processed;
-            let end = (processed + cardinality).min(self.offset + self.limit) - processed;
+            let end = match end_position {
+                Some(end_position) => (processed + cardinality).min(end_position) - processed,
+                None => cardinality,
+            };
             processed += cardinality;
             if start >= end {
                 continue;
@@ -33,7 +39,7 @@ impl LimitExecutor {
             } else {
                 yield batch.slice(start..end);
             }
-            if processed >= self.offset + self.limit {
+            if end_position.is_some_and(|end_position| processed >= end_position) {
                 break;
             }
         }
@@ -50,17 +56,19 @@ mod tests {
     use super::*;
     use crate::array::ArrayImpl;
 
-    #[test_case(&[(0..6)], 1, 4, &[(1..5)])]
-    #[test_case(&[(0..6)], 0, 10, &[(0..6)])]
-    #[test_case(&[(0..6)], 10, 0, &[])]
-    #[test_case(&[(0..2), (2..4), (4..6)], 1, 4, &[(1..2),(2..4),(4..5)])]
-    #[test_case(&[(0..2), (2..4), (4..6)], 1, 2, &[(1..2),(2..3)])]
-    #[test_case(&[(0..2), (2..4), (4..6)], 3, 0, &[])]
+    #[test_case(&[(0..6)], 1, Some(4), &[(1..5)])]
+    #[test_case(&[(0..6)], 0, Some(10), &[(0..6)])]
+    #[test_case(&[(0..6)], 10, Some(0), &[])]
+    #[test_case(&[(0..2), (2..4), (4..6)], 1, Some(4), &[(1..2),(2..4),(4..5)])]
+    #[test_case(&[(0..2), (2..4), (4..6)], 1, Some(2), &[(1..2),(2..3)])]
+    #[test_case(&[(0..2), (2..4), (4..6)], 3, Some(0), &[])]
+    #[test_case(&[(0..2), (2..4), (4..6)], 3, None, &[(3..4),(4..6)])]
+    #[test_case(&[(0..6)], 10, None, &[])]
     #[tokio::test]
     async fn limit(
         inputs: &'static [Range<i32>],
         offset: usize,
-        limit: usize,
+        limit: Option<usize>,
         outputs: &'static [Range<i32>],
     ) {
         let executor = LimitExecutor { offset, limit };
@@ -22,11 +22,35 @@ impl<const T: JoinType> MergeJoinExecutor<T> {
         let mut left_group = left_groups.next().await.transpose()?;
         let mut right_group = right_groups.next().await.transpose()?;
 
-        let data_types = self.left_types.iter().chain(self.right_types.iter());
-        let mut builder = DataChunkBuilder::new(data_types, PROCESSING_WINDOW_SIZE);
+        let is_semi_or_anti = T == JoinType::Semi || T == JoinType::Anti;
+        let data_types = if is_semi_or_anti {
+            self.left_types.clone()
+        } else {
+            self.left_types
+                .iter()
+                .chain(self.right_types.iter())
+                .cloned()
+                .collect()
+        };
+        let mut builder = DataChunkBuilder::new(&data_types, PROCESSING_WINDOW_SIZE);
 
         loop {
             match (&left_group, &right_group) {
+                // semi join outputs left rows if left key == right key
+                // anti join outputs left rows otherwise
+                (Some((lkey, lchunk)), Some((rkey, _))) if is_semi_or_anti && lkey == rkey => {
+                    // NULL keys never match
+                    let matched = !lkey.iter().any(|v| v.is_null());
+                    if matched == (T == JoinType::Semi) {
+                        for left_row in lchunk {
+                            if let Some(chunk) = builder.push_row(left_row.iter().cloned()) {
+                                yield chunk;
+                            }
+                        }
+                    }
+                    left_group = left_groups.next().await.transpose()?;
+                    right_group = right_groups.next().await.transpose()?;
+                }
                 // cross join if left key == right key
                 (Some((lkey, lchunk)), Some((rkey, rchunk))) if lkey == rkey => {
                     for left_row in lchunk {
@@ -52,6 +76,12 @@ impl<const T: JoinType> MergeJoinExecutor<T> {
                                 yield chunk;
                             }
                         }
+                    } else if T == JoinType::Anti {
+                        for left_row in lchunk {
+                            if let Some(chunk) = builder.push_row(left_row.iter().cloned()) {
+                                yield chunk;
+                            }
+                        }
                     }
                     left_group = left_groups.next().await.transpose()?;
                 }
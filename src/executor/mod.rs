@@ -20,6 +20,8 @@ use futures_async_stream::try_stream;
 use itertools::Itertools;
 
 // use minitrace::prelude::*;
+use self::analyze::*;
+use self::apply::*;
 use self::copy_from_file::*;
 use self::copy_to_file::*;
 use self::create::*;
@@ -43,14 +45,19 @@ use self::simple_agg::*;
 use self::sort_agg::*;
 use self::table_scan::*;
 use self::top_n::TopNExecutor;
+use self::truncate::*;
 use self::values::*;
 use self::window::*;
 use crate::array::DataChunk;
-use crate::catalog::RootCatalogRef;
-use crate::planner::{Expr, ExprAnalysis, RecExpr, TypeSchemaAnalysis};
-use crate::storage::{Storage, TracedStorageError};
-use crate::types::{ColumnIndex, ConvertError, DataType};
-
+use crate::catalog::{ColumnId, RootCatalogRef, TableRefId};
+use crate::planner::{
+    scan_ranges, Config, Expr, ExprAnalysis, Optimizer, RecExpr, Statistic, TypeSchemaAnalysis,
+};
+use crate::storage::{InList, Storage, TracedStorageError};
+use crate::types::{ColumnIndex, ConvertError, DataType, DataTypeKind, DataValue, TimestampTz};
+
+mod analyze;
+mod apply;
 mod copy_from_file;
 mod copy_to_file;
 mod create;
@@ -71,8 +78,10 @@ mod merge_join;
 mod projection;
 mod simple_agg;
 mod sort_agg;
+mod spill;
 mod table_scan;
 mod top_n;
+mod truncate;
 mod values;
 mod window;
 
@@ -96,18 +105,38 @@ pub enum ExecutorError {
         #[source]
         std::io::Error,
     ),
-    #[error("csv error")]
+    #[error("csv error: {0}")]
     Csv(
         #[from]
         #[source]
         csv::Error,
     ),
+    #[error("parquet error: {0}")]
+    Parquet(
+        #[from]
+        #[source]
+        parquet::errors::ParquetError,
+    ),
+    #[error("unsupported type in parquet file: {0}")]
+    UnsupportedParquetType(String),
     #[error("value can not be null")]
     NotNullable,
+    #[error("malformed record at line {line}: {source}")]
+    MalformedRecord {
+        line: u64,
+        #[source]
+        source: Box<ExecutorError>,
+    },
     #[error("exceed char/varchar length limit: item length {length} > char/varchar width {width}")]
     ExceedLengthLimit { length: u64, width: u64 },
     #[error("abort")]
     Abort,
+    #[error("duplicate key value {0} violates unique constraint on ({1})")]
+    UniqueViolation(String, String),
+    #[error("key {0} is not present in the referenced table")]
+    ForeignKeyViolation(String),
+    #[error("key {0} is still referenced from table {1}")]
+    ForeignKeyReferenced(String, String),
 }
 
 /// The maximum chunk length produced by executor at a time.
@@ -121,30 +150,65 @@ const PROCESSING_WINDOW_SIZE: usize = 1024;
 /// and produces a stream to its parent.
 pub type BoxedExecutor = BoxStream<'static, Result<DataChunk, ExecutorError>>;
 
-pub fn build(catalog: RootCatalogRef, storage: Arc<impl Storage>, plan: &RecExpr) -> BoxedExecutor {
-    Builder::new(catalog, storage, plan).build()
+pub fn build(
+    config: Config,
+    stat: Statistic,
+    catalog: RootCatalogRef,
+    storage: Arc<impl Storage>,
+    plan: &RecExpr,
+) -> BoxedExecutor {
+    Builder::new(config, stat, catalog, storage, plan).build()
+}
+
+tokio::task_local! {
+    /// The configuration of the current query.
+    static CONFIG: Config;
+}
+
+/// Returns the configuration of the current query.
+fn config() -> Config {
+    CONFIG.try_with(Config::clone).unwrap_or_default()
 }
 
 /// The builder of executor.
 struct Builder<S: Storage> {
+    config: Config,
+    /// The statistic used to optimize the plan, for the costs in EXPLAIN.
+    stat: Statistic,
+    /// The start time of the query, returned by `now()`.
+    now: TimestampTz,
     storage: Arc<S>,
     catalog: RootCatalogRef,
     egraph: egg::EGraph<Expr, TypeSchemaAnalysis>,
     root: Id,
+    /// Collects the metrics of executors for `explain analyze`.
+    profiler: Option<Arc<Profiler>>,
 }
 
 impl<S: Storage> Builder<S> {
     /// Create a new executor builder.
-    fn new(catalog: RootCatalogRef, storage: Arc<S>, plan: &RecExpr) -> Self {
+    fn new(
+        config: Config,
+        stat: Statistic,
+        catalog: RootCatalogRef,
+        storage: Arc<S>,
+        plan: &RecExpr,
+    ) -> Self {
         let mut egraph = egg::EGraph::new(TypeSchemaAnalysis {
             catalog: catalog.clone(),
         });
         let root = egraph.add_expr(plan);
+        let profiler = matches!(egraph[root].nodes[0], Expr::Analyze(_))
+            .then(|| Arc::new(Profiler::default()));
         Builder {
+            config,
+            stat,
+            now: TimestampTz::now(),
             storage,
             catalog,
             egraph,
             root,
+            profiler,
         }
     }
 
@@ -152,31 +216,204 @@ impl<S: Storage> Builder<S> {
         &self.egraph[id].nodes[0]
     }
 
+    /// Returns an optimizer with the same statistic and configuration as the one that
+    /// optimized the plan, to compute its costs.
+    fn optimizer(&self) -> Optimizer {
+        Optimizer::new(self.catalog.clone(), self.stat.clone(), self.config.clone())
+    }
+
     /// Extract a `RecExpr` from id.
     fn recexpr(&self, id: Id) -> RecExpr {
         self.node(id).build_recexpr(|id| self.node(id).clone())
     }
 
+    /// Extract a `RecExpr` from id, along with the id in the e-graph of each node.
+    fn recexpr_with_ids(&self, id: Id) -> (RecExpr, Vec<Id>) {
+        let mut expr = RecExpr::default();
+        let mut ids = vec![];
+        self.add_to_recexpr(id, &mut expr, &mut ids);
+        (expr, ids)
+    }
+
+    fn add_to_recexpr(&self, id: Id, expr: &mut RecExpr, ids: &mut Vec<Id>) -> Id {
+        if let Some(idx) = ids.iter().position(|x| *x == id) {
+            return Id::from(idx);
+        }
+        let node = self.node(id).clone();
+        let node = node.map_children(|child| self.add_to_recexpr(child, expr, ids));
+        ids.push(id);
+        expr.add(node)
+    }
+
     /// Returns the output types of a plan node.
     fn plan_types(&self, id: Id) -> &[DataType] {
         let ty = self.egraph[id].data.type_.as_ref().unwrap();
         ty.kind.as_struct()
     }
 
+    /// Returns the number of tasks to scan the table.
+    fn scan_parallelism(&self, table_id: TableRefId) -> usize {
+        // keep the order of rows if the plan may depend on it
+        let is_sorted = self.config.table_is_sorted_by_primary_key
+            && (self.catalog.get_table(&table_id))
+                .is_some_and(|table| !table.primary_keys().is_empty());
+        match is_sorted {
+            true => 1,
+            false => self.config.scan_parallelism,
+        }
+    }
+
+    /// Returns the FOREIGN KEY constraints referencing `table_id`, resolving the referenced
+    /// columns in the output of `plan`.
+    fn references(&self, table_id: TableRefId, plan: Id) -> Vec<Reference> {
+        let schema = &self.egraph[plan].data.schema;
+        let table = self.catalog.get_table(&table_id).unwrap();
+        let mut references = vec![];
+        for (referencing, fk) in self.catalog.get_referencing_foreign_keys(&table_id) {
+            let ref_indexes = (fk.ref_columns.iter())
+                .map(|id| {
+                    (schema.iter())
+                        .position(
+                            |x| matches!(self.node(*x), Expr::Column(c) if c.column_id == *id),
+                        )
+                        .expect("referenced column not found from input")
+                })
+                .collect();
+            let ref_names = (fk.ref_columns.iter())
+                .map(|id| table.get_column_by_id(*id).unwrap().name().to_string())
+                .collect();
+            references.push(Reference {
+                table_id: referencing,
+                table_name: self.catalog.get_table(&referencing).unwrap().name(),
+                columns: fk.columns,
+                ref_indexes,
+                ref_names,
+            });
+        }
+        references
+    }
+
+    /// Returns the position of the column in `columns` that the table can seek to by a range
+    /// scan.
+    ///
+    /// Like a range filter of scan, the column must be both the first column and the leading
+    /// primary key of `INT` type.
+    fn seek_column(&self, table_id: TableRefId, columns: &[ColumnId]) -> Option<usize> {
+        if !self.config.enable_range_filter_scan {
+            return None;
+        }
+        let table = self.catalog.get_table(&table_id)?;
+        let column = table.get_column_by_id(0)?;
+        if table.primary_keys().first() != Some(&0)
+            || column.datatype().kind() != DataTypeKind::Int32
+        {
+            return None;
+        }
+        columns.iter().position(|id| *id == 0)
+    }
+
     /// Resolve the column index of `expr` in `plan`.
     fn resolve_column_index(&self, expr: Id, plan: Id) -> RecExpr {
-        let schema = &self.egraph[plan].data.schema;
+        self.resolve_column_index_in(expr, &self.egraph[plan].data.schema)
+    }
+
+    /// Resolve the column index of `expr` in `left` || `right`.
+    fn resolve_column_index2(&self, expr: Id, left: Id, right: Id) -> RecExpr {
+        let schema = (self.egraph[left].data.schema.iter())
+            .chain(self.egraph[right].data.schema.iter())
+            .cloned()
+            .collect_vec();
+        self.resolve_column_index_in(expr, &schema)
+    }
+
+    /// Resolve the column index of join keys `lkeys` in `left` and `rkeys` in `right`.
+    ///
+    /// A pair of keys of different numeric types are casted to a common type,
+    /// so that equal values are hashed and compared as equal.
+    fn resolve_join_keys(&self, lkeys: Id, rkeys: Id, left: Id, right: Id) -> (RecExpr, RecExpr) {
+        let kinds = |keys: Id| {
+            (self.node(keys).as_list().iter())
+                .map(|id| self.egraph[*id].data.type_.as_ref().unwrap().kind())
+                .collect_vec()
+        };
+        let common = (kinds(lkeys).iter().zip(kinds(rkeys).iter()))
+            .map(|(l, r)| match (l, r) {
+                _ if l == r || !l.is_number() || !r.is_number() => None,
+                (DataTypeKind::Decimal(_, _), DataTypeKind::Decimal(_, _)) => None,
+                // NaN and infinity are not valid decimals
+                (DataTypeKind::Float64, _) | (_, DataTypeKind::Float64) => {
+                    Some(DataTypeKind::Float64)
+                }
+                // the precision of decimal depends on the value being casted
+                (DataTypeKind::Decimal(_, _), _) | (_, DataTypeKind::Decimal(_, _)) => {
+                    Some(DataTypeKind::Decimal(None, None))
+                }
+                _ => l.union(r),
+            })
+            .collect_vec();
+        let left_keys = self.resolve_column_index(lkeys, left);
+        let right_keys = self.resolve_column_index(rkeys, right);
+        (
+            cast_keys(left_keys, &common),
+            cast_keys(right_keys, &common),
+        )
+    }
+
+    /// Resolve the column index of `expr` in the given schema.
+    ///
+    /// `now()` is replaced by the start time of the query.
+    fn resolve_column_index_in(&self, expr: Id, schema: &[Id]) -> RecExpr {
         self.node(expr).build_recexpr(|id| {
             if let Some(idx) = schema.iter().position(|x| *x == id) {
                 return Expr::ColumnIndex(ColumnIndex(idx as _));
             }
             match self.node(id) {
                 Expr::Column(c) => panic!("column {c} not found from input"),
+                Expr::Now => Expr::Constant(DataValue::TimestampTz(self.now)),
                 e => e.clone(),
             }
         })
     }
 
+    /// Resolve the column index of aggregations `aggs` in the output of `plan`.
+    ///
+    /// A `partial` aggregation computes the inner aggregation. An aggregation whose `partial`
+    /// result is produced by the child comes from eager aggregation, and is merged.
+    fn resolve_aggs(&self, aggs: Id, plan: Id) -> RecExpr {
+        let schema = &self.egraph[plan].data.schema;
+        let mut recexpr = RecExpr::default();
+        let mut items = vec![];
+        for &agg in self.node(aggs).as_list() {
+            let partial_idx = (self.egraph.lookup(Expr::Partial(agg)))
+                .and_then(|partial| schema.iter().position(|x| *x == partial));
+            let id = if let Some(idx) = partial_idx {
+                let partial = recexpr.add(Expr::ColumnIndex(ColumnIndex(idx as _)));
+                recexpr.add(match self.node(agg) {
+                    Expr::RowCount | Expr::Count(_) | Expr::Sum(_) => Expr::Sum(partial),
+                    Expr::Min(_) => Expr::Min(partial),
+                    Expr::Max(_) => Expr::Max(partial),
+                    e => panic!("can not merge partial aggregation: {e}"),
+                })
+            } else {
+                let agg = match self.node(agg) {
+                    Expr::Partial(inner) => *inner,
+                    _ => agg,
+                };
+                let offset = recexpr.as_ref().len();
+                for node in self.resolve_column_index(agg, plan).as_ref() {
+                    recexpr.add(
+                        node.clone()
+                            .map_children(|id| Id::from(usize::from(id) + offset)),
+                    );
+                }
+                Id::from(recexpr.as_ref().len() - 1)
+            };
+            items.push(id);
+        }
+        recexpr.add(Expr::List(items.into()));
+        recexpr
+    }
+
     fn build(self) -> BoxedExecutor {
         self.build_id(self.root)
     }
@@ -184,28 +421,19 @@ impl<S: Storage> Builder<S> {
     fn build_id(&self, id: Id) -> BoxedExecutor {
         use Expr::*;
         let stream = match self.node(id).clone() {
-            Scan([table, list, filter]) => TableScanExecutor {
-                table_id: self.node(table).as_table(),
-                columns: (self.node(list).as_list().iter())
-                    .map(|id| self.node(*id).as_column())
-                    .collect(),
-                filter: {
-                    // analyze range for the filter
-                    let mut egraph = egg::EGraph::new(ExprAnalysis::default());
-                    let root = egraph.add_expr(&self.recexpr(filter));
-                    egraph[root].data.range.clone().map(|(_, r)| r)
-                },
-                storage: self.storage.clone(),
-            }
-            .execute(),
+            Scan(args) => self.build_scan(args, None),
 
-            Values(rows) => ValuesExecutor {
+            Values(columns) => ValuesExecutor {
                 column_types: self.plan_types(id).to_vec(),
                 values: {
-                    rows.iter()
-                        .map(|row| {
-                            (self.node(*row).as_list().iter())
-                                .map(|id| self.recexpr(*id))
+                    let columns = (columns.iter())
+                        .map(|column| self.node(*column).children())
+                        .collect_vec();
+                    let rows = columns.first().map_or(0, |column| column.len());
+                    (0..rows)
+                        .map(|i| {
+                            (columns.iter())
+                                .map(|column| self.resolve_column_index_in(column[i], &[]))
                                 .collect()
                         })
                         .collect()
@@ -215,6 +443,7 @@ impl<S: Storage> Builder<S> {
 
             Internal([table, _]) => InternalTableExecutor {
                 table_id: self.node(table).as_table(),
+                catalog: self.catalog.clone(),
             }
             .execute(),
 
@@ -226,7 +455,11 @@ impl<S: Storage> Builder<S> {
             Filter([cond, child]) => FilterExecutor {
                 condition: self.resolve_column_index(cond, child),
             }
-            .execute(self.build_id(child)),
+            .execute(match self.node(child).clone() {
+                // pass the condition to the scan to skip rows by indexes
+                Scan(args) => self.instrument(child, self.build_scan(args, Some(cond))),
+                _ => self.build_id(child),
+            }),
 
             Order([order_keys, child]) => OrderExecutor {
                 order_keys: self.resolve_column_index(order_keys, child),
@@ -235,32 +468,44 @@ impl<S: Storage> Builder<S> {
             .execute(self.build_id(child)),
 
             Limit([limit, offset, child]) => LimitExecutor {
-                limit: (self.node(limit).as_const().as_usize().unwrap()).unwrap_or(usize::MAX / 2),
+                limit: self.node(limit).as_const().as_usize().unwrap(),
                 offset: self.node(offset).as_const().as_usize().unwrap().unwrap(),
             }
             .execute(self.build_id(child)),
 
-            TopN([limit, offset, order_keys, child]) => TopNExecutor {
-                limit: (self.node(limit).as_const().as_usize().unwrap()).unwrap_or(usize::MAX / 2),
+            TopN([limit, offset, order_keys, with_ties, child]) => TopNExecutor {
+                limit: self.node(limit).as_const().as_usize().unwrap(),
                 offset: self.node(offset).as_const().as_usize().unwrap().unwrap(),
                 order_keys: self.resolve_column_index(order_keys, child),
+                with_ties: self.node(with_ties).as_const() == DataValue::Bool(true),
                 types: self.plan_types(id).to_vec(),
             }
             .execute(self.build_id(child)),
 
             Join([op, on, left, right]) => NestedLoopJoinExecutor {
                 op: self.node(op).clone(),
-                condition: self.resolve_column_index(on, id),
+                condition: self.resolve_column_index2(on, left, right),
                 left_types: self.plan_types(left).to_vec(),
                 right_types: self.plan_types(right).to_vec(),
             }
             .execute(self.build_id(left), self.build_id(right)),
 
+            Apply([op, on, left, right]) => ApplyExecutor {
+                op: self.node(op).clone(),
+                condition: self.resolve_column_index2(on, left, right),
+                left_types: self.plan_types(left).to_vec(),
+                right_types: self.plan_types(right).to_vec(),
+                build_right: self.build_apply_right(left, right),
+            }
+            .execute(self.build_id(left)),
+
             HashJoin(args @ [op, ..]) => match self.node(op) {
                 Inner => self.build_hashjoin::<{ JoinType::Inner }>(args),
                 LeftOuter => self.build_hashjoin::<{ JoinType::LeftOuter }>(args),
                 RightOuter => self.build_hashjoin::<{ JoinType::RightOuter }>(args),
                 FullOuter => self.build_hashjoin::<{ JoinType::FullOuter }>(args),
+                Semi => self.build_hashjoin::<{ JoinType::Semi }>(args),
+                Anti => self.build_hashjoin::<{ JoinType::Anti }>(args),
                 t => panic!("invalid join type: {t:?}"),
             },
 
@@ -269,23 +514,27 @@ impl<S: Storage> Builder<S> {
                 LeftOuter => self.build_mergejoin::<{ JoinType::LeftOuter }>(args),
                 RightOuter => self.build_mergejoin::<{ JoinType::RightOuter }>(args),
                 FullOuter => self.build_mergejoin::<{ JoinType::FullOuter }>(args),
+                Semi => self.build_mergejoin::<{ JoinType::Semi }>(args),
+                Anti => self.build_mergejoin::<{ JoinType::Anti }>(args),
                 t => panic!("invalid join type: {t:?}"),
             },
 
             Agg([aggs, child]) => SimpleAggExecutor {
-                aggs: self.resolve_column_index(aggs, child),
+                aggs: self.resolve_aggs(aggs, child),
+                types: self.plan_types(id).to_vec(),
             }
             .execute(self.build_id(child)),
 
             HashAgg([aggs, group_keys, child]) => HashAggExecutor {
-                aggs: self.resolve_column_index(aggs, child),
+                aggs: self.resolve_aggs(aggs, child),
                 group_keys: self.resolve_column_index(group_keys, child),
                 types: self.plan_types(id).to_vec(),
+                memory_budget: self.config.hash_agg_memory_budget,
             }
             .execute(self.build_id(child)),
 
             SortAgg([aggs, group_keys, child]) => SortAggExecutor {
-                aggs: self.resolve_column_index(aggs, child),
+                aggs: self.resolve_aggs(aggs, child),
                 group_keys: self.resolve_column_index(group_keys, child),
                 types: self.plan_types(id).to_vec(),
             }
@@ -297,6 +546,13 @@ impl<S: Storage> Builder<S> {
             }
             .execute(self.build_id(child)),
 
+            CreateSchema(plan) => CreateSchemaExecutor {
+                plan,
+                catalog: self.catalog.clone(),
+                storage: self.storage.clone(),
+            }
+            .execute(),
+
             CreateTable(plan) => CreateTableExecutor {
                 plan,
                 storage: self.storage.clone(),
@@ -314,56 +570,263 @@ impl<S: Storage> Builder<S> {
                 column_ids: (self.node(cols).as_list().iter())
                     .map(|id| self.node(*id).as_column().column_id)
                     .collect(),
+                unique_keys: (self.catalog.get_table(&self.node(table).as_table()))
+                    .map(|table| table.unique_keys().to_vec())
+                    .unwrap_or_default(),
+                foreign_keys: (self.catalog.get_table(&self.node(table).as_table()))
+                    .map(|table| table.foreign_keys().to_vec())
+                    .unwrap_or_default(),
+                unique_seek_columns: (self.catalog.get_table(&self.node(table).as_table()))
+                    .map(|catalog| {
+                        (catalog.unique_keys().iter())
+                            .map(|key| self.seek_column(self.node(table).as_table(), key))
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+                seek_columns: (self.catalog.get_table(&self.node(table).as_table()))
+                    .map(|table| {
+                        (table.foreign_keys().iter())
+                            .map(|fk| self.seek_column(fk.ref_table, &fk.ref_columns))
+                            .collect()
+                    })
+                    .unwrap_or_default(),
                 storage: self.storage.clone(),
             }
             .execute(self.build_id(child)),
 
             Delete([table, child]) => DeleteExecutor {
                 table_id: self.node(table).as_table(),
+                references: self.references(self.node(table).as_table(), child),
                 storage: self.storage.clone(),
             }
             .execute(self.build_id(child)),
 
+            Truncate(table) => TruncateExecutor {
+                table_id: self.node(table).as_table(),
+                storage: self.storage.clone(),
+            }
+            .execute(),
+
             CopyFrom([src, types]) => CopyFromFileExecutor {
                 source: self.node(src).as_ext_source(),
                 types: self.node(types).as_type().as_struct().to_vec(),
             }
             .execute(),
 
-            CopyTo([src, child]) => CopyToFileExecutor {
+            CopyTo([src, names, child]) => CopyToFileExecutor {
                 source: self.node(src).as_ext_source(),
+                columns: (self.node(names).as_list().iter())
+                    .map(|id| match self.node(*id) {
+                        Constant(DataValue::String(name)) => name.clone(),
+                        e => panic!("not a column name: {e}"),
+                    })
+                    .collect(),
+                types: self.plan_types(child).to_vec(),
             }
             .execute(self.build_id(child)),
 
-            Explain(plan) => ExplainExecutor {
+            Explain([verbose, plan]) => ExplainExecutor {
                 plan: self.recexpr(plan),
+                verbose: self.node(verbose) == &Expr::true_(),
+                optimizer: self.optimizer(),
                 catalog: self.catalog.clone(),
             }
             .execute(),
 
+            Analyze([verbose, plan]) => {
+                let (plan_expr, ids) = self.recexpr_with_ids(plan);
+                AnalyzeExecutor {
+                    plan: plan_expr,
+                    ids,
+                    verbose: self.node(verbose) == &Expr::true_(),
+                    profiler: self.profiler.clone().unwrap(),
+                    optimizer: self.optimizer(),
+                    catalog: self.catalog.clone(),
+                }
+                .execute(self.build_id(plan))
+            }
+
             Empty(_) => futures::stream::empty().boxed(),
 
             node => panic!("not a plan: {node:?}"),
         };
-        spawn(&self.node(id).to_string(), stream)
+        self.instrument(id, stream)
+    }
+
+    /// Profiles the stream of node `id` if needed, and spawns a task to execute it.
+    fn instrument(&self, id: Id, stream: BoxedExecutor) -> BoxedExecutor {
+        let stream = match &self.profiler {
+            Some(profiler) => profiler.instrument(id, stream),
+            None => stream,
+        };
+        spawn(&self.node(id).to_string(), self.config.clone(), stream)
+    }
+
+    /// Builds a scan. The condition of the filter above it, if any, is used to skip rows.
+    fn build_scan(&self, args: [Id; 3], cond: Option<Id>) -> BoxedExecutor {
+        let [table, list, filter] = args;
+        TableScanExecutor {
+            table_id: self.node(table).as_table(),
+            columns: (self.node(list).as_list().iter())
+                .map(|id| self.node(*id).as_column())
+                .collect(),
+            filters: {
+                // analyze ranges for the filter
+                let mut egraph = egg::EGraph::new(ExprAnalysis::default());
+                let root = egraph.add_expr(&self.recexpr(filter));
+                scan_ranges(&egraph, root)
+            },
+            equal_to: cond.map_or(vec![], |cond| self.equal_to(cond)),
+            in_list: cond.and_then(|cond| self.in_list(cond)),
+            not_null: cond.map_or(vec![], |cond| self.not_null(cond)),
+            parallelism: self.scan_parallelism(self.node(table).as_table()),
+            storage: self.storage.clone(),
+        }
+        .execute()
+    }
+
+    /// Returns the `column = constant` conditions in the conjunctive condition.
+    fn equal_to(&self, cond: Id) -> Vec<(u32, DataValue)> {
+        use Expr::*;
+        match self.node(cond) {
+            And([a, b]) => {
+                let mut conds = self.equal_to(*a);
+                conds.extend(self.equal_to(*b));
+                conds
+            }
+            Eq([a, b]) => match (self.node(*a), self.node(*b)) {
+                (Column(c), Constant(v)) | (Constant(v), Column(c)) if !v.is_null() => {
+                    vec![(c.column_id, v.clone())]
+                }
+                _ => vec![],
+            },
+            _ => vec![],
+        }
+    }
+
+    /// Returns an IN-list in the conjunctive condition.
+    ///
+    /// Both `column IN (constant..)` and disjunctions of `column = constant` conjunctions on the
+    /// same columns, e.g. `(a = 1 AND b = 2) OR (a = 3 AND b = 4)`, are recognized.
+    fn in_list(&self, cond: Id) -> Option<InList> {
+        use Expr::*;
+        match self.node(cond) {
+            And([a, b]) => self.in_list(*a).or_else(|| self.in_list(*b)),
+            In([a, list]) => {
+                let Column(c) = self.node(*a) else {
+                    return None;
+                };
+                let values = (self.node(*list).as_list().iter())
+                    .map(|id| match self.node(*id) {
+                        Constant(v) if !v.is_null() => Some(vec![v.clone()]),
+                        _ => None,
+                    })
+                    .collect::<Option<_>>()?;
+                Some(InList {
+                    columns: vec![c.column_id],
+                    values,
+                })
+            }
+            Or(_) => {
+                let mut columns = None;
+                let mut values = vec![];
+                for disjunct in self.disjuncts(cond) {
+                    let mut conds = self.equal_to(disjunct);
+                    conds.sort_by_key(|(column, _)| *column);
+                    let (cols, tuple): (Vec<_>, Vec<_>) = conds.into_iter().unzip();
+                    if cols.is_empty() || cols.windows(2).any(|w| w[0] == w[1]) {
+                        return None;
+                    }
+                    if *columns.get_or_insert_with(|| cols.clone()) != cols {
+                        return None;
+                    }
+                    values.push(tuple);
+                }
+                Some(InList {
+                    columns: columns?,
+                    values,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the operands of the disjunctive condition.
+    fn disjuncts(&self, cond: Id) -> Vec<Id> {
+        match self.node(cond) {
+            Expr::Or([a, b]) => {
+                let mut conds = self.disjuncts(*a);
+                conds.extend(self.disjuncts(*b));
+                conds
+            }
+            _ => vec![cond],
+        }
+    }
+
+    /// Returns the columns of the `column IS NOT NULL` conditions in the conjunctive condition.
+    fn not_null(&self, cond: Id) -> Vec<u32> {
+        use Expr::*;
+        match self.node(cond) {
+            And([a, b]) => {
+                let mut columns = self.not_null(*a);
+                columns.extend(self.not_null(*b));
+                columns
+            }
+            IsNotNull(a) => match self.node(*a) {
+                Column(c) => vec![c.column_id],
+                _ => vec![],
+            },
+            _ => vec![],
+        }
+    }
+
+    /// Returns a function to build the right side of apply for each row of the `left` side.
+    ///
+    /// The columns of the left side referred to by the right side are replaced with the values
+    /// of the row.
+    fn build_apply_right(
+        &self,
+        left: Id,
+        right: Id,
+    ) -> Box<dyn Fn(&[DataValue]) -> BoxedExecutor + Send + Sync> {
+        let schema = &self.egraph[left].data.schema;
+        let (plan, ids) = self.recexpr_with_ids(right);
+        // (index of the node in `plan`, index of the column in the left row)
+        let outer_refs = (ids.iter().enumerate())
+            .filter_map(|(i, id)| Some((i, schema.iter().position(|x| x == id)?)))
+            .collect_vec();
+        let config = self.config.clone();
+        let catalog = self.catalog.clone();
+        let storage = self.storage.clone();
+        Box::new(move |values| {
+            let mut nodes = plan.as_ref().to_vec();
+            for (i, j) in &outer_refs {
+                nodes[*i] = Expr::Constant(values[*j].clone());
+            }
+            let plan = RecExpr::from(nodes);
+            Builder::new(config.clone(), catalog.clone(), storage.clone(), &plan).build()
+        })
     }
 
     fn build_hashjoin<const T: JoinType>(&self, args: [Id; 5]) -> BoxedExecutor {
         let [_, lkeys, rkeys, left, right] = args;
+        let (left_keys, right_keys) = self.resolve_join_keys(lkeys, rkeys, left, right);
         HashJoinExecutor::<T> {
-            left_keys: self.resolve_column_index(lkeys, left),
-            right_keys: self.resolve_column_index(rkeys, right),
+            left_keys,
+            right_keys,
             left_types: self.plan_types(left).to_vec(),
             right_types: self.plan_types(right).to_vec(),
+            memory_budget: self.config.hash_join_memory_budget,
         }
         .execute(self.build_id(left), self.build_id(right))
     }
 
     fn build_mergejoin<const T: JoinType>(&self, args: [Id; 5]) -> BoxedExecutor {
         let [_, lkeys, rkeys, left, right] = args;
+        let (left_keys, right_keys) = self.resolve_join_keys(lkeys, rkeys, left, right);
         MergeJoinExecutor::<T> {
-            left_keys: self.resolve_column_index(lkeys, left),
-            right_keys: self.resolve_column_index(rkeys, right),
+            left_keys,
+            right_keys,
             left_types: self.plan_types(left).to_vec(),
             right_types: self.plan_types(right).to_vec(),
         }
@@ -371,18 +834,37 @@ impl<S: Storage> Builder<S> {
     }
 }
 
+/// Casts the keys in the list `keys` to the given types. `None` means no cast.
+fn cast_keys(mut keys: RecExpr, kinds: &[Option<DataTypeKind>]) -> RecExpr {
+    if kinds.iter().all(Option::is_none) {
+        return keys;
+    }
+    let list = keys.as_ref().last().unwrap().as_list().to_vec();
+    let items = (list.into_iter().zip(kinds))
+        .map(|(id, kind)| match kind {
+            Some(kind) => {
+                let ty = keys.add(Expr::Type(kind.clone()));
+                keys.add(Expr::Cast([ty, id]))
+            }
+            None => id,
+        })
+        .collect();
+    keys.add(Expr::List(items));
+    keys
+}
+
 /// Spawn a new task to execute the given stream.
-fn spawn(name: &str, mut stream: BoxedExecutor) -> BoxedExecutor {
+fn spawn(name: &str, config: Config, mut stream: BoxedExecutor) -> BoxedExecutor {
     let (tx, rx) = tokio::sync::mpsc::channel(16);
     let handle = tokio::task::Builder::default()
         .name(name)
-        .spawn(async move {
+        .spawn(CONFIG.scope(config, async move {
             while let Some(item) = stream.next().await {
                 if tx.send(item).await.is_err() {
                     return;
                 }
             }
-        })
+        }))
         .expect("failed to spawn task");
     use std::pin::Pin;
     use std::task::{Context, Poll};
@@ -26,6 +26,51 @@ impl NestedLoopJoinExecutor {
 
         let left_rows = || left_chunks.iter().flat_map(|chunk| chunk.rows());
 
+        // semi join: left rows that match any right row
+        // anti join: left rows that match no right row
+        if matches!(self.op, Expr::Semi | Expr::Anti) {
+            let right_chunks = right_child.try_collect::<Vec<DataChunk>>().await?;
+            let data_types = self.left_types.iter().chain(self.right_types.iter());
+            let mut builder = DataChunkBuilder::new(&self.left_types, PROCESSING_WINDOW_SIZE);
+            for left_row in left_rows() {
+                // evaluate the condition on (left_row, right) chunk by chunk,
+                // and stop as soon as a match is found
+                let mut cross_builder =
+                    DataChunkBuilder::new(data_types.clone(), PROCESSING_WINDOW_SIZE);
+                let mut right_rows = right_chunks.iter().flat_map(|chunk| chunk.rows());
+                let mut matched = false;
+                while !matched {
+                    let chunk = match right_rows.next() {
+                        Some(right_row) => {
+                            let values = left_row.values().chain(right_row.values());
+                            match cross_builder.push_row(values) {
+                                Some(chunk) => chunk,
+                                None => continue,
+                            }
+                        }
+                        None => match cross_builder.take() {
+                            Some(chunk) => chunk,
+                            None => break,
+                        },
+                    };
+                    let ArrayImpl::Bool(a) = Evaluator::new(&self.condition).eval(&chunk)? else {
+                        panic!("join condition should return bool");
+                    };
+                    matched = a.true_array().iter().any(|b| *b);
+                }
+                if matched == matches!(self.op, Expr::Semi) {
+                    if let Some(chunk) = builder.push_row(left_row.values()) {
+                        yield chunk;
+                    }
+                }
+                tokio::task::consume_budget().await;
+            }
+            if let Some(chunk) = builder.take() {
+                yield chunk;
+            }
+            return Ok(());
+        }
+
         let data_types = self.left_types.iter().chain(self.right_types.iter());
         let mut builder = DataChunkBuilder::new(data_types, PROCESSING_WINDOW_SIZE);
         let mut filter_builder = BoolArrayBuilder::with_capacity(PROCESSING_WINDOW_SIZE);
@@ -94,3 +139,38 @@ impl NestedLoopJoinExecutor {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::DataTypeKind;
+
+    #[tokio::test]
+    async fn semi_join_stops_early() {
+        // SELECT * FROM t WHERE EXISTS (SELECT * FROM infinite LIMIT 1)
+        let int32 = |values: &[i32]| -> DataChunk {
+            [ArrayImpl::new_int32(values.iter().cloned().collect())]
+                .into_iter()
+                .collect()
+        };
+        let left = futures::stream::iter([Ok(int32(&[1, 2, 3]))]).boxed();
+        let right = futures::stream::repeat_with(move || Ok(int32(&[0]))).boxed();
+        let right = LimitExecutor {
+            offset: 0,
+            limit: 1,
+        }
+        .execute(right);
+        let executor = NestedLoopJoinExecutor {
+            op: Expr::Semi,
+            condition: "true".parse().unwrap(),
+            left_types: vec![DataTypeKind::Int32.not_null()],
+            right_types: vec![DataTypeKind::Int32.not_null()],
+        };
+        let output = executor
+            .execute(left, right)
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(output, vec![int32(&[1, 2, 3])]);
+    }
+}
@@ -1,14 +1,17 @@
 // Copyright 2023 RisingLight Project Authors. Licensed under Apache-2.0.
 
 use super::*;
-use crate::array::ArrayImpl;
+use crate::array::DataChunkBuilder;
 
 /// The executor of simple aggregation.
+///
+/// Exactly one row is returned, even if the input is empty.
 pub struct SimpleAggExecutor {
     /// A list of aggregations.
     ///
     /// e.g. `(list (sum #0) (count #1))`
     pub aggs: RecExpr,
+    pub types: Vec<DataType>,
 }
 
 impl SimpleAggExecutor {
@@ -20,6 +23,8 @@ impl SimpleAggExecutor {
             let chunk = chunk?;
             Evaluator::new(&self.aggs).eval_agg_list(&mut states, &chunk)?;
         }
-        yield states.iter().map(ArrayImpl::from).collect();
+        // build arrays of the output types, since a state can be NULL of no type
+        let mut builder = DataChunkBuilder::new(&self.types, 1);
+        yield builder.push_row(states).expect("the chunk should be full");
     }
 }
@@ -0,0 +1,254 @@
+// Copyright 2023 RisingLight Project Authors. Licensed under Apache-2.0.
+
+//! Temporary files for executors to spill rows that exceed their memory budget.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use bytes::{Buf, BufMut};
+use rust_decimal::Decimal;
+use tokio::fs::File;
+use tokio::io::{AsyncReadExt, AsyncWriteExt, BufReader, BufWriter};
+
+use super::*;
+use crate::array::DataChunkBuilder;
+use crate::types::{Blob, Date, Interval, Timestamp, F64};
+
+/// The number of partitions that rows are divided into by hash when spilling.
+pub const SPILL_PARTITIONS: usize = 16;
+
+/// Returns the partition of a key in `0..SPILL_PARTITIONS`.
+pub fn partition_of(key: &impl Hash) -> usize {
+    let mut hasher = DefaultHasher::new();
+    key.hash(&mut hasher);
+    hasher.finish() as usize % SPILL_PARTITIONS
+}
+
+/// A temporary file of rows. The file is removed on drop.
+pub struct SpillFile {
+    path: PathBuf,
+    writer: BufWriter<File>,
+}
+
+impl SpillFile {
+    /// Creates an empty file in the temporary directory.
+    pub async fn create() -> std::io::Result<Self> {
+        static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+        let name = format!("risinglight-spill-{}-{id}", std::process::id());
+        let path = std::env::temp_dir().join(name);
+        let file = File::create(&path).await?;
+        Ok(SpillFile {
+            path,
+            writer: BufWriter::new(file),
+        })
+    }
+
+    /// Appends a row to the file.
+    pub async fn write_row(
+        &mut self,
+        row: impl IntoIterator<Item = DataValue>,
+    ) -> std::io::Result<()> {
+        let mut buf = vec![];
+        for value in row {
+            encode(&value, &mut buf);
+        }
+        self.writer.write_u32_le(buf.len() as u32).await?;
+        self.writer.write_all(&buf).await
+    }
+
+    /// Flushes the file and returns a reader of rows from the beginning.
+    pub async fn into_reader(mut self) -> std::io::Result<SpillReader> {
+        self.writer.flush().await?;
+        let file = File::open(&self.path).await?;
+        Ok(SpillReader {
+            reader: BufReader::new(file),
+            _file: self,
+        })
+    }
+
+    /// Returns a stream of chunks of all rows in the file.
+    #[try_stream(boxed, ok = DataChunk, error = ExecutorError)]
+    pub async fn into_chunks(self, types: Vec<DataType>) {
+        let mut reader = self.into_reader().await?;
+        let mut builder = DataChunkBuilder::new(&types, PROCESSING_WINDOW_SIZE);
+        while let Some(row) = reader.read_row().await? {
+            if let Some(chunk) = builder.push_row(row) {
+                yield chunk;
+            }
+        }
+        if let Some(chunk) = builder.take() {
+            yield chunk;
+        }
+    }
+}
+
+impl Drop for SpillFile {
+    fn drop(&mut self) {
+        if let Err(e) = std::fs::remove_file(&self.path) {
+            tracing::warn!("failed to remove spill file {}: {e}", self.path.display());
+        }
+    }
+}
+
+/// A reader of rows in a [`SpillFile`].
+pub struct SpillReader {
+    reader: BufReader<File>,
+    /// Keep the file until the reader is dropped.
+    _file: SpillFile,
+}
+
+impl SpillReader {
+    /// Reads the next row. Returns `None` at the end of the file.
+    pub async fn read_row(&mut self) -> std::io::Result<Option<Vec<DataValue>>> {
+        let len = match self.reader.read_u32_le().await {
+            Ok(len) => len,
+            Err(e) if e.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        let mut buf = vec![0; len as usize];
+        self.reader.read_exact(&mut buf).await?;
+        let mut buf = &buf[..];
+        let mut row = vec![];
+        while buf.has_remaining() {
+            row.push(decode(&mut buf));
+        }
+        Ok(Some(row))
+    }
+}
+
+/// Encode a value with a leading tag of its type.
+fn encode(value: &DataValue, buf: &mut impl BufMut) {
+    match value {
+        DataValue::Null => buf.put_u8(0),
+        DataValue::Bool(v) => {
+            buf.put_u8(1);
+            buf.put_u8(*v as u8);
+        }
+        DataValue::Int16(v) => {
+            buf.put_u8(2);
+            buf.put_i16_le(*v);
+        }
+        DataValue::Int32(v) => {
+            buf.put_u8(3);
+            buf.put_i32_le(*v);
+        }
+        DataValue::Int64(v) => {
+            buf.put_u8(4);
+            buf.put_i64_le(*v);
+        }
+        DataValue::Float64(v) => {
+            buf.put_u8(5);
+            buf.put_f64_le(v.0);
+        }
+        DataValue::String(v) => {
+            buf.put_u8(6);
+            buf.put_u32_le(v.len() as u32);
+            buf.put_slice(v.as_bytes());
+        }
+        DataValue::Blob(v) => {
+            buf.put_u8(7);
+            buf.put_u32_le(v.len() as u32);
+            buf.put_slice(v);
+        }
+        DataValue::Decimal(v) => {
+            buf.put_u8(8);
+            buf.put_slice(&v.serialize());
+        }
+        DataValue::Date(v) => {
+            buf.put_u8(9);
+            buf.put_i32_le(v.get_inner());
+        }
+        DataValue::Timestamp(v) => {
+            buf.put_u8(10);
+            buf.put_i64_le(v.get_inner());
+        }
+        DataValue::TimestampTz(v) => {
+            buf.put_u8(11);
+            buf.put_i64_le(v.get_inner());
+        }
+        DataValue::Interval(v) => {
+            buf.put_u8(12);
+            buf.put_i32_le(v.num_months());
+            buf.put_i32_le(v.days());
+            buf.put_i32_le(v.num_milliseconds());
+        }
+    }
+}
+
+/// Decode a value encoded by [`encode`].
+fn decode(buf: &mut impl Buf) -> DataValue {
+    match buf.get_u8() {
+        0 => DataValue::Null,
+        1 => DataValue::Bool(buf.get_u8() != 0),
+        2 => DataValue::Int16(buf.get_i16_le()),
+        3 => DataValue::Int32(buf.get_i32_le()),
+        4 => DataValue::Int64(buf.get_i64_le()),
+        5 => DataValue::Float64(F64::from(buf.get_f64_le())),
+        6 => {
+            let len = buf.get_u32_le() as usize;
+            let bytes = buf.copy_to_bytes(len).to_vec();
+            DataValue::String(String::from_utf8(bytes).expect("invalid utf-8 string"))
+        }
+        7 => {
+            let len = buf.get_u32_le() as usize;
+            DataValue::Blob(Blob::from(buf.copy_to_bytes(len).to_vec()))
+        }
+        8 => {
+            let mut bytes = [0; 16];
+            buf.copy_to_slice(&mut bytes);
+            DataValue::Decimal(Decimal::deserialize(bytes))
+        }
+        9 => DataValue::Date(Date::new(buf.get_i32_le())),
+        10 => DataValue::Timestamp(Timestamp::new(buf.get_i64_le())),
+        11 => DataValue::TimestampTz(TimestampTz::new(buf.get_i64_le())),
+        12 => {
+            let months = buf.get_i32_le();
+            let days = buf.get_i32_le();
+            let ms = buf.get_i32_le();
+            DataValue::Interval(Interval::from_parts(months, days, ms))
+        }
+        tag => panic!("invalid tag of spilled value: {tag}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn spill_file() {
+        let rows = vec![
+            vec![DataValue::Null, DataValue::Int32(1)],
+            vec![],
+            vec![
+                DataValue::Bool(true),
+                DataValue::Int16(-2),
+                DataValue::Int64(i64::MAX),
+                DataValue::Float64(F64::from(f64::NAN)),
+                DataValue::String("hello".into()),
+                DataValue::Blob(Blob::from(vec![0, 1, 2])),
+                DataValue::Decimal("-1.23".parse().unwrap()),
+                DataValue::Date(Date::new(19000)),
+                DataValue::Timestamp(Timestamp::new(123456789)),
+                DataValue::TimestampTz(TimestampTz::new(-1)),
+                DataValue::Interval(Interval::from_parts(1, 2, 3)),
+            ],
+        ];
+        let mut file = SpillFile::create().await.unwrap();
+        for row in &rows {
+            file.write_row(row.iter().cloned()).await.unwrap();
+        }
+        let path = file.path.clone();
+        let mut reader = file.into_reader().await.unwrap();
+        for row in &rows {
+            assert_eq!(reader.read_row().await.unwrap().as_ref(), Some(row));
+        }
+        assert_eq!(reader.read_row().await.unwrap(), None);
+        drop(reader);
+        assert!(!path.exists());
+    }
+}
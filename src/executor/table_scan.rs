@@ -6,14 +6,24 @@ use super::*;
 use crate::array::DataChunk;
 use crate::catalog::{ColumnRefId, TableRefId};
 use crate::storage::{
-    KeyRange, ScanOptions, Storage, StorageColumnRef, Table, Transaction, TxnIterator,
+    InList, KeyRange, ScanOptions, Storage, StorageColumnRef, Table, Transaction, TxnIterator,
 };
 
 /// The executor of table scan operation.
 pub struct TableScanExecutor<S: Storage> {
     pub table_id: TableRefId,
     pub columns: Vec<ColumnRefId>,
-    pub filter: Option<KeyRange>,
+    /// The key ranges to scan in order. The whole table is scanned if empty.
+    pub filters: Vec<KeyRange>,
+    /// Conditions that columns equal to constants, used to skip rows by indexes.
+    pub equal_to: Vec<(u32, DataValue)>,
+    /// Tuples that some columns must be one of, used to skip blocks.
+    pub in_list: Option<InList>,
+    /// Columns that must not be null, used to skip blocks where all values are null.
+    pub not_null: Vec<u32>,
+    /// The number of tasks to scan the table.
+    /// The order of rows is not preserved if it is greater than 1.
+    pub parallelism: usize,
     pub storage: Arc<S>,
 }
 
@@ -38,14 +48,60 @@ impl<S: Storage> TableScanExecutor<S> {
 
         let txn = table.read().await?;
 
-        let mut it = txn
-            .scan(
-                &col_idx,
-                ScanOptions::default().with_filter_opt(self.filter),
-            )
-            .await?;
+        let mut filters = self.filters.into_iter().map(Some).collect_vec();
+        if filters.is_empty() {
+            filters.push(None);
+        }
+
+        // multiple ranges are scanned one by one to keep the order of keys
+        if self.parallelism <= 1 || filters.len() > 1 {
+            for filter in filters {
+                let mut it = txn
+                    .scan(
+                        &col_idx,
+                        ScanOptions::default()
+                            .with_filter_opt(filter)
+                            .with_in_list_opt(self.in_list.clone())
+                            .with_equal_to(self.equal_to.clone())
+                            .with_not_null(self.not_null.clone()),
+                    )
+                    .await?;
+
+                while let Some(mut x) = it.next_batch(None).await? {
+                    if self.columns.is_empty() {
+                        x = DataChunk::no_column(x.cardinality());
+                    }
+                    yield x;
+                }
+            }
+            return Ok(());
+        }
+        let filter = filters.pop().unwrap();
 
-        while let Some(mut x) = it.next_batch(None).await? {
+        // scan each partition in a separate task, and merge chunks in the order they arrive
+        let (tx, mut rx) = tokio::sync::mpsc::channel(self.parallelism);
+        let mut tasks = vec![];
+        for index in 0..self.parallelism {
+            let options = ScanOptions::default()
+                .with_filter_opt(filter.clone())
+                .with_in_list_opt(self.in_list.clone())
+                .with_equal_to(self.equal_to.clone())
+                .with_not_null(self.not_null.clone())
+                .with_partition(index, self.parallelism);
+            let mut it = txn.scan(&col_idx, options).await?;
+            let tx = tx.clone();
+            tasks.push(AbortOnDrop(tokio::spawn(async move {
+                while let Some(chunk) = it.next_batch(None).await.transpose() {
+                    if tx.send(chunk).await.is_err() {
+                        return;
+                    }
+                }
+            })));
+        }
+        drop(tx);
+
+        while let Some(chunk) = rx.recv().await {
+            let mut x = chunk?;
             if self.columns.is_empty() {
                 x = DataChunk::no_column(x.cardinality());
             }
@@ -53,3 +109,12 @@ impl<S: Storage> TableScanExecutor<S> {
         }
     }
 }
+
+/// Aborts the task when dropped.
+struct AbortOnDrop(tokio::task::JoinHandle<()>);
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
@@ -11,11 +11,14 @@ use crate::types::{DataType, Row};
 /// The executor of a Top N operation.
 pub struct TopNExecutor {
     pub offset: usize,
-    pub limit: usize,
+    /// The maximum number of rows to output. `None` means unbounded.
+    pub limit: Option<usize>,
     /// A list of expressions to order by.
     ///
     /// e.g. `(list (+ #0 #1) (desc #0))`
     pub order_keys: RecExpr,
+    /// Whether to keep the rows tying with the last one on the order keys.
+    pub with_ties: bool,
     pub types: Vec<DataType>,
 }
 
@@ -23,21 +26,49 @@ impl TopNExecutor {
     #[try_stream(boxed, ok = DataChunk, error = ExecutorError)]
     pub async fn execute(self, child: BoxedExecutor) {
         // initialize heap
-        let heap_size = self.offset + self.limit;
+        // each row is attached with its sequence number in the input,
+        // which breaks ties between rows with equal keys,
+        // so that the earliest rows are always kept.
+        let heap_size = self.limit.map(|limit| self.offset.saturating_add(limit));
         let orders = Evaluator::new(&self.order_keys).orders();
-        let mut heap =
-            BinaryHeap::with_capacity_by(heap_size, |row1, row2| cmp(row1, row2, &orders));
+        let mut heap = BinaryHeap::with_capacity_by(
+            heap_size.unwrap_or(0).min(PROCESSING_WINDOW_SIZE),
+            |(row1, seq1): &(Row, usize), (row2, seq2): &(Row, usize)| {
+                cmp(row1, row2, &orders).then(usize::cmp(seq1, seq2))
+            },
+        );
+
+        // rows popped from the heap that tie with the top of the heap
+        let with_ties = self.with_ties && self.limit != Some(0);
+        let mut ties = vec![];
 
         // evaluate order keys and append the original rows
         // chunks = keys || child
+        let mut seq = 0;
         #[for_await]
         for chunk in child {
             let chunk = chunk?;
             let order_key_chunk = Evaluator::new(&self.order_keys).eval_list(&chunk)?;
             for row in order_key_chunk.row_concat(chunk).rows() {
-                heap.push(row.to_owned());
-                if heap.len() > heap_size {
-                    heap.pop();
+                heap.push((row.to_owned(), seq));
+                seq += 1;
+                if heap_size.is_some_and(|heap_size| heap.len() > heap_size) {
+                    let popped = heap.pop().unwrap();
+                    if !with_ties {
+                        continue;
+                    }
+                    let Some((top, _)) = heap.peek() else {
+                        continue;
+                    };
+                    if ties
+                        .first()
+                        .is_some_and(|(tie, _)| cmp(tie, top, &orders).is_ne())
+                    {
+                        ties.clear();
+                    }
+                    if cmp(&popped.0, top, &orders).is_eq() {
+                        ties.push(popped);
+                    }
                 }
             }
         }
@@ -45,11 +76,13 @@ impl TopNExecutor {
         // build chunk
         let order_keys_len = self.order_keys.as_ref().last().unwrap().as_list().len();
         let mut builder = DataChunkBuilder::new(self.types.iter(), PROCESSING_WINDOW_SIZE);
-        for row in heap
+        ties.sort_by_key(|(_, seq)| *seq);
+        for (row, _) in heap
             .into_sorted_vec()
             .into_iter()
             .skip(self.offset)
-            .take(self.limit)
+            .take(self.limit.unwrap_or(usize::MAX))
+            .chain(ties)
         {
             if let Some(chunk) = builder.push_row(row.into_iter().skip(order_keys_len)) {
                 yield chunk;
@@ -74,3 +107,83 @@ fn cmp(row1: &Row, row2: &Row, orders: &[bool]) -> Ordering {
     }
     Ordering::Equal
 }
+
+#[cfg(test)]
+mod tests {
+    use futures::TryStreamExt;
+
+    use super::*;
+    use crate::array::ArrayImpl;
+    use crate::types::DataTypeKind;
+
+    #[tokio::test]
+    async fn tie_breaking() {
+        // (key, id): key is 0 for even ids and 1 for odd ids
+        let inputs = (0..10)
+            .map(|i| {
+                let ids = (i * 100..(i + 1) * 100).collect_vec();
+                let keys = ids.iter().map(|id| id % 2).collect();
+                [
+                    ArrayImpl::new_int32(keys),
+                    ArrayImpl::new_int32(ids.into_iter().collect()),
+                ]
+                .into_iter()
+                .collect::<DataChunk>()
+            })
+            .collect_vec();
+        let run = || async {
+            let executor = TopNExecutor {
+                offset: 5,
+                limit: Some(10),
+                order_keys: "(list #0)".parse().unwrap(),
+                with_ties: false,
+                types: vec![DataTypeKind::Int32.not_null(); 2],
+            };
+            let child = futures::stream::iter(inputs.clone().into_iter().map(Ok)).boxed();
+            executor
+                .execute(child)
+                .try_collect::<Vec<_>>()
+                .await
+                .unwrap()
+        };
+        let expected: DataChunk = [
+            ArrayImpl::new_int32(vec![0; 10]),
+            ArrayImpl::new_int32((5..15).map(|i| i * 2).collect()),
+        ]
+        .into_iter()
+        .collect();
+        for _ in 0..5 {
+            assert_eq!(run().await, vec![expected.clone()]);
+        }
+    }
+
+    #[tokio::test]
+    async fn with_ties() {
+        let input: DataChunk = [
+            ArrayImpl::new_int32([3, 2, 1, 2, 2, 4].into_iter().collect()),
+            ArrayImpl::new_int32((0..6).collect()),
+        ]
+        .into_iter()
+        .collect();
+        let executor = TopNExecutor {
+            offset: 0,
+            limit: Some(2),
+            order_keys: "(list #0)".parse().unwrap(),
+            with_ties: true,
+            types: vec![DataTypeKind::Int32.not_null(); 2],
+        };
+        let child = futures::stream::iter([Ok(input)]).boxed();
+        let actual = executor
+            .execute(child)
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        let expected: DataChunk = [
+            ArrayImpl::new_int32([1, 2, 2, 2].into_iter().collect()),
+            ArrayImpl::new_int32([2, 1, 3, 4].into_iter().collect()),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(actual, vec![expected]);
+    }
+}
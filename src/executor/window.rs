@@ -4,6 +4,9 @@ use super::*;
 use crate::array::DataChunkBuilder;
 
 /// The executor of window functions.
+///
+/// The input must be sorted by the partition keys and then the order keys of each window
+/// function. The states of a window function are reset at the start of each partition.
 pub struct WindowExecutor {
     /// A list of over window functions.
     ///
@@ -16,14 +19,40 @@ pub struct WindowExecutor {
 impl WindowExecutor {
     #[try_stream(boxed, ok = DataChunk, error = ExecutorError)]
     pub async fn execute(self, child: BoxedExecutor) {
-        let mut states = Evaluator::new(&self.exprs).init_agg_states::<Vec<_>>();
+        let evaluator = Evaluator::new(&self.exprs);
+        let init_states = evaluator.init_agg_states::<Vec<_>>();
+        let mut states = init_states.clone();
+        // the partition keys of the last row for each window function
+        let mut last_keys = vec![None; states.len()];
+        // the order keys of the last row for each window function
+        let mut last_orders = vec![None; states.len()];
+        // the number of rows in the current partition for each window function
+        let mut rows = vec![0; states.len()];
+        let mut peers = vec![false; states.len()];
 
         #[for_await]
         for chunk in child {
             let chunk = chunk?;
+            let args_chunk = evaluator.eval_list(&chunk)?;
+            let keys_chunks = evaluator.eval_partition_keys(&chunk)?;
+            let orders_chunks = evaluator.eval_order_keys(&chunk)?;
             let mut builder = DataChunkBuilder::new(&self.types, chunk.cardinality() + 1);
             for i in 0..chunk.cardinality() {
-                Evaluator::new(&self.exprs).agg_list_append(&mut states, chunk.row(i).values());
+                for (j, keys_chunk) in keys_chunks.iter().enumerate() {
+                    let keys = keys_chunk.row(i).values().collect_vec();
+                    if last_keys[j].as_ref() != Some(&keys) {
+                        states[j] = init_states[j].clone();
+                        last_keys[j] = Some(keys);
+                        last_orders[j] = None;
+                        rows[j] = 0;
+                    }
+                    let orders = orders_chunks[j].row(i).values().collect_vec();
+                    peers[j] = last_orders[j].as_ref() == Some(&orders);
+                    last_orders[j] = Some(orders);
+                    rows[j] += 1;
+                }
+                evaluator.agg_list_append(&mut states, args_chunk.row(i).values());
+                evaluator.rank_list_update(&mut states, &rows, &peers);
                 _ = builder.push_row(states.clone());
             }
             let window_chunk = builder.take().unwrap();
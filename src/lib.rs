@@ -63,7 +63,7 @@ use python::open;
 #[cfg(feature = "jemalloc")]
 use tikv_jemallocator::Jemalloc;
 
-pub use self::db::{Database, Error};
+pub use self::db::{Database, Error, PreparedStatement};
 
 /// Jemalloc can significantly improve performance compared to the default system allocator.
 #[cfg(feature = "jemalloc")]
@@ -15,7 +15,7 @@ use clap::Parser;
 use humantime::format_duration;
 use itertools::Itertools;
 use minitrace::prelude::*;
-use risinglight::array::{datachunk_to_sqllogictest_string, Chunk};
+use risinglight::array::{chunk_to_json_lines, datachunk_to_sqllogictest_string, Chunk};
 use risinglight::server::run_server;
 use risinglight::storage::SecondaryStorageOptions;
 use risinglight::utils::time::RoundingDuration;
@@ -42,6 +42,7 @@ struct Args {
     /// Control the output format
     /// - `text`: plain text
     /// - `human`: human readable format
+    /// - `json`: JSON Lines, one object per row keyed by column names
     #[clap(long)]
     output_format: Option<String>,
 
@@ -53,6 +54,10 @@ struct Args {
     #[clap(short, long)]
     storage_path: Option<String>,
 
+    /// Whether to build bitmap indexes on low-cardinality columns
+    #[clap(long)]
+    bitmap_index: bool,
+
     /// Whether to use tokio console.
     #[clap(long)]
     tokio_console: bool,
@@ -106,6 +111,11 @@ fn print_chunk(chunk: &Chunk, output_format: &Option<String>) {
                 .iter()
                 .format_with("\n", |row, f| f(&row.iter().format(","))),
         ),
+        Some("json") => {
+            for line in chunk_to_json_lines(chunk) {
+                println!("{line}");
+            }
+        }
         Some(format) => panic!("unsupported output format: {}", format),
     }
 }
@@ -383,6 +393,7 @@ async fn main() -> Result<()> {
         if let Some(path) = args.storage_path {
             options.path = PathBuf::new().join(path);
         }
+        options.bitmap_index = args.bitmap_index;
         Database::new_on_disk(options).await
     };
 
@@ -3,13 +3,204 @@
 //! The parser module directly uses the [`sqlparser`] crate
 //! and re-exports its AST types.
 
+use std::any::TypeId;
+
 pub use sqlparser::ast::*;
-use sqlparser::dialect::MySqlDialect;
+use sqlparser::dialect::{Dialect, MySqlDialect};
+use sqlparser::keywords::Keyword;
 use sqlparser::parser::Parser;
 pub use sqlparser::parser::ParserError;
+use sqlparser::tokenizer::Token;
 
 /// Parse the SQL string into a list of ASTs.
 pub fn parse(sql: &str) -> Result<Vec<Statement>, ParserError> {
-    let dialect = MySqlDialect{};
-    Parser::parse_sql(&dialect, sql)
+    Parser::parse_sql(&RisingLightDialect, sql)
+}
+
+/// Parse the SQL string into an expression.
+pub fn parse_expr(sql: &str) -> Result<Expr, ParserError> {
+    let mut parser = Parser::new(&RisingLightDialect).try_with_sql(sql)?;
+    let expr = parser.parse_expr()?;
+    parser.expect_token(&Token::EOF)?;
+    Ok(expr)
+}
+
+/// Parse the SQL string into an object name, such as `schema.table`.
+pub fn parse_object_name(sql: &str) -> Result<ObjectName, ParserError> {
+    let mut parser = Parser::new(&RisingLightDialect).try_with_sql(sql)?;
+    let name = parser.parse_object_name()?;
+    parser.expect_token(&Token::EOF)?;
+    Ok(name)
+}
+
+/// The SQL dialect of RisingLight.
+///
+/// It is [`MySqlDialect`] with the bitwise shift operators `<<` and `>>`, which are tokenized but
+/// not parsed by [`MySqlDialect`].
+#[derive(Debug)]
+struct RisingLightDialect;
+
+impl RisingLightDialect {
+    /// The precedence of shift operators, the same as in [`GenericDialect`].
+    ///
+    /// [`GenericDialect`]: sqlparser::dialect::GenericDialect
+    const SHIFT_PREC: u8 = 22;
+}
+
+impl Dialect for RisingLightDialect {
+    /// Behaves as [`MySqlDialect`] where the parser checks for the dialect.
+    fn dialect(&self) -> TypeId {
+        TypeId::of::<MySqlDialect>()
+    }
+
+    fn is_identifier_start(&self, ch: char) -> bool {
+        MySqlDialect {}.is_identifier_start(ch)
+    }
+
+    fn is_identifier_part(&self, ch: char) -> bool {
+        MySqlDialect {}.is_identifier_part(ch)
+    }
+
+    fn is_delimited_identifier_start(&self, ch: char) -> bool {
+        MySqlDialect {}.is_delimited_identifier_start(ch)
+    }
+
+    fn parse_prefix(&self, parser: &mut Parser) -> Option<Result<Expr, ParserError>> {
+        MySqlDialect {}.parse_prefix(parser)
+    }
+
+    fn parse_infix(
+        &self,
+        parser: &mut Parser,
+        expr: &Expr,
+        precedence: u8,
+    ) -> Option<Result<Expr, ParserError>> {
+        let op = match parser.peek_token().token {
+            Token::ShiftLeft => BinaryOperator::PGBitwiseShiftLeft,
+            Token::ShiftRight => BinaryOperator::PGBitwiseShiftRight,
+            _ => return MySqlDialect {}.parse_infix(parser, expr, precedence),
+        };
+        parser.next_token();
+        Some(
+            parser
+                .parse_subexpr(precedence)
+                .map(|right| Expr::BinaryOp {
+                    left: Box::new(expr.clone()),
+                    op,
+                    right: Box::new(right),
+                }),
+        )
+    }
+
+    fn get_next_precedence(&self, parser: &Parser) -> Option<Result<u8, ParserError>> {
+        match parser.peek_token().token {
+            Token::ShiftLeft | Token::ShiftRight => Some(Ok(Self::SHIFT_PREC)),
+            _ => MySqlDialect {}.get_next_precedence(parser),
+        }
+    }
+
+    fn parse_statement(&self, parser: &mut Parser) -> Option<Result<Statement, ParserError>> {
+        MySqlDialect {}.parse_statement(parser)
+    }
+}
+
+/// A statement to backup or restore a table, which is not supported by [`sqlparser`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BackupStatement {
+    /// `BACKUP TABLE <table> TO '<path>'`
+    Backup { table: ObjectName, path: String },
+    /// `RESTORE TABLE <table> FROM '<path>' [REPLACE]`
+    Restore {
+        table: ObjectName,
+        path: String,
+        replace: bool,
+    },
+}
+
+/// Parse the SQL string as a backup or restore statement.
+///
+/// Returns `None` if it doesn't start with `BACKUP` or `RESTORE`.
+pub fn parse_backup(sql: &str) -> Result<Option<BackupStatement>, ParserError> {
+    let mut parser = Parser::new(&RisingLightDialect).try_with_sql(sql)?;
+    let is_backup = match parser.peek_token().token {
+        Token::Word(w) if w.value.eq_ignore_ascii_case("backup") => true,
+        Token::Word(w) if w.value.eq_ignore_ascii_case("restore") => false,
+        _ => return Ok(None),
+    };
+    parser.next_token();
+    parser.expect_keyword(Keyword::TABLE)?;
+    let table = parser.parse_object_name()?;
+    let stmt = if is_backup {
+        parser.expect_keyword(Keyword::TO)?;
+        let path = parser.parse_literal_string()?;
+        BackupStatement::Backup { table, path }
+    } else {
+        parser.expect_keyword(Keyword::FROM)?;
+        let path = parser.parse_literal_string()?;
+        let replace = parser.parse_keyword(Keyword::REPLACE);
+        BackupStatement::Restore {
+            table,
+            path,
+            replace,
+        }
+    };
+    parser.consume_token(&Token::SemiColon);
+    parser.expect_token(&Token::EOF)?;
+    Ok(Some(stmt))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_backup_statement() {
+        let table = ObjectName(vec![Ident::new("t")]);
+        assert_eq!(
+            parse_backup("backup table t to '/tmp/t.bak'").unwrap(),
+            Some(BackupStatement::Backup {
+                table: table.clone(),
+                path: "/tmp/t.bak".into(),
+            })
+        );
+        assert_eq!(
+            parse_backup("RESTORE TABLE t FROM '/tmp/t.bak' REPLACE;").unwrap(),
+            Some(BackupStatement::Restore {
+                table,
+                path: "/tmp/t.bak".into(),
+                replace: true,
+            })
+        );
+        assert_eq!(parse_backup("select 1").unwrap(), None);
+        assert!(parse_backup("backup table t").is_err());
+    }
+
+    #[test]
+    fn parse_shift_operators() {
+        for (sql, op) in [
+            ("a << 1", BinaryOperator::PGBitwiseShiftLeft),
+            ("`a` >> 1", BinaryOperator::PGBitwiseShiftRight),
+        ] {
+            let Expr::BinaryOp { op: parsed, .. } = parse_expr(sql).unwrap() else {
+                panic!("not a binary operation: {sql}");
+            };
+            assert_eq!(parsed, op);
+        }
+        // shifts bind looser than arithmetic operators
+        let Expr::BinaryOp { left, .. } = parse_expr("1 + 2 << 3").unwrap() else {
+            panic!("not a binary operation");
+        };
+        assert!(matches!(
+            *left,
+            Expr::BinaryOp {
+                op: BinaryOperator::Plus,
+                ..
+            }
+        ));
+        // the rest is parsed as MySQL
+        let Expr::BinaryOp { left, .. } = parse_expr(r#""a" << 1"#).unwrap() else {
+            panic!("not a binary operation");
+        };
+        assert_eq!(*left, Expr::Value(Value::DoubleQuotedString("a".into())));
+    }
 }
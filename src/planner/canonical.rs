@@ -0,0 +1,202 @@
+// Copyright 2023 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use std::collections::HashMap;
+
+use egg::{Id, Language};
+
+use super::{Expr, RecExpr};
+
+/// Rewrites the expression into a canonical form.
+///
+/// The extracted expression may differ in the order of nodes, of the operands of commutative
+/// operators and of the values in an IN list. This function sorts them and rebuilds the nodes in
+/// post-order from the root without duplicates, so that equal plans are printed identically.
+pub fn canonicalize(expr: &RecExpr) -> RecExpr {
+    let nodes = expr.as_ref();
+
+    // the canonical text of each node, used as the sort key of operands
+    let mut keys: Vec<String> = Vec::with_capacity(nodes.len());
+    for node in nodes {
+        let mut children = node
+            .children()
+            .iter()
+            .map(|id| keys[usize::from(*id)].clone())
+            .collect::<Vec<_>>();
+        if is_commutative(node) {
+            children.sort_unstable();
+        }
+        if let Some(list) = unordered_list(nodes, node) {
+            let mut values = (list.iter())
+                .map(|id| keys[usize::from(*id)].as_str())
+                .collect::<Vec<_>>();
+            values.sort_unstable();
+            children[1] = key_of(&Expr::List([].into()), &values);
+        }
+        let children = children.iter().map(|s| s.as_str()).collect::<Vec<_>>();
+        keys.push(key_of(node, &children));
+    }
+
+    let mut builder = Builder {
+        nodes,
+        keys: &keys,
+        expr: RecExpr::default(),
+        added: HashMap::new(),
+    };
+    builder.add(Id::from(nodes.len() - 1));
+    builder.expr
+}
+
+/// Returns the text of a node with the given text of its children.
+fn key_of(node: &Expr, children: &[&str]) -> String {
+    if children.is_empty() {
+        node.to_string()
+    } else {
+        format!("({} {})", node, children.join(" "))
+    }
+}
+
+/// Returns true if the operands of the node can be swapped.
+///
+/// Arithmetic operators are excluded since their operand types are not always symmetric.
+/// `AND` and `OR` short-circuit on each chunk, but are commuted by the optimizer anyway.
+fn is_commutative(node: &Expr) -> bool {
+    use Expr::*;
+    matches!(
+        node,
+        Eq(_)
+            | NotEq(_)
+            | And(_)
+            | Or(_)
+            | Xor(_)
+            | IsDistinct(_)
+            | IsNotDistinct(_)
+            | BitAnd(_)
+            | BitOr(_)
+            | BitXor(_)
+    )
+}
+
+/// Returns the values of the IN list if the node is an IN expression.
+///
+/// Unlike other lists, the order of the values doesn't matter.
+fn unordered_list<'a>(nodes: &'a [Expr], node: &Expr) -> Option<&'a [Id]> {
+    match node {
+        Expr::In([_, list]) => nodes[usize::from(*list)].try_as_list().ok(),
+        _ => None,
+    }
+}
+
+struct Builder<'a> {
+    nodes: &'a [Expr],
+    keys: &'a [String],
+    expr: RecExpr,
+    /// Nodes already added to the new expression.
+    added: HashMap<Expr, Id>,
+}
+
+impl Builder<'_> {
+    /// Adds the node `id` of the original expression and its children.
+    /// Returns the id in the new expression.
+    fn add(&mut self, id: Id) -> Id {
+        let mut node = self.nodes[usize::from(id)].clone();
+        let keys = self.keys;
+        let by_key = |a: &Id, b: &Id| keys[usize::from(*a)].cmp(&keys[usize::from(*b)]);
+        if is_commutative(&node) {
+            node.children_mut().sort_by(by_key);
+        }
+        if let (Some(list), &Expr::In([expr, _])) = (unordered_list(self.nodes, &node), &node) {
+            let expr = self.add(expr);
+            let mut values = list.to_vec();
+            values.sort_by(by_key);
+            let values = values.into_iter().map(|value| self.add(value)).collect();
+            let list = self.insert(Expr::List(values));
+            return self.insert(Expr::In([expr, list]));
+        }
+        let node = node.map_children(|child| self.add(child));
+        self.insert(node)
+    }
+
+    /// Adds a node whose children are already added, or returns the existing one.
+    fn insert(&mut self, node: Expr) -> Id {
+        if let Some(&id) = self.added.get(&node) {
+            return id;
+        }
+        let id = self.expr.add(node.clone());
+        self.added.insert(node, id);
+        id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::DataValue;
+
+    #[test]
+    fn commutative_operands() {
+        let a: RecExpr = "(filter (and (= $1.2 1) (= 2 $1.1)) (scan $1 (list $1.1 $1.2) null))"
+            .parse()
+            .unwrap();
+        let b: RecExpr = "(filter (and (= 1 $1.2) (= $1.1 2)) (scan $1 (list $1.1 $1.2) null))"
+            .parse()
+            .unwrap();
+        assert_eq!(canonicalize(&a), canonicalize(&b));
+        assert_eq!(
+            canonicalize(&a).to_string(),
+            "(filter (and (= $1.1 2) (= $1.2 1)) (scan $1 (list $1.1 $1.2) null))"
+        );
+    }
+
+    #[test]
+    fn non_commutative_operands() {
+        let a: RecExpr = "(proj (list $1.2 (- $1.2 $1.1)) (scan $1 (list $1.1 $1.2) null))"
+            .parse()
+            .unwrap();
+        assert_eq!(canonicalize(&a).to_string(), a.to_string());
+
+        // the order of a list matters except in IN
+        let a: RecExpr = "(proj (list $1.2 $1.1) (scan $1 (list $1.2 $1.1) null))"
+            .parse()
+            .unwrap();
+        assert_eq!(canonicalize(&a).to_string(), a.to_string());
+    }
+
+    #[test]
+    fn and_or_operands() {
+        let a: RecExpr =
+            "(filter (or (< $1.1 1) (and (> $1.2 2) (= $1.1 3))) (scan $1 (list $1.1 $1.2) null))"
+                .parse()
+                .unwrap();
+        let b: RecExpr =
+            "(filter (or (and (= $1.1 3) (> $1.2 2)) (< $1.1 1)) (scan $1 (list $1.1 $1.2) null))"
+                .parse()
+                .unwrap();
+        assert_eq!(canonicalize(&a), canonicalize(&b));
+    }
+
+    #[test]
+    fn in_list_values() {
+        let a: RecExpr = "(filter (in $1.1 (list 3 1 2)) (scan $1 (list $1.1) null))"
+            .parse()
+            .unwrap();
+        let b: RecExpr = "(filter (in $1.1 (list 2 3 1)) (scan $1 (list $1.1) null))"
+            .parse()
+            .unwrap();
+        assert_eq!(canonicalize(&a), canonicalize(&b));
+        assert_eq!(
+            canonicalize(&a).to_string(),
+            "(filter (in $1.1 (list 1 2 3)) (scan $1 (list $1.1) null))"
+        );
+    }
+
+    #[test]
+    fn dedup_nodes() {
+        let mut a = RecExpr::default();
+        let c1 = a.add(Expr::Constant(DataValue::Int32(1)));
+        let c2 = a.add(Expr::Constant(DataValue::Int32(1)));
+        a.add(Expr::Add([c1, c2]));
+        let b = canonicalize(&a);
+        assert_eq!(b.as_ref().len(), 2);
+        assert_eq!(b.to_string(), "(+ 1 1)");
+    }
+}
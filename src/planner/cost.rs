@@ -8,10 +8,32 @@ use crate::catalog::*;
 
 use super::*;
 
+/// Provides the cost multipliers of accessing tables.
+///
+/// Storage backends can implement it to reflect their actual costs,
+/// e.g. scanning an on-disk table is more expensive than an in-memory one.
+pub trait CostProvider: Send + Sync {
+    /// Returns the multiplier of the cost to scan the whole table.
+    fn scan_cost(&self, _table: TableRefId) -> f32 {
+        1.0
+    }
+
+    /// Returns the multiplier of the cost to seek into the table by its key.
+    fn seek_cost(&self, _table: TableRefId) -> f32 {
+        1.0
+    }
+}
+
+/// The cost provider assuming the same costs for all tables.
+pub struct DefaultCostProvider;
+
+impl CostProvider for DefaultCostProvider {}
+
 /// The main cost function.
 pub struct CostFn<'a> {
     pub egraph: &'a EGraph,
     pub catalog: &'a RootCatalog,
+    pub provider: &'a dyn CostProvider,
 }
 
 impl CostFn<'_> {
@@ -24,9 +46,12 @@ impl CostFn<'_> {
     pub fn is_constant(&mut self, id:&Id) -> bool{
         let node = &self.egraph[id.clone()].nodes[0];
         match node {
-            Expr::Constant(_) | Expr::Type(_) => true,
+            Expr::Constant(_) | Expr::Type(_) | Expr::Placeholder(_) => true,
 
-            Expr::Neg(id) | Expr::Not(id) | Expr::IsNull(id) 
+            Expr::Neg(id) | Expr::Not(id) | Expr::IsNull(id) | Expr::IsNotNull(id) |
+            Expr::Length(id) | Expr::CharLength(id) |
+            Expr::Ascii(id) | Expr::Chr(id) | Expr::Reverse(id) |
+            Expr::Sign(id) | Expr::Ln(id) | Expr::Exp(id)
                 => self.is_constant(id),
             
             Expr::Sub([lhs,rhs]) | Expr::Add([lhs,rhs]) |
@@ -37,7 +62,14 @@ impl CostFn<'_> {
             Expr::Eq([lhs,rhs]) | Expr::NotEq([lhs,rhs]) | 
             Expr::And([lhs,rhs]) | Expr::Or([lhs,rhs]) | 
             Expr::Xor([lhs,rhs]) | Expr::Like([lhs,rhs]) | 
-            Expr::Extract([lhs,rhs]) | Expr::Cast([lhs,rhs]) 
+            Expr::BitAnd([lhs,rhs]) | Expr::BitOr([lhs,rhs]) |
+            Expr::BitXor([lhs,rhs]) | Expr::Shl([lhs,rhs]) |
+            Expr::Shr([lhs,rhs]) |
+            Expr::IsDistinct([lhs,rhs]) | Expr::IsNotDistinct([lhs,rhs]) |
+            Expr::Extract([lhs,rhs]) | Expr::Cast([lhs,rhs]) |
+            Expr::TryCast([lhs,rhs]) |
+            Expr::DateTrunc([lhs,rhs]) |
+            Expr::Log([lhs,rhs]) | Expr::Repeat([lhs,rhs])
                 => self.is_constant(&lhs) && self.is_constant(&rhs),
             
             Expr::Replace([expr,a,b]) | Expr::Substring([expr,a,b])
@@ -47,36 +79,41 @@ impl CostFn<'_> {
         }
     }
 
-    pub fn cond_check(&mut self, lhs:&Id, rhs:&Id, out: &impl Fn() -> f32) -> f32{
+    pub fn cond_check(&mut self, table: TableRefId, lhs:&Id, rhs:&Id, out: &impl Fn() -> f32) -> f32{
         let lhs_node = &self.egraph[lhs.clone()].nodes[0];
         let rhs_node = &self.egraph[rhs.clone()].nodes[0];
 
-        let mut factor:f32 = 100000.0;
+        let scan = 100000.0 * self.provider.scan_cost(table);
+        let mut factor:f32 = scan;
         match lhs_node {
             Expr::Column(idx) => if self.column_is_required(idx) && self.is_constant(rhs){
-                factor = 1.0;
+                factor = self.provider.seek_cost(table);
             },
             _ => {}
         };
         match rhs_node {
-            Expr::Column(_) => factor = 100000.0,
+            Expr::Column(_) => factor = scan,
             _ => {}
         }
-        println!("factor {}",factor);
         factor //* out()
     }
 
     pub fn condition_out(&mut self, table:&Id, filter:&Id, out: &impl Fn() -> f32) -> f32{
-        let _table_node = &self.egraph[table.clone()].nodes;
+        let table = self.egraph[table.clone()].nodes[0].as_table();
         let filter_nodes = &self.egraph[filter.clone()].nodes;
+        let scan = self.provider.scan_cost(table);
 
         if filter_nodes.len() == 0 {
-            return 100000.0 * out();
+            return 100000.0 * out() * scan;
         }
 
         let res = match &filter_nodes[0] {
-            Expr::Eq([lhs, rhs]) => self.cond_check(&lhs, &rhs, &out),
-            _ => 100000.0 * out(),
+            Expr::Eq([lhs, rhs]) => self.cond_check(table, &lhs, &rhs, &out),
+            // seek to each range, e.g. each point of a list or the prefix of a LIKE pattern
+            _ => match rules::scan_ranges(self.egraph, *filter).len() {
+                0 => 100000.0 * out() * scan,
+                n => n as f32 * self.provider.seek_cost(table),
+            },
         };
 
         return res;
@@ -114,16 +151,25 @@ impl egg::CostFunction<Expr> for CostFn<'_> {
                 (costs(exprs) + costs(groupby)) * rows(c) + out() + costs(c)
             }
             Limit([_, _, c]) => out() + costs(c),
-            TopN([_, _, _, c]) => (rows(id) + 1.0).log2() * rows(c) + out() + costs(c),
+            TopN([_, _, _, _, c]) => (rows(id) + 1.0).log2() * rows(c) + out() + costs(c),
             Join([_, on, l, r]) => costs(on) * rows(l) * rows(r) + out() + costs(l) + costs(r),
             HashJoin([_, _, _, l, r]) => {
                 (rows(l) + 1.0).log2() * (rows(l) + rows(r)) + out() + costs(l) + costs(r)
             }
             MergeJoin([_, _, _, l, r]) => out() + costs(l) + costs(r),
-            Insert([_, _, c]) | CopyTo([_, c]) => rows(c) * cols(c) + costs(c),
+            // the right side is evaluated for each row of the left side
+            Apply([_, on, l, r]) => {
+                costs(on) * rows(l) * rows(r) + out() + costs(l) + rows(l) * costs(r)
+            }
+            Insert([_, _, c]) | CopyTo([_, _, c]) => rows(c) * cols(c) + costs(c),
             Empty(_) => 0.0,
+            // the expression is compared with each value in the list
+            In([a, list]) => {
+                let n = self.egraph[*list].as_list().len() as f32;
+                (costs(a) + 0.2) * n + costs(list)
+            }
             // for expressions, the cost is 0.1x AST size
-            Column(_) | Ref(_) => 0.01,
+            Column(_) | Ref(_) | ValuesColumn(_) => 0.01,
             _ => enode.fold(0.1, |sum, id| sum + costs(&id)),
         };
         debug!(
@@ -1,8 +1,9 @@
 // Copyright 2023 RisingLight Project Authors. Licensed under Apache-2.0.
 
 use std::fmt;
+use std::time::Duration;
 
-use egg::Id;
+use egg::{Id, Language};
 use pretty_xmlish::helper::delegate_fmt;
 use pretty_xmlish::Pretty;
 
@@ -11,6 +12,7 @@ use crate::catalog::RootCatalog;
 
 trait Insertable<'a> {
     fn with_cost(self, value: Option<f32>) -> Self;
+    fn with_properties(self, value: Vec<(&'a str, Pretty<'a>)>) -> Self;
 }
 
 impl<'a> Insertable<'a> for Vec<(&'a str, Pretty<'a>)> {
@@ -20,6 +22,29 @@ impl<'a> Insertable<'a> for Vec<(&'a str, Pretty<'a>)> {
         }
         self
     }
+
+    fn with_properties(mut self, value: Vec<(&'a str, Pretty<'a>)>) -> Self {
+        self.extend(value);
+        self
+    }
+}
+
+/// Physical properties of a plan node derived from the analysis.
+#[derive(Debug, Clone, Default)]
+pub struct Properties {
+    /// The keys by which the output is ordered. Empty if unordered.
+    pub orderby: Box<[Id]>,
+    /// The estimated number of output rows, or distinct groups for aggregations.
+    pub rows: f32,
+}
+
+/// Actual metrics of a plan node collected during execution.
+#[derive(Debug, Clone, Default)]
+pub struct Metrics {
+    /// The number of rows produced.
+    pub rows: u64,
+    /// The wall-clock time spent in the executor, excluding its children.
+    pub time: Duration,
 }
 
 /// A wrapper over [`RecExpr`] to explain it in [`Display`].
@@ -33,6 +58,8 @@ impl<'a> Insertable<'a> for Vec<(&'a str, Pretty<'a>)> {
 pub struct Explain<'a> {
     expr: &'a RecExpr,
     costs: Option<&'a [f32]>,
+    properties: Option<&'a [Properties]>,
+    metrics: Option<&'a [Metrics]>,
     catalog: Option<&'a RootCatalog>,
     id: Id,
 }
@@ -43,6 +70,8 @@ impl<'a> Explain<'a> {
         Self {
             expr,
             costs: None,
+            properties: None,
+            metrics: None,
             catalog: None,
             id: Id::from(expr.as_ref().len() - 1),
         }
@@ -54,6 +83,18 @@ impl<'a> Explain<'a> {
         self
     }
 
+    /// Explain with physical properties.
+    pub fn with_properties(mut self, properties: &'a [Properties]) -> Self {
+        self.properties = Some(properties);
+        self
+    }
+
+    /// Explain with actual metrics collected during execution.
+    pub fn with_metrics(mut self, metrics: &'a [Metrics]) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
     /// Explain column in name.
     pub fn with_catalog(mut self, catalog: &'a RootCatalog) -> Self {
         self.catalog = Some(catalog);
@@ -66,6 +107,8 @@ impl<'a> Explain<'a> {
         Explain {
             expr: self.expr,
             costs: self.costs,
+            properties: self.properties,
+            metrics: self.metrics,
             catalog: self.catalog,
             id: *id,
         }
@@ -77,6 +120,8 @@ impl<'a> Explain<'a> {
         Explain {
             expr: self.expr,
             costs: self.costs,
+            properties: self.properties,
+            metrics: self.metrics,
             catalog: self.catalog,
             id: *id,
         }
@@ -88,6 +133,23 @@ impl<'a> Explain<'a> {
         self.costs.map(|cs| cs[usize::from(self.id)])
     }
 
+    /// Returns the fields displaying the physical properties and the actual metrics.
+    fn properties(&self) -> Vec<(&'a str, Pretty<'a>)> {
+        let mut fields = vec![];
+        if let Some(properties) = self.properties {
+            let props = &properties[usize::from(self.id)];
+            let orderby = props.orderby.iter().map(|id| self.expr(id).pretty());
+            fields.push(("ordered_by", Pretty::Array(orderby.collect())));
+            fields.push(("rows", Pretty::display(&props.rows)));
+        }
+        if let Some(metrics) = self.metrics {
+            let metrics = &metrics[usize::from(self.id)];
+            fields.push(("actual_rows", Pretty::display(&metrics.rows)));
+            fields.push(("time", format!("{:?}", metrics.time).into()));
+        }
+        fields
+    }
+
     /// Returns whether the expression is `true`.
     #[inline]
     fn is_true(&self, id: &Id) -> bool {
@@ -118,18 +180,31 @@ impl<'a> Explain<'a> {
                 }
             }
             ColumnIndex(i) => Pretty::display(i),
+            Placeholder(i) => Pretty::display(i),
 
             // TODO: use object
             ExtSource(src) => format!("path={:?}, format={}", src.path, src.format).into(),
             Symbol(s) => Pretty::display(s),
             Ref(e) => self.expr(e).pretty(),
-            List(list) => Pretty::Array(list.iter().map(|e| self.expr(e).pretty()).collect()),
+            List(list) | ValuesColumn(list) => {
+                Pretty::Array(list.iter().map(|e| self.expr(e).pretty()).collect())
+            }
 
             // binary operations
             Add([a, b]) | Sub([a, b]) | Mul([a, b]) | Div([a, b]) | Mod([a, b])
             | StringConcat([a, b]) | Gt([a, b]) | Lt([a, b]) | GtEq([a, b]) | LtEq([a, b])
             | Eq([a, b]) | NotEq([a, b]) | And([a, b]) | Or([a, b]) | Xor([a, b])
-            | Like([a, b]) => Pretty::childless_record(
+            | Like([a, b]) | BitAnd([a, b]) | BitOr([a, b]) | BitXor([a, b]) | Shl([a, b])
+            | Shr([a, b]) => Pretty::childless_record(
+                enode.to_string(),
+                vec![
+                    ("lhs", self.expr(a).pretty()),
+                    ("rhs", self.expr(b).pretty()),
+                ],
+            ),
+
+            // null-safe comparisons
+            IsDistinct([a, b]) | IsNotDistinct([a, b]) => Pretty::childless_record(
                 enode.to_string(),
                 vec![
                     ("lhs", self.expr(a).pretty()),
@@ -138,7 +213,8 @@ impl<'a> Explain<'a> {
             ),
 
             // unary operations
-            Neg(a) | Not(a) | IsNull(a) => {
+            Neg(a) | Not(a) | IsNull(a) | IsNotNull(a) | Length(a) | CharLength(a) | Ascii(a)
+            | Chr(a) | Reverse(a) | Sign(a) | Ln(a) | Exp(a) => {
                 let name = enode.to_string();
                 let v = vec![self.expr(a).pretty()];
                 Pretty::fieldless_record(name, v)
@@ -162,6 +238,27 @@ impl<'a> Explain<'a> {
                 ],
             ),
             Field(field) => Pretty::display(field),
+            DateTrunc([field, e]) => Pretty::childless_record(
+                "DateTrunc",
+                vec![
+                    ("field", self.expr(field).pretty()),
+                    ("from", self.expr(e).pretty()),
+                ],
+            ),
+            Log([base, a]) => Pretty::childless_record(
+                "Log",
+                vec![
+                    ("base", self.expr(base).pretty()),
+                    ("value", self.expr(a).pretty()),
+                ],
+            ),
+            Repeat([a, n]) => Pretty::childless_record(
+                "Repeat",
+                vec![
+                    ("str", self.expr(a).pretty()),
+                    ("count", self.expr(n).pretty()),
+                ],
+            ),
             Replace([a, b, c]) => Pretty::childless_record(
                 "Replace",
                 vec![
@@ -180,13 +277,20 @@ impl<'a> Explain<'a> {
             ),
 
             // aggregations
-            RowCount | RowNumber => enode.to_string().into(),
-            Max(a) | Min(a) | Sum(a) | Avg(a) | Count(a) | First(a) | Last(a) => {
+            RowCount | RowNumber | Rank | DenseRank | Now => enode.to_string().into(),
+            Max(a) | Min(a) | Sum(a) | Avg(a) | Count(a) | First(a) | Last(a) | Partial(a) => {
                 let name = enode.to_string();
                 let v = vec![self.expr(a).pretty()];
                 Pretty::fieldless_record(name, v)
             }
-            Over([f, orderby, partitionby]) => Pretty::simple_record(
+            StringAgg([a, sep]) => Pretty::childless_record(
+                "string_agg",
+                vec![
+                    ("expr", self.expr(a).pretty()),
+                    ("separator", self.expr(sep).pretty()),
+                ],
+            ),
+            Over([f, partitionby, orderby]) => Pretty::simple_record(
                 "Over",
                 vec![
                     ("order_by", self.expr(orderby).pretty()),
@@ -204,9 +308,14 @@ impl<'a> Explain<'a> {
                 vec![("in", self.expr(b).pretty())],
                 vec![self.expr(a).pretty()],
             ),
-            Cast([a, b]) => Pretty::simple_record(
+            Cast([ty, a]) => Pretty::simple_record(
                 "Cast",
-                vec![("type", self.expr(b).pretty())],
+                vec![("type", self.expr(ty).pretty())],
+                vec![self.expr(a).pretty()],
+            ),
+            TryCast([ty, a]) => Pretty::simple_record(
+                "TryCast",
+                vec![("type", self.expr(ty).pretty())],
                 vec![self.expr(a).pretty()],
             ),
 
@@ -217,7 +326,8 @@ impl<'a> Explain<'a> {
                     ("list", self.expr(list).pretty()),
                     ("filter", self.expr(filter).pretty()),
                 ]
-                .with_cost(cost),
+                .with_cost(cost)
+                .with_properties(self.properties()),
             ),
             Internal([table, list]) => Pretty::childless_record(
                 "Internal",
@@ -225,26 +335,41 @@ impl<'a> Explain<'a> {
                     ("table", self.expr(table).pretty()),
                     ("list", self.expr(list).pretty()),
                 ]
-                .with_cost(cost),
-            ),
-            Values(rows) => Pretty::simple_record(
-                "Values",
-                vec![("rows", Pretty::display(&rows.len()))].with_cost(cost),
-                rows.iter().map(|id| self.expr(id).pretty()).collect(),
+                .with_cost(cost)
+                .with_properties(self.properties()),
             ),
+            Values(columns) => {
+                let rows = columns.first().map_or(0, |id| self.expr[*id].len());
+                Pretty::simple_record(
+                    "Values",
+                    vec![
+                        ("rows", Pretty::display(&rows)),
+                        ("columns", Pretty::display(&columns.len())),
+                    ]
+                    .with_cost(cost)
+                    .with_properties(self.properties()),
+                    columns.iter().map(|id| self.expr(id).pretty()).collect(),
+                )
+            }
             Proj([exprs, child]) => Pretty::simple_record(
                 "Projection",
-                vec![("exprs", self.expr(exprs).pretty())].with_cost(cost),
+                vec![("exprs", self.expr(exprs).pretty())]
+                    .with_cost(cost)
+                    .with_properties(self.properties()),
                 vec![self.child(child).pretty()],
             ),
             Filter([cond, child]) => Pretty::simple_record(
                 "Filter",
-                vec![("cond", self.expr(cond).pretty())].with_cost(cost),
+                vec![("cond", self.expr(cond).pretty())]
+                    .with_cost(cost)
+                    .with_properties(self.properties()),
                 vec![self.child(child).pretty()],
             ),
             Order([orderby, child]) => Pretty::simple_record(
                 "Order",
-                vec![("by", self.expr(orderby).pretty())].with_cost(cost),
+                vec![("by", self.expr(orderby).pretty())]
+                    .with_cost(cost)
+                    .with_properties(self.properties()),
                 vec![self.child(child).pretty()],
             ),
             Desc(a) => {
@@ -257,19 +382,25 @@ impl<'a> Explain<'a> {
                     ("limit", self.expr(limit).pretty()),
                     ("offset", self.expr(offset).pretty()),
                 ]
-                .with_cost(cost),
+                .with_cost(cost)
+                .with_properties(self.properties()),
                 vec![self.child(child).pretty()],
             ),
-            TopN([limit, offset, orderby, child]) => Pretty::simple_record(
-                "TopN",
-                vec![
+            TopN([limit, offset, orderby, with_ties, child]) => {
+                let mut fields = vec![
                     ("limit", self.expr(limit).pretty()),
                     ("offset", self.expr(offset).pretty()),
                     ("order_by", self.expr(orderby).pretty()),
-                ]
-                .with_cost(cost),
-                vec![self.child(child).pretty()],
-            ),
+                ];
+                if self.is_true(with_ties) {
+                    fields.push(("with_ties", self.expr(with_ties).pretty()));
+                }
+                Pretty::simple_record(
+                    "TopN",
+                    fields.with_cost(cost).with_properties(self.properties()),
+                    vec![self.child(child).pretty()],
+                )
+            }
             Join([ty, cond, left, right]) => {
                 let mut fields = vec![("type", self.expr(ty).pretty())];
 
@@ -278,7 +409,19 @@ impl<'a> Explain<'a> {
                 }
                 Pretty::simple_record(
                     "Join",
-                    fields.with_cost(cost),
+                    fields.with_cost(cost).with_properties(self.properties()),
+                    vec![self.child(left).pretty(), self.child(right).pretty()],
+                )
+            }
+            Apply([ty, cond, left, right]) => {
+                let mut fields = vec![("type", self.expr(ty).pretty())];
+
+                if !self.is_true(cond) {
+                    fields.push(("on", self.expr(cond).pretty()));
+                }
+                Pretty::simple_record(
+                    "Apply",
+                    fields.with_cost(cost).with_properties(self.properties()),
                     vec![self.child(left).pretty(), self.child(right).pretty()],
                 )
             }
@@ -294,14 +437,18 @@ impl<'a> Explain<'a> {
                     ("rhs", self.expr(rkeys).pretty()),
                 ];
                 let eq = Pretty::childless_record("=", fields);
-                let fields = vec![("type", self.expr(ty).pretty()), ("on", eq)].with_cost(cost);
+                let fields = vec![("type", self.expr(ty).pretty()), ("on", eq)]
+                    .with_cost(cost)
+                    .with_properties(self.properties());
                 let children = vec![self.child(left).pretty(), self.child(right).pretty()];
                 Pretty::simple_record(name, fields, children)
             }
-            Inner | LeftOuter | RightOuter | FullOuter => Pretty::display(enode),
+            Inner | LeftOuter | RightOuter | FullOuter | Semi | Anti => Pretty::display(enode),
             Agg([aggs, child]) => Pretty::simple_record(
                 "Agg",
-                vec![("aggs", self.expr(aggs).pretty())].with_cost(cost),
+                vec![("aggs", self.expr(aggs).pretty())]
+                    .with_cost(cost)
+                    .with_properties(self.properties()),
                 vec![self.child(child).pretty()],
             ),
             HashAgg([aggs, group_keys, child]) | SortAgg([aggs, group_keys, child]) => {
@@ -315,21 +462,37 @@ impl<'a> Explain<'a> {
                         ("aggs", self.expr(aggs).pretty()),
                         ("group_by", self.expr(group_keys).pretty()),
                     ]
-                    .with_cost(cost),
+                    .with_cost(cost)
+                    .with_properties(self.properties()),
                     vec![self.child(child).pretty()],
                 )
             }
             Window([windows, child]) => Pretty::simple_record(
                 "Window",
-                vec![("windows", self.expr(windows).pretty())].with_cost(cost),
+                vec![("windows", self.expr(windows).pretty())]
+                    .with_cost(cost)
+                    .with_properties(self.properties()),
                 vec![self.child(child).pretty()],
             ),
+            CreateSchema(s) => {
+                let fields = s
+                    .pretty_schema()
+                    .with_cost(cost)
+                    .with_properties(self.properties());
+                Pretty::childless_record("CreateSchema", fields)
+            }
             CreateTable(t) => {
-                let fields = t.pretty_table().with_cost(cost);
+                let fields = t
+                    .pretty_table()
+                    .with_cost(cost)
+                    .with_properties(self.properties());
                 Pretty::childless_record("CreateTable", fields)
             }
             Drop(t) => {
-                let fields = t.pretty_table().with_cost(cost);
+                let fields = t
+                    .pretty_table()
+                    .with_cost(cost)
+                    .with_properties(self.properties());
                 Pretty::childless_record("Drop", fields)
             }
             Insert([table, cols, child]) => Pretty::simple_record(
@@ -338,29 +501,50 @@ impl<'a> Explain<'a> {
                     ("table", self.expr(table).pretty()),
                     ("cols", self.expr(cols).pretty()),
                 ]
-                .with_cost(cost),
+                .with_cost(cost)
+                .with_properties(self.properties()),
                 vec![self.child(child).pretty()],
             ),
             Delete([table, child]) => Pretty::simple_record(
                 "Delete",
-                vec![("table", self.expr(table).pretty())].with_cost(cost),
+                vec![("table", self.expr(table).pretty())]
+                    .with_cost(cost)
+                    .with_properties(self.properties()),
                 vec![self.child(child).pretty()],
             ),
+            Truncate(table) => Pretty::childless_record(
+                "Truncate",
+                vec![("table", self.expr(table).pretty())]
+                    .with_cost(cost)
+                    .with_properties(self.properties()),
+            ),
             CopyFrom([src, _]) => Pretty::childless_record(
                 "CopyFrom",
-                vec![("src", self.expr(src).pretty())].with_cost(cost),
+                vec![("src", self.expr(src).pretty())]
+                    .with_cost(cost)
+                    .with_properties(self.properties()),
             ),
-            CopyTo([dst, child]) => Pretty::simple_record(
+            CopyTo([dst, _, child]) => Pretty::simple_record(
                 "CopyTo",
-                vec![("dst", self.expr(dst).pretty())].with_cost(cost),
+                vec![("dst", self.expr(dst).pretty())]
+                    .with_cost(cost)
+                    .with_properties(self.properties()),
                 vec![self.child(child).pretty()],
             ),
-            Explain(child) => Pretty::simple_record(
+            Explain([_, child]) => Pretty::simple_record(
                 "Explain",
-                vec![].with_cost(cost),
+                vec![].with_cost(cost).with_properties(self.properties()),
+                vec![self.child(child).pretty()],
+            ),
+            Analyze([_, child]) => Pretty::simple_record(
+                "Analyze",
+                vec![].with_cost(cost).with_properties(self.properties()),
                 vec![self.child(child).pretty()],
             ),
-            Empty(_) => Pretty::childless_record("Empty", vec![].with_cost(cost)),
+            Empty(_) => Pretty::childless_record(
+                "Empty",
+                vec![].with_cost(cost).with_properties(self.properties()),
+            ),
         }
     }
 }
@@ -0,0 +1,191 @@
+// Copyright 2023 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use std::collections::HashMap;
+
+use egg::{Id, Language};
+
+use super::{EGraph, Expr, ExprAnalysis, RecExpr};
+
+/// Orders the relations of inner join trees with more than `limit` relations greedily.
+///
+/// Exploring all orders of such trees is too expensive, so a left-deep tree is built instead.
+/// It starts from the relation with the fewest estimated rows, and repeatedly joins the smallest
+/// relation connected to the joined ones by a condition, or the smallest one if none is connected.
+/// Each condition is evaluated by the lowest join where all its columns are available.
+///
+/// Returns the new expression and the ids of the greedily ordered joins in it,
+/// or `None` if there is no such join tree.
+pub fn greedy_join_order(
+    expr: &RecExpr,
+    analysis: ExprAnalysis,
+    limit: usize,
+) -> Option<(RecExpr, Vec<Id>)> {
+    let nodes = expr.as_ref();
+
+    // analyze the rows and columns of each node
+    let mut egraph = EGraph::new(analysis);
+    let mut classes = Vec::with_capacity(nodes.len());
+    for node in nodes {
+        let node = node.clone().map_children(|id| classes[usize::from(id)]);
+        classes.push(egraph.add(node));
+    }
+    egraph.rebuild();
+    let mut class_nodes = HashMap::new();
+    for (i, class) in classes.iter().enumerate() {
+        class_nodes
+            .entry(egraph.find(*class))
+            .or_insert(Id::from(i));
+    }
+
+    let mut builder = Builder {
+        nodes,
+        egraph: &egraph,
+        classes: &classes,
+        class_nodes: &class_nodes,
+        limit,
+        expr: RecExpr::default(),
+        added: HashMap::new(),
+        greedy_joins: vec![],
+    };
+    builder.add(Id::from(nodes.len() - 1));
+    (!builder.greedy_joins.is_empty()).then_some((builder.expr, builder.greedy_joins))
+}
+
+struct Builder<'a> {
+    nodes: &'a [Expr],
+    egraph: &'a EGraph,
+    /// The eclass of each node in the original expression.
+    classes: &'a [Id],
+    /// A node of the original expression in each eclass.
+    class_nodes: &'a HashMap<Id, Id>,
+    limit: usize,
+    expr: RecExpr,
+    /// Nodes already added to the new expression.
+    added: HashMap<Expr, Id>,
+    /// The joins added in greedy order.
+    greedy_joins: Vec<Id>,
+}
+
+impl Builder<'_> {
+    /// Adds the node `id` of the original expression and its children.
+    /// Returns the id in the new expression.
+    fn add(&mut self, id: Id) -> Id {
+        if self.is_inner_join(id) {
+            let mut relations = vec![];
+            let mut conds = vec![];
+            self.flatten(id, &mut relations, &mut conds);
+            if relations.len() > self.limit {
+                return self.add_greedy(id, relations, conds);
+            }
+        }
+        let node = self.nodes[usize::from(id)]
+            .clone()
+            .map_children(|child| self.add(child));
+        self.push(node)
+    }
+
+    /// Adds a node to the new expression without duplicates.
+    fn push(&mut self, node: Expr) -> Id {
+        if let Some(&id) = self.added.get(&node) {
+            return id;
+        }
+        let id = self.expr.add(node.clone());
+        self.added.insert(node, id);
+        id
+    }
+
+    fn is_inner_join(&self, id: Id) -> bool {
+        matches!(self.nodes[usize::from(id)], Expr::Join([ty, _, _, _])
+            if self.nodes[usize::from(ty)] == Expr::Inner)
+    }
+
+    /// Collects the relations and conjunctive conditions of the inner join tree `id`.
+    ///
+    /// Projections between the joins only prune columns, so they are looked through.
+    fn flatten(&self, id: Id, relations: &mut Vec<Id>, conds: &mut Vec<Id>) {
+        let Expr::Join([_, on, left, right]) = self.nodes[usize::from(id)] else {
+            unreachable!("not a join");
+        };
+        self.split_and(on, conds);
+        for child in [left, right] {
+            match self.nodes[usize::from(child)] {
+                _ if self.is_inner_join(child) => self.flatten(child, relations, conds),
+                Expr::Proj([_, c]) if self.is_inner_join(c) => self.flatten(c, relations, conds),
+                _ => relations.push(child),
+            }
+        }
+    }
+
+    fn split_and(&self, id: Id, conds: &mut Vec<Id>) {
+        match &self.nodes[usize::from(id)] {
+            Expr::And([a, b]) => {
+                self.split_and(*a, conds);
+                self.split_and(*b, conds);
+            }
+            e if *e == Expr::true_() => {}
+            _ => conds.push(id),
+        }
+    }
+
+    /// Adds a left-deep join of the relations in greedy order.
+    /// The output is projected to the schema of the original join tree `id`.
+    fn add_greedy(&mut self, id: Id, mut relations: Vec<Id>, mut conds: Vec<Id>) -> Id {
+        let egraph = self.egraph;
+        let classes = self.classes;
+        let data = move |id: Id| &egraph[classes[usize::from(id)]].data;
+        let smallest = |candidates: &mut dyn Iterator<Item = usize>, relations: &[Id]| {
+            candidates.min_by(|a, b| {
+                let (a, b) = (data(relations[*a]).rows, data(relations[*b]).rows);
+                a.total_cmp(&b)
+            })
+        };
+
+        let first = smallest(&mut (0..relations.len()), &relations).unwrap();
+        let first = relations.remove(first);
+        let mut available = data(first).columns.clone();
+        let mut plan = self.add(first);
+        while !relations.is_empty() {
+            // prefer connected relations to avoid cross joins
+            let connected = |i: &usize| {
+                let columns = &data(relations[*i]).columns;
+                conds.iter().any(|c| {
+                    let used = &data(*c).columns;
+                    !used.is_disjoint(columns)
+                        && used
+                            .iter()
+                            .all(|col| available.contains(col) || columns.contains(col))
+                })
+            };
+            let next = smallest(&mut (0..relations.len()).filter(connected), &relations)
+                .or_else(|| smallest(&mut (0..relations.len()), &relations))
+                .unwrap();
+            let relation = relations.remove(next);
+            available.extend(data(relation).columns.iter().cloned());
+
+            // conditions that can not be evaluated by any join are left to the top one
+            let (ready, rest) = conds.into_iter().partition::<Vec<_>, _>(|c| {
+                relations.is_empty() || data(*c).columns.is_subset(&available)
+            });
+            conds = rest;
+            let mut on = None;
+            for cond in ready {
+                let cond = self.add(cond);
+                on = Some(match on {
+                    Some(on) => self.push(Expr::And([on, cond])),
+                    None => cond,
+                });
+            }
+            let on = on.unwrap_or_else(|| self.push(Expr::true_()));
+            let right = self.add(relation);
+            let inner = self.push(Expr::Inner);
+            plan = self.push(Expr::Join([inner, on, plan, right]));
+            self.greedy_joins.push(plan);
+        }
+
+        let schema = (data(id).schema.iter())
+            .map(|c| self.add(self.class_nodes[&egraph.find(*c)]))
+            .collect();
+        let list = self.push(Expr::List(schema));
+        self.push(Expr::Proj([list, plan]))
+    }
+}
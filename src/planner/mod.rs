@@ -1,19 +1,26 @@
 // Copyright 2023 RisingLight Project Authors. Licensed under Apache-2.0.
 
-use egg::{define_language, CostFunction, Id, Symbol};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use egg::{define_language, CostFunction, Id, Language, Symbol};
 
 use crate::binder::copy::ExtSource;
-use crate::binder::{BoundDrop, CreateTable};
+use crate::binder::{BoundDrop, CreateSchema, CreateTable};
 use crate::catalog::{ColumnRefId, RootCatalogRef, TableRefId};
 use crate::parser::{BinaryOperator, UnaryOperator};
-use crate::types::{ColumnIndex, DataTypeKind, DataValue, DateTimeField};
+use crate::types::{ColumnIndex, DataTypeKind, DataValue, DateTimeField, ParamIndex};
 
+mod canonical;
 mod cost;
 mod explain;
+mod join_order;
 mod rules;
 
-pub use explain::Explain;
-pub use rules::{ExprAnalysis, TypeError, TypeSchemaAnalysis};
+pub use cost::{CostProvider, DefaultCostProvider};
+pub use explain::{Explain, Metrics, Properties};
+pub use rules::{scan_ranges, ExprAnalysis, TypeError, TypeSchemaAnalysis};
 
 // Alias types for our language.
 type EGraph = egg::EGraph<Expr, ExprAnalysis>;
@@ -29,6 +36,7 @@ define_language! {
         Column(ColumnRefId),            // $1.2, $2.1, ...
         Table(TableRefId),              // $1, $2, ...
         ColumnIndex(ColumnIndex),       // #0, #1, ...
+        Placeholder(ParamIndex),        // ?1, ?2, ...
         ExtSource(ExtSource),
 
         // utilities
@@ -54,19 +62,40 @@ define_language! {
         "or" = Or([Id; 2]),
         "xor" = Xor([Id; 2]),
         "like" = Like([Id; 2]),
+        "is_distinct" = IsDistinct([Id; 2]),        // null-safe `<>`
+        "is_not_distinct" = IsNotDistinct([Id; 2]), // null-safe `=`
+        "bitand" = BitAnd([Id; 2]),
+        "bitor" = BitOr([Id; 2]),
+        "bitxor" = BitXor([Id; 2]),
+        "shl" = Shl([Id; 2]),
+        "shr" = Shr([Id; 2]),
 
         // unary operations
         "-" = Neg(Id),
         "not" = Not(Id),
         "isnull" = IsNull(Id),
+        "isnotnull" = IsNotNull(Id),
 
         "if" = If([Id; 3]),                     // (if cond then else)
+        "in" = In([Id; 2]),                     // (in expr (list value..))
 
         // functions
         "extract" = Extract([Id; 2]),           // (extract field expr)
             Field(DateTimeField),
+        "date_trunc" = DateTrunc([Id; 2]),      // (date_trunc field expr)
+        "now" = Now,                            // the start time of the query
         "replace" = Replace([Id; 3]),           // (replace expr pattern replacement)
         "substring" = Substring([Id; 3]),       // (substring expr start length)
+        "length" = Length(Id),                  // the number of bytes
+        "char_length" = CharLength(Id),         // the number of characters
+        "ascii" = Ascii(Id),                    // the code point of the first character
+        "chr" = Chr(Id),                        // the character of a code point
+        "reverse" = Reverse(Id),
+        "repeat" = Repeat([Id; 2]),             // (repeat expr count)
+        "sign" = Sign(Id),
+        "ln" = Ln(Id),
+        "log" = Log([Id; 2]),                   // (log base expr)
+        "exp" = Exp(Id),
 
         // aggregations
         "max" = Max(Id),
@@ -77,35 +106,47 @@ define_language! {
         "rowcount" = RowCount,
         "first" = First(Id),
         "last" = Last(Id),
+        "string_agg" = StringAgg([Id; 2]),      // (string_agg expr separator)
+        "partial" = Partial(Id),                // (partial agg) partial result of agg from eager aggregation
         // window functions
         "over" = Over([Id; 3]),                 // (over window_function [partition_key..] [order_key..])
         // TODO: support frame clause
             // "range" = Range([Id; 2]),               // (range start end)
         "row_number" = RowNumber,
+        "rank" = Rank,
+        "dense_rank" = DenseRank,
 
         // subquery related
         "exists" = Exists(Id),
-        "in" = In([Id; 2]),
 
         "cast" = Cast([Id; 2]),                 // (cast type expr)
+        "try_cast" = TryCast([Id; 2]),          // (try_cast type expr)
 
         // plans
         "scan" = Scan([Id; 3]),                 // (scan table [column..] filter)
         "internal" = Internal([Id; 2]),         // (internal table [column..])
-        "values" = Values(Box<[Id]>),           // (values [expr..]..)
+        "values" = Values(Box<[Id]>),           // (values column..)
+            "values_column" = ValuesColumn(Box<[Id]>),  // (values_column expr..)
+                                                        // a column of values with one expr per row
         "proj" = Proj([Id; 2]),                 // (proj [expr..] child)
         "filter" = Filter([Id; 2]),             // (filter expr child)
         "order" = Order([Id; 2]),               // (order [order_key..] child)
             "desc" = Desc(Id),                      // (desc key)
         "limit" = Limit([Id; 3]),               // (limit limit offset child)
-        "topn" = TopN([Id; 4]),                 // (topn limit offset [order_key..] child)
+        "topn" = TopN([Id; 5]),                 // (topn limit offset [order_key..] with_ties child)
+                                                    // with_ties: keep rows tying with the last one
         "join" = Join([Id; 4]),                 // (join join_type expr left right)
+                                                    // semi and anti join only output left
         "hashjoin" = HashJoin([Id; 5]),         // (hashjoin join_type [left_expr..] [right_expr..] left right)
         "mergejoin" = MergeJoin([Id; 5]),       // (mergejoin join_type [left_expr..] [right_expr..] left right)
+        "apply" = Apply([Id; 4]),               // (apply join_type expr left right)
+                                                    // right is evaluated for each row of left
             "inner" = Inner,
             "left_outer" = LeftOuter,
             "right_outer" = RightOuter,
             "full_outer" = FullOuter,
+            "semi" = Semi,
+            "anti" = Anti,
         "agg" = Agg([Id; 2]),                   // (agg aggs=[expr..] child)
                                                     // expressions must be aggregate functions
         "hashagg" = HashAgg([Id; 3]),           // (hashagg aggs=[expr..] group_keys=[expr..] child)
@@ -114,13 +155,18 @@ define_language! {
                                                     // child must be ordered by group_keys
         "window" = Window([Id; 2]),             // (window [over..] child)
                                                     // output = child || exprs
+        CreateSchema(CreateSchema),
         CreateTable(CreateTable),
         Drop(BoundDrop),
         "insert" = Insert([Id; 3]),             // (insert table [column..] child)
         "delete" = Delete([Id; 2]),             // (delete table child)
+        "truncate" = Truncate(Id),              // (truncate table)
         "copy_from" = CopyFrom([Id; 2]),        // (copy_from dest types)
-        "copy_to" = CopyTo([Id; 2]),            // (copy_to dest child)
-        "explain" = Explain(Id),                // (explain child)
+        "copy_to" = CopyTo([Id; 3]),            // (copy_to dest [name..] child)
+        "explain" = Explain([Id; 2]),           // (explain verbose child)
+                                                    // verbose: whether to show physical properties
+        "analyze" = Analyze([Id; 2]),           // (analyze verbose child)
+                                                    // execute child and explain it with metrics
 
         // internal functions
         "empty" = Empty(Box<[Id]>),             // (empty child..)
@@ -145,18 +191,15 @@ impl Expr {
     }
 
     pub fn as_const(&self) -> DataValue {
-        let Self::Constant(v) = self else { panic!("not a constant: {self}") };
-        v.clone()
+        self.try_as_const().unwrap_or_else(|e| panic!("{e}"))
     }
 
     pub fn as_list(&self) -> &[Id] {
-        let Self::List(l) = self else { panic!("not a list: {self}") };
-        l
+        self.try_as_list().unwrap_or_else(|e| panic!("{e}"))
     }
 
     pub fn as_column(&self) -> ColumnRefId {
-        let Self::Column(c) = self else { panic!("not a columnn: {self}") };
-        *c
+        self.try_as_column().unwrap_or_else(|e| panic!("{e}"))
     }
 
     pub fn as_table(&self) -> TableRefId {
@@ -165,8 +208,34 @@ impl Expr {
     }
 
     pub fn as_type(&self) -> &DataTypeKind {
-        let Self::Type(t) = self else { panic!("not a type: {self}") };
-        t
+        self.try_as_type().unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn try_as_const(&self) -> Result<DataValue, UnexpectedNode> {
+        let Self::Constant(v) = self else { return Err(self.unexpected("a constant")) };
+        Ok(v.clone())
+    }
+
+    pub fn try_as_list(&self) -> Result<&[Id], UnexpectedNode> {
+        let Self::List(l) = self else { return Err(self.unexpected("a list")) };
+        Ok(l)
+    }
+
+    pub fn try_as_column(&self) -> Result<ColumnRefId, UnexpectedNode> {
+        let Self::Column(c) = self else { return Err(self.unexpected("a column")) };
+        Ok(*c)
+    }
+
+    pub fn try_as_type(&self) -> Result<&DataTypeKind, UnexpectedNode> {
+        let Self::Type(t) = self else { return Err(self.unexpected("a type")) };
+        Ok(t)
+    }
+
+    fn unexpected(&self, expected: &'static str) -> UnexpectedNode {
+        UnexpectedNode {
+            expected,
+            found: self.to_string(),
+        }
     }
 
     pub fn as_ext_source(&self) -> ExtSource {
@@ -193,6 +262,11 @@ impl Expr {
             &Self::And([a, b]) => (Op::And, a, b),
             &Self::Or([a, b]) => (Op::Or, a, b),
             &Self::Xor([a, b]) => (Op::Xor, a, b),
+            &Self::BitAnd([a, b]) => (Op::BitwiseAnd, a, b),
+            &Self::BitOr([a, b]) => (Op::BitwiseOr, a, b),
+            &Self::BitXor([a, b]) => (Op::BitwiseXor, a, b),
+            &Self::Shl([a, b]) => (Op::PGBitwiseShiftLeft, a, b),
+            &Self::Shr([a, b]) => (Op::PGBitwiseShiftRight, a, b),
             _ => return None,
         })
     }
@@ -211,84 +285,243 @@ impl Expr {
         use Expr::*;
         matches!(
             self,
-            RowCount | Max(_) | Min(_) | Sum(_) | Avg(_) | Count(_) | First(_) | Last(_)
+            RowCount
+                | Max(_)
+                | Min(_)
+                | Sum(_)
+                | Avg(_)
+                | Count(_)
+                | First(_)
+                | Last(_)
+                | StringAgg(_)
         )
     }
 
-    pub const fn is_window_function(&self) -> bool {
+    /// Returns true if the node is a join type that only outputs the left side.
+    pub const fn is_semi_or_anti(&self) -> bool {
+        matches!(self, Self::Semi | Self::Anti)
+    }
+
+    /// Returns true if the node is a window function that can not be used as an aggregation.
+    pub const fn is_pure_window_function(&self) -> bool {
         use Expr::*;
-        matches!(self, RowNumber) || self.is_aggregate_function()
+        matches!(self, RowNumber | Rank | DenseRank)
+    }
+
+    /// Returns true if the node can be used as a window function.
+    pub const fn is_window_function(&self) -> bool {
+        self.is_pure_window_function() || self.is_aggregate_function()
     }
 }
 
-trait ExprExt {
-    fn as_list(&self) -> &[Id];
-    fn as_column(&self) -> ColumnRefId;
+/// The error of a node not being the expected variant, e.g. in a malformed plan.
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[error("not {expected}: {found}")]
+pub struct UnexpectedNode {
+    expected: &'static str,
+    found: String,
 }
 
-impl<D> ExprExt for egg::EClass<Expr, D> {
+trait ExprExt {
     fn as_list(&self) -> &[Id] {
-        self.iter()
-            .find_map(|e| match e {
-                Expr::List(list) => Some(list),
-                _ => None,
-            })
-            .expect("not a list")
+        self.try_as_list().unwrap_or_else(|e| panic!("{e}"))
     }
 
     fn as_column(&self) -> ColumnRefId {
-        self.iter()
-            .find_map(|e| match e {
-                Expr::Column(cid) => Some(*cid),
-                _ => None,
-            })
-            .expect("not a column")
+        self.try_as_column().unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    fn try_as_list(&self) -> Result<&[Id], UnexpectedNode>;
+    fn try_as_column(&self) -> Result<ColumnRefId, UnexpectedNode>;
+}
+
+impl<D> ExprExt for egg::EClass<Expr, D> {
+    fn try_as_list(&self) -> Result<&[Id], UnexpectedNode> {
+        (self.iter())
+            .find_map(|e| e.try_as_list().ok())
+            .ok_or_else(|| self.nodes[0].unexpected("a list"))
+    }
+
+    fn try_as_column(&self) -> Result<ColumnRefId, UnexpectedNode> {
+        (self.iter())
+            .find_map(|e| e.try_as_column().ok())
+            .ok_or_else(|| self.nodes[0].unexpected("a column"))
     }
 }
 
 /// Plan optimizer.
 pub struct Optimizer {
     catalog: RootCatalogRef,
+    stat: Statistic,
     config: Config,
+    cost_provider: Arc<dyn CostProvider>,
 }
 
-/// Optimizer configurations.
+/// Statistics of tables for cost estimation.
 #[derive(Debug, Clone, Default)]
+pub struct Statistic {
+    row_counts: HashMap<TableRefId, u32>,
+}
+
+impl Statistic {
+    /// Sets the number of rows in the table.
+    pub fn add_row_count(&mut self, table_id: TableRefId, row_count: u32) {
+        self.row_counts.insert(table_id, row_count);
+    }
+
+    /// Returns the number of rows in the table if known.
+    pub fn get_row_count(&self, table_id: TableRefId) -> Option<u32> {
+        self.row_counts.get(&table_id).cloned()
+    }
+}
+
+/// Optimizer configurations.
+#[derive(Debug, Clone)]
 pub struct Config {
     pub enable_range_filter_scan: bool,
     pub table_is_sorted_by_primary_key: bool,
+    /// Whether integer arithmetic wraps around on overflow, instead of returning an error.
+    /// It is respected by both constant folding and the executor.
+    pub wrapping_arithmetic: bool,
+    /// Whether an invalid cast returns an error, or yields NULL otherwise.
+    /// It is respected by both constant folding and the executor.
+    pub strict_casts: bool,
+    /// Whether to push partial aggregations below inner joins.
+    /// It is not always a win, so the optimizer only explores it if enabled.
+    pub enable_eager_aggregation: bool,
+    /// Whether to explore bushy join trees by rotating joins through the projections between them.
+    /// It enlarges the search space a lot, so the optimizer only explores it if enabled.
+    pub enable_bushy_join: bool,
+    /// The number of tasks to scan a table in parallel.
+    /// Tables with a primary key are scanned serially if they are assumed to be sorted by it.
+    pub scan_parallelism: usize,
+    /// The memory budget of each hash aggregation in bytes.
+    /// Groups are spilled to temporary files if it is exceeded. `None` means unlimited.
+    pub hash_agg_memory_budget: Option<usize>,
+    /// The memory budget of the build side of each hash join in bytes.
+    /// Both sides are spilled to temporary files if it is exceeded. `None` means unlimited.
+    pub hash_join_memory_budget: Option<usize>,
+    /// The maximum number of rounds to run the pushdown rules.
+    /// Each round restarts from the best plan of the last one to prune costly nodes.
+    pub pushdown_rounds: usize,
+    /// The maximum number of iterations to run the rules on the e-graph in each pushdown round.
+    pub egraph_iter_limit: usize,
+    /// The maximum number of nodes in the e-graph of each run.
+    pub egraph_node_limit: usize,
+    /// The time budget to optimize a query.
+    /// If exceeded, the best plan found so far is returned.
+    pub optimizer_time_limit: Duration,
+    /// The maximum number of relations in an inner join tree to search all join orders.
+    /// Larger join trees are ordered by a greedy heuristic instead.
+    pub join_reorder_limit: usize,
+    /// The number of significant digits to display floats in query results.
+    /// It only affects how results are rendered, not the values. `None` means full precision.
+    pub float_precision: Option<usize>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            enable_range_filter_scan: false,
+            table_is_sorted_by_primary_key: false,
+            wrapping_arithmetic: false,
+            strict_casts: true,
+            enable_eager_aggregation: false,
+            enable_bushy_join: false,
+            scan_parallelism: 1,
+            hash_agg_memory_budget: None,
+            hash_join_memory_budget: None,
+            pushdown_rounds: 10,
+            egraph_iter_limit: 60,
+            egraph_node_limit: 10_000,
+            optimizer_time_limit: Duration::from_secs(10),
+            join_reorder_limit: 6,
+            float_precision: None,
+        }
+    }
 }
 
 impl Optimizer {
     /// Creates a new optimizer.
-    pub fn new(catalog: RootCatalogRef, config: Config) -> Self {
-        Self { catalog, config }
+    pub fn new(catalog: RootCatalogRef, stat: Statistic, config: Config) -> Self {
+        Self {
+            catalog,
+            stat,
+            config,
+            cost_provider: Arc::new(DefaultCostProvider),
+        }
+    }
+
+    /// Sets the provider of table access costs.
+    ///
+    /// The default one assumes the same costs for all tables.
+    pub fn with_cost_provider(mut self, cost_provider: Arc<dyn CostProvider>) -> Self {
+        self.cost_provider = cost_provider;
+        self
+    }
+
+    /// Returns a new analysis for the e-graph.
+    fn analysis(&self) -> ExprAnalysis {
+        ExprAnalysis {
+            catalog: self.catalog.clone(),
+            stat: self.stat.clone(),
+            config: self.config.clone(),
+            greedy_joins: vec![],
+        }
+    }
+
+    /// Returns a runner limited by the budget of the optimizer.
+    fn runner(&self, expr: &RecExpr, deadline: Instant) -> egg::Runner<Expr, ExprAnalysis> {
+        egg::Runner::new(self.analysis())
+            .with_expr(expr)
+            .with_node_limit(self.config.egraph_node_limit)
+            .with_time_limit(deadline.saturating_duration_since(Instant::now()))
+    }
+
+    /// Warns if the runner stopped at the node limit of the e-graph.
+    fn warn_node_limit(&self, runner: &egg::Runner<Expr, ExprAnalysis>) {
+        if let Some(egg::StopReason::NodeLimit(limit)) = runner.stop_reason {
+            tracing::warn!(
+                "e-graph exceeded the node limit of {limit}, returning the best plan found so far"
+            );
+        }
     }
 
     /// Optimize the given expression.
+    ///
+    /// If the time or node budget is exceeded, the best plan found so far is returned.
     pub fn optimize(&self, expr: &RecExpr) -> RecExpr {
         let mut expr = expr.clone();
+        let deadline = Instant::now() + self.config.optimizer_time_limit;
 
         // define extra rules for some configurations
         let mut extra_rules = vec![];
         if self.config.enable_range_filter_scan {
             extra_rules.append(&mut rules::filter_scan_rule());
         }
+        let mut extra_stage2_rules = vec![];
+        if self.config.enable_eager_aggregation {
+            extra_stage2_rules.append(&mut rules::eager_agg_rules());
+        }
+        if self.config.enable_bushy_join {
+            extra_stage2_rules.append(&mut rules::bushy_join_rules());
+        }
 
         // 1. pushdown
         let mut best_cost = f32::MAX;
         // to prune costy nodes, we iterate multiple times and only keep the best one for each run.
-        for _ in 0..10 {
-            let runner = egg::Runner::<_, _, ()>::new(ExprAnalysis {
-                catalog: self.catalog.clone(),
-                config: self.config.clone(),
-            })
-            .with_expr(&expr)
-            .with_iter_limit(60)
-            .run(rules::STAGE1_RULES.iter().chain(&extra_rules));
+        for _ in 0..self.config.pushdown_rounds {
+            let runner = self
+                .runner(&expr, deadline)
+                .with_iter_limit(self.config.egraph_iter_limit)
+                .run(rules::STAGE1_RULES.iter().chain(&extra_rules));
+            self.warn_node_limit(&runner);
+            let saturated = matches!(runner.stop_reason, Some(egg::StopReason::Saturated));
+            let timeout = matches!(runner.stop_reason, Some(egg::StopReason::TimeLimit(_)));
             let cost_fn = cost::CostFn {
                 egraph: &runner.egraph,
                 catalog: &self.catalog ,
+                provider: &*self.cost_provider,
             };
             let extractor = egg::Extractor::new(&runner.egraph, cost_fn);
             let cost;
@@ -297,6 +530,10 @@ impl Optimizer {
                 break;
             }
             best_cost = cost;
+            // another round won't find a better plan if no more rewrites apply or out of time
+            if saturated || timeout {
+                break;
+            }
             // println!(
             //     "{}",
             //     crate::planner::Explain::of(&expr).with_costs(&costs(&expr))
@@ -304,30 +541,56 @@ impl Optimizer {
         }
 
         // 2. join reorder and hashjoin
-        let runner = egg::Runner::<_, _, ()>::new(ExprAnalysis {
-            catalog: self.catalog.clone(),
-            config: self.config.clone(),
-        })
-        .with_expr(&expr)
-        .run(&*rules::STAGE2_RULES);
+        // large join trees are ordered greedily, and not reordered by rules any more
+        let mut greedy_joins = vec![];
+        if let Some((greedy, joins)) =
+            join_order::greedy_join_order(&expr, self.analysis(), self.config.join_reorder_limit)
+        {
+            expr = greedy;
+            greedy_joins = joins;
+        }
+        let mut runner = self.runner(&expr, deadline);
+        if !greedy_joins.is_empty() {
+            // find the eclasses of the joins by adding the existing nodes again
+            let mut classes: Vec<Id> = Vec::with_capacity(expr.as_ref().len());
+            for node in expr.as_ref() {
+                let node = node.clone().map_children(|id| classes[usize::from(id)]);
+                classes.push(runner.egraph.add(node));
+            }
+            runner.egraph.analysis.greedy_joins = (greedy_joins.iter())
+                .map(|id| classes[usize::from(*id)])
+                .collect();
+        }
+        let runner = runner.run(rules::STAGE2_RULES.iter().chain(&extra_stage2_rules));
+        self.warn_node_limit(&runner);
         let cost_fn = cost::CostFn {
             egraph: &runner.egraph,
             catalog: &self.catalog ,
+            provider: &*self.cost_provider,
         };
         let extractor = egg::Extractor::new(&runner.egraph, cost_fn);
         (_, expr) = extractor.find_best(runner.roots[0]);
 
-        expr
+        if Instant::now() >= deadline {
+            tracing::warn!(
+                "optimizer exceeded the time limit of {:?}, returning the best plan found so far",
+                self.config.optimizer_time_limit
+            );
+        }
+
+        // 3. make the output deterministic
+        canonical::canonicalize(&expr)
     }
 
     /// Returns the cost for each node in the expression.
     pub fn costs(&self, expr: &RecExpr) -> Vec<f32> {
-        let mut egraph = EGraph::default();
+        let mut egraph = EGraph::new(self.analysis());
         // NOTE: we assume Expr node has the same Id in both EGraph and RecExpr.
         egraph.add_expr(expr);
         let mut cost_fn = cost::CostFn { 
             egraph: &egraph , 
             catalog: &self.catalog ,
+            provider: &*self.cost_provider,
         };
         let mut costs = vec![0.0; expr.as_ref().len()];
         for (i, node) in expr.as_ref().iter().enumerate() {
@@ -336,4 +599,27 @@ impl Optimizer {
         }
         costs
     }
+
+    /// Returns the physical properties for each node in the expression.
+    pub fn properties(&self, expr: &RecExpr) -> Vec<Properties> {
+        let mut egraph = EGraph::new(self.analysis());
+        // NOTE: we assume Expr node has the same Id in both EGraph and RecExpr.
+        egraph.add_expr(expr);
+        (0..expr.as_ref().len())
+            .map(|i| {
+                let data = &egraph[Id::from(i)].data;
+                Properties {
+                    orderby: data.orderby.clone(),
+                    rows: data.rows,
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the estimated number of rows produced by the plan.
+    pub fn estimate_rows(&self, expr: &RecExpr) -> f32 {
+        let mut egraph = EGraph::new(self.analysis());
+        let root = egraph.add_expr(expr);
+        egraph[root].data.rows
+    }
 }
@@ -12,7 +12,8 @@ pub fn analyze_aggs(enode: &Expr, x: impl Fn(&Id) -> AggSet) -> AggSet {
     use Expr::*;
     match enode {
         _ if enode.is_aggregate_function() => vec![enode.clone()],
-        Over(_) | Ref(_) => vec![],
+        // subqueries are isolated
+        Over(_) | Ref(_) | Exists(_) => vec![],
         // merge the set from all children
         _ => enode.children().iter().flat_map(x).collect(),
     }
@@ -26,7 +27,7 @@ pub fn analyze_overs(enode: &Expr, x: impl Fn(&Id) -> OverSet) -> OverSet {
     use Expr::*;
     match enode {
         Over(_) => vec![enode.clone()],
-        Ref(_) => vec![],
+        Ref(_) | Exists(_) => vec![],
         // merge the set from all children
         _ => enode.children().iter().flat_map(x).collect(),
     }
@@ -4,6 +4,7 @@
 
 use super::*;
 use crate::array::ArrayImpl;
+use crate::parser::UnaryOperator;
 use crate::types::DataValue;
 
 /// Returns all rules of expression simplification.
@@ -64,6 +65,8 @@ pub fn rules() -> Vec<Rewrite> { vec![
     rw!("not-ge";    "(not (>= ?a ?b))" => "(<  ?a ?b)"),
     rw!("not-lt";    "(not (<  ?a ?b))" => "(>= ?a ?b)"),
     rw!("not-le";    "(not (<= ?a ?b))" => "(>  ?a ?b)"),
+    rw!("not-distinct";     "(not (is_distinct ?a ?b))"     => "(is_not_distinct ?a ?b)"),
+    rw!("not-not-distinct"; "(not (is_not_distinct ?a ?b))" => "(is_distinct ?a ?b)"),
     rw!("not-and";   "(not (and ?a ?b))" => "(or  (not ?a) (not ?b))"),
     rw!("not-or";    "(not (or  ?a ?b))" => "(and (not ?a) (not ?b))"),
     rw!("not-not";   "(not (not ?a))"    => "?a"),
@@ -80,11 +83,22 @@ pub fn rules() -> Vec<Rewrite> { vec![
     rw!("or-comm";   "(or ?a ?b)"    => "(or ?b ?a)"),
     rw!("or-assoc";  "(or ?a (or ?b ?c))" => "(or (or ?a ?b) ?c)"),
 
+    rw!("isnull-not-null"; "(isnull ?a)" => "false" if is_not_null_column("?a")),
+    rw!("isnotnull-not-null"; "(isnotnull ?a)" => "true" if is_not_null_column("?a")),
+    rw!("not-isnull";    "(not (isnull ?a))"    => "(isnotnull ?a)"),
+    rw!("not-isnotnull"; "(not (isnotnull ?a))" => "(isnull ?a)"),
+
     rw!("if-false";  "(if false ?then ?else)" => "?else"),
     rw!("if-true";   "(if true ?then ?else)" => "?then"),
     rw!("if-not";    "(if (not ?cond) ?then ?else)" => "(if ?cond ?else ?then)"),
 
-    rw!("avg";       "(avg ?a)" => "(/ (sum ?a) (count ?a))"),
+    // a LIKE pattern with a literal prefix implies a range on the prefix
+    rw!("like-prefix"; "(like ?a ?pattern)" => { LikePrefix {
+        pattern: pattern("(and (and (>= ?a ?start) (< ?a ?end)) (like ?a ?pattern))"),
+    }}),
+
+    // small or non-constant IN lists are evaluated as a chain of `=` and `OR`
+    rw!("in-list-expand"; "(in ?a ?list)" => { InListExpand }),
 
     // Extract Common Predicate
     // example:
@@ -123,24 +137,81 @@ pub fn eval_constant(egraph: &EGraph, enode: &Expr) -> ConstValue {
         }
         let array_a = ArrayImpl::from(a);
         let array_b = ArrayImpl::from(b);
-        Some(array_a.binary_op(&op, &array_b).ok()?.get(0))
+        let result = match egraph.analysis.config.wrapping_arithmetic {
+            true => array_a.wrapping_binary_op(&op, &array_b),
+            false => array_a.binary_op(&op, &array_b),
+        };
+        Some(result.ok()?.get(0))
     } else if let Some((op, a)) = enode.unary_op() {
         let a = x(a)?;
         if a.is_null() {
             return Some(DataValue::Null);
         }
         let array_a = ArrayImpl::from(a);
-        Some(array_a.unary_op(&op).ok()?.get(0))
+        let result = match (op, egraph.analysis.config.wrapping_arithmetic) {
+            (UnaryOperator::Minus, true) => array_a.wrapping_neg(),
+            (op, _) => array_a.unary_op(&op),
+        };
+        Some(result.ok()?.get(0))
     } else if let &IsNull(a) = enode {
         Some(DataValue::Bool(x(a)?.is_null()))
+    } else if let &IsNotNull(a) = enode {
+        Some(DataValue::Bool(!x(a)?.is_null()))
+    } else if let &IsDistinct([a, b]) | &IsNotDistinct([a, b]) = enode {
+        let distinct = ArrayImpl::from(x(a)?).is_distinct(&ArrayImpl::from(x(b)?));
+        let result = match enode {
+            IsDistinct(_) => distinct,
+            _ => distinct.and_then(|d| d.not()),
+        };
+        Some(result.ok()?.get(0))
     } else if let &Cast([ty, a]) = enode {
         let a = x(a)?;
         if a.is_null() {
             return Some(DataValue::Null);
         }
         let ty = egraph[ty].nodes[0].as_type();
-        // TODO: handle cast error
-        a.cast(ty).ok()
+        match egraph.analysis.config.strict_casts {
+            // leave the error to the executor
+            true => a.cast(ty).ok(),
+            false => Some(a.cast(ty).unwrap_or(DataValue::Null)),
+        }
+    } else if let &TryCast([ty, a]) = enode {
+        let ty = egraph[ty].nodes[0].as_type();
+        Some(x(a)?.cast(ty).unwrap_or(DataValue::Null))
+    } else if let &Length(a) | &CharLength(a) | &Ascii(a) | &Chr(a) | &Reverse(a) | &Sign(a)
+    | &Ln(a) | &Exp(a) = enode
+    {
+        let a = x(a)?;
+        if a.is_null() {
+            return Some(DataValue::Null);
+        }
+        let array = ArrayImpl::from(a);
+        let result = match enode {
+            Length(_) => array.length(),
+            CharLength(_) => array.char_length(),
+            Ascii(_) => array.ascii(),
+            Chr(_) => array.chr(),
+            Reverse(_) => array.reverse(),
+            Sign(_) => array.sign(),
+            Ln(_) => array.ln(),
+            _ => array.exp(),
+        };
+        // leave domain errors to the executor
+        Some(result.ok()?.get(0))
+    } else if let &Log([base, a]) = enode {
+        let (base, a) = (x(base)?, x(a)?);
+        if base.is_null() || a.is_null() {
+            return Some(DataValue::Null);
+        }
+        let result = ArrayImpl::from(base).log(&ArrayImpl::from(a));
+        Some(result.ok()?.get(0))
+    } else if let &Repeat([a, n]) = enode {
+        let (a, n) = (x(a)?, x(n)?);
+        if a.is_null() || n.is_null() {
+            return Some(DataValue::Null);
+        }
+        let result = ArrayImpl::from(a).repeat(&ArrayImpl::from(n));
+        Some(result.ok()?.get(0))
     } else if let &Max(a) | &Min(a) | &Avg(a) | &First(a) | &Last(a) = enode {
         x(a).cloned()
     } else {
@@ -163,6 +234,128 @@ fn is_not_zero(var: &str) -> impl Fn(&mut EGraph, Id, &Subst) -> bool {
     value_is(var, |v| !v.is_zero())
 }
 
+/// Returns true if the expression is a column declared NOT NULL.
+///
+/// Outer joins may produce NULL for such a column, so it is never the case if the plan contains
+/// any of them.
+fn is_not_null_column(v: &str) -> impl Fn(&mut EGraph, Id, &Subst) -> bool {
+    let var = var(v);
+    move |egraph, _, subst| {
+        let catalog = &egraph.analysis.catalog;
+        let not_null = egraph[subst[var]].nodes.iter().any(|e| match e {
+            Expr::Column(c) => catalog.get_column(c).is_some_and(|c| !c.is_nullable()),
+            _ => false,
+        });
+        let has_outer_join = || {
+            egraph.classes().any(|class| {
+                (class.nodes.iter())
+                    .any(|e| matches!(e, Expr::LeftOuter | Expr::RightOuter | Expr::FullOuter))
+            })
+        };
+        not_null && !has_outer_join()
+    }
+}
+
+/// Generate a range condition from the literal prefix of a LIKE pattern.
+///
+/// The original LIKE is kept as a residual condition to match the rest of the pattern.
+struct LikePrefix {
+    pattern: Pattern,
+}
+
+impl Applier<Expr, ExprAnalysis> for LikePrefix {
+    fn apply_one(
+        &self,
+        egraph: &mut EGraph,
+        eclass: Id,
+        subst: &Subst,
+        searcher_ast: Option<&PatternAst<Expr>>,
+        rule_name: Symbol,
+    ) -> Vec<Id> {
+        let Some(DataValue::String(pattern)) = &egraph[subst[var("?pattern")]].data.constant
+        else {
+            return vec![];
+        };
+        let start: String = (pattern.chars())
+            .take_while(|c| !matches!(c, '%' | '_'))
+            .collect();
+        let Some(end) = prefix_upper_bound(&start) else {
+            return vec![];
+        };
+        let start = egraph.add(Expr::Constant(DataValue::String(start)));
+        let end = egraph.add(Expr::Constant(DataValue::String(end)));
+
+        let mut subst = subst.clone();
+        subst.insert(var("?start"), start);
+        subst.insert(var("?end"), end);
+        self.pattern
+            .apply_one(egraph, eclass, &subst, searcher_ast, rule_name)
+    }
+}
+
+/// The maximum length of an IN list to be expanded into a chain of `=` and `OR`.
+///
+/// Longer lists of constants are evaluated by probing a hash set instead.
+const IN_LIST_EXPAND_LIMIT: usize = 16;
+
+/// Expand `a IN (v1, v2, ..)` into `a = v1 OR a = v2 OR ..`.
+///
+/// Lists of constants are left as they are if they are longer than [`IN_LIST_EXPAND_LIMIT`],
+/// or contain NULL which can not be compared by `=`.
+struct InListExpand;
+
+impl Applier<Expr, ExprAnalysis> for InListExpand {
+    fn apply_one(
+        &self,
+        egraph: &mut EGraph,
+        eclass: Id,
+        subst: &Subst,
+        searcher_ast: Option<&PatternAst<Expr>>,
+        rule_name: Symbol,
+    ) -> Vec<Id> {
+        let a = subst[var("?a")];
+        let list = egraph[subst[var("?list")]].as_list().to_vec();
+        let constant = |id: &Id| egraph[*id].data.constant.as_ref();
+        let all_constant = list.iter().all(|id| constant(id).is_some());
+        let has_null = (list.iter()).any(|id| constant(id) == Some(&DataValue::Null));
+        if all_constant && (list.len() > IN_LIST_EXPAND_LIMIT || has_null) {
+            return vec![];
+        }
+        let mut cond = None;
+        for value in list {
+            let eq = egraph.add(Expr::Eq([a, value]));
+            cond = Some(match cond {
+                Some(cond) => egraph.add(Expr::Or([cond, eq])),
+                None => eq,
+            });
+        }
+        let Some(cond) = cond else {
+            return vec![];
+        };
+        let mut subst = subst.clone();
+        subst.insert(var("?cond"), cond);
+        pattern("?cond").apply_one(egraph, eclass, &subst, searcher_ast, rule_name)
+    }
+}
+
+/// Returns the smallest string greater than all strings starting with `prefix`.
+///
+/// Returns `None` if there is no such string, e.g. `prefix` is empty.
+fn prefix_upper_bound(prefix: &str) -> Option<String> {
+    let mut chars: Vec<char> = prefix.chars().collect();
+    while let Some(c) = chars.pop() {
+        let next = match c {
+            '\u{D7FF}' => Some('\u{E000}'),
+            c => char::from_u32(c as u32 + 1),
+        };
+        if let Some(next) = next {
+            chars.push(next);
+            return Some(chars.into_iter().collect());
+        }
+    }
+    None
+}
+
 fn value_is(v: &str, f: impl Fn(&DataValue) -> bool) -> impl Fn(&mut EGraph, Id, &Subst) -> bool {
     let v = var(v);
     move |egraph, _, subst| {
@@ -176,7 +369,11 @@ fn value_is(v: &str, f: impl Fn(&DataValue) -> bool) -> impl Fn(&mut EGraph, Id,
 
 #[cfg(test)]
 mod tests {
+    use itertools::Itertools;
+
     use super::rules;
+    use crate::planner::{EGraph, Expr};
+    use crate::types::DataValue;
 
     egg::test_fn! {
         and_eq_const,
@@ -197,9 +394,148 @@ mod tests {
         "(> (+ 100 a) 300)" => "(> a 200)",
     }
 
+    egg::test_fn! {
+        not_eq,
+        rules(),
+        "(not (= a b))" => "(<> a b)",
+    }
+
+    egg::test_fn! {
+        not_ne,
+        rules(),
+        "(not (<> a b))" => "(= a b)",
+    }
+
+    egg::test_fn! {
+        not_gt,
+        rules(),
+        "(not (> a b))" => "(<= a b)",
+    }
+
+    egg::test_fn! {
+        not_ge,
+        rules(),
+        "(not (>= a b))" => "(< a b)",
+    }
+
+    egg::test_fn! {
+        not_lt,
+        rules(),
+        "(not (< a b))" => "(>= a b)",
+    }
+
+    egg::test_fn! {
+        not_le,
+        rules(),
+        "(not (<= a b))" => "(> a b)",
+    }
+
+    egg::test_fn! {
+        not_distinct,
+        rules(),
+        "(not (is_distinct a b))" => "(is_not_distinct a b)",
+    }
+
+    egg::test_fn! {
+        not_not_distinct,
+        rules(),
+        "(not (is_not_distinct a b))" => "(is_distinct a b)",
+    }
+
+    egg::test_fn! {
+        not_isnull,
+        rules(),
+        "(not (isnull a))" => "(isnotnull a)",
+    }
+
+    egg::test_fn! {
+        not_isnotnull,
+        rules(),
+        "(not (isnotnull a))" => "(isnull a)",
+    }
+
+    egg::test_fn! {
+        isnotnull_constant,
+        rules(),
+        "(and (isnotnull 1) (isnotnull null))" => "false",
+    }
+
+    egg::test_fn! {
+        not_and_or,
+        rules(),
+        "(not (and (> a 1) (or (= b 2) (< c 3))))" => "(or (<= a 1) (and (<> b 2) (>= c 3)))",
+    }
+
+    egg::test_fn! {
+        like_prefix_range,
+        rules(),
+        "(like a 'abc%')" => "(and (and (>= a 'abc') (< a 'abd')) (like a 'abc%'))",
+    }
+
+    egg::test_fn! {
+        in_list_expand,
+        rules(),
+        "(in a (list 1 2 b))" => "(or (or (= a 1) (= a 2)) (= a b))",
+    }
+
     egg::test_fn! {
         constant_type_cast,
         rules(),
         "(cast BOOLEAN 1)" => "true",
     }
+
+    egg::test_fn! {
+        constant_type_try_cast,
+        rules(),
+        "(try_cast INT 'abc')" => "null",
+    }
+
+    #[test]
+    fn like_without_prefix() {
+        let runner = egg::Runner::<_, _, ()>::default()
+            .with_expr(&"(like a '%abc')".parse().unwrap())
+            .run(&rules());
+        let has_range =
+            (runner.egraph.classes()).any(|class| class.iter().any(|e| matches!(e, Expr::GtEq(_))));
+        assert!(!has_range);
+    }
+
+    #[test]
+    fn in_list_not_expanded() {
+        let values = (0..=super::IN_LIST_EXPAND_LIMIT).join(" ");
+        for expr in [
+            format!("(in a (list {values}))"),
+            "(in a (list 1 null))".into(),
+        ] {
+            let runner = egg::Runner::<_, _, ()>::default()
+                .with_expr(&expr.parse().unwrap())
+                .run(&rules());
+            let has_or = (runner.egraph.classes())
+                .any(|class| class.iter().any(|e| matches!(e, Expr::Or(_))));
+            assert!(!has_or, "{expr}");
+        }
+    }
+
+    #[test]
+    fn prefix_upper_bound() {
+        assert_eq!(super::prefix_upper_bound("abc").as_deref(), Some("abd"));
+        assert_eq!(
+            super::prefix_upper_bound("a\u{10FFFF}").as_deref(),
+            Some("b")
+        );
+        assert_eq!(super::prefix_upper_bound(""), None);
+    }
+
+    #[test]
+    fn constant_math_function() {
+        let mut egraph = EGraph::default();
+        let exp = egraph.add_expr(&"(exp 0)".parse().unwrap());
+        assert_eq!(
+            egraph[exp].data.constant,
+            Some(DataValue::Float64(1.0.into()))
+        );
+        // domain errors are left to the executor
+        let ln = egraph.add_expr(&"(ln -1)".parse().unwrap());
+        assert_eq!(egraph[ln].data.constant, None);
+    }
 }
@@ -34,7 +34,7 @@ use std::sync::LazyLock;
 
 use egg::{rewrite as rw, *};
 
-use super::{Config, EGraph, Expr, ExprExt, Pattern, Rewrite};
+use super::{Config, EGraph, Expr, ExprExt, Pattern, Rewrite, Statistic};
 use crate::catalog::RootCatalogRef;
 use crate::types::F32;
 
@@ -48,7 +48,8 @@ mod schema;
 mod type_;
 mod self_rules;
 
-pub use range::filter_scan_rule;
+pub use plan::{bushy_join_rules, eager_agg_rules};
+pub use range::{filter_scan_rule, scan_ranges};
 
 pub use self::type_::TypeError;
 
@@ -56,6 +57,7 @@ pub use self::type_::TypeError;
 pub static STAGE1_RULES: LazyLock<Vec<Rewrite>> = LazyLock::new(|| {
     let mut rules = vec![];
     rules.append(&mut expr::rules());
+    rules.append(&mut range::range_rules());
     rules.append(&mut plan::always_better_rules());
     rules.append(&mut order::order_rules());
     // mod
@@ -67,8 +69,10 @@ pub static STAGE1_RULES: LazyLock<Vec<Rewrite>> = LazyLock::new(|| {
 pub static STAGE2_RULES: LazyLock<Vec<Rewrite>> = LazyLock::new(|| {
     let mut rules = vec![];
     rules.append(&mut expr::rules());
+    rules.append(&mut range::range_rules());
     rules.append(&mut plan::join_rules());
     rules.append(&mut order::order_rules());
+    rules.append(&mut self_rules::and_eq_rules());
     rules
 });
 
@@ -76,7 +80,10 @@ pub static STAGE2_RULES: LazyLock<Vec<Rewrite>> = LazyLock::new(|| {
 #[derive(Default)]
 pub struct ExprAnalysis {
     pub catalog: RootCatalogRef,
+    pub stat: Statistic,
     pub config: Config,
+    /// The eclasses of joins ordered greedily, which are not reordered by rules.
+    pub greedy_joins: Vec<Id>,
 }
 
 /// The analysis data associated with each eclass.
@@ -113,7 +120,11 @@ impl Analysis<Expr> for ExprAnalysis {
             constant: expr::eval_constant(egraph, enode),
             range: range::analyze_range(egraph, enode),
             columns: plan::analyze_columns(egraph, enode),
-            schema: schema::analyze_schema(enode, |id| egraph[*id].data.schema.clone()),
+            schema: schema::analyze_schema(
+                enode,
+                |id| egraph[*id].data.schema.clone(),
+                |id| &egraph[*id].nodes[0],
+            ),
             rows: rows::analyze_rows(egraph, enode),
             orderby: order::analyze_order(egraph, enode),
         }
@@ -180,9 +191,14 @@ impl Analysis<Expr> for TypeSchemaAnalysis {
             type_: type_::analyze_type(
                 enode,
                 |i| egraph[*i].data.type_.clone(),
+                |i| &egraph[*i].nodes[0],
                 &egraph.analysis.catalog,
             ),
-            schema: schema::analyze_schema(enode, |i| egraph[*i].data.schema.clone()),
+            schema: schema::analyze_schema(
+                enode,
+                |i| egraph[*i].data.schema.clone(),
+                |i| &egraph[*i].nodes[0],
+            ),
             aggs: agg::analyze_aggs(enode, |i| egraph[*i].data.aggs.clone()),
             overs: agg::analyze_overs(enode, |i| egraph[*i].data.overs.clone()),
         }
@@ -17,23 +17,25 @@ pub fn analyze_order(egraph: &EGraph, enode: &Expr) -> OrderKey {
     match enode {
         List(keys) => keys.clone(),
         // scanned table is ordered by primary key in secondary storage
-        Scan([_, cols, _]) if egraph.analysis.config.table_is_sorted_by_primary_key => {
-            let primary_key = egraph[*cols].as_list().iter().find(|id| {
-                let catalog = &egraph.analysis.catalog;
-                match catalog.get_column(&egraph[**id].as_column()) {
-                    Some(col) => col.is_primary(),
-                    None => false,
-                }
-            });
-            match primary_key {
-                Some(id) => Box::new([*id]),
-                None => Box::new([]),
-            }
+        Scan([table, cols, _]) if egraph.analysis.config.table_is_sorted_by_primary_key => {
+            let catalog = &egraph.analysis.catalog;
+            let Some(table) = catalog.get_table(&egraph[*table].nodes[0].as_table()) else {
+                return Box::new([]);
+            };
+            // the longest prefix of primary key columns in the scan
+            let columns = egraph[*cols].as_list();
+            (table.primary_keys().iter())
+                .map_while(|pk| {
+                    (columns.iter()).find(|id| egraph[**id].as_column().column_id == *pk)
+                })
+                .copied()
+                .collect()
         }
         // plans that sort rows
-        Order([keys, _]) | TopN([_, _, keys, _]) => x(keys).clone(),
+        Order([keys, _]) | TopN([_, _, keys, _, _]) => x(keys).clone(),
         // plans that preserve order
         Proj([_, c]) | Filter([_, c]) | Window([_, c]) | Limit([_, _, c]) => x(c).clone(),
+        MergeJoin([t, _, _, l, _]) if egraph[*t].nodes[0].is_semi_or_anti() => x(l).clone(),
         MergeJoin([_, _, _, _, r]) => x(r).clone(),
         SortAgg([_, _, c]) => x(c).clone(),
         // unordered for other plans
@@ -70,3 +72,25 @@ fn is_orderby(keys: &str, plan: &str) -> impl Fn(&mut EGraph, Id, &Subst) -> boo
         plan_keys.starts_with(keys)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    egg::test_fn! {
+        sort_agg,
+        order_rules(),
+        // SELECT a, sum(b) FROM (SELECT a, b FROM t ORDER BY a) GROUP BY a
+        "
+        (hashagg (list (sum $1.2)) (list $1.1)
+            (order (list $1.1)
+                (scan $1 (list $1.1 $1.2) null)
+            )
+        )" => "
+        (sortagg (list (sum $1.2)) (list $1.1)
+            (order (list $1.1)
+                (scan $1 (list $1.1 $1.2) null)
+            )
+        )"
+    }
+}
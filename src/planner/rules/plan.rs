@@ -13,36 +13,46 @@ pub fn always_better_rules() -> Vec<Rewrite> {
     rules.extend(merge_rules());
     rules.extend(predicate_pushdown_rules());
     rules.extend(projection_pushdown_rules());
+    rules.extend(subquery_rules());
     rules
 }
 
 #[rustfmt::skip]
 fn cancel_rules() -> Vec<Rewrite> { vec![
     rw!("limit-null";       "(limit null 0 ?child)"     => "?child"),
-    rw!("limit-0";          "(limit 0 ?offset ?child)"  => "(empty ?child)"),
     rw!("order-null";       "(order (list) ?child)"     => "?child"),
     rw!("filter-true";      "(filter true ?child)"      => "?child"),
     rw!("filter-false";     "(filter false ?child)"     => "(empty ?child)"),
     rw!("window-null";      "(window (list) ?child)"    => "?child"),
-    rw!("inner-join-false"; "(join inner false ?l ?r)"  => "(empty ?l ?r)"),
-
-    rw!("proj-on-empty";    "(proj ?exprs (empty ?c))"                  => "(empty ?c)"),
-    rw!("window-on-empty";  "(window ?exprs (empty ?c))"                => "(empty ?c)"),
-    rw!("hashagg-on-empty"; "(hashagg ?aggs ?groupby (empty ?c))"       => "(empty ?c)"),
-    rw!("sortagg-on-empty"; "(sortagg ?aggs ?groupby (empty ?c))"       => "(empty ?c)"),
-    rw!("filter-on-empty";  "(filter ?cond (empty ?c))"                 => "(empty ?c)"),
-    rw!("order-on-empty";   "(order ?keys (empty ?c))"                  => "(empty ?c)"),
-    rw!("limit-on-empty";   "(limit ?limit ?offset (empty ?c))"         => "(empty ?c)"),
-    rw!("topn-on-empty";    "(topn ?limit ?offset ?keys (empty ?c))"    => "(empty ?c)"),
-    rw!("inner-join-on-left-empty";  "(join inner ?on (empty ?l) ?r)"   => "(empty ?l ?r)"),
-    rw!("inner-join-on-right-empty"; "(join inner ?on ?l (empty ?r))"   => "(empty ?l ?r)"),
+    rw!("inner-join-false"; "(join inner false ?l ?r)"  => "(empty (join inner true ?l ?r))"),
+
+    // keep the schema of the operator above an empty relation
+    rw!("proj-on-empty";    "(proj ?exprs (empty ?c))"                     => "(empty (proj ?exprs ?c))"),
+    rw!("window-on-empty";  "(window ?exprs (empty ?c))"                   => "(empty (window ?exprs ?c))"),
+    rw!("hashagg-on-empty"; "(hashagg ?aggs ?groupby (empty ?c))"          => "(empty (hashagg ?aggs ?groupby ?c))"),
+    rw!("sortagg-on-empty"; "(sortagg ?aggs ?groupby (empty ?c))"          => "(empty (sortagg ?aggs ?groupby ?c))"),
+    rw!("filter-on-empty";  "(filter ?cond (empty ?c))"                    => "(empty ?c)"),
+    rw!("order-on-empty";   "(order ?keys (empty ?c))"                     => "(empty ?c)"),
+    rw!("limit-on-empty";   "(limit ?limit ?offset (empty ?c))"            => "(empty ?c)"),
+    rw!("topn-on-empty";    "(topn ?limit ?offset ?keys ?ties (empty ?c))" => "(empty ?c)"),
+    rw!("inner-join-on-left-empty";  "(join inner ?on (empty ?l) ?r)"   => "(empty (join inner ?on ?l ?r))"),
+    rw!("inner-join-on-right-empty"; "(join inner ?on ?l (empty ?r))"   => "(empty (join inner ?on ?l ?r))"),
+
+    rw!("limit-0";
+        "(limit ?limit ?offset ?child)" => "(empty ?child)"
+        if is_zero_limit("?limit")
+    ),
+    rw!("topn-0";
+        "(topn ?limit ?offset ?keys ?ties ?child)" => "(empty ?child)"
+        if is_zero_limit("?limit")
+    ),
 ]}
 
 #[rustfmt::skip]
 fn merge_rules() -> Vec<Rewrite> { vec![
     rw!("limit-order-topn";
         "(limit ?limit ?offset (order ?keys ?child))" =>
-        "(topn ?limit ?offset ?keys ?child)"
+        "(topn ?limit ?offset ?keys false ?child)"
     ),
     rw!("filter-merge";
         "(filter ?cond1 (filter ?cond2 ?child))" =>
@@ -54,7 +64,7 @@ fn merge_rules() -> Vec<Rewrite> { vec![
 fn predicate_pushdown_rules() -> Vec<Rewrite> { vec![
     pushdown("filter", "?cond", "order", "?keys"),
     pushdown("filter", "?cond", "limit", "?limit ?offset"),
-    pushdown("filter", "?cond", "topn", "?limit ?offset ?keys"),
+    pushdown("filter", "?cond", "topn", "?limit ?offset ?keys ?ties"),
     rw!("pushdown-filter-join";
         "(filter ?cond (join inner ?on ?left ?right))" =>
         "(join inner (and ?on ?cond) ?left ?right)"
@@ -81,6 +91,50 @@ fn predicate_pushdown_rules() -> Vec<Rewrite> { vec![
     ),
 ]}
 
+/// Rewrite EXISTS subqueries in filters into semi/anti joins,
+/// and pull correlated predicates up to the join condition.
+///
+/// Only equality correlations are supported.
+/// The binder is responsible for rejecting other shapes.
+#[rustfmt::skip]
+fn subquery_rules() -> Vec<Rewrite> { vec![
+    rw!("filter-exists";
+        "(filter (exists ?subquery) ?child)" =>
+        "(join semi true ?child ?subquery)"
+    ),
+    rw!("filter-not-exists";
+        "(filter (not (exists ?subquery)) ?child)" =>
+        "(join anti true ?child ?subquery)"
+    ),
+    rw!("filter-and-exists";
+        "(filter (and ?cond (exists ?subquery)) ?child)" =>
+        "(filter ?cond (join semi true ?child ?subquery))"
+    ),
+    rw!("filter-and-not-exists";
+        "(filter (and ?cond (not (exists ?subquery))) ?child)" =>
+        "(filter ?cond (join anti true ?child ?subquery))"
+    ),
+    rw!("semi-join-pull-filter";
+        "(join semi ?on ?left (filter ?cond ?right))" =>
+        "(join semi (and ?on ?cond) ?left ?right)"
+    ),
+    rw!("anti-join-pull-filter";
+        "(join anti ?on ?left (filter ?cond ?right))" =>
+        "(join anti (and ?on ?cond) ?left ?right)"
+    ),
+    // the output of right side is discarded by semi and anti join
+    rw!("semi-join-remove-proj";
+        "(join semi ?on ?left (proj ?exprs ?right))" =>
+        "(join semi ?on ?left ?right)"
+        if columns_is_subset_of_join("?on", "?left", "?right")
+    ),
+    rw!("anti-join-remove-proj";
+        "(join anti ?on ?left (proj ?exprs ?right))" =>
+        "(join anti ?on ?left ?right)"
+        if columns_is_subset_of_join("?on", "?left", "?right")
+    ),
+]}
+
 /// Returns a rule to pushdown plan `a` through `b`.
 fn pushdown(a: &str, a_args: &str, b: &str, b_args: &str) -> Rewrite {
     let name = format!("pushdown-{a}-{b}");
@@ -94,10 +148,12 @@ pub fn join_rules() -> Vec<Rewrite> { vec![
     // we only have right rotation rule,
     // because the initial state is always a left-deep tree
     // thus left rotation is not needed.
+    // only inner joins are associative.
     rw!("join-reorder";
-        "(join ?type ?cond2 (join ?type ?cond1 ?left ?mid) ?right)" =>
-        "(join ?type ?cond1 ?left (join ?type ?cond2 ?mid ?right))"
+        "(join inner ?cond2 (join inner ?cond1 ?left ?mid) ?right)" =>
+        "(join inner ?cond1 ?left (join inner ?cond2 ?mid ?right))"
         if columns_is_disjoint("?cond2", "?left")
+        if not_greedy("?cond1", "?left", "?mid")
     ),
     rw!("hash-join-on-one-eq";
         "(join ?type (= ?el ?er) ?left ?right)" =>
@@ -116,6 +172,138 @@ pub fn join_rules() -> Vec<Rewrite> { vec![
     // TODO: support more than two equals
 ]}
 
+/// Rotate joins through the projections between them to explore bushy join trees.
+///
+/// The projections only prune columns, so they are replaced by one on top of the rotated joins to
+/// keep the schema. It enlarges the search space a lot, so the optimizer only explores it if enabled.
+#[rustfmt::skip]
+pub fn bushy_join_rules() -> Vec<Rewrite> { vec![
+    rw!("join-reorder-through-proj";
+        "(join inner ?cond2 (proj ?exprs (join inner ?cond1 ?left ?mid)) ?right)" =>
+        { KeepSchema {
+            pattern: pattern("(proj ?schema (join inner ?cond1 ?left (join inner ?cond2 ?mid ?right)))"),
+        }}
+        if is_column_list("?exprs")
+        if columns_is_disjoint("?cond2", "?left")
+        if not_greedy("?cond1", "?left", "?mid")
+    ),
+]}
+
+/// Push a partial aggregation below an inner join if the aggregations only reference one side,
+/// leaving a final aggregation above the join to merge the partial results.
+///
+/// The partial aggregation groups by the join keys and the group keys from that side.
+/// Its aggregations are wrapped in `partial` so that the final aggregation knows to merge them.
+#[rustfmt::skip]
+pub fn eager_agg_rules() -> Vec<Rewrite> { vec![
+    rw!("eager-agg-left";
+        "(hashagg ?aggs ?keys (hashjoin inner ?lkeys ?rkeys ?left ?right))" =>
+        { EagerAgg::left() }
+        if is_mergeable("?aggs")
+        if columns_is_subset("?aggs", "?left")
+    ),
+    rw!("eager-agg-left-proj";
+        "(hashagg ?aggs ?keys (proj ?exprs (hashjoin inner ?lkeys ?rkeys ?left ?right)))" =>
+        { EagerAgg::left() }
+        if is_mergeable("?aggs")
+        if columns_is_subset("?aggs", "?left")
+    ),
+    rw!("eager-agg-right";
+        "(hashagg ?aggs ?keys (hashjoin inner ?lkeys ?rkeys ?left ?right))" =>
+        { EagerAgg::right() }
+        if is_mergeable("?aggs")
+        if columns_is_subset("?aggs", "?right")
+    ),
+    rw!("eager-agg-right-proj";
+        "(hashagg ?aggs ?keys (proj ?exprs (hashjoin inner ?lkeys ?rkeys ?left ?right)))" =>
+        { EagerAgg::right() }
+        if is_mergeable("?aggs")
+        if columns_is_subset("?aggs", "?right")
+    ),
+]}
+
+/// Returns true if all aggregations can be merged from partial results.
+fn is_mergeable(aggs: &str) -> impl Fn(&mut EGraph, Id, &Subst) -> bool {
+    let aggs = var(aggs);
+    move |egraph, _, subst| {
+        use Expr::*;
+        egraph[subst[aggs]].as_list().iter().all(|id| {
+            (egraph[*id].nodes.iter())
+                .any(|e| matches!(e, RowCount | Count(_) | Sum(_) | Min(_) | Max(_)))
+        })
+    }
+}
+
+/// Generate a partial aggregation below one side of the join.
+struct EagerAgg {
+    pattern: Pattern,
+    /// The join keys of the aggregated side.
+    join_keys: Var,
+    /// The aggregated side.
+    child: Var,
+}
+
+impl EagerAgg {
+    fn left() -> Self {
+        EagerAgg {
+            pattern: pattern(
+                "(hashagg ?aggs ?keys (hashjoin inner ?lkeys ?rkeys (hashagg ?paggs ?pkeys ?left) ?right))",
+            ),
+            join_keys: var("?lkeys"),
+            child: var("?left"),
+        }
+    }
+
+    fn right() -> Self {
+        EagerAgg {
+            pattern: pattern(
+                "(hashagg ?aggs ?keys (hashjoin inner ?lkeys ?rkeys ?left (hashagg ?paggs ?pkeys ?right)))",
+            ),
+            join_keys: var("?rkeys"),
+            child: var("?right"),
+        }
+    }
+}
+
+impl Applier<Expr, ExprAnalysis> for EagerAgg {
+    fn apply_one(
+        &self,
+        egraph: &mut EGraph,
+        eclass: Id,
+        subst: &Subst,
+        searcher_ast: Option<&PatternAst<Expr>>,
+        rule_name: Symbol,
+    ) -> Vec<Id> {
+        let child_columns = &egraph[subst[self.child]].data.columns;
+        let mut partial_keys = egraph[subst[self.join_keys]].as_list().to_vec();
+        // group keys from the aggregated side must be kept by the partial aggregation
+        for &key in egraph[subst[var("?keys")]].as_list() {
+            let key_columns = &egraph[key].data.columns;
+            if key_columns.is_subset(child_columns) {
+                if !partial_keys.contains(&key) {
+                    partial_keys.push(key);
+                }
+            } else if !key_columns.is_disjoint(child_columns) {
+                // the key depends on both sides of the join
+                return vec![];
+            }
+        }
+        let keys_id = egraph.add(Expr::List(partial_keys.into()));
+
+        let aggs = egraph[subst[var("?aggs")]].as_list().to_vec();
+        let partial_aggs = (aggs.into_iter())
+            .map(|agg| egraph.add(Expr::Partial(agg)))
+            .collect();
+        let aggs_id = egraph.add(Expr::List(partial_aggs));
+
+        let mut subst = subst.clone();
+        subst.insert(var("?pkeys"), keys_id);
+        subst.insert(var("?paggs"), aggs_id);
+        self.pattern
+            .apply_one(egraph, eclass, &subst, searcher_ast, rule_name)
+    }
+}
+
 /// Pushdown projections and prune unused columns.
 #[rustfmt::skip]
 pub fn projection_pushdown_rules() -> Vec<Rewrite> { vec![
@@ -134,9 +322,9 @@ pub fn projection_pushdown_rules() -> Vec<Rewrite> { vec![
         }}
     ),
     rw!("pushdown-proj-topn";
-        "(proj ?exprs (topn ?limit ?offset ?keys ?child))" =>
+        "(proj ?exprs (topn ?limit ?offset ?keys ?ties ?child))" =>
         { ProjectionPushdown {
-            pattern: pattern("(proj ?exprs (topn ?limit ?offset ?keys ?child))"),
+            pattern: pattern("(proj ?exprs (topn ?limit ?offset ?keys ?ties ?child))"),
             used: vec![var("?exprs"), var("?keys")],
             children: vec![var("?child")],
         }}
@@ -184,6 +372,44 @@ pub fn projection_pushdown_rules() -> Vec<Rewrite> { vec![
     ),
 ]}
 
+/// Returns true if the limit is a constant zero of any integer type.
+fn is_zero_limit(limit: &str) -> impl Fn(&mut EGraph, Id, &Subst) -> bool {
+    let limit = var(limit);
+    move |egraph, _, subst| {
+        let constant = &egraph[subst[limit]].data.constant;
+        matches!(constant.as_ref().map(|v| v.as_usize()), Some(Ok(Some(0))))
+    }
+}
+
+/// Returns true if all expressions in the list are columns.
+fn is_column_list(list: &str) -> impl Fn(&mut EGraph, Id, &Subst) -> bool {
+    let list = var(list);
+    move |egraph, _, subst| {
+        (egraph[subst[list]].as_list().iter())
+            .all(|id| egraph[*id].iter().any(|e| matches!(e, Expr::Column(_))))
+    }
+}
+
+/// Returns true if neither the matched join nor its child join `(join inner ?cond ?left ?right)`
+/// is ordered greedily. See [`ExprAnalysis::greedy_joins`].
+fn not_greedy(cond: &str, left: &str, right: &str) -> impl Fn(&mut EGraph, Id, &Subst) -> bool {
+    let cond = var(cond);
+    let left = var(left);
+    let right = var(right);
+    move |egraph, id, subst| {
+        let egraph = &*egraph;
+        let greedy = &egraph.analysis.greedy_joins;
+        if greedy.is_empty() {
+            return true;
+        }
+        let is_greedy = |id: Id| greedy.iter().any(|g| egraph.find(*g) == egraph.find(id));
+        let child = egraph.lookup(Expr::Inner).and_then(|inner| {
+            egraph.lookup(Expr::Join([inner, subst[cond], subst[left], subst[right]]))
+        });
+        !is_greedy(id) && !child.is_some_and(is_greedy)
+    }
+}
+
 /// Returns true if the columns in `var1` are a subset of the columns in `var2`.
 fn columns_is_subset(var1: &str, var2: &str) -> impl Fn(&mut EGraph, Id, &Subst) -> bool {
     columns_is(var1, var2, HashSet::is_subset)
@@ -194,6 +420,25 @@ fn columns_is_disjoint(var1: &str, var2: &str) -> impl Fn(&mut EGraph, Id, &Subs
     columns_is(var1, var2, HashSet::is_disjoint)
 }
 
+/// Returns true if the columns in `expr` are a subset of the columns in `left` or `right`.
+fn columns_is_subset_of_join(
+    expr: &str,
+    left: &str,
+    right: &str,
+) -> impl Fn(&mut EGraph, Id, &Subst) -> bool {
+    let expr = var(expr);
+    let left = var(left);
+    let right = var(right);
+    move |egraph, _, subst| {
+        let columns = &egraph[subst[expr]].data.columns;
+        let left = &egraph[subst[left]].data.columns;
+        let right = &egraph[subst[right]].data.columns;
+        columns
+            .iter()
+            .all(|c| left.contains(c) || right.contains(c))
+    }
+}
+
 fn columns_is(
     var1: &str,
     var2: &str,
@@ -234,14 +479,16 @@ pub fn analyze_columns(egraph: &EGraph, enode: &Expr) -> ColumnSet {
         egraph[*i].as_list().iter().map(|id| {
             egraph[*id]
                 .iter()
-                .find(|e| matches!(e, Column(_) | Ref(_)))
+                .find(|e| matches!(e, Column(_) | Ref(_) | Over(_) | ValuesColumn(_)))
                 .cloned()
                 .unwrap_or(Expr::Ref(*id))
         })
     };
     match enode {
         // column unit
-        Column(_) | Ref(_) => [enode.clone()].into_iter().collect(),
+        // window functions are computed by the window plan and referenced as columns above it
+        // so are the columns of values
+        Column(_) | Ref(_) | Over(_) | ValuesColumn(_) => [enode.clone()].into_iter().collect(),
 
         Proj([exprs, _]) | Agg([exprs, _]) => produced(exprs).collect(),
         HashAgg([exprs, group_keys, _]) | SortAgg([exprs, group_keys, _]) => {
@@ -337,6 +584,32 @@ impl Applier<Expr, ExprAnalysis> for ColumnPrune {
     }
 }
 
+/// Apply the pattern with `?schema` bound to the schema of the matched plan.
+///
+/// It keeps the schema of plans rewritten into ones producing more columns.
+struct KeepSchema {
+    pattern: Pattern,
+}
+
+impl Applier<Expr, ExprAnalysis> for KeepSchema {
+    fn apply_one(
+        &self,
+        egraph: &mut EGraph,
+        eclass: Id,
+        subst: &Subst,
+        searcher_ast: Option<&PatternAst<Expr>>,
+        rule_name: Symbol,
+    ) -> Vec<Id> {
+        let schema = egraph[eclass].data.schema.clone();
+        let id = egraph.add(Expr::List(schema.into()));
+
+        let mut subst = subst.clone();
+        subst.insert(var("?schema"), id);
+        self.pattern
+            .apply_one(egraph, eclass, &subst, searcher_ast, rule_name)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -348,6 +621,87 @@ mod tests {
         rules
     }
 
+    egg::test_fn! {
+        filter_folded_true,
+        rules(),
+        // SELECT * FROM t WHERE 1 = 1
+        "(filter (= 1 1) (scan $1 (list $1.1 $1.2) null))" =>
+        "(scan $1 (list $1.1 $1.2) null)"
+    }
+
+    egg::test_fn! {
+        filter_folded_false,
+        rules(),
+        // SELECT * FROM t WHERE 1 = 0
+        "(filter (= 1 0) (scan $1 (list $1.1 $1.2) null))" =>
+        "(empty (scan $1 (list $1.1 $1.2) null))"
+    }
+
+    egg::test_fn! {
+        filter_partially_folded,
+        rules(),
+        // SELECT * FROM t WHERE 1 = 1 AND a > 1
+        "(filter (and (= 1 1) (> $1.1 1)) (scan $1 (list $1.1 $1.2) null))" =>
+        "(filter (> $1.1 1) (scan $1 (list $1.1 $1.2) null))"
+    }
+
+    egg::test_fn! {
+        limit_0,
+        rules(),
+        // SELECT a FROM t LIMIT 0
+        "(limit 0 0 (proj (list $1.1) (scan $1 (list $1.1 $1.2) null)))" =>
+        "(empty (proj (list $1.1) (scan $1 (list $1.1 $1.2) null)))"
+    }
+
+    egg::test_fn! {
+        topn_0,
+        rules(),
+        // SELECT a FROM t ORDER BY a FETCH FIRST 0 ROWS WITH TIES
+        "(proj (list $1.1) (topn 0 0 (list $1.1) true (scan $1 (list $1.1 $1.2) null)))" =>
+        "(empty (proj (list $1.1) (scan $1 (list $1.1 $1.2) null)))"
+    }
+
+    egg::test_fn! {
+        proj_on_empty,
+        rules(),
+        // SELECT a + 1 FROM t WHERE false
+        "(proj (list (+ $1.1 1)) (filter false (scan $1 (list $1.1 $1.2) null)))" =>
+        "(empty (proj (list (+ $1.1 1)) (scan $1 (list $1.1 $1.2) null)))"
+    }
+
+    egg::test_fn! {
+        join_on_empty,
+        rules(),
+        // SELECT * FROM t JOIN u ON a = c WHERE false
+        "
+        (filter false
+        (join inner (= $1.1 $2.1)
+            (scan $1 (list $1.1 $1.2) null)
+            (scan $2 (list $2.1 $2.2) null)
+        ))" => "
+        (empty
+        (join inner (= $1.1 $2.1)
+            (scan $1 (list $1.1 $1.2) null)
+            (scan $2 (list $2.1 $2.2) null)
+        ))"
+    }
+
+    egg::test_fn! {
+        join_with_empty_side,
+        rules(),
+        // SELECT * FROM t JOIN (SELECT * FROM u WHERE false) ON a = c
+        "
+        (join inner (= $1.1 $2.1)
+            (scan $1 (list $1.1 $1.2) null)
+            (filter false (scan $2 (list $2.1 $2.2) null))
+        )" => "
+        (empty
+        (join inner (= $1.1 $2.1)
+            (scan $1 (list $1.1 $1.2) null)
+            (scan $2 (list $2.1 $2.2) null)
+        ))"
+    }
+
     egg::test_fn! {
         predicate_pushdown,
         rules(),
@@ -393,6 +747,115 @@ mod tests {
         )"
     }
 
+    egg::test_fn! {
+        join_reorder_through_proj,
+        bushy_join_rules(),
+        // SELECT t1.v FROM t1, t2, t3
+        // WHERE t1.id = t2.id AND t3.id = t2.v
+        "
+        (join inner (= $3.1 $2.2)
+            (proj (list $1.2 $2.2)
+            (join inner (= $1.1 $2.1)
+                (scan $1 (list $1.1 $1.2) null)
+                (scan $2 (list $2.1 $2.2) null)
+            ))
+            (scan $3 (list $3.1) null)
+        )" => "
+        (proj (list $1.2 $2.2 $3.1)
+        (join inner (= $1.1 $2.1)
+            (scan $1 (list $1.1 $1.2) null)
+            (join inner (= $3.1 $2.2)
+                (scan $2 (list $2.1 $2.2) null)
+                (scan $3 (list $3.1) null)
+            )
+        ))"
+    }
+
+    /// Returns true if any join has a join as its right child after applying the rules.
+    fn has_right_deep_join(expr: &str, rules: &[Rewrite]) -> bool {
+        let runner = egg::Runner::<_, _, ()>::default()
+            .with_expr(&expr.parse().unwrap())
+            .run(rules);
+        let egraph = &runner.egraph;
+        egraph.classes().any(|class| {
+            class.iter().any(|e| match e {
+                Expr::Join([_, _, _, right]) => {
+                    egraph[*right].iter().any(|e| matches!(e, Expr::Join(_)))
+                }
+                _ => false,
+            })
+        })
+    }
+
+    #[test]
+    fn join_reorder_only_inner() {
+        // SELECT * FROM t1 WHERE NOT EXISTS (SELECT 1 FROM t2 WHERE t2.id = t1.id)
+        // AND NOT EXISTS (SELECT 1 FROM t3)
+        let expr = "
+        (join anti true
+            (join anti (= $1.1 $2.1)
+                (scan $1 (list $1.1) null)
+                (scan $2 (list $2.1) null)
+            )
+            (scan $3 (list $3.1) null)
+        )";
+        assert!(!has_right_deep_join(expr, &rules()));
+    }
+
+    #[test]
+    fn join_reorder_skips_greedy_joins() {
+        let left_deep = |[a, b, c]: [usize; 3]| {
+            format!(
+                "(join inner true
+                    (join inner true (scan ${a} (list ${a}.1) null) (scan ${b} (list ${b}.1) null))
+                    (scan ${c} (list ${c}.1) null)
+                )"
+            )
+        };
+        let right_deep = |[a, b, c]: [usize; 3]| {
+            format!(
+                "(join inner true
+                    (scan ${a} (list ${a}.1) null)
+                    (join inner true (scan ${b} (list ${b}.1) null) (scan ${c} (list ${c}.1) null))
+                )"
+            )
+        };
+        let parse = |expr: &str| -> egg::RecExpr<Expr> { expr.parse().unwrap() };
+        let (greedy, other) = ([1, 2, 3], [4, 5, 6]);
+        let expr = format!(
+            "(join semi (= $1.1 $4.1) {} {})",
+            left_deep(greedy),
+            left_deep(other)
+        );
+
+        let mut egraph = EGraph::default();
+        egraph.add_expr(&parse(&expr));
+        egraph.analysis.greedy_joins = vec![
+            egraph.add_expr(&parse(&left_deep(greedy))),
+            egraph.add_expr(&parse(
+                "(join inner true (scan $1 (list $1.1) null) (scan $2 (list $2.1) null))",
+            )),
+        ];
+        let runner = egg::Runner::default().with_egraph(egraph).run(&rules());
+        let egraph = &runner.egraph;
+        assert!(egraph.lookup_expr(&parse(&right_deep(greedy))).is_none());
+        assert!(egraph.lookup_expr(&parse(&right_deep(other))).is_some());
+    }
+
+    #[test]
+    fn join_reorder_through_proj_only_inner() {
+        let expr = "
+        (join anti true
+            (proj (list $1.1)
+            (join anti (= $1.1 $2.1)
+                (scan $1 (list $1.1) null)
+                (scan $2 (list $2.1) null)
+            ))
+            (scan $3 (list $3.1) null)
+        )";
+        assert!(!has_right_deep_join(expr, &bushy_join_rules()));
+    }
+
     egg::test_fn! {
         hash_join,
         rules(),
@@ -412,6 +875,62 @@ mod tests {
         )"
     }
 
+    egg::test_fn! {
+        exists_to_semi_join,
+        rules(),
+        // SELECT * FROM a WHERE EXISTS (SELECT 1 FROM b WHERE b.k = a.k)
+        "
+        (filter (exists
+            (proj (list 1)
+            (filter (= $2.1 $1.1)
+                (scan $2 (list $2.1 $2.2) null)
+            )))
+            (scan $1 (list $1.1 $1.2) null)
+        )" => "
+        (hashjoin semi (list $1.1) (list $2.1)
+            (scan $1 (list $1.1 $1.2) null)
+            (scan $2 (list $2.1 $2.2) null)
+        )"
+    }
+
+    egg::test_fn! {
+        uncorrelated_exists_to_semi_join,
+        rules(),
+        // SELECT * FROM a WHERE EXISTS (SELECT 1 FROM b LIMIT 1)
+        "
+        (filter (exists
+            (limit 1 0
+            (proj (list 1)
+                (scan $2 (list $2.1 $2.2) null)
+            )))
+            (scan $1 (list $1.1 $1.2) null)
+        )" => "
+        (join semi true
+            (scan $1 (list $1.1 $1.2) null)
+            (limit 1 0
+                (scan $2 (list $2.1 $2.2) null)
+            )
+        )"
+    }
+
+    egg::test_fn! {
+        not_exists_to_anti_join,
+        rules(),
+        // SELECT * FROM a WHERE NOT EXISTS (SELECT 1 FROM b WHERE b.k = a.k)
+        "
+        (filter (not (exists
+            (proj (list 1)
+            (filter (= $2.1 $1.1)
+                (scan $2 (list $2.1 $2.2) null)
+            ))))
+            (scan $1 (list $1.1 $1.2) null)
+        )" => "
+        (hashjoin anti (list $1.1) (list $2.1)
+            (scan $1 (list $1.1 $1.2) null)
+            (scan $2 (list $2.1 $2.2) null)
+        )"
+    }
+
     egg::test_fn! {
         projection_pushdown,
         projection_pushdown_rules(),
@@ -431,4 +950,23 @@ mod tests {
             (scan $2 (list $2.1 $2.2) null)
         ))))"
     }
+
+    egg::test_fn! {
+        eager_aggregation,
+        eager_agg_rules(),
+        // SELECT sum(f.v) FROM f JOIN d ON f.k = d.k GROUP BY d.name
+        "
+        (hashagg (list (sum $1.2)) (list $2.2)
+        (hashjoin inner (list $1.1) (list $2.1)
+            (scan $1 (list $1.1 $1.2) null)
+            (scan $2 (list $2.1 $2.2) null)
+        ))" => "
+        (hashagg (list (sum $1.2)) (list $2.2)
+        (hashjoin inner (list $1.1) (list $2.1)
+            (hashagg (list (partial (sum $1.2))) (list $1.1)
+                (scan $1 (list $1.1 $1.2) null)
+            )
+            (scan $2 (list $2.1 $2.2) null)
+        ))"
+    }
 }
@@ -2,11 +2,14 @@
 
 //! Range filter.
 
+use std::cmp::Ordering;
+use std::mem::discriminant;
 use std::ops::Bound;
 
 use super::*;
 use crate::catalog::ColumnRefId;
 use crate::storage::KeyRange;
+use crate::types::DataValue;
 
 /// The data type of range analysis.
 ///
@@ -49,6 +52,9 @@ pub fn analyze_range(egraph: &EGraph, enode: &Expr) -> RangeCondition {
             } else {
                 return None;
             };
+            if v.is_null() {
+                return None;
+            }
             let start = match enode {
                 Eq(_) | GtEq(_) => Bound::Included(v.clone()),
                 Gt(_) => Bound::Excluded(v.clone()),
@@ -69,46 +75,247 @@ pub fn analyze_range(egraph: &EGraph, enode: &Expr) -> RangeCondition {
             if ka != kb {
                 return None;
             }
-            // if both a and b have bound at start or end, return None
-            let start = match (&ra.start, &rb.start) {
-                (Bound::Unbounded, s) | (s, Bound::Unbounded) => s.clone(),
-                _ => return None,
-            };
-            let end = match (&ra.end, &rb.end) {
-                (Bound::Unbounded, s) | (s, Bound::Unbounded) => s.clone(),
-                _ => return None,
-            };
+            // the intersection of both ranges
+            let start = tighter_bound(&ra.start, &rb.start, Ordering::Greater)?;
+            let end = tighter_bound(&ra.end, &rb.end, Ordering::Less)?;
             Some((*ka, KeyRange { start, end }))
         }
         _ => None,
     }
 }
 
+/// Returns the tighter one of two start bounds if `order` is `Greater`, or of two end bounds if
+/// `order` is `Less`. Returns `None` if the values are of different types.
+fn tighter_bound(
+    a: &Bound<DataValue>,
+    b: &Bound<DataValue>,
+    order: Ordering,
+) -> Option<Bound<DataValue>> {
+    let (x, y) = match (a, b) {
+        (Bound::Unbounded, s) | (s, Bound::Unbounded) => return Some(s.clone()),
+        (Bound::Included(x) | Bound::Excluded(x), Bound::Included(y) | Bound::Excluded(y)) => {
+            (x, y)
+        }
+    };
+    if discriminant(x) != discriminant(y) {
+        return None;
+    }
+    let tighter = match x.cmp(y) {
+        Ordering::Equal if matches!(a, Bound::Excluded(_)) => a,
+        Ordering::Equal => b,
+        o if o == order => a,
+        _ => b,
+    };
+    Some(tighter.clone())
+}
+
+/// Returns true if no value is in the range.
+fn is_empty(range: &KeyRange) -> bool {
+    let (start, end) = match (&range.start, &range.end) {
+        (Bound::Included(s) | Bound::Excluded(s), Bound::Included(e) | Bound::Excluded(e)) => {
+            (s, e)
+        }
+        _ => return false,
+    };
+    if discriminant(start) != discriminant(end) {
+        return false;
+    }
+    match (&range.start, &range.end) {
+        (Bound::Included(_), Bound::Included(_)) => start > end,
+        _ => start >= end,
+    }
+}
+
+/// Simplify conjunctions of range conditions on the same column.
+#[rustfmt::skip]
+pub fn range_rules() -> Vec<Rewrite> { vec![
+    // WHERE a > 5 AND a < 3 => WHERE false
+    // the condition is NULL rather than false if `a` is NULL, so it is only folded in filters
+    rw!("range-empty"; "(filter ?cond ?child)" => "(filter false ?child)" if is_empty_range("?cond")),
+    // a > 5 AND a > 3 => a > 5
+    rw!("range-left";  "(and ?a ?b)" => "?a" if is_same_range("?a")),
+    rw!("range-right"; "(and ?a ?b)" => "?b" if is_same_range("?b")),
+]}
+
+/// Returns true if `expr` is a range condition that no value satisfies.
+fn is_empty_range(expr: &str) -> impl Fn(&mut EGraph, Id, &Subst) -> bool {
+    let var = var(expr);
+    move |egraph, _, subst| {
+        (egraph[subst[var]].data.range.as_ref()).is_some_and(|(_, range)| is_empty(range))
+    }
+}
+
+/// Returns true if the matched expression is a range condition the same as `expr`.
+fn is_same_range(expr: &str) -> impl Fn(&mut EGraph, Id, &Subst) -> bool {
+    let var = var(expr);
+    move |egraph, id, subst| {
+        let (Some((k1, r1)), Some((k2, r2))) =
+            (&egraph[id].data.range, &egraph[subst[var]].data.range)
+        else {
+            return false;
+        };
+        k1 == k2 && r1.start == r2.start && r1.end == r2.end
+    }
+}
+
 #[rustfmt::skip]
 pub fn filter_scan_rule() -> Vec<Rewrite> { vec![
     // pushdown range condition to scan
     rw!("filter-scan";
-        "(filter ?cond (scan ?table ?columns true))" =>
+        "(filter ?cond (scan ?table ?columns null))" =>
         "(scan ?table ?columns ?cond)"
         if is_primary_key_range("?cond")
     ),
     rw!("filter-scan-1";
-        "(filter (and ?cond1 ?cond2) (scan ?table ?columns true))" =>
+        "(filter (and ?cond1 ?cond2) (scan ?table ?columns null))" =>
         "(filter ?cond2 (scan ?table ?columns ?cond1))"
         if is_primary_key_range("?cond1")
     ),
 ]}
 
-/// Returns true if the expression is a primary key range.
+/// The maximum number of points to scan by seeking to each of them.
+///
+/// Longer lists, e.g. from `a IN (...)`, are cheaper to filter after scanning the whole table.
+const POINT_SCAN_LIMIT: usize = 16;
+
+/// Returns the key ranges to scan for the filter of a scan, in the order of keys.
+///
+/// The filter is either a range condition, or a disjunction of `column = constant` on the same
+/// column whose values are scanned one by one. Otherwise the whole table is scanned.
+pub fn scan_ranges(egraph: &EGraph, filter: Id) -> Vec<KeyRange> {
+    if let Some((_, range)) = &egraph[filter].data.range {
+        return vec![range.clone()];
+    }
+    let Some((_, mut values)) = point_list(egraph, filter, POINT_SCAN_LIMIT) else {
+        return vec![];
+    };
+    values.sort();
+    values.dedup();
+    (values.into_iter())
+        .map(|v| KeyRange {
+            start: Bound::Included(v.clone()),
+            end: Bound::Included(v),
+        })
+        .collect()
+}
+
+/// Returns the column and values if the expression is a disjunction of at most `limit`
+/// conditions like `column = constant` on the same column.
+fn point_list(egraph: &EGraph, id: Id, limit: usize) -> Option<(ColumnRefId, Vec<DataValue>)> {
+    if limit == 0 {
+        return None;
+    }
+    if let Some((column, range)) = &egraph[id].data.range {
+        return match (&range.start, &range.end) {
+            (Bound::Included(a), Bound::Included(b)) if a == b => Some((*column, vec![a.clone()])),
+            _ => None,
+        };
+    }
+    // all disjunctions in the eclass are equivalent, so only the first one is checked
+    let [a, b] = egraph[id].iter().find_map(|e| match e {
+        Expr::Or(args) => Some(*args),
+        _ => None,
+    })?;
+    let (column, mut values) = point_list(egraph, a, limit - 1)?;
+    let (column_b, values_b) = point_list(egraph, b, limit - values.len())?;
+    if column != column_b {
+        return None;
+    }
+    values.extend(values_b);
+    Some((column, values))
+}
+
+/// Returns true if the expression is a range or a list of points on the primary key prefix.
+///
+/// The storage is sorted by the primary key, and only seeks into the first column by `INT` keys
+/// for now. So the column must be both the first column and the leading primary key.
 fn is_primary_key_range(expr: &str) -> impl Fn(&mut EGraph, Id, &Subst) -> bool {
     let var = var(expr);
     move |egraph, _, subst| {
-        let Some((column, _)) = &egraph[subst[var]].data.range else { return false };
-        egraph
-            .analysis
-            .catalog
-            .get_column(column)
-            .unwrap()
-            .is_primary()
+        let is_int32 = |v: &DataValue| matches!(v, DataValue::Int32(_));
+        let is_int32_bound = |bound: &Bound<DataValue>| match bound {
+            Bound::Included(v) | Bound::Excluded(v) => is_int32(v),
+            Bound::Unbounded => true,
+        };
+        let column = if let Some((column, range)) = &egraph[subst[var]].data.range {
+            if !is_int32_bound(&range.start) || !is_int32_bound(&range.end) {
+                return false;
+            }
+            *column
+        } else if let Some((column, values)) = point_list(egraph, subst[var], POINT_SCAN_LIMIT) {
+            if !values.iter().all(is_int32) {
+                return false;
+            }
+            column
+        } else {
+            return false;
+        };
+        column.column_id == 0
+            && egraph
+                .analysis
+                .catalog
+                .get_table(&column.table())
+                .is_some_and(|table| table.primary_keys().first() == Some(&column.column_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules() -> Vec<Rewrite> {
+        let mut rules = vec![];
+        rules.append(&mut expr::rules());
+        rules.append(&mut range_rules());
+        rules
+    }
+
+    egg::test_fn! {
+        range_narrowing,
+        rules(),
+        // a > 5 AND a > 3
+        "(and (> $1.1 5) (> $1.1 3))" => "(> $1.1 5)"
+    }
+
+    egg::test_fn! {
+        range_narrowing_both_ends,
+        rules(),
+        // a > 3 AND a < 10 AND a >= 5
+        "(and (and (> $1.1 3) (< $1.1 10)) (>= $1.1 5))" => "(and (>= $1.1 5) (< $1.1 10))"
+    }
+
+    egg::test_fn! {
+        range_narrowing_to_point,
+        rules(),
+        // a >= 5 AND a = 5
+        "(and (>= $1.1 5) (= $1.1 5))" => "(= $1.1 5)"
+    }
+
+    egg::test_fn! {
+        range_contradiction,
+        rules(),
+        // WHERE a > 5 AND a < 3
+        "(filter (and (> $1.1 5) (< $1.1 3)) (scan $1 (list $1.1) null))" =>
+        "(filter false (scan $1 (list $1.1) null))"
+    }
+
+    egg::test_fn! {
+        range_contradiction_at_bound,
+        rules(),
+        // WHERE a >= 5 AND a < 5
+        "(filter (and (>= $1.1 5) (< $1.1 5)) (scan $1 (list $1.1) null))" =>
+        "(filter false (scan $1 (list $1.1) null))"
+    }
+
+    #[test]
+    fn range_contradiction_outside_filter() {
+        // NOT (a > 5 AND a < 3) is NULL if `a` is NULL
+        let expr = "(not (and (> $1.1 5) (< $1.1 3)))".parse().unwrap();
+        let runner = egg::Runner::<_, _, ()>::default()
+            .with_expr(&expr)
+            .run(&rules());
+        let root = runner.egraph.find(runner.roots[0]);
+        let is_true = (runner.egraph[root].iter()).any(|e| *e == Expr::true_());
+        assert!(!is_true);
     }
 }
@@ -19,16 +19,30 @@ pub fn analyze_rows(egraph: &EGraph, enode: &Expr) -> Rows {
     };
     match enode {
         // for plan nodes, the result represents estimated rows
-        Values(v) => v.len() as f32,
-        Scan(_) => 1000.0, // TODO: get from table
+        Values(columns) => columns
+            .first()
+            .map_or(0.0, |c| egraph[*c].nodes[0].len() as f32),
+        Scan([table, _, _]) => {
+            let table_id = egraph[*table].nodes[0].as_table();
+            // tables without statistics are assumed to have 1000 rows
+            (egraph.analysis.stat.get_row_count(table_id)).map_or(1000.0, |rows| rows as f32)
+        }
         Proj([_, c]) | Order([_, c]) | Window([_, c]) => x(c),
         Agg(_) => 1.0,
         HashAgg([_, _, c]) | SortAgg([_, _, c]) => {
             x(c) / 2.0 // TODO: group by cardinality
         }
         Filter([cond, c]) => x(c) * x(cond),
-        Limit([limit, _, c]) | TopN([limit, _, _, c]) => x(c).min(get_limit_num(limit)),
-        Join([_, on, l, r]) => x(l) * x(r) * x(on),
+        Limit([limit, offset, c]) | TopN([limit, offset, _, _, c]) => {
+            let rows = (x(c) - get_limit_num(offset)).max(0.0);
+            rows.min(get_limit_num(limit))
+        }
+        Join([t, _, l, _]) | HashJoin([t, _, _, l, _]) | MergeJoin([t, _, _, l, _])
+            if egraph[*t].nodes[0].is_semi_or_anti() =>
+        {
+            x(l)
+        }
+        Join([_, on, l, r]) | Apply([_, on, l, r]) => x(l) * x(r) * x(on),
         HashJoin([_, _, _, l, r]) | MergeJoin([_, _, _, l, r]) => x(l).max(x(r)),
         Empty(_) => 0.0,
 
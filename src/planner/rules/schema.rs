@@ -8,24 +8,36 @@ use super::*;
 pub type Schema = Vec<Id>;
 
 /// Returns the output expressions for plan node.
-pub fn analyze_schema(enode: &Expr, x: impl Fn(&Id) -> Schema) -> Schema {
+pub fn analyze_schema<'a>(
+    enode: &Expr,
+    x: impl Fn(&Id) -> Schema,
+    node0: impl Fn(&Id) -> &'a Expr,
+) -> Schema {
     use Expr::*;
     let concat = |v1: Vec<Id>, v2: Vec<Id>| v1.into_iter().chain(v2.into_iter()).collect();
     match enode {
         // equal to child
-        Filter([_, c]) | Order([_, c]) | Limit([_, _, c]) | TopN([_, _, _, c]) => x(c),
+        Filter([_, c]) | Order([_, c]) | Limit([_, _, c]) | TopN([_, _, _, _, c]) => x(c),
 
-        // concat 2 children
-        Join([_, _, l, r]) | HashJoin([_, _, _, l, r]) | MergeJoin([_, _, _, l, r]) => {
-            concat(x(l), x(r))
+        // semi and anti join only output the left child
+        Join([t, _, l, _]) | HashJoin([t, _, _, l, _]) | MergeJoin([t, _, _, l, _])
+            if node0(t).is_semi_or_anti() =>
+        {
+            x(l)
         }
 
+        // concat 2 children
+        Join([_, _, l, r])
+        | HashJoin([_, _, _, l, r])
+        | MergeJoin([_, _, _, l, r])
+        | Apply([_, _, l, r]) => concat(x(l), x(r)),
+
         // list is the source for the following nodes
         List(ids) => ids.to_vec(),
 
         // plans that change schema
         Scan([_, columns, _]) | Internal([_, columns]) => x(columns),
-        Values(vs) => x(&vs[0]),
+        Values(columns) => columns.to_vec(),
         Proj([exprs, _]) | Agg([exprs, _]) => x(exprs),
         Window([exprs, child]) => concat(x(child), x(exprs)),
         HashAgg([exprs, group_keys, _]) | SortAgg([exprs, group_keys, _]) => {
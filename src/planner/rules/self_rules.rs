@@ -1,8 +1,11 @@
 use super::*;
+use crate::types::DataValue;
 
 pub fn self_def_rules() -> Vec<Rewrite> {
     let mut rules = vec![];
     rules.extend(cancel_rules());
+    rules.extend(filter_join_rules());
+    rules.extend(and_eq_rules());
     rules
 }
 
@@ -13,17 +16,317 @@ fn cancel_rules() -> Vec<Rewrite> { vec![
     //     "(scan ?table ?columns ?cond)"
     // ),
     // rw!(
-    //     "filter_join_union";
-    //     "(filter ?c (join ?t ?d ?l ?r))"=>
-    //     "(filter (and ?c ?d) (join ?t ?d ?l ?r))"
-    // ),
-    // rw!(
-    //     "and_eq";
-    //     "(and (= ?a ?b) (= ?a ?c))" => 
-    //     "(and (= ?a ?c) (= ?b ?c))"
-    // ),
-    // rw!(
     //     "duplicate_filter";
     //     "(filter ?e ?c)" => "(filter ?e (filter ?e ?c))"
     // )
-]}
\ No newline at end of file
+]}
+
+/// Overlay the predicate of an inner join onto the filter above it,
+/// so that rules on the filter can act on the join predicate as well.
+///
+/// Every output row of an inner join satisfies its predicate, so this is sound for inner joins
+/// only. The predicate is merged at most once: without the guard, the rule would keep producing
+/// `(and (and ?cond ?on) ?on)` and never saturate.
+#[rustfmt::skip]
+fn filter_join_rules() -> Vec<Rewrite> { vec![
+    rw!("filter-join-union";
+        "(filter ?cond (join inner ?on ?left ?right))" =>
+        "(filter (and ?cond ?on) (join inner ?on ?left ?right))"
+        if can_merge_predicate("?cond", "?on")
+    ),
+]}
+
+/// Returns true if `var2` has conjuncts that are not in `var1`, and vice versa.
+///
+/// The latter means the predicate `var1` has not been pushed into `var2` yet.
+fn can_merge_predicate(var1: &str, var2: &str) -> impl Fn(&mut EGraph, Id, &Subst) -> bool {
+    let var1 = var(var1);
+    let var2 = var(var2);
+    move |egraph, _, subst| {
+        let set1 = conjuncts(egraph, subst[var1]);
+        let set2 = conjuncts(egraph, subst[var2]);
+        !set2.is_subset(&set1) && !set1.is_subset(&set2)
+    }
+}
+
+/// Returns the eclasses of all conjuncts in the predicate, ignoring `true`.
+///
+/// All `and` nodes in an eclass are expanded, so the result may contain redundant conjuncts.
+fn conjuncts(egraph: &EGraph, id: Id) -> HashSet<Id> {
+    let mut conjuncts = HashSet::new();
+    let mut visited = HashSet::new();
+    let mut stack = vec![id];
+    while let Some(id) = stack.pop() {
+        let id = egraph.find(id);
+        if !visited.insert(id) {
+            continue;
+        }
+        let mut is_and = false;
+        for node in &egraph[id].nodes {
+            if let Expr::And([a, b]) = node {
+                stack.extend([*a, *b]);
+                is_and = true;
+            }
+        }
+        if !is_and && egraph[id].data.constant != Some(DataValue::Bool(true)) {
+            conjuncts.insert(id);
+        }
+    }
+    conjuncts
+}
+
+/// Derive equalities from the transitivity of equalities in a predicate.
+/// e.g. `a = b AND a = c` derives `b = c`.
+///
+/// This is only sound for the predicate of filters and inner joins, where NULL and false are
+/// both rejected. In other places, `(a = b AND a = c) AND b = c` may be false instead of NULL.
+#[rustfmt::skip]
+pub fn and_eq_rules() -> Vec<Rewrite> { vec![
+    rw!("filter-and-eq";
+        "(filter ?cond ?child)" =>
+        { AndEq {
+            pattern: pattern("(filter ?cond ?child)"),
+            cond: var("?cond"),
+        }}
+    ),
+    rw!("join-and-eq";
+        "(join inner ?cond ?left ?right)" =>
+        { AndEq {
+            pattern: pattern("(join inner ?cond ?left ?right)"),
+            cond: var("?cond"),
+        }}
+    ),
+]}
+
+/// Add the equalities in the transitive closure of `cond` that are missing from it.
+///
+/// Nothing is added if `cond` is already closed, so the rule is applied once for each predicate.
+struct AndEq {
+    pattern: Pattern,
+    cond: Var,
+}
+
+impl Applier<Expr, ExprAnalysis> for AndEq {
+    fn apply_one(
+        &self,
+        egraph: &mut EGraph,
+        eclass: Id,
+        subst: &Subst,
+        searcher_ast: Option<&PatternAst<Expr>>,
+        rule_name: Symbol,
+    ) -> Vec<Id> {
+        let cond = subst[self.cond];
+
+        // collect equalities between eclasses
+        let mut equalities = HashSet::new();
+        for id in conjuncts(egraph, cond) {
+            for node in &egraph[id].nodes {
+                if let Expr::Eq([a, b]) = node {
+                    let (a, b) = (egraph.find(*a), egraph.find(*b));
+                    if a != b {
+                        equalities.insert((a.min(b), a.max(b)));
+                    }
+                }
+            }
+        }
+
+        // group eclasses that are equal to each other
+        let mut groups: Vec<Vec<Id>> = vec![];
+        for &(a, b) in &equalities {
+            let ga = groups.iter().position(|g| g.contains(&a));
+            let gb = groups.iter().position(|g| g.contains(&b));
+            match (ga, gb) {
+                (Some(ga), Some(gb)) if ga == gb => {}
+                (Some(ga), Some(gb)) => {
+                    let g = groups.remove(ga.max(gb));
+                    groups[ga.min(gb)].extend(g);
+                }
+                (Some(g), None) => groups[g].push(b),
+                (None, Some(g)) => groups[g].push(a),
+                (None, None) => groups.push(vec![a, b]),
+            }
+        }
+
+        let mut missing = vec![];
+        for group in &mut groups {
+            group.sort_unstable();
+            for (i, &a) in group.iter().enumerate() {
+                for &b in &group[i + 1..] {
+                    if !equalities.contains(&(a, b)) {
+                        missing.push((a, b));
+                    }
+                }
+            }
+        }
+        if missing.is_empty() {
+            return vec![];
+        }
+        missing.sort_unstable();
+
+        let mut cond = cond;
+        for (a, b) in missing {
+            let eq = egraph.add(Expr::Eq([a, b]));
+            cond = egraph.add(Expr::And([cond, eq]));
+        }
+        let mut subst = subst.clone();
+        subst.insert(self.cond, cond);
+        self.pattern
+            .apply_one(egraph, eclass, &subst, searcher_ast, rule_name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules() -> Vec<Rewrite> {
+        let mut rules = vec![];
+        rules.append(&mut expr::rules());
+        rules.append(&mut self_def_rules());
+        rules
+    }
+
+    egg::test_fn! {
+        and_eq_hash_join,
+        {
+            let mut rules = vec![];
+            rules.append(&mut expr::rules());
+            rules.append(&mut plan::always_better_rules());
+            rules.append(&mut plan::join_rules());
+            rules.append(&mut and_eq_rules());
+            rules
+        },
+        // SELECT * FROM t1, t2, t3
+        // WHERE t1.id = t2.id AND t1.id = t3.id
+        "
+        (filter (and (= $1.1 $2.1) (= $1.1 $3.1))
+        (join inner true
+            (join inner true
+                (scan $1 (list $1.1 $1.2) null)
+                (scan $2 (list $2.1 $2.2) null)
+            )
+            (scan $3 (list $3.1 $3.2) null)
+        ))" => "
+        (hashjoin inner (list $1.1) (list $2.1)
+            (scan $1 (list $1.1 $1.2) null)
+            (hashjoin inner (list $2.1) (list $3.1)
+                (scan $2 (list $2.1 $2.2) null)
+                (scan $3 (list $3.1 $3.2) null)
+            )
+        )"
+    }
+
+    #[test]
+    fn and_eq_terminates() {
+        let runner = egg::Runner::<Expr, ExprAnalysis>::default()
+            .with_expr(
+                &"
+                (filter (and (= $1.1 $2.1) (and (= $1.1 $3.1) (= $3.1 $4.1)))
+                    (scan $1 (list $1.1 $2.1 $3.1 $4.1) null)
+                )"
+                .parse()
+                .unwrap(),
+            )
+            .run(&and_eq_rules());
+        assert!(
+            matches!(runner.stop_reason, Some(egg::StopReason::Saturated)),
+            "{:?}",
+            runner.stop_reason
+        );
+        // all pairs are equal to each other
+        let columns = ["$1.1", "$2.1", "$3.1", "$4.1"];
+        for (i, a) in columns.iter().enumerate() {
+            for b in &columns[i + 1..] {
+                let eq1 = format!("(= {a} {b})").parse().unwrap();
+                let eq2 = format!("(= {b} {a})").parse().unwrap();
+                assert!(
+                    runner.egraph.lookup_expr(&eq1).is_some()
+                        || runner.egraph.lookup_expr(&eq2).is_some(),
+                    "{a} = {b} is not derived"
+                );
+            }
+        }
+    }
+
+    egg::test_fn! {
+        filter_join_union,
+        rules(),
+        "
+        (filter (> $1.2 1)
+        (join inner (= $1.1 $2.1)
+            (scan $1 (list $1.1 $1.2) null)
+            (scan $2 (list $2.1 $2.2) null)
+        ))" => "
+        (filter (and (> $1.2 1) (= $1.1 $2.1))
+        (join inner (= $1.1 $2.1)
+            (scan $1 (list $1.1 $1.2) null)
+            (scan $2 (list $2.1 $2.2) null)
+        ))"
+    }
+
+    fn run(expr: &str) -> egg::Runner<Expr, ExprAnalysis> {
+        egg::Runner::default()
+            .with_expr(&expr.parse().unwrap())
+            .run(&rules())
+    }
+
+    #[test]
+    fn filter_join_union_terminates() {
+        let runner = run("
+            (filter (> $1.2 1)
+            (join inner (= $1.1 $2.1)
+                (scan $1 (list $1.1 $1.2) null)
+                (scan $2 (list $2.1 $2.2) null)
+            ))");
+        assert!(
+            matches!(runner.stop_reason, Some(egg::StopReason::Saturated)),
+            "{:?}",
+            runner.stop_reason
+        );
+        let twice = "(and (and (> $1.2 1) (= $1.1 $2.1)) (= $1.1 $2.1))"
+            .parse()
+            .unwrap();
+        assert!(runner.egraph.lookup_expr(&twice).is_none());
+    }
+
+    #[test]
+    fn filter_join_union_terminates_in_optimizer() {
+        let mut expr: RecExpr = "
+            (filter (> $1.2 1)
+            (join inner (= $1.1 $2.1)
+                (scan $1 (list $1.1 $1.2) null)
+                (scan $2 (list $2.1 $2.2) null)
+            ))"
+        .parse()
+        .unwrap();
+        let twice = "(and (and (> $1.2 1) (= $1.1 $2.1)) (= $1.1 $2.1))"
+            .parse()
+            .unwrap();
+        // run both stages of the optimizer, extracting the plan in between
+        for rules in [&*STAGE1_RULES, &*STAGE2_RULES] {
+            let runner = egg::Runner::<Expr, ExprAnalysis>::default()
+                .with_expr(&expr)
+                .run(rules);
+            assert!(
+                matches!(runner.stop_reason, Some(egg::StopReason::Saturated)),
+                "{:?}",
+                runner.stop_reason
+            );
+            assert!(runner.egraph.lookup_expr(&twice).is_none());
+            (_, expr) =
+                egg::Extractor::new(&runner.egraph, egg::AstSize).find_best(runner.roots[0]);
+        }
+    }
+
+    #[test]
+    fn filter_join_union_not_on_outer_join() {
+        let runner = run("
+            (filter (> $1.2 1)
+            (join left_outer (= $1.1 $2.1)
+                (scan $1 (list $1.1 $1.2) null)
+                (scan $2 (list $2.1 $2.2) null)
+            ))");
+        let merged = "(and (> $1.2 1) (= $1.1 $2.1))".parse().unwrap();
+        assert!(runner.egraph.lookup_expr(&merged).is_none());
+    }
+}
@@ -17,7 +17,12 @@ pub enum TypeError {
 }
 
 /// Returns data type of the expression.
-pub fn analyze_type(enode: &Expr, x: impl Fn(&Id) -> Type, catalog: &RootCatalogRef) -> Type {
+pub fn analyze_type<'a>(
+    enode: &Expr,
+    x: impl Fn(&Id) -> Type,
+    node0: impl Fn(&Id) -> &'a Expr,
+    catalog: &RootCatalogRef,
+) -> Type {
     use Expr::*;
     let concat_struct = |t1: DataType, t2: DataType| match (t1.kind, t2.kind) {
         (Kind::Struct(l), Kind::Struct(r)) => {
@@ -28,6 +33,8 @@ pub fn analyze_type(enode: &Expr, x: impl Fn(&Id) -> Type, catalog: &RootCatalog
     match enode {
         // values
         Constant(v) => Ok(v.data_type()),
+        // the type of parameters is unknown until execution
+        Placeholder(_) => Ok(Kind::Null.nullable()),
         Type(t) => Ok(t.clone().not_null()),
         Column(col) => Ok(catalog
             .get_column(col)
@@ -35,14 +42,32 @@ pub fn analyze_type(enode: &Expr, x: impl Fn(&Id) -> Type, catalog: &RootCatalog
             .datatype()),
         Ref(a) => x(a),
         List(list) => Ok(Kind::Struct(list.iter().map(x).try_collect()?).not_null()),
+        // values of a column are unified into a common type
+        ValuesColumn(values) => {
+            let mut type_ = x(&values[0])?;
+            for value in values.iter().skip(1) {
+                let ty = x(value)?;
+                type_ = type_.union(&ty).ok_or(TypeError::NoCast {
+                    from: ty.kind,
+                    to: type_.kind,
+                })?;
+            }
+            Ok(type_)
+        }
 
         // cast
         Cast([ty, a]) => merge(enode, [x(ty)?, x(a)?], |[ty, _]| Some(ty)),
+        TryCast([ty, a]) => {
+            x(a)?;
+            // failed conversions become NULL
+            Ok(x(ty)?.kind.nullable())
+        }
 
         // number ops
         Neg(a) => check(enode, x(a)?, |a| a.is_number()),
         Add([a, b]) | Sub([a, b]) | Mul([a, b]) | Div([a, b]) | Mod([a, b]) => {
             merge(enode, [x(a)?, x(b)?], |[a, b]| {
+                let date_first = a == Kind::Date;
                 match if a > b { (b, a) } else { (a, b) } {
                     (Kind::Null, _) => Some(Kind::Null),
                     (Kind::Decimal(Some(p1), Some(s1)), Kind::Decimal(Some(p2), Some(s2))) => {
@@ -57,12 +82,30 @@ pub fn analyze_type(enode: &Expr, x: impl Fn(&Id) -> Type, catalog: &RootCatalog
                         }
                     }
                     (a, b) if a.is_number() && b.is_number() => Some(b),
-                    (Kind::Date, Kind::Interval) => Some(Kind::Date),
+                    // date + interval, interval + date, date - interval
+                    (Kind::Date, Kind::Interval) => match enode {
+                        Add(_) => Some(Kind::Date),
+                        Sub(_) if date_first => Some(Kind::Date),
+                        _ => None,
+                    },
                     _ => None,
                 }
             })
         }
 
+        // bitwise ops
+        BitAnd([a, b]) | BitOr([a, b]) | BitXor([a, b]) => merge(enode, [x(a)?, x(b)?], |[a, b]| {
+            match if a > b { (b, a) } else { (a, b) } {
+                (Kind::Null, b) if b.is_null() || b.is_integer() => Some(b),
+                (a, b) if a.is_integer() && b.is_integer() => Some(b),
+                _ => None,
+            }
+        }),
+        // the result of shifts has the type of the left operand
+        Shl([a, b]) | Shr([a, b]) => merge(enode, [x(a)?, x(b)?], |[a, b]| {
+            ((a.is_null() || a.is_integer()) && (b.is_null() || b.is_integer())).then_some(a)
+        }),
+
         // string ops
         StringConcat([a, b]) => merge(enode, [x(a)?, x(b)?], |[a, b]| {
             (a == Kind::String && b == Kind::String).then_some(Kind::String)
@@ -75,11 +118,32 @@ pub fn analyze_type(enode: &Expr, x: impl Fn(&Id) -> Type, catalog: &RootCatalog
         Not(a) => check(enode, x(a)?, |a| a == Kind::Bool),
         Gt([a, b]) | Lt([a, b]) | GtEq([a, b]) | LtEq([a, b]) | Eq([a, b]) | NotEq([a, b]) => {
             merge(enode, [x(a)?, x(b)?], |[a, b]| {
-                (a.is_number() && b.is_number()
-                    || a == b
-                    || (a == Kind::String || b == Kind::String)
-                    || (a == Kind::Null || b == Kind::Null))
-                    .then_some(Kind::Bool)
+                is_comparable(&a, &b).then_some(Kind::Bool)
+            })
+        }
+        // null-safe comparisons never return NULL
+        IsDistinct([a, b]) | IsNotDistinct([a, b]) => {
+            let ty = merge(enode, [x(a)?, x(b)?], |[a, b]| {
+                is_comparable(&a, &b).then_some(Kind::Bool)
+            })?;
+            Ok(ty.kind.not_null())
+        }
+        In([a, list]) => {
+            let a = x(a)?;
+            let Kind::Struct(items) = x(list)?.kind else { panic!("not struct type") };
+            let mut nullable = a.nullable;
+            for item in items {
+                if !is_comparable(&a.kind, &item.kind) {
+                    return Err(TypeError::NoFunction {
+                        op: enode.to_string(),
+                        operands: vec![a.kind, item.kind],
+                    });
+                }
+                nullable |= item.nullable;
+            }
+            Ok(DataType {
+                kind: Kind::Bool,
+                nullable,
             })
         }
         And([a, b]) | Or([a, b]) | Xor([a, b]) => merge(enode, [x(a)?, x(b)?], |[a, b]| {
@@ -93,27 +157,56 @@ pub fn analyze_type(enode: &Expr, x: impl Fn(&Id) -> Type, catalog: &RootCatalog
         ),
 
         // null ops
-        IsNull(_) => Ok(Kind::Bool.not_null()),
+        IsNull(_) | IsNotNull(_) => Ok(Kind::Bool.not_null()),
+
+        // subquery
+        Exists(_) => Ok(Kind::Bool.not_null()),
 
         // functions
         Extract([_, a]) => merge(enode, [x(a)?], |[a]| {
             matches!(a, Kind::Date | Kind::Interval).then_some(Kind::Int32)
         }),
+        Now => Ok(Kind::TimestampTz.not_null()),
+        DateTrunc([_, a]) => merge(enode, [x(a)?], |[a]| {
+            matches!(a, Kind::Timestamp | Kind::TimestampTz).then_some(a)
+        }),
         Substring([str, start, len]) => {
             merge(enode, [x(str)?, x(start)?, x(len)?], |[str, start, len]| {
                 (str == Kind::String && start == Kind::Int32 && len == Kind::Int32)
                     .then_some(Kind::String)
             })
         }
+        Length(a) => merge(enode, [x(a)?], |[a]| {
+            matches!(a, Kind::String | Kind::Blob).then_some(Kind::Int32)
+        }),
+        CharLength(a) | Ascii(a) => merge(enode, [x(a)?], |[a]| {
+            (a == Kind::String).then_some(Kind::Int32)
+        }),
+        Chr(a) => merge(enode, [x(a)?], |[a]| a.is_integer().then_some(Kind::String)),
+        Reverse(a) => check(enode, x(a)?, |a| a == Kind::String),
+        Repeat([a, n]) => merge(enode, [x(a)?, x(n)?], |[a, n]| {
+            (a == Kind::String && n.is_integer()).then_some(Kind::String)
+        }),
+        Sign(a) => check(enode, x(a)?, |a| a.is_number()),
+        Ln(a) | Exp(a) => merge(enode, [x(a)?], |[a]| a.is_number().then_some(Kind::Float64)),
+        Log([base, a]) => merge(enode, [x(base)?, x(a)?], |[base, a]| {
+            (base.is_number() && a.is_number()).then_some(Kind::Float64)
+        }),
 
         // number agg
         Max(a) | Min(a) => x(a),
         Sum(a) => check(enode, x(a)?, |a| a.is_number()),
-        Avg(a) => check(enode, x(a)?, |a| a.is_number()),
+        Avg(a) => merge(enode, [x(a)?], |[a]| match a {
+            a if a.is_integer() => Some(Kind::Float64),
+            a => a.is_number().then_some(a),
+        }),
 
         // agg
-        RowCount | RowNumber | Count(_) => Ok(Kind::Int32.not_null()),
-        First(a) | Last(a) => x(a),
+        RowCount | RowNumber | Rank | DenseRank | Count(_) => Ok(Kind::Int32.not_null()),
+        First(a) | Last(a) | Partial(a) => x(a),
+        StringAgg([a, sep]) => merge(enode, [x(a)?, x(sep)?], |[a, sep]| {
+            (a == Kind::String && sep == Kind::String).then_some(Kind::String)
+        }),
         Over([f, _, _]) => x(f),
 
         // scalar functions
@@ -123,29 +216,24 @@ pub fn analyze_type(enode: &Expr, x: impl Fn(&Id) -> Type, catalog: &RootCatalog
         }),
 
         // equal to child
-        Filter([_, c]) | Order([_, c]) | Limit([_, _, c]) | TopN([_, _, _, c]) => x(c),
+        Filter([_, c]) | Order([_, c]) | Limit([_, _, c]) | TopN([_, _, _, _, c]) => x(c),
 
-        // concat 2 children
-        Join([_, _, l, r]) | HashJoin([_, _, _, l, r]) | MergeJoin([_, _, _, l, r]) => {
-            concat_struct(x(l)?, x(r)?)
+        // semi and anti join only output the left child
+        Join([t, _, l, _]) | HashJoin([t, _, _, l, _]) | MergeJoin([t, _, _, l, _])
+            if node0(t).is_semi_or_anti() =>
+        {
+            x(l)
         }
 
+        // concat 2 children
+        Join([_, _, l, r])
+        | HashJoin([_, _, _, l, r])
+        | MergeJoin([_, _, _, l, r])
+        | Apply([_, _, l, r]) => concat_struct(x(l)?, x(r)?),
+
         // plans that change schema
         Scan([_, columns, _]) => x(columns),
-        Values(rows) => {
-            if rows.is_empty() {
-                return Ok(Kind::Null.not_null());
-            }
-            let mut type_ = x(&rows[0])?;
-            for row in rows.iter().skip(1) {
-                let ty = x(row)?;
-                type_ = type_.union(&ty).ok_or(TypeError::NoCast {
-                    from: ty.kind,
-                    to: type_.kind,
-                })?;
-            }
-            Ok(type_)
-        }
+        Values(columns) => Ok(Kind::Struct(columns.iter().map(x).try_collect()?).not_null()),
         Proj([exprs, _]) | Agg([exprs, _]) => x(exprs),
         Window([exprs, c]) => concat_struct(x(c)?, x(exprs)?),
         HashAgg([exprs, group_keys, _]) | SortAgg([exprs, group_keys, _]) => {
@@ -165,6 +253,14 @@ pub fn analyze_type(enode: &Expr, x: impl Fn(&Id) -> Type, catalog: &RootCatalog
     }
 }
 
+/// Returns true if values of the two types can be compared.
+fn is_comparable(a: &Kind, b: &Kind) -> bool {
+    a.is_number() && b.is_number()
+        || a == b
+        || (*a == Kind::String || *b == Kind::String)
+        || (*a == Kind::Null || *b == Kind::Null)
+}
+
 fn check(enode: &Expr, a: DataType, check: impl FnOnce(Kind) -> bool) -> Type {
     if check(a.kind()) {
         Ok(a)
@@ -252,6 +348,21 @@ mod tests {
         );
     }
 
+    #[test]
+    fn sub() {
+        assert_type_eq(
+            "(- date'2022-10-14' interval'1_day')",
+            Ok(Kind::Date.not_null()),
+        );
+        assert_type_eq(
+            "(- interval'1_day' date'2022-10-14')",
+            Err(TypeError::NoFunction {
+                op: "-".into(),
+                operands: vec![Kind::Interval, Kind::Date],
+            }),
+        );
+    }
+
     #[test]
     fn cmp() {
         assert_type_eq("(= 1 1)", Ok(Kind::Bool.not_null()));
@@ -271,6 +382,50 @@ mod tests {
         );
     }
 
+    #[test]
+    fn logical() {
+        assert_type_eq("(xor true false)", Ok(Kind::Bool.not_null()));
+        assert_type_eq("(xor true null)", Ok(Kind::Bool.nullable()));
+        assert_type_eq("(and null null)", Ok(Kind::Bool.nullable()));
+        assert_type_eq(
+            "(xor 1 2)",
+            Err(TypeError::NoFunction {
+                op: "xor".into(),
+                operands: vec![Kind::Int32, Kind::Int32],
+            }),
+        );
+    }
+
+    #[test]
+    fn bitwise() {
+        assert_type_eq("(bitand 1 2)", Ok(Kind::Int32.not_null()));
+        assert_type_eq("(bitor 1 (cast BIGINT 2))", Ok(Kind::Int64.not_null()));
+        assert_type_eq("(bitxor 1 null)", Ok(Kind::Int32.nullable()));
+        assert_type_eq("(shl (cast BIGINT 1) 2)", Ok(Kind::Int64.not_null()));
+        assert_type_eq("(shl 1 (cast BIGINT 2))", Ok(Kind::Int32.not_null()));
+        assert_type_eq("(shr null 2)", Ok(Kind::Null.nullable()));
+        assert_type_eq(
+            "(bitand 1 1.0)",
+            Err(TypeError::NoFunction {
+                op: "bitand".into(),
+                operands: vec![Kind::Int32, Kind::Decimal(None, None)],
+            }),
+        );
+    }
+
+    #[test]
+    fn in_list() {
+        assert_type_eq("(in 1 (list 1 2.0))", Ok(Kind::Bool.not_null()));
+        assert_type_eq("(in 1 (list 1 null))", Ok(Kind::Bool.nullable()));
+        assert_type_eq(
+            "(in date'2022-10-14' (list date'2022-10-14' 1))",
+            Err(TypeError::NoFunction {
+                op: "in".into(),
+                operands: vec![Kind::Date, Kind::Int32],
+            }),
+        );
+    }
+
     #[track_caller]
     fn assert_type_eq(expr: &str, expected: Type) {
         assert_eq!(type_of(expr), expected);
@@ -8,6 +8,7 @@ use pgwire::api::{ClientInfo, Type};
 use pgwire::error::{PgWireError, PgWireResult};
 use tracing::info;
 
+use crate::array::Chunk;
 use crate::Database;
 
 pub struct Processor {
@@ -45,20 +46,10 @@ impl SimpleQueryHandler for Processor {
         let mut results = Vec::new();
         let mut headers = None;
         for chunk in chunks {
+            // the header is known even if there is no output row
+            let headers = headers.get_or_insert_with(|| Arc::new(field_infos(&chunk)));
             for data_chunk in chunk.data_chunks() {
                 for i in 0..data_chunk.cardinality() {
-                    let headers = headers.get_or_insert_with(|| {
-                        Arc::new(vec![
-                            FieldInfo::new(
-                                "++".into(),
-                                None,
-                                None,
-                                Type::CHAR,
-                                FieldFormat::Text
-                            );
-                            data_chunk.arrays().len()
-                        ])
-                    });
                     let mut encoder = DataRowEncoder::new(headers.clone());
                     data_chunk.arrays().iter().for_each(|a| {
                         let field = a.get_to_string(i);
@@ -69,8 +60,22 @@ impl SimpleQueryHandler for Processor {
             }
         }
         Ok(vec![Response::Query(QueryResponse::new(
-            headers.expect("fixme: db should return schema even if no output data"),
+            headers.unwrap_or_default(),
             stream::iter(results.into_iter()),
         ))])
     }
 }
+
+/// Returns the fields of a chunk, named by its header if any.
+fn field_infos(chunk: &Chunk) -> Vec<FieldInfo> {
+    let names = match chunk.header() {
+        Some(header) => header.to_vec(),
+        None => {
+            let columns = chunk.data_chunks().first().map_or(0, |c| c.column_count());
+            vec!["++".into(); columns]
+        }
+    };
+    (names.into_iter())
+        .map(|name| FieldInfo::new(name, None, None, Type::CHAR, FieldFormat::Text))
+        .collect()
+}
@@ -17,6 +17,8 @@ pub enum StorageError {
     NotFound(&'static str, String),
     #[error("duplicated {0}: {1}")]
     Duplicated(&'static str, String),
+    #[error("table {0} is referenced by a foreign key constraint of table {1}")]
+    ReferencedTable(String, String),
     #[error("invalid column id: {0}")]
     InvalidColumn(ColumnId),
     #[error("IO error: {0}")]
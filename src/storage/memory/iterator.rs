@@ -19,6 +19,8 @@ pub struct InMemoryTxnIterator {
     col_idx: Vec<StorageColumnRef>,
     cnt: usize,
     row_cnt: usize,
+    /// `(index, count)`: only chunks at `index` modulo `count` are returned.
+    partition: Option<(usize, usize)>,
 }
 
 impl InMemoryTxnIterator {
@@ -26,6 +28,7 @@ impl InMemoryTxnIterator {
         chunks: Arc<Vec<DataChunk>>,
         deleted_rows: Arc<HashSet<usize>>,
         col_idx: &[StorageColumnRef],
+        partition: Option<(usize, usize)>,
     ) -> Self {
         Self {
             chunks,
@@ -33,6 +36,7 @@ impl InMemoryTxnIterator {
             cnt: 0,
             row_cnt: 0,
             deleted_rows,
+            partition,
         }
     }
 
@@ -40,6 +44,13 @@ impl InMemoryTxnIterator {
         &mut self,
         _expected_size: Option<usize>,
     ) -> StorageResult<Option<DataChunk>> {
+        // skip chunks of other partitions
+        if let Some((index, count)) = self.partition {
+            while self.cnt < self.chunks.len() && self.cnt % count != index {
+                self.row_cnt += self.chunks[self.cnt].cardinality();
+                self.cnt += 1;
+            }
+        }
         if self.cnt >= self.chunks.len() {
             Ok(None)
         } else {
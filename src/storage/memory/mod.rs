@@ -21,7 +21,9 @@ use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 
 use super::{Storage, StorageError, StorageResult, TracedStorageError};
-use crate::catalog::{ColumnCatalog, ColumnId, RootCatalog, RootCatalogRef, SchemaId, TableRefId};
+use crate::catalog::{
+    ColumnCatalog, ColumnId, ForeignKey, RootCatalog, RootCatalogRef, SchemaId, TableRefId,
+};
 
 mod table;
 pub use table::InMemoryTable;
@@ -64,12 +66,21 @@ impl Storage for InMemoryStorage {
     type Transaction = InMemoryTransaction;
     type Table = InMemoryTable;
 
+    async fn create_schema(&self, schema_name: &str) -> StorageResult<()> {
+        self.catalog
+            .add_schema(schema_name.into())
+            .map_err(|_| TracedStorageError::duplicated("schema", schema_name))?;
+        Ok(())
+    }
+
     async fn create_table(
         &self,
         schema_id: SchemaId,
         table_name: &str,
         column_descs: &[ColumnCatalog],
         ordered_pk_ids: &[ColumnId],
+        unique_keys: &[Vec<ColumnId>],
+        foreign_keys: &[ForeignKey],
     ) -> StorageResult<()> {
         let schema = self
             .catalog
@@ -86,6 +97,8 @@ impl Storage for InMemoryStorage {
                 column_descs.to_vec(),
                 false,
                 ordered_pk_ids.to_vec(),
+                unique_keys.to_vec(),
+                foreign_keys.to_vec(),
             )
             .map_err(|_| StorageError::Duplicated("table", table_name.into()))?;
 
@@ -93,7 +106,7 @@ impl Storage for InMemoryStorage {
             schema_id,
             table_id,
         };
-        let table = InMemoryTable::new(id, column_descs);
+        let table = InMemoryTable::new(id, column_descs, ordered_pk_ids);
         self.tables.lock().unwrap().insert(id, table);
         Ok(())
     }
@@ -118,4 +131,10 @@ impl Storage for InMemoryStorage {
         self.catalog.drop_table(table_id);
         Ok(())
     }
+
+    async fn truncate_table(&self, table_id: TableRefId) -> StorageResult<()> {
+        let table = self.get_table(table_id)?;
+        table.inner.write().unwrap().truncate();
+        Ok(())
+    }
 }
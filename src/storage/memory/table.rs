@@ -6,7 +6,7 @@ use std::vec::Vec;
 
 use super::*;
 use crate::array::DataChunk;
-use crate::catalog::TableRefId;
+use crate::catalog::{ColumnId, TableRefId};
 use crate::storage::Table;
 
 /// A table in in-memory engine. This struct can be freely cloned, as it
@@ -15,6 +15,7 @@ use crate::storage::Table;
 pub struct InMemoryTable {
     pub(super) table_ref_id: TableRefId,
     pub(super) columns: Arc<[ColumnCatalog]>,
+    pub(super) ordered_pk_ids: Arc<[ColumnId]>,
     pub(super) inner: InMemoryTableInnerRef,
 }
 
@@ -43,6 +44,11 @@ impl InMemoryTableInner {
         Ok(())
     }
 
+    pub fn truncate(&mut self) {
+        self.chunks.clear();
+        self.deleted_rows.clear();
+    }
+
     pub fn get_all_chunks(&self) -> Vec<DataChunk> {
         self.chunks.clone()
     }
@@ -53,10 +59,15 @@ impl InMemoryTableInner {
 }
 
 impl InMemoryTable {
-    pub fn new(table_ref_id: TableRefId, columns: &[ColumnCatalog]) -> Self {
+    pub fn new(
+        table_ref_id: TableRefId,
+        columns: &[ColumnCatalog],
+        ordered_pk_ids: &[ColumnId],
+    ) -> Self {
         Self {
             table_ref_id,
             columns: columns.into(),
+            ordered_pk_ids: ordered_pk_ids.into(),
             inner: Arc::new(RwLock::new(InMemoryTableInner::new())),
         }
     }
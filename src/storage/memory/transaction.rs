@@ -7,8 +7,8 @@ use itertools::Itertools;
 
 use super::table::InMemoryTableInnerRef;
 use super::{InMemoryRowHandler, InMemoryTable, InMemoryTxnIterator};
-use crate::array::{ArrayBuilderImpl, ArrayImplBuilderPickExt, ArrayImplSortExt, DataChunk};
-use crate::catalog::{find_sort_key_id, ColumnCatalog};
+use crate::array::{ArrayBuilderImpl, ArrayImplBuilderPickExt, DataChunk};
+use crate::catalog::{find_sort_key_id, ColumnCatalog, ColumnId};
 use crate::storage::{ScanOptions, StorageColumnRef, StorageResult, Transaction};
 
 /// A transaction running on `InMemoryStorage`.
@@ -36,6 +36,9 @@ pub struct InMemoryTransaction {
 
     /// All information about columns
     column_infos: Arc<[ColumnCatalog]>,
+
+    /// Primary key columns in order
+    ordered_pk_ids: Arc<[ColumnId]>,
 }
 
 impl InMemoryTransaction {
@@ -49,16 +52,19 @@ impl InMemoryTransaction {
             snapshot: Arc::new(inner.get_all_chunks()),
             deleted_rows: Arc::new(inner.get_all_deleted_rows()),
             column_infos: table.columns.clone(),
+            ordered_pk_ids: table.ordered_pk_ids.clone(),
         })
     }
 }
 
-/// If primary key is found in [`ColumnCatalog`], sort all in-memory data using that key.
+/// If the table has a primary key, sort all in-memory data by the primary key columns.
 fn sort_datachunk_by_pk(
     chunks: &Arc<Vec<DataChunk>>,
     column_infos: &[ColumnCatalog],
+    ordered_pk_ids: &[ColumnId],
 ) -> Arc<Vec<DataChunk>> {
-    if let Some(sort_key_id) = find_sort_key_id(column_infos) {
+    let sort_key = find_sort_key_id(column_infos, ordered_pk_ids);
+    if !sort_key.is_empty() {
         if chunks.is_empty() {
             return chunks.clone();
         }
@@ -78,7 +84,9 @@ fn sort_datachunk_by_pk(
             .into_iter()
             .map(|builder| builder.finish())
             .collect_vec();
-        let sorted_index = arrays[sort_key_id].get_sorted_indices();
+        let sorted_index = (0..arrays[0].len())
+            .sorted_by_key(|row| sort_key.iter().map(|i| arrays[*i].get(*row)).collect_vec())
+            .collect_vec();
 
         let chunk = arrays
             .into_iter()
@@ -109,7 +117,7 @@ impl Transaction for InMemoryTransaction {
         assert!(!opts.reversed, "reverse iterator is not supported for now");
 
         let snapshot = if opts.is_sorted {
-            sort_datachunk_by_pk(&self.snapshot, &self.column_infos)
+            sort_datachunk_by_pk(&self.snapshot, &self.column_infos, &self.ordered_pk_ids)
         } else {
             self.snapshot.clone()
         };
@@ -118,6 +126,7 @@ impl Transaction for InMemoryTransaction {
             snapshot,
             self.deleted_rows.clone(),
             col_idx,
+            opts.partition,
         ))
     }
 
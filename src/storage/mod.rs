@@ -21,7 +21,7 @@ pub use chunk::*;
 use enum_dispatch::enum_dispatch;
 
 use crate::array::{ArrayImpl, DataChunk};
-use crate::catalog::{ColumnCatalog, ColumnId, SchemaId, TableRefId};
+use crate::catalog::{ColumnCatalog, ColumnId, ForeignKey, SchemaId, TableRefId};
 use crate::types::DataValue;
 
 #[enum_dispatch(StorageDispatch)]
@@ -70,12 +70,19 @@ pub trait Storage: Sync + Send + 'static {
     /// Type of the table belonging to this storage engine.
     type Table: Table<Transaction = Self::Transaction>;
 
+    fn create_schema<'a>(
+        &'a self,
+        schema_name: &'a str,
+    ) -> impl Future<Output = StorageResult<()>> + Send + 'a;
+
     fn create_table<'a>(
         &'a self,
         schema_id: SchemaId,
         table_name: &'a str,
         column_descs: &'a [ColumnCatalog],
         ordered_pk_ids: &'a [ColumnId],
+        unique_keys: &'a [Vec<ColumnId>],
+        foreign_keys: &'a [ForeignKey],
     ) -> impl Future<Output = StorageResult<()>> + Send + 'a;
 
     fn get_table(&self, table_id: TableRefId) -> StorageResult<Self::Table>;
@@ -84,6 +91,12 @@ pub trait Storage: Sync + Send + 'static {
         &self,
         table_id: TableRefId,
     ) -> impl Future<Output = StorageResult<()>> + Send + '_;
+
+    /// Removes all rows from the table.
+    fn truncate_table(
+        &self,
+        table_id: TableRefId,
+    ) -> impl Future<Output = StorageResult<()>> + Send + '_;
 }
 
 /// A table in the storage engine. [`Table`] is by default a reference to a table,
@@ -130,7 +143,7 @@ pub trait RowHandler: Sync + Send + 'static {
 /// Dropping a [`Transaction`] implicitly aborts it.
 pub trait Transaction: Sync + Send + 'static {
     /// Type of the table iterator
-    type TxnIteratorType: TxnIterator;
+    type TxnIteratorType: TxnIterator + 'static;
 
     /// Type of the unique reference to a row
     type RowHandlerType: RowHandler;
@@ -166,6 +179,13 @@ pub struct ScanOptions {
     is_sorted: bool,
     reversed: bool,
     filter: Option<KeyRange>,
+    in_list: Option<InList>,
+    /// `(column, value)`: conditions that the columns equal to the values.
+    equal_to: Vec<(u32, DataValue)>,
+    /// Columns whose values must not be null.
+    not_null: Vec<u32>,
+    /// `(index, count)`: only scan the `index`-th of `count` disjoint parts of the table.
+    partition: Option<(usize, usize)>,
 }
 
 impl ScanOptions {
@@ -175,10 +195,42 @@ impl ScanOptions {
         self
     }
 
+    /// Scan with a multi-column IN-list to skip blocks.
+    pub fn with_in_list_opt(mut self, in_list: Option<InList>) -> Self {
+        self.in_list = in_list;
+        self
+    }
+
+    /// Scan with equality conditions on storage columns.
+    ///
+    /// Like [`InList`], it is only used to skip rows by indexes if available.
+    /// The returned rows may not match the conditions.
+    pub fn with_equal_to(mut self, equal_to: Vec<(u32, DataValue)>) -> Self {
+        self.equal_to = equal_to;
+        self
+    }
+
+    /// Scan with `IS NOT NULL` conditions on storage columns.
+    ///
+    /// It is only used to skip blocks where all values of the columns are null.
+    /// The returned rows may not match the conditions.
+    pub fn with_not_null(mut self, not_null: Vec<u32>) -> Self {
+        self.not_null = not_null;
+        self
+    }
+
     pub fn with_sorted(mut self, sorted: bool) -> Self {
         self.is_sorted = sorted;
         self
     }
+
+    /// Scan the `index`-th of `count` disjoint parts of the table, so that the parts can be
+    /// scanned in parallel. The table is partitioned by rowsets or chunks.
+    pub fn with_partition(mut self, index: usize, count: usize) -> Self {
+        assert!(index < count, "invalid partition {index} of {count}");
+        self.partition = Some((index, count));
+        self
+    }
 }
 
 /// A range of keys.
@@ -223,6 +275,30 @@ impl RangeBounds<DataValue> for KeyRange {
     }
 }
 
+/// A list of tuples that the values of some columns must match.
+///
+/// Unlike [`KeyRange`], it is only used to skip blocks that can not contain any of the tuples.
+/// Rows in the remaining blocks are not filtered.
+///
+/// # Example
+/// ```text
+/// // (v1, v2) IN ((1, 2), (3, 4))
+/// InList {
+///     columns: vec![0, 1],
+///     values: vec![
+///         vec![DataValue::Int32(1), DataValue::Int32(2)],
+///         vec![DataValue::Int32(3), DataValue::Int32(4)],
+///     ],
+/// }
+/// ```
+#[derive(Debug, Clone, Serialize)]
+pub struct InList {
+    /// Storage column indexes.
+    pub columns: Vec<u32>,
+    /// Tuples of values, in the order of `columns`.
+    pub values: Vec<Vec<DataValue>>,
+}
+
 /// An iterator over table in a transaction.
 pub trait TxnIterator: Send {
     /// get next batch of elements
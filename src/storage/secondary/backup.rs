@@ -0,0 +1,309 @@
+// Copyright 2023 RisingLight Project Authors. Licensed under Apache-2.0.
+
+//! Backup and restore of tables.
+//!
+//! A backup file contains the catalog entry of a table, and the column files and deleted rows of
+//! all its rowsets in a snapshot. Rowsets are restored as they are, so the primary key order and
+//! block structure of the table are preserved.
+//!
+//! The file starts with [`MAGIC`] and the length of a JSON header describing the table, followed
+//! by the header and the raw content of all column files in the order they appear in the header.
+
+use std::path::Path;
+
+use bytes::{Buf, BufMut, Bytes};
+use risinglight_proto::rowset::DeleteRecord;
+use serde::{Deserialize, Serialize};
+use tokio::io::AsyncWriteExt;
+
+use super::*;
+use crate::storage::StorageError;
+
+/// The magic number at the beginning of a backup file.
+const MAGIC: &[u8; 8] = b"RLBACKUP";
+
+/// The header of a backup file.
+#[derive(Serialize, Deserialize)]
+struct TableBackup {
+    column_descs: Vec<ColumnCatalog>,
+    ordered_pk_ids: Vec<ColumnId>,
+    #[serde(default)]
+    unique_keys: Vec<Vec<ColumnId>>,
+    #[serde(default)]
+    foreign_keys: Vec<ForeignKey>,
+    /// Rowsets in the order of creation.
+    rowsets: Vec<RowsetBackup>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RowsetBackup {
+    /// The files of each column.
+    columns: Vec<ColumnBackup>,
+    /// Row ids deleted from the rowset.
+    deletes: Vec<u32>,
+}
+
+/// The length of each file of a column in the backup file.
+#[derive(Serialize, Deserialize)]
+struct ColumnBackup {
+    data: u64,
+    index: u64,
+    /// The bitmap index is optional.
+    bitmap: Option<u64>,
+}
+
+impl SecondaryStorage {
+    /// Serialize the catalog entry and rowsets of a table to the file at `path`.
+    pub async fn backup_table(
+        &self,
+        table_id: TableRefId,
+        path: impl AsRef<Path>,
+    ) -> StorageResult<()> {
+        let table = self.get_table_inner(table_id)?;
+        let catalog = (self.catalog.get_table(&table_id))
+            .ok_or_else(|| TracedStorageError::not_found("table", table_id.table_id))?;
+
+        // pin a version so that the rowsets won't be vacuumed while reading
+        let version = self.version.pin();
+        let mut rowset_ids = (version.snapshot.get_rowsets_of(table_id.table_id))
+            .map(|ids| ids.iter().copied().collect::<Vec<_>>())
+            .unwrap_or_default();
+        rowset_ids.sort_unstable();
+
+        let io_backend = &self.options.io_backend;
+        let mut rowsets = vec![];
+        let mut files = vec![];
+        for rowset_id in rowset_ids {
+            let directory = table.get_rowset_path(rowset_id);
+            let mut columns = vec![];
+            for column in table.columns.iter() {
+                let data = read_file(io_backend, path_of_data_column(&directory, column)).await?;
+                let index = read_file(io_backend, path_of_index_column(&directory, column)).await?;
+                let bitmap_path = path_of_bitmap_index_column(&directory, column);
+                let bitmap = try_read_file(io_backend, bitmap_path).await?;
+                columns.push(ColumnBackup {
+                    data: data.len() as u64,
+                    index: index.len() as u64,
+                    bitmap: bitmap.as_ref().map(|bitmap| bitmap.len() as u64),
+                });
+                files.extend([data, index]);
+                files.extend(bitmap);
+            }
+            let mut deletes = vec![];
+            if let Some(dvs) = version.snapshot.get_dvs_of(table_id.table_id, rowset_id) {
+                for dv_id in dvs {
+                    deletes.extend_from_slice(
+                        self.version.get_dv(table_id.table_id, *dv_id).deletes(),
+                    );
+                }
+            }
+            deletes.sort_unstable();
+            deletes.dedup();
+            rowsets.push(RowsetBackup { columns, deletes });
+        }
+
+        let header = serde_json::to_vec(&TableBackup {
+            column_descs: table.columns.to_vec(),
+            ordered_pk_ids: catalog.primary_keys(),
+            unique_keys: catalog.unique_keys().to_vec(),
+            foreign_keys: catalog.foreign_keys().to_vec(),
+            rowsets,
+        })?;
+        let mut buf = vec![];
+        buf.put_slice(MAGIC);
+        buf.put_u64_le(header.len() as u64);
+        buf.put_slice(&header);
+        let mut file = tokio::fs::File::create(path).await?;
+        file.write_all(&buf).await?;
+        for data in files {
+            file.write_all(&data).await?;
+        }
+        file.sync_data().await?;
+        Ok(())
+    }
+
+    /// Restore a table from the backup file at `path`.
+    ///
+    /// Returns an error if the table already exists, unless `replace` is set, in which case the
+    /// existing table is dropped in the same transaction. A table referenced by a FOREIGN KEY
+    /// constraint of another table can not be replaced.
+    pub async fn restore_table(
+        &self,
+        schema_id: SchemaId,
+        table_name: &str,
+        path: impl AsRef<Path>,
+        replace: bool,
+    ) -> StorageResult<()> {
+        // decode the whole file before touching the catalog
+        let mut buf = Bytes::from(tokio::fs::read(path).await?);
+        let mut take = |len: u64| {
+            if (buf.remaining() as u64) < len {
+                return Err(TracedStorageError::decode("backup file is truncated"));
+            }
+            Ok(buf.split_to(len as usize))
+        };
+        if take(MAGIC.len() as u64)? != MAGIC[..] {
+            return Err(TracedStorageError::decode("not a backup file"));
+        }
+        let header_len = take(8)?.get_u64_le();
+        let backup: TableBackup = serde_json::from_slice(&take(header_len)?)?;
+        if (backup.rowsets.iter()).any(|rowset| rowset.columns.len() != backup.column_descs.len()) {
+            return Err(TracedStorageError::decode("column count mismatch"));
+        }
+        let mut files = vec![];
+        for rowset in &backup.rowsets {
+            let mut columns = vec![];
+            for column in &rowset.columns {
+                let data = take(column.data)?;
+                let index = take(column.index)?;
+                let bitmap = column.bitmap.map(&mut take).transpose()?;
+                columns.push((data, index, bitmap));
+            }
+            files.push(columns);
+        }
+
+        let schema = (self.catalog.get_schema_by_id(schema_id))
+            .ok_or_else(|| TracedStorageError::not_found("schema", schema_id))?;
+        let mut changeset = vec![];
+        if let Some(table_id) = schema.get_table_id_by_name(table_name) {
+            if !replace {
+                return Err(TracedStorageError::duplicated("table", table_name));
+            }
+            let table_id = TableRefId {
+                schema_id,
+                table_id,
+            };
+            for (referencing, _) in self.catalog.get_referencing_foreign_keys(&table_id) {
+                if referencing != table_id {
+                    let name = |id| self.catalog.get_table(id).unwrap().name();
+                    return Err(
+                        StorageError::ReferencedTable(name(&table_id), name(&referencing)).into(),
+                    );
+                }
+            }
+            let entry = DropTableEntry { table_id };
+            self.apply_drop_table(&entry)?;
+            changeset.push(EpochOp::DropTable(entry));
+            changeset.extend(self.delete_all_rowsets(table_id));
+        }
+        let entry = CreateTableEntry {
+            schema_id,
+            table_name: table_name.to_string(),
+            column_descs: backup.column_descs,
+            ordered_pk_ids: backup.ordered_pk_ids,
+            unique_keys: backup.unique_keys,
+            foreign_keys: backup.foreign_keys,
+        };
+        self.apply_create_table(&entry)?;
+        changeset.push(EpochOp::CreateTable(entry));
+        let table_id = TableRefId {
+            schema_id,
+            table_id: (self.catalog.get_schema_by_id(schema_id))
+                .and_then(|schema| schema.get_table_id_by_name(table_name))
+                .ok_or_else(|| TracedStorageError::not_found("table", table_name))?,
+        };
+        let table = self.get_table_inner(table_id)?;
+
+        let io_backend = &self.options.io_backend;
+        for (rowset, columns) in backup.rowsets.into_iter().zip(files) {
+            let rowset_id = table.generate_rowset_id();
+            let directory = table.get_rowset_path(rowset_id);
+            if !io_backend.is_in_memory() {
+                tokio::fs::create_dir(&directory).await?;
+            }
+            for (column, (data, index, bitmap)) in table.columns.iter().zip(columns) {
+                write_file(io_backend, path_of_data_column(&directory, column), data).await?;
+                write_file(io_backend, path_of_index_column(&directory, column), index).await?;
+                if let Some(bitmap) = bitmap {
+                    let path = path_of_bitmap_index_column(&directory, column);
+                    write_file(io_backend, path, bitmap).await?;
+                }
+            }
+            let disk_rowset = DiskRowset::open(
+                directory,
+                table.columns.clone(),
+                self.block_cache.clone(),
+                rowset_id,
+                io_backend.clone(),
+            )
+            .await?;
+            changeset.push(EpochOp::AddRowSet((
+                AddRowSetEntry {
+                    table_id,
+                    rowset_id,
+                },
+                disk_rowset,
+            )));
+
+            if rowset.deletes.is_empty() {
+                continue;
+            }
+            let dv_id = table.generate_dv_id();
+            let deletes = (rowset.deletes.into_iter())
+                .map(|row_id| DeleteRecord { row_id })
+                .collect::<Vec<_>>();
+            let mut buf = vec![];
+            DeleteVector::write_all(&mut buf, &deletes).await?;
+            write_file(io_backend, table.get_dv_path(rowset_id, dv_id), buf.into()).await?;
+            changeset.push(EpochOp::AddDV((
+                AddDVEntry {
+                    table_id,
+                    dv_id,
+                    rowset_id,
+                },
+                DeleteVector::new(dv_id, rowset_id, deletes),
+            )));
+        }
+        // the old table, the new table and its rowsets are persisted in one epoch
+        self.version.commit_changes(changeset).await?;
+        Ok(())
+    }
+}
+
+/// Read the whole file from the IO backend.
+async fn read_file(io_backend: &IOBackend, path: impl AsRef<Path>) -> StorageResult<Bytes> {
+    let path = path.as_ref();
+    try_read_file(io_backend, path)
+        .await?
+        .ok_or_else(|| TracedStorageError::not_found("file", path.display()))
+}
+
+/// Read the whole file from the IO backend, or returns `None` if it doesn't exist.
+async fn try_read_file(
+    io_backend: &IOBackend,
+    path: impl AsRef<Path>,
+) -> StorageResult<Option<Bytes>> {
+    let path = path.as_ref();
+    match io_backend {
+        IOBackend::InMemory(map) => Ok(map.lock().get(path).cloned()),
+        _ => match tokio::fs::read(path).await {
+            Ok(data) => Ok(Some(data.into())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        },
+    }
+}
+
+/// Write a new file to the IO backend.
+async fn write_file(
+    io_backend: &IOBackend,
+    path: impl AsRef<Path>,
+    data: Bytes,
+) -> StorageResult<()> {
+    let path = path.as_ref();
+    match io_backend {
+        IOBackend::InMemory(map) => {
+            map.lock().insert(path.to_path_buf(), data);
+        }
+        _ => {
+            let mut file = tokio::fs::OpenOptions::default()
+                .write(true)
+                .create_new(true)
+                .open(path)
+                .await?;
+            file.write_all(&data).await?;
+            file.sync_data().await?;
+        }
+    }
+    Ok(())
+}
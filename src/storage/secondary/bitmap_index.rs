@@ -0,0 +1,249 @@
+// Copyright 2023 RisingLight Project Authors. Licensed under Apache-2.0.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+use bitvec::prelude::BitVec;
+use bytes::{Buf, BufMut};
+use risinglight_proto::rowset::block_checksum::ChecksumType;
+
+use super::{build_checksum, verify_checksum, PrimitiveFixedWidthEncode};
+use crate::array::ArrayImpl;
+use crate::storage::{StorageResult, TracedStorageError};
+use crate::types::{DataTypeKind, DataValue};
+
+pub const BITMAP_INDEX_MAGIC: u32 = 0x2334;
+pub const BITMAP_INDEX_FOOTER_SIZE: usize = 4 + 4 + 8;
+
+/// The maximum number of distinct values in a column to build a bitmap index.
+pub const BITMAP_INDEX_MAX_DISTINCT: usize = 64;
+
+/// Returns true if a bitmap index can be built on columns of the type.
+pub fn support_bitmap_index(kind: &DataTypeKind) -> bool {
+    matches!(kind, DataTypeKind::Int32 | DataTypeKind::String)
+}
+
+/// Builds bitmap index file for a column.
+///
+/// The `.bitmap` file records the rows of each distinct value in the rowset. NULLs are not
+/// recorded. The last 16 bytes of the file is the checksum.
+///
+/// ```plain
+/// | row count (4B) | value count (4B) | value | bitmap | value | bitmap | ... | magic number (4B) | checksum type (4B) | checksum (8B) |
+/// ```
+///
+/// An `INT` value takes 4 bytes, and a string value is prefixed by its length (4B).
+/// Each bitmap takes `(row count + 7) / 8` bytes.
+pub struct BitmapIndexBuilder {
+    /// Rows of each distinct value, or `None` if there are too many distinct values.
+    bitmaps: Option<BTreeMap<DataValue, BitVec>>,
+    row_count: usize,
+    checksum_type: ChecksumType,
+}
+
+impl BitmapIndexBuilder {
+    pub fn new(checksum_type: ChecksumType) -> Self {
+        Self {
+            bitmaps: Some(BTreeMap::new()),
+            row_count: 0,
+            checksum_type,
+        }
+    }
+
+    pub fn append(&mut self, array: &ArrayImpl) {
+        if let Some(bitmaps) = &mut self.bitmaps {
+            for i in 0..array.len() {
+                let value = array.get(i);
+                if value.is_null() {
+                    continue;
+                }
+                let rows = bitmaps.entry(value).or_default();
+                rows.resize(self.row_count + i, false);
+                rows.push(true);
+            }
+            if bitmaps.len() > BITMAP_INDEX_MAX_DISTINCT {
+                self.bitmaps = None;
+            }
+        }
+        self.row_count += array.len();
+    }
+
+    /// Returns the encoded index, or `None` if there are too many distinct values.
+    pub fn finish(self) -> Option<Vec<u8>> {
+        let bitmaps = self.bitmaps?;
+        let mut data = vec![];
+        data.put_u32(self.row_count as u32);
+        data.put_u32(bitmaps.len() as u32);
+        for (value, mut rows) in bitmaps {
+            match value {
+                DataValue::Int32(v) => v.encode(&mut data),
+                DataValue::String(s) => {
+                    data.put_u32(s.len() as u32);
+                    data.put_slice(s.as_bytes());
+                }
+                _ => panic!("unsupported value for bitmap index: {value}"),
+            }
+            rows.resize(self.row_count, false);
+            for bits in rows.chunks(8) {
+                let byte = (bits.iter().by_vals().enumerate())
+                    .fold(0u8, |byte, (i, bit)| byte | (bit as u8) << i);
+                data.put_u8(byte);
+            }
+        }
+        let checksum = build_checksum(self.checksum_type, &data);
+
+        data.put_u32(BITMAP_INDEX_MAGIC);
+        data.put_i32(self.checksum_type.into());
+        data.put_u64(checksum);
+
+        Some(data)
+    }
+}
+
+/// The bitmap index of a column.
+#[derive(Clone)]
+pub struct BitmapIndex {
+    bitmaps: Arc<HashMap<DataValue, BitVec>>,
+    row_count: usize,
+}
+
+impl BitmapIndex {
+    pub fn from_bytes(data: &[u8], kind: &DataTypeKind) -> StorageResult<Self> {
+        if data.len() < BITMAP_INDEX_FOOTER_SIZE {
+            return Err(TracedStorageError::decode(
+                "failed to decode bitmap index: too short",
+            ));
+        }
+        let mut index_data = &data[..data.len() - BITMAP_INDEX_FOOTER_SIZE];
+        let mut footer = &data[data.len() - BITMAP_INDEX_FOOTER_SIZE..];
+        if footer.get_u32() != BITMAP_INDEX_MAGIC {
+            return Err(TracedStorageError::decode(
+                "failed to decode bitmap index: invalid magic",
+            ));
+        }
+        let checksum_type = ChecksumType::from_i32(footer.get_i32())
+            .ok_or_else(|| TracedStorageError::decode("invalid checksum type"))?;
+        let checksum = footer.get_u64();
+        verify_checksum(checksum_type, index_data, checksum)?;
+
+        let row_count = take(&mut index_data, 4)?.get_u32() as usize;
+        let value_count = take(&mut index_data, 4)?.get_u32() as usize;
+        if value_count > BITMAP_INDEX_MAX_DISTINCT {
+            return Err(TracedStorageError::decode(
+                "failed to decode bitmap index: too many values",
+            ));
+        }
+        let mut bitmaps = HashMap::with_capacity(value_count);
+        for _ in 0..value_count {
+            let value = match kind {
+                DataTypeKind::Int32 => {
+                    DataValue::Int32(i32::decode(&mut take(&mut index_data, 4)?))
+                }
+                DataTypeKind::String => {
+                    let len = take(&mut index_data, 4)?.get_u32() as usize;
+                    let s = String::from_utf8(take(&mut index_data, len)?.to_vec())
+                        .map_err(|_| TracedStorageError::decode("invalid utf8 value"))?;
+                    DataValue::String(s)
+                }
+                _ => return Err(TracedStorageError::decode("unsupported bitmap index type")),
+            };
+            let bytes = take(&mut index_data, (row_count + 7) / 8)?;
+            let rows: BitVec = (0..row_count)
+                .map(|i| (bytes[i / 8] >> (i % 8)) & 1 == 1)
+                .collect();
+            bitmaps.insert(value, rows);
+        }
+        if !index_data.is_empty() {
+            return Err(TracedStorageError::decode(
+                "failed to decode bitmap index: trailing data",
+            ));
+        }
+
+        Ok(Self {
+            bitmaps: Arc::new(bitmaps),
+            row_count,
+        })
+    }
+
+    /// Returns the rows equal to the value.
+    pub fn rows(&self, value: &DataValue) -> BitVec {
+        match self.bitmaps.get(value) {
+            Some(rows) => rows.clone(),
+            None => BitVec::repeat(false, self.row_count),
+        }
+    }
+}
+
+/// Splits the first `len` bytes off `data`, or returns an error if `data` is too short.
+fn take<'a>(data: &mut &'a [u8], len: usize) -> StorageResult<&'a [u8]> {
+    if data.len() < len {
+        return Err(TracedStorageError::decode(
+            "failed to decode bitmap index: truncated",
+        ));
+    }
+    let (head, tail) = data.split_at(len);
+    *data = tail;
+    Ok(head)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_decode() {
+        let mut builder = BitmapIndexBuilder::new(ChecksumType::Crc32);
+        builder.append(&ArrayImpl::new_utf8(
+            [Some("a"), None, Some("b")].into_iter().collect(),
+        ));
+        builder.append(&ArrayImpl::new_utf8(
+            [Some("b"), Some("a")].into_iter().collect(),
+        ));
+        let data = builder.finish().unwrap();
+
+        let index = BitmapIndex::from_bytes(&data, &DataTypeKind::String).unwrap();
+        let rows = |s: &str| index.rows(&DataValue::String(s.into()));
+        assert_eq!(
+            rows("a").iter().by_vals().collect::<Vec<_>>(),
+            [true, false, false, false, true]
+        );
+        assert_eq!(
+            rows("b").iter().by_vals().collect::<Vec<_>>(),
+            [false, false, true, true, false]
+        );
+        assert!(rows("c").not_any());
+        assert_eq!(rows("c").len(), 5);
+    }
+
+    #[test]
+    fn too_many_distinct_values() {
+        let mut builder = BitmapIndexBuilder::new(ChecksumType::None);
+        builder.append(&ArrayImpl::new_int32(
+            (0..BITMAP_INDEX_MAX_DISTINCT as i32 + 1).collect(),
+        ));
+        assert!(builder.finish().is_none());
+    }
+
+    #[test]
+    fn decode_corrupted() {
+        let mut builder = BitmapIndexBuilder::new(ChecksumType::None);
+        builder.append(&ArrayImpl::new_utf8(
+            [Some("a"), Some("bc")].into_iter().collect(),
+        ));
+        let data = builder.finish().unwrap();
+        let (index_data, footer) = data.split_at(data.len() - BITMAP_INDEX_FOOTER_SIZE);
+
+        // truncated at every position of the index data
+        for len in 0..index_data.len() {
+            let data = [&index_data[..len], footer].concat();
+            assert!(BitmapIndex::from_bytes(&data, &DataTypeKind::String).is_err());
+        }
+        // oversized string length
+        let mut data = data.clone();
+        data[8..12].copy_from_slice(&u32::MAX.to_be_bytes());
+        assert!(BitmapIndex::from_bytes(&data, &DataTypeKind::String).is_err());
+        // too many values
+        data[4..8].copy_from_slice(&u32::MAX.to_be_bytes());
+        assert!(BitmapIndex::from_bytes(&data, &DataTypeKind::String).is_err());
+    }
+}
@@ -14,6 +14,9 @@ pub struct BlockIndexBuilder {
     /// Begin row count of the current block
     last_row_count: usize,
 
+    /// Count of null values in the current block
+    null_count: usize,
+
     /// All indexes in the current builder
     indexes: Vec<BlockIndex>,
 
@@ -29,6 +32,7 @@ impl BlockIndexBuilder {
         Self {
             row_count: 0,
             last_row_count: 0,
+            null_count: 0,
             indexes: vec![],
             block_header: vec![],
             options,
@@ -43,7 +47,10 @@ impl BlockIndexBuilder {
         block_data: &mut Vec<u8>,
         stats: Vec<BlockStatistics>,
         first_key: Option<Vec<u8>>,
+        min_max: Option<(Vec<u8>, Vec<u8>)>,
     ) {
+        let has_min_max = min_max.is_some();
+        let (min_key, max_key) = min_max.unwrap_or_default();
         self.indexes.push(BlockIndex {
             offset: column_data.len() as u64,
             length: block_data.len() as u64 + BLOCK_META_SIZE as u64,
@@ -53,10 +60,15 @@ impl BlockIndexBuilder {
             is_first_key_null: first_key.is_none(),
             first_key: first_key.unwrap_or_default(),
             stats,
+            min_key,
+            max_key,
+            has_min_max,
+            null_count: self.null_count as u32,
         });
 
         // the new block will begin at the current row count
         self.last_row_count = self.row_count;
+        self.null_count = 0;
 
         self.block_header.resize(BLOCK_META_SIZE, 0);
         let mut block_header_nonchecksum = &mut self.block_header[..BLOCK_META_NON_CHECKSUM_SIZE];
@@ -89,6 +101,11 @@ impl BlockIndexBuilder {
         self.row_count += rows;
     }
 
+    /// Add null values of the new rows into the block index
+    pub fn add_nulls(&mut self, nulls: usize) {
+        self.null_count += nulls;
+    }
+
     pub fn into_index(self) -> Vec<BlockIndex> {
         self.indexes
     }
@@ -7,6 +7,12 @@ use risinglight_proto::rowset::BlockStatistics;
 use super::{BlockBuilder, NonNullableBlockBuilder};
 use crate::array::Array;
 
+/// Bitmap length written in place of the bitmap if the block contains no null value.
+pub(super) const BITMAP_ALL_PRESENT: u32 = u32::MAX;
+
+/// Bitmap length written in place of the bitmap if the block contains only null values.
+pub(super) const BITMAP_ALL_NULL: u32 = u32::MAX - 1;
+
 pub struct NullableBlockBuilder<A, B>
 where
     A: Array,
@@ -70,8 +76,14 @@ where
 
     fn finish(self) -> Vec<u8> {
         let mut data = self.inner_builder.finish();
-        data.extend(self.bitmap.as_raw_slice().iter());
-        data.put_u32_le(self.bitmap.as_raw_slice().len() as u32);
+        if self.bitmap.all() {
+            data.put_u32_le(BITMAP_ALL_PRESENT);
+        } else if self.bitmap.not_any() {
+            data.put_u32_le(BITMAP_ALL_NULL);
+        } else {
+            data.extend(self.bitmap.as_raw_slice().iter());
+            data.put_u32_le(self.bitmap.as_raw_slice().len() as u32);
+        }
         data
     }
 
@@ -114,4 +126,24 @@ mod tests {
         assert!(!builder.should_finish(&Some("23333333")));
         builder.finish();
     }
+
+    #[test]
+    fn test_build_compact_bitmap() {
+        let build = |items: &[Option<i32>]| {
+            let inner_builder = PlainPrimitiveBlockBuilder::<i32>::new(128);
+            let mut builder = NullableBlockBuilder::new(inner_builder, 128);
+            for item in items {
+                builder.append(item.as_ref());
+            }
+            builder.finish()
+        };
+
+        // all-present and all-null blocks only have a 4-byte flag after the values
+        assert_eq!(build(&[Some(1); 10]).len(), 40 + 4);
+        assert_eq!(build(&[None; 10]).len(), 40 + 4);
+        // mixed blocks keep the full bitmap
+        let mut items = [Some(1); 10];
+        items[3] = None;
+        assert_eq!(build(&items).len(), 40 + 2 + 4);
+    }
 }
@@ -5,15 +5,33 @@ use bitvec::slice::BitSlice;
 use bitvec::vec::BitVec;
 use bytes::Buf;
 
+use super::nullable_block_builder::{BITMAP_ALL_NULL, BITMAP_ALL_PRESENT};
 use super::{Block, BlockIterator, NonNullableBlockIterator};
 use crate::array::{Array, ArrayBuilder};
 
-pub fn decode_nullable_block(data: Block) -> (Block, Block) {
+/// Validity bitmap of a nullable block.
+pub enum NullBitmap {
+    /// No value in the block is null.
+    AllPresent,
+    /// All values in the block are null.
+    AllNull,
+    /// The block mixes null and non-null values.
+    Bitmap(Block),
+}
+
+pub fn decode_nullable_block(data: Block) -> (Block, NullBitmap) {
     let mut bitmap_len_buf = &data[data.len() - 4..];
-    let bitmap_len = bitmap_len_buf.get_u32_le() as usize;
-    let bitmap_block = data.slice(data.len() - 4 - bitmap_len..data.len() - 4);
-    let inner_block = data.slice(..data.len() - 4 - bitmap_len);
-    (inner_block, bitmap_block)
+    let bitmap_len = bitmap_len_buf.get_u32_le();
+    let inner_end = data.len() - 4;
+    match bitmap_len {
+        BITMAP_ALL_PRESENT => (data.slice(..inner_end), NullBitmap::AllPresent),
+        BITMAP_ALL_NULL => (data.slice(..inner_end), NullBitmap::AllNull),
+        len => {
+            let bitmap_start = inner_end - len as usize;
+            let bitmap_block = data.slice(bitmap_start..inner_end);
+            (data.slice(..bitmap_start), NullBitmap::Bitmap(bitmap_block))
+        }
+    }
 }
 
 pub struct NullableBlockIterator<A, B>
@@ -25,7 +43,7 @@ where
     inner_iter: B,
     /// Indicates current position in the block
     cur_row: usize,
-    bitmap_block: Block,
+    bitmap: NullBitmap,
     _phantom: PhantomData<A>,
 }
 
@@ -34,11 +52,11 @@ where
     A: Array,
     B: BlockIterator<A> + NonNullableBlockIterator<A>,
 {
-    pub fn new(inner_iter: B, bitmap_block: Block) -> Self {
+    pub fn new(inner_iter: B, bitmap: NullBitmap) -> Self {
         Self {
             inner_iter,
             cur_row: 0,
-            bitmap_block,
+            bitmap,
             _phantom: PhantomData,
         }
     }
@@ -56,12 +74,19 @@ where
     ) -> usize {
         let inner_result = self.inner_iter.next_batch_non_null(expected_size, builder);
 
-        let bitmap_slice = &BitSlice::<u8, Lsb0>::from_slice(&self.bitmap_block)
-            [self.cur_row..self.cur_row + inner_result];
-        let mut bitmap_for_builder: BitVec = BitVec::with_capacity(bitmap_slice.len());
-        bitmap_slice
-            .iter()
-            .for_each(|x| bitmap_for_builder.push(*x));
+        let bitmap_for_builder: BitVec = match &self.bitmap {
+            NullBitmap::AllPresent => BitVec::repeat(true, inner_result),
+            NullBitmap::AllNull => BitVec::repeat(false, inner_result),
+            NullBitmap::Bitmap(bitmap_block) => {
+                let bitmap_slice = &BitSlice::<u8, Lsb0>::from_slice(bitmap_block)
+                    [self.cur_row..self.cur_row + inner_result];
+                let mut bitmap_for_builder = BitVec::with_capacity(bitmap_slice.len());
+                bitmap_slice
+                    .iter()
+                    .for_each(|x| bitmap_for_builder.push(*x));
+                bitmap_for_builder
+            }
+        };
         builder.replace_bitmap(bitmap_for_builder);
         self.cur_row += inner_result;
         inner_result
@@ -142,4 +167,47 @@ mod tests {
         let mut builder = Utf8ArrayBuilder::new();
         assert_eq!(iter.next_batch(None, &mut builder), 0);
     }
+
+    #[test]
+    fn test_scan_all_present() {
+        let inner_builder = PlainPrimitiveBlockBuilder::<i32>::new(128);
+        let mut builder = NullableBlockBuilder::new(inner_builder, 128);
+        builder.append(Some(&1));
+        builder.append(Some(&2));
+        builder.append(Some(&3));
+        let data = builder.finish();
+
+        let (inner_block, bitmap) = decode_nullable_block(Bytes::from(data));
+        assert!(matches!(bitmap, NullBitmap::AllPresent));
+        let inner_iter = PlainPrimitiveBlockIterator::<i32>::new(inner_block, 3);
+        let mut iter = NullableBlockIterator::new(inner_iter, bitmap);
+        iter.skip(1);
+
+        let mut builder = I32ArrayBuilder::new();
+        assert_eq!(iter.next_batch(None, &mut builder), 2);
+        assert_eq!(builder.finish().to_vec(), vec![Some(2), Some(3)]);
+    }
+
+    #[test]
+    fn test_scan_all_null() {
+        let inner_builder = PlainBlobBlockBuilder::<str>::new(128);
+        let mut builder = NullableBlockBuilder::new(inner_builder, 128);
+        builder.append(None);
+        builder.append(None);
+        builder.append(None);
+        let data = builder.finish();
+
+        let (inner_block, bitmap) = decode_nullable_block(Bytes::from(data));
+        assert!(matches!(bitmap, NullBitmap::AllNull));
+        let inner_iter = PlainBlobBlockIterator::<str>::new(inner_block, 3);
+        let mut iter = NullableBlockIterator::new(inner_iter, bitmap);
+
+        let mut builder = Utf8ArrayBuilder::new();
+        assert_eq!(iter.next_batch(Some(2), &mut builder), 2);
+        assert_eq!(builder.finish().to_vec(), vec![None, None]);
+
+        let mut builder = Utf8ArrayBuilder::new();
+        assert_eq!(iter.next_batch(None, &mut builder), 1);
+        assert_eq!(builder.finish().to_vec(), vec![None]);
+    }
 }
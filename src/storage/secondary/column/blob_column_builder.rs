@@ -94,6 +94,7 @@ impl BlobColumnBuilder {
             &mut block_data,
             stats,
             self.first_key.clone(),
+            None,
         );
     }
 }
@@ -101,17 +102,26 @@ impl BlobColumnBuilder {
 impl ColumnBuilder<BlobArray> for BlobColumnBuilder {
     fn append(&mut self, array: &BlobArray) {
         let mut iter = array.iter().peekable();
+        let mut pos = 0;
 
         while iter.peek().is_some() {
             if self.current_builder.is_none() {
                 let target_size = self.options.target_block_size - 16;
                 match (self.nullable, self.options.encode_type) {
-                    (false, crate::storage::secondary::EncodeType::Plain) => {
+                    (
+                        false,
+                        crate::storage::secondary::EncodeType::Plain
+                        | crate::storage::secondary::EncodeType::Auto,
+                    ) => {
                         self.current_builder = Some(BlobBlockBuilderImpl::Plain(
                             PlainBlobBlockBuilder::new(target_size),
                         ));
                     }
-                    (true, crate::storage::secondary::EncodeType::Plain) => {
+                    (
+                        true,
+                        crate::storage::secondary::EncodeType::Plain
+                        | crate::storage::secondary::EncodeType::Auto,
+                    ) => {
                         self.current_builder = Some(BlobBlockBuilderImpl::PlainNullable(
                             NullableBlockBuilder::new(
                                 PlainBlobBlockBuilder::new(target_size),
@@ -179,6 +189,11 @@ impl ColumnBuilder<BlobArray> for BlobColumnBuilder {
             let (row_count, should_finish) = for_all_blob_block_builder_enum! { append_one_by_one };
 
             self.block_index_builder.add_rows(row_count);
+            let nulls = (pos..pos + row_count)
+                .filter(|&i| array.get(i).is_none())
+                .count();
+            self.block_index_builder.add_nulls(nulls);
+            pos += row_count;
 
             // finish the current block
             if should_finish {
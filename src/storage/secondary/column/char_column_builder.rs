@@ -1,10 +1,12 @@
 // Copyright 2023 RisingLight Project Authors. Licensed under Apache-2.0.
 
+use std::collections::HashSet;
+
 use risinglight_proto::rowset::block_index::BlockType;
 use risinglight_proto::rowset::BlockIndex;
 
 use super::super::{BlockBuilder, BlockIndexBuilder, PlainCharBlockBuilder};
-use super::{append_one_by_one, ColumnBuilder};
+use super::{append_one_by_one, update_min_max, ColumnBuilder};
 use crate::array::{Array, Utf8Array};
 use crate::storage::secondary::block::{
     DictBlockBuilder, NullableBlockBuilder, PlainBlobBlockBuilder, RleBlockBuilder,
@@ -49,6 +51,13 @@ macro_rules! for_all_char_block_builder_enum {
     };
 }
 
+/// Number of values sampled to estimate the cardinality of a new block.
+const CARDINALITY_SAMPLE_SIZE: usize = 1024;
+
+/// With [`EncodeType::Auto`], a block is dictionary-encoded if the sampled values contain at most
+/// `1 / DICT_CARDINALITY_RATIO` distinct values.
+const DICT_CARDINALITY_RATIO: usize = 4;
+
 /// Column builder of char types.
 pub struct CharColumnBuilder {
     data: Vec<u8>,
@@ -68,6 +77,9 @@ pub struct CharColumnBuilder {
 
     /// First key
     first_key: Option<Vec<u8>>,
+
+    /// Minimum and maximum value of the current block
+    min_max: Option<(String, String)>,
 }
 
 impl CharColumnBuilder {
@@ -80,6 +92,22 @@ impl CharColumnBuilder {
             nullable,
             char_width,
             first_key: None,
+            min_max: None,
+        }
+    }
+
+    /// Returns the encode type of a new block starting at `pos` of the array.
+    fn encode_type_at(&self, array: &Utf8Array, pos: usize) -> EncodeType {
+        let EncodeType::Auto = self.options.encode_type else {
+            return self.options.encode_type;
+        };
+        let end = array.len().min(pos + CARDINALITY_SAMPLE_SIZE);
+        let values: Vec<&str> = (pos..end).filter_map(|i| array.get(i)).collect();
+        let distinct = values.iter().collect::<HashSet<_>>().len();
+        if distinct * DICT_CARDINALITY_RATIO <= values.len() {
+            EncodeType::Dictionary
+        } else {
+            EncodeType::Plain
         }
     }
 
@@ -110,6 +138,7 @@ impl CharColumnBuilder {
             &mut block_data,
             stats,
             self.first_key.clone(),
+            (self.min_max.take()).map(|(min, max)| (min.into_bytes(), max.into_bytes())),
         );
     }
 }
@@ -117,11 +146,16 @@ impl CharColumnBuilder {
 impl ColumnBuilder<Utf8Array> for CharColumnBuilder {
     fn append(&mut self, array: &Utf8Array) {
         let mut iter = array.iter().peekable();
+        let mut pos = 0;
 
         while iter.peek().is_some() {
             if self.current_builder.is_none() {
                 let target_size = self.options.target_block_size - 16;
-                match (self.char_width, self.nullable, self.options.encode_type) {
+                match (
+                    self.char_width,
+                    self.nullable,
+                    self.encode_type_at(array, pos),
+                ) {
                     (Some(char_width), false, EncodeType::RunLength) => {
                         let builder = PlainCharBlockBuilder::new(target_size, char_width);
                         self.current_builder =
@@ -141,12 +175,12 @@ impl ColumnBuilder<Utf8Array> for CharColumnBuilder {
                             RleBlockBuilder::new(nullable_builder),
                         ));
                     }
-                    (Some(char_width), false, EncodeType::Plain) => {
+                    (Some(char_width), false, EncodeType::Plain | EncodeType::Auto) => {
                         self.current_builder = Some(CharBlockBuilderImpl::PlainFixedChar(
                             PlainCharBlockBuilder::new(target_size, char_width),
                         ));
                     }
-                    (Some(char_width), true, EncodeType::Plain) => {
+                    (Some(char_width), true, EncodeType::Plain | EncodeType::Auto) => {
                         self.current_builder = Some(CharBlockBuilderImpl::PlainNullableFixedChar(
                             NullableBlockBuilder::new(
                                 PlainCharBlockBuilder::new(target_size, char_width),
@@ -193,12 +227,12 @@ impl ColumnBuilder<Utf8Array> for CharColumnBuilder {
                             RleBlockBuilder::new(nullable_builder),
                         ));
                     }
-                    (None, false, EncodeType::Plain) => {
+                    (None, false, EncodeType::Plain | EncodeType::Auto) => {
                         self.current_builder = Some(CharBlockBuilderImpl::PlainVarchar(
                             PlainBlobBlockBuilder::new(target_size),
                         ));
                     }
-                    (None, true, EncodeType::Plain) => {
+                    (None, true, EncodeType::Plain | EncodeType::Auto) => {
                         self.current_builder = Some(CharBlockBuilderImpl::PlainNullableVarchar(
                             NullableBlockBuilder::new(
                                 PlainBlobBlockBuilder::new(target_size),
@@ -248,6 +282,15 @@ impl ColumnBuilder<Utf8Array> for CharColumnBuilder {
             let (row_count, should_finish) = for_all_char_block_builder_enum! { append_one_by_one };
 
             self.block_index_builder.add_rows(row_count);
+            let mut nulls = 0;
+            for i in pos..pos + row_count {
+                match array.get(i) {
+                    Some(item) => update_min_max(&mut self.min_max, item),
+                    None => nulls += 1,
+                }
+            }
+            self.block_index_builder.add_nulls(nulls);
+            pos += row_count;
 
             // finish the current block
             if should_finish {
@@ -245,3 +245,41 @@ impl BlockIteratorFactory<Utf8Array> for CharBlockIteratorFactory {
         it
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use super::*;
+    use crate::storage::secondary::rowset::tests::helper_build_varchar_rowset;
+    use crate::storage::secondary::{ColumnIterator, EncodeType};
+
+    #[tokio::test]
+    async fn test_scan_auto_dict_varchar() {
+        let values = ["United States", "Japan", "Germany"];
+        let tempdir = tempfile::tempdir().unwrap();
+        let auto = helper_build_varchar_rowset(&tempdir, EncodeType::Auto, &values).await;
+        let tempdir = tempfile::tempdir().unwrap();
+        let plain = helper_build_varchar_rowset(&tempdir, EncodeType::Plain, &values).await;
+
+        // the low-cardinality column is dictionary-encoded
+        assert!(auto.column(0).on_disk_size() * 2 < plain.column(0).on_disk_size());
+
+        let mut scanner = CharColumnIterator::new(
+            auto.column(0).clone(),
+            0,
+            CharBlockIteratorFactory::new(None),
+        )
+        .await
+        .unwrap();
+        let mut recv_data = vec![];
+        while let Some((_, data)) = scanner.next_batch(None).await.unwrap() {
+            recv_data.extend(data.iter().map(|s| s.map(str::to_string)));
+        }
+        let expected = (values.iter().cycle().take(1000))
+            .map(|s| Some(s.to_string()))
+            .collect_vec()
+            .repeat(100);
+        assert_eq!(recv_data, expected);
+    }
+}
@@ -1,5 +1,6 @@
 // Copyright 2023 RisingLight Project Authors. Licensed under Apache-2.0.
 
+use std::borrow::Borrow;
 use std::iter::Peekable;
 
 use risinglight_proto::rowset::block_index::BlockType;
@@ -64,6 +65,9 @@ pub struct PrimitiveColumnBuilder<T: PrimitiveFixedWidthEncode> {
 
     /// First key
     first_key: Option<Vec<u8>>,
+
+    /// Minimum and maximum value of the current block
+    min_max: Option<(T, T)>,
 }
 
 impl<T: PrimitiveFixedWidthEncode> PrimitiveColumnBuilder<T> {
@@ -75,6 +79,7 @@ impl<T: PrimitiveFixedWidthEncode> PrimitiveColumnBuilder<T> {
             current_builder: None,
             nullable,
             first_key: None,
+            min_max: None,
         }
     }
 
@@ -120,6 +125,12 @@ impl<T: PrimitiveFixedWidthEncode> PrimitiveColumnBuilder<T> {
             &mut block_data,
             stats,
             self.first_key.clone(),
+            self.min_max.take().map(|(min, max)| {
+                let (mut min_key, mut max_key) = (vec![], vec![]);
+                min.encode(&mut min_key);
+                max.encode(&mut max_key);
+                (min_key, max_key)
+            }),
         );
     }
 }
@@ -153,9 +164,28 @@ pub fn append_one_by_one<'a, A: Array>(
     (cnt, false)
 }
 
+/// Update the minimum and maximum value of a block with a newly appended item.
+pub fn update_min_max<T: PartialOrd + ToOwned + ?Sized>(
+    min_max: &mut Option<(T::Owned, T::Owned)>,
+    item: &T,
+) {
+    match min_max {
+        Some((min, max)) => {
+            if item < Borrow::<T>::borrow(&*min) {
+                *min = item.to_owned();
+            }
+            if item > Borrow::<T>::borrow(&*max) {
+                *max = item.to_owned();
+            }
+        }
+        None => *min_max = Some((item.to_owned(), item.to_owned())),
+    }
+}
+
 impl<T: PrimitiveFixedWidthEncode> ColumnBuilder<T::ArrayType> for PrimitiveColumnBuilder<T> {
     fn append(&mut self, array: &T::ArrayType) {
         let mut iter = array.iter().peekable();
+        let mut pos = 0;
         while iter.peek().is_some() {
             if self.current_builder.is_none() {
                 match (self.nullable, self.options.encode_type) {
@@ -172,7 +202,7 @@ impl<T: PrimitiveFixedWidthEncode> ColumnBuilder<T::ArrayType> for PrimitiveColu
                                 builder
                             )));
                     }
-                    (true, EncodeType::Plain) => {
+                    (true, EncodeType::Plain | EncodeType::Auto) => {
                         self.current_builder =
                             Some(BlockBuilderImpl::PlainNullable(NullableBlockBuilder::new(
                                 PlainPrimitiveBlockBuilder::new(
@@ -205,7 +235,7 @@ impl<T: PrimitiveFixedWidthEncode> ColumnBuilder<T::ArrayType> for PrimitiveColu
                                 builder
                             )));
                     }
-                    (false, EncodeType::Plain) => {
+                    (false, EncodeType::Plain | EncodeType::Auto) => {
                         self.current_builder = Some(BlockBuilderImpl::Plain(
                             PlainPrimitiveBlockBuilder::new(self.options.target_block_size - 16),
                         ));
@@ -244,6 +274,15 @@ impl<T: PrimitiveFixedWidthEncode> ColumnBuilder<T::ArrayType> for PrimitiveColu
             };
 
             self.block_index_builder.add_rows(row_count);
+            let mut nulls = 0;
+            for i in pos..pos + row_count {
+                match array.get(i) {
+                    Some(item) => update_min_max(&mut self.min_max, item),
+                    None => nulls += 1,
+                }
+            }
+            self.block_index_builder.add_nulls(nulls);
+            pos += row_count;
 
             // finish the current block
             if should_finish {
@@ -353,6 +392,38 @@ mod tests {
         assert_eq!(builder.finish().0.len(), 2);
     }
 
+    #[test]
+    fn test_i32_block_index_min_max() {
+        let item_each_block = (128 - 16) / 4;
+
+        let mut builder =
+            I32ColumnBuilder::new(false, ColumnBuilderOptions::default_for_block_test());
+        builder.append(&I32Array::from_iter(
+            (0..item_each_block as i32 * 2).rev().map(Some),
+        ));
+        let (index, _) = builder.finish();
+        assert_eq!(index.len(), 2);
+
+        let decode = |mut key: &[u8]| -> i32 { PrimitiveFixedWidthEncode::decode(&mut key) };
+        let expected = [(28, 55), (0, 27)];
+        for (item, (min, max)) in index.iter().zip(expected) {
+            assert!(item.has_min_max);
+            assert_eq!(item.null_count, 0);
+            assert_eq!(decode(&item.min_key), min);
+            assert_eq!(decode(&item.max_key), max);
+        }
+
+        // min/max is not recorded if all values are null
+        let mut builder =
+            I32ColumnBuilder::new(true, ColumnBuilderOptions::default_for_block_test());
+        builder.append(&I32Array::from_iter(
+            [None].iter().cycle().cloned().take(10),
+        ));
+        let (index, _) = builder.finish();
+        assert!(!index[0].has_min_max);
+        assert_eq!(index[0].null_count, 10);
+    }
+
     #[test]
     fn test_i32_block_index_first_key() {
         let item_each_block = (128 - 16) / 4;
@@ -82,11 +82,11 @@ impl Compactor {
             );
         }
 
-        let sort_key = find_sort_key_id(&table.columns);
-        let mut iter: SecondaryIterator = if let Some(sort_key) = sort_key {
+        let sort_key = find_sort_key_id(&table.columns, &table.ordered_pk_ids);
+        let mut iter: SecondaryIterator = if !sort_key.is_empty() {
             MergeIterator::new(
                 iters.into_iter().map(|iter| iter.into()).collect_vec(),
-                vec![sort_key],
+                sort_key,
             )
             .into()
         } else {
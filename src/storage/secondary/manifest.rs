@@ -18,7 +18,12 @@ use tracing::warn;
 
 use super::version_manager::EpochOp;
 use super::{SecondaryStorage, SecondaryTable, StorageResult, TracedStorageError};
-use crate::catalog::{ColumnCatalog, ColumnId, SchemaId, TableRefId};
+use crate::catalog::{ColumnCatalog, ColumnId, ForeignKey, SchemaId, TableRefId};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CreateSchemaEntry {
+    pub schema_name: String,
+}
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct CreateTableEntry {
@@ -26,6 +31,10 @@ pub struct CreateTableEntry {
     pub table_name: String,
     pub column_descs: Vec<ColumnCatalog>,
     pub ordered_pk_ids: Vec<ColumnId>,
+    #[serde(default)]
+    pub unique_keys: Vec<Vec<ColumnId>>,
+    #[serde(default)]
+    pub foreign_keys: Vec<ForeignKey>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -60,6 +69,7 @@ pub struct DeleteDVEntry {
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum ManifestOperation {
+    CreateSchema(CreateSchemaEntry),
     CreateTable(CreateTableEntry),
     DropTable(DropTableEntry),
     AddRowSet(AddRowSetEntry),
@@ -184,12 +194,43 @@ impl Manifest {
 }
 
 impl SecondaryStorage {
+    pub(super) fn apply_create_schema(&self, entry: &CreateSchemaEntry) -> StorageResult<()> {
+        let CreateSchemaEntry { schema_name } = entry.clone();
+
+        self.catalog
+            .add_schema(schema_name.clone())
+            .map_err(|_| TracedStorageError::duplicated("schema", schema_name))?;
+
+        Ok(())
+    }
+
+    pub(super) async fn create_schema_inner(&self, schema_name: &str) -> StorageResult<()> {
+        if self.catalog.get_schema_id_by_name(schema_name).is_some() {
+            return Err(TracedStorageError::duplicated("schema", schema_name));
+        }
+        let entry = CreateSchemaEntry {
+            schema_name: schema_name.to_string(),
+        };
+
+        // persist to manifest first
+        self.version
+            .commit_changes(vec![EpochOp::CreateSchema(entry.clone())])
+            .await?;
+
+        // then apply to catalog
+        self.apply_create_schema(&entry)?;
+
+        Ok(())
+    }
+
     pub(super) fn apply_create_table(&self, entry: &CreateTableEntry) -> StorageResult<()> {
         let CreateTableEntry {
             schema_id,
             table_name,
             column_descs,
             ordered_pk_ids,
+            unique_keys,
+            foreign_keys,
         } = entry.clone();
 
         let schema = self
@@ -206,7 +247,9 @@ impl SecondaryStorage {
                 table_name.clone(),
                 column_descs.to_vec(),
                 false,
-                ordered_pk_ids,
+                ordered_pk_ids.clone(),
+                unique_keys,
+                foreign_keys,
             )
             .map_err(|_| TracedStorageError::duplicated("table", table_name))?;
 
@@ -218,6 +261,7 @@ impl SecondaryStorage {
             self.options.clone(),
             id,
             &column_descs,
+            &ordered_pk_ids,
             self.next_id.clone(),
             self.version.clone(),
             self.block_cache.clone(),
@@ -234,12 +278,16 @@ impl SecondaryStorage {
         table_name: &str,
         column_descs: &[ColumnCatalog],
         ordered_pk_ids: &[ColumnId],
+        unique_keys: &[Vec<ColumnId>],
+        foreign_keys: &[ForeignKey],
     ) -> StorageResult<()> {
         let entry = CreateTableEntry {
             schema_id,
             table_name: table_name.to_string(),
             column_descs: column_descs.to_vec(),
             ordered_pk_ids: ordered_pk_ids.to_vec(),
+            unique_keys: unique_keys.to_vec(),
+            foreign_keys: foreign_keys.to_vec(),
         };
 
         // persist to manifest first
@@ -285,6 +333,29 @@ impl SecondaryStorage {
 
         changeset.push(EpochOp::DropTable(entry));
 
+        changeset.extend(self.delete_all_rowsets(table_id));
+
+        // and then persist to manifest
+        self.version.commit_changes(changeset).await?;
+
+        Ok(())
+    }
+
+    pub(super) async fn truncate_table_inner(&self, table_id: TableRefId) -> StorageResult<()> {
+        // make sure the table exists
+        self.get_table_inner(table_id)?;
+
+        // remove all rowsets in one epoch, so that readers either see the whole table or nothing.
+        // statistics are aggregated from rowsets, so they are reset as well.
+        let changeset = self.delete_all_rowsets(table_id);
+        self.version.commit_changes(changeset).await?;
+
+        Ok(())
+    }
+
+    /// Returns the operations to delete all rowsets and delete vectors of the table.
+    pub(super) fn delete_all_rowsets(&self, table_id: TableRefId) -> Vec<EpochOp> {
+        let mut changeset = vec![];
         let pin_version = self.version.pin();
 
         if let Some(rowsets) = pin_version.snapshot.get_rowsets_of(table_id.table_id) {
@@ -308,10 +379,6 @@ impl SecondaryStorage {
                 }
             }
         }
-
-        // and then persist to manifest
-        self.version.commit_changes(changeset).await?;
-
-        Ok(())
+        changeset
     }
 }
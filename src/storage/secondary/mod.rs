@@ -6,6 +6,7 @@ use std::collections::HashMap;
 use std::sync::atomic::{AtomicU32, AtomicU64};
 use std::sync::Arc;
 
+use bitmap_index::*;
 use block::*;
 pub use checksum::*;
 use column::*;
@@ -33,7 +34,7 @@ pub use txn_iterator::*;
 use version_manager::*;
 
 use super::{Storage, StorageResult, TracedStorageError};
-use crate::catalog::{ColumnCatalog, ColumnId, RootCatalogRef, SchemaId, TableRefId};
+use crate::catalog::{ColumnCatalog, ColumnId, ForeignKey, RootCatalogRef, SchemaId, TableRefId};
 
 // public modules and structures
 mod options;
@@ -43,6 +44,8 @@ mod transaction;
 mod txn_iterator;
 
 // internal modules and structures
+mod backup;
+mod bitmap_index;
 mod block;
 mod checksum;
 mod column;
@@ -165,15 +168,28 @@ impl Storage for SecondaryStorage {
     type Transaction = SecondaryTransaction;
     type Table = SecondaryTable;
 
+    async fn create_schema(&self, schema_name: &str) -> StorageResult<()> {
+        self.create_schema_inner(schema_name).await
+    }
+
     async fn create_table(
         &self,
         schema_id: SchemaId,
         table_name: &str,
         column_descs: &[ColumnCatalog],
         ordered_pk_ids: &[ColumnId],
+        unique_keys: &[Vec<ColumnId>],
+        foreign_keys: &[ForeignKey],
     ) -> StorageResult<()> {
-        self.create_table_inner(schema_id, table_name, column_descs, ordered_pk_ids)
-            .await
+        self.create_table_inner(
+            schema_id,
+            table_name,
+            column_descs,
+            ordered_pk_ids,
+            unique_keys,
+            foreign_keys,
+        )
+        .await
     }
 
     fn get_table(&self, table_id: TableRefId) -> StorageResult<SecondaryTable> {
@@ -183,4 +199,8 @@ impl Storage for SecondaryStorage {
     async fn drop_table(&self, table_id: TableRefId) -> StorageResult<()> {
         self.drop_table_inner(table_id).await
     }
+
+    async fn truncate_table(&self, table_id: TableRefId) -> StorageResult<()> {
+        self.truncate_table_inner(table_id).await
+    }
 }
@@ -35,6 +35,8 @@ pub enum EncodeType {
     Plain,
     RunLength,
     Dictionary,
+    /// Dictionary encoding for low-cardinality blocks of char columns, plain encoding otherwise.
+    Auto,
 }
 
 /// Options for `SecondaryStorage`
@@ -64,6 +66,10 @@ pub struct StorageOptions {
     /// Whether record first_key of each block into block_index
     pub record_first_key: bool,
 
+    /// Whether to build bitmap indexes on low-cardinality columns. Disabled by default, as the
+    /// indexes are loaded into memory when a rowset is opened.
+    pub bitmap_index: bool,
+
     /// Whether to disable all disk operations, only for test use
     pub disable_all_disk_operation: bool,
 }
@@ -85,6 +91,7 @@ impl StorageOptions {
             encode_type: EncodeType::Plain,
             // required by range-filter scan rule
             record_first_key: true,
+            bitmap_index: false,
             disable_all_disk_operation: false,
         }
     }
@@ -100,6 +107,7 @@ impl StorageOptions {
             encode_type: EncodeType::Plain,
             // required by range-filter scan rule
             record_first_key: true,
+            bitmap_index: false,
             disable_all_disk_operation: true,
         }
     }
@@ -119,6 +127,9 @@ pub struct ColumnBuilderOptions {
 
     /// Whether record first_key of each block
     pub record_first_key: bool,
+
+    /// Whether to build bitmap indexes on low-cardinality columns
+    pub bitmap_index: bool,
 }
 
 impl ColumnBuilderOptions {
@@ -126,8 +137,9 @@ impl ColumnBuilderOptions {
         Self {
             target_block_size: options.target_block_size,
             checksum_type: options.checksum_type,
-            encode_type: EncodeType::Plain,
+            encode_type: options.encode_type,
             record_first_key: options.record_first_key,
+            bitmap_index: options.bitmap_index,
         }
     }
 
@@ -138,6 +150,7 @@ impl ColumnBuilderOptions {
             checksum_type: ChecksumType::Crc32,
             encode_type: EncodeType::Plain,
             record_first_key: false,
+            bitmap_index: false,
         }
     }
 
@@ -148,6 +161,7 @@ impl ColumnBuilderOptions {
             checksum_type: ChecksumType::None,
             encode_type: EncodeType::Plain,
             record_first_key: false,
+            bitmap_index: false,
         }
     }
 
@@ -158,6 +172,7 @@ impl ColumnBuilderOptions {
             checksum_type: ChecksumType::None,
             encode_type: EncodeType::RunLength,
             record_first_key: false,
+            bitmap_index: false,
         }
     }
     #[cfg(test)]
@@ -167,6 +182,7 @@ impl ColumnBuilderOptions {
             checksum_type: ChecksumType::None,
             encode_type: EncodeType::Dictionary,
             record_first_key: false,
+            bitmap_index: false,
         }
     }
 
@@ -177,6 +193,7 @@ impl ColumnBuilderOptions {
             checksum_type: ChecksumType::None,
             encode_type: EncodeType::Plain,
             record_first_key: true,
+            bitmap_index: false,
         }
     }
 }
@@ -9,8 +9,12 @@ use moka::future::Cache;
 use tokio::fs::OpenOptions;
 use tokio::io::AsyncReadExt;
 
-use super::super::{Block, BlockCacheKey, Column, ColumnIndex, ColumnSeekPosition, IOBackend};
-use super::{path_of_data_column, path_of_index_column, RowSetIterator};
+use super::super::{
+    BitmapIndex, Block, BlockCacheKey, Column, ColumnIndex, ColumnSeekPosition, IOBackend,
+};
+use super::{
+    path_of_bitmap_index_column, path_of_data_column, path_of_index_column, RowSetIterator,
+};
 use crate::catalog::ColumnCatalog;
 use crate::storage::secondary::column::ColumnReadableFile;
 use crate::storage::secondary::encode::PrimitiveFixedWidthEncode;
@@ -24,6 +28,8 @@ use crate::types::DataValue;
 pub struct DiskRowset {
     column_infos: Arc<[ColumnCatalog]>,
     columns: Vec<Column>,
+    /// Bitmap index of each column, if built.
+    bitmap_indexes: Vec<Option<BitmapIndex>>,
     rowset_id: u32,
 }
 
@@ -36,6 +42,7 @@ impl DiskRowset {
         io_backend: IOBackend,
     ) -> StorageResult<Self> {
         let mut columns = vec![];
+        let mut bitmap_indexes = vec![];
 
         for (id, column_info) in column_infos.iter().enumerate() {
             let path_of_index_column = path_of_index_column(&directory, column_info);
@@ -94,11 +101,32 @@ impl DiskRowset {
                 BlockCacheKey::default().rowset(rowset_id).column(id as u32),
             );
             columns.push(column);
+
+            let path_of_bitmap_index = path_of_bitmap_index_column(&directory, column_info);
+            let bitmap_index_content = match &io_backend {
+                IOBackend::NormalRead | IOBackend::PositionedRead => {
+                    match tokio::fs::read(path_of_bitmap_index).await {
+                        Ok(content) => Some(Bytes::from(content)),
+                        Err(err) if err.kind() == std::io::ErrorKind::NotFound => None,
+                        Err(err) => return Err(err.into()),
+                    }
+                }
+                IOBackend::InMemory(map) => map.lock().get(&path_of_bitmap_index).cloned(),
+            };
+            let bitmap_index = match bitmap_index_content {
+                Some(content) => Some(BitmapIndex::from_bytes(
+                    &content,
+                    &column_info.datatype().kind(),
+                )?),
+                None => None,
+            };
+            bitmap_indexes.push(bitmap_index);
         }
 
         Ok(Self {
             column_infos,
             columns,
+            bitmap_indexes,
             rowset_id,
         })
     }
@@ -111,6 +139,11 @@ impl DiskRowset {
         &self.columns
     }
 
+    /// Returns the bitmap index of the column, if built.
+    pub fn bitmap_index(&self, storage_column_id: usize) -> Option<&BitmapIndex> {
+        self.bitmap_indexes[storage_column_id].as_ref()
+    }
+
     pub fn column_info(&self, storage_column_id: usize) -> &ColumnCatalog {
         &self.column_infos[storage_column_id]
     }
@@ -400,6 +433,147 @@ pub mod tests {
         .unwrap()
     }
 
+    pub async fn helper_build_varchar_rowset(
+        tempdir: &TempDir,
+        encode_type: EncodeType,
+        values: &[&str],
+    ) -> DiskRowset {
+        let columns = vec![ColumnCatalog::new(
+            0,
+            DataTypeKind::String.not_null().to_column("v1".to_string(), false),
+        )];
+        let mut column_options = ColumnBuilderOptions::default_for_test();
+        column_options.encode_type = encode_type;
+        let mut builder = RowsetBuilder::new(columns.clone().into(), column_options);
+
+        for _ in 0..100 {
+            builder.append(
+                [ArrayImpl::new_utf8(
+                    values.iter().cycle().take(1000).map(|s| Some(*s)).collect(),
+                )]
+                .into_iter()
+                .collect(),
+            )
+        }
+
+        let backend = IOBackend::in_memory();
+
+        let writer = RowsetWriter::new(tempdir.path(), backend.clone());
+        writer.flush(builder.finish()).await.unwrap();
+
+        DiskRowset::open(
+            tempdir.path().to_path_buf(),
+            columns.into(),
+            Cache::new(2333),
+            0,
+            backend,
+        )
+        .await
+        .unwrap()
+    }
+
+    /// Builds a rowset of 1000 rows with bitmap indexes, where `v1 = i`, `v2 = i % 5` and
+    /// `v3 = ["a", "b", "c"][i % 3]`. `v1` has too many distinct values to be indexed.
+    pub async fn helper_build_rowset_with_bitmap_index(tempdir: &TempDir) -> DiskRowset {
+        let columns = vec![
+            ColumnCatalog::new(
+                0,
+                DataTypeKind::Int32.not_null().to_column("v1".to_string(), false),
+            ),
+            ColumnCatalog::new(
+                1,
+                DataTypeKind::Int32.not_null().to_column("v2".to_string(), false),
+            ),
+            ColumnCatalog::new(
+                2,
+                DataTypeKind::String.not_null().to_column("v3".to_string(), false),
+            ),
+        ];
+        let mut column_options = ColumnBuilderOptions::default_for_test();
+        column_options.bitmap_index = true;
+        let mut builder = RowsetBuilder::new(columns.clone().into(), column_options);
+
+        for start in (0..1000).step_by(100) {
+            builder.append(
+                [
+                    ArrayImpl::new_int32((start..start + 100).collect()),
+                    ArrayImpl::new_int32((start..start + 100).map(|i| i % 5).collect()),
+                    ArrayImpl::new_utf8(
+                        (start..start + 100)
+                            .map(|i| Some(["a", "b", "c"][i as usize % 3]))
+                            .collect(),
+                    ),
+                ]
+                .into_iter()
+                .collect(),
+            )
+        }
+
+        let backend = IOBackend::in_memory();
+
+        let writer = RowsetWriter::new(tempdir.path(), backend.clone());
+        writer.flush(builder.finish()).await.unwrap();
+
+        DiskRowset::open(
+            tempdir.path().to_path_buf(),
+            columns.into(),
+            Cache::new(2333),
+            0,
+            backend,
+        )
+        .await
+        .unwrap()
+    }
+
+    /// Builds a rowset of 1000 rows with small blocks, where `v1 = i` and `v2 = i`,
+    /// except that `v2` is null for `200 <= i < 600`.
+    pub async fn helper_build_rowset_with_null_blocks(tempdir: &TempDir) -> DiskRowset {
+        let columns = vec![
+            ColumnCatalog::new(
+                0,
+                DataTypeKind::Int32.not_null().to_column("v1".to_string(), false),
+            ),
+            ColumnCatalog::new(
+                1,
+                DataTypeKind::Int32.nullable().to_column("v2".to_string(), false),
+            ),
+        ];
+        let mut builder = RowsetBuilder::new(
+            columns.clone().into(),
+            ColumnBuilderOptions::record_first_key_test(),
+        );
+
+        for start in (0..1000).step_by(100) {
+            builder.append(
+                [
+                    ArrayImpl::new_int32((start..start + 100).collect()),
+                    ArrayImpl::new_int32(
+                        (start..start + 100)
+                            .map(|i| (!(200..600).contains(&i)).then_some(i))
+                            .collect(),
+                    ),
+                ]
+                .into_iter()
+                .collect(),
+            )
+        }
+
+        let backend = IOBackend::in_memory();
+
+        let writer = RowsetWriter::new(tempdir.path(), backend.clone());
+        writer.flush(builder.finish()).await.unwrap();
+
+        DiskRowset::open(
+            tempdir.path().to_path_buf(),
+            columns.into(),
+            Cache::new(2333),
+            0,
+            backend,
+        )
+        .await
+        .unwrap()
+    }
+
     #[tokio::test]
     async fn test_get_block() {
         let tempdir = tempfile::tempdir().unwrap();
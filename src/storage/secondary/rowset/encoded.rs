@@ -6,6 +6,8 @@ use crate::catalog::ColumnCatalog;
 pub struct EncodedColumn {
     pub index: Vec<u8>,
     pub data: Vec<u8>,
+    /// Bitmap index, if built on the column.
+    pub bitmap_index: Option<Vec<u8>>,
 }
 
 /// Encoded rowset.
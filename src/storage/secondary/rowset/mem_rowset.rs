@@ -9,7 +9,7 @@ use itertools::Itertools;
 
 use super::rowset_builder::RowsetBuilder;
 use crate::array::{ArrayBuilderImpl, DataChunk};
-use crate::catalog::{find_sort_key_id, ColumnCatalog};
+use crate::catalog::{find_sort_key_id, ColumnCatalog, ColumnId};
 use crate::storage::secondary::rowset::RowsetWriter;
 use crate::storage::secondary::{ColumnBuilderOptions, IOBackend};
 use crate::storage::StorageResult;
@@ -40,15 +40,16 @@ pub trait MemTable {
 
 pub struct BTreeMapMemTable {
     columns: Arc<[ColumnCatalog]>,
-    primary_key_idx: usize,
-    multi_btree_map: BTreeMultiMap<ComparableDataValue, Row>,
+    /// Indexes of the sort key columns, in the order of primary key.
+    sort_key_idx: Vec<usize>,
+    multi_btree_map: BTreeMultiMap<Vec<ComparableDataValue>, Row>,
 }
 
 impl BTreeMapMemTable {
-    fn new(columns: Arc<[ColumnCatalog]>, primary_key_idx: usize) -> Self {
+    fn new(columns: Arc<[ColumnCatalog]>, sort_key_idx: Vec<usize>) -> Self {
         Self {
             columns,
-            primary_key_idx,
+            sort_key_idx,
             multi_btree_map: BTreeMultiMap::new(),
         }
     }
@@ -57,10 +58,11 @@ impl BTreeMapMemTable {
 impl MemTable for BTreeMapMemTable {
     fn append(&mut self, columns: DataChunk) -> StorageResult<()> {
         for row_idx in 0..columns.cardinality() {
-            self.multi_btree_map.insert(
-                ComparableDataValue(columns.array_at(self.primary_key_idx).get(row_idx)),
-                columns.row(row_idx).values().collect(),
-            );
+            let key = (self.sort_key_idx.iter())
+                .map(|idx| ComparableDataValue(columns.array_at(*idx).get(row_idx)))
+                .collect();
+            self.multi_btree_map
+                .insert(key, columns.row(row_idx).values().collect());
         }
         Ok(())
     }
@@ -171,10 +173,12 @@ pub enum SecondaryMemRowsetImpl {
 impl SecondaryMemRowsetImpl {
     pub fn new(
         columns: Arc<[ColumnCatalog]>,
+        ordered_pk_ids: &[ColumnId],
         column_options: ColumnBuilderOptions,
         rowset_id: u32,
     ) -> Self {
-        if let Some(sort_key_idx) = find_sort_key_id(&columns) {
+        let sort_key_idx = find_sort_key_id(&columns, ordered_pk_ids);
+        if !sort_key_idx.is_empty() {
             Self::BTree(SecondaryMemRowset::<BTreeMapMemTable> {
                 mem_table: BTreeMapMemTable::new(columns.clone(), sort_key_idx),
                 rowset_builder: RowsetBuilder::new(columns, column_options),
@@ -214,3 +218,96 @@ impl SecondaryMemRowsetImpl {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use moka::future::Cache;
+
+    use super::*;
+    use crate::array::ArrayImpl;
+    use crate::storage::secondary::column::ColumnSeekPosition;
+    use crate::storage::secondary::rowset::DiskRowset;
+    use crate::storage::StorageColumnRef;
+    use crate::types::DataTypeKind;
+
+    #[tokio::test]
+    async fn test_flush_sorted_by_composite_primary_key() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let columns: Arc<[ColumnCatalog]> = vec![
+            ColumnCatalog::new(
+                0,
+                DataTypeKind::Int32
+                    .not_null()
+                    .to_column("v1".to_string(), false),
+            ),
+            ColumnCatalog::new(
+                1,
+                DataTypeKind::Int32
+                    .not_null()
+                    .to_column("v2".to_string(), false),
+            ),
+            ColumnCatalog::new(
+                2,
+                DataTypeKind::Int32
+                    .not_null()
+                    .to_column("v3".to_string(), false),
+            ),
+        ]
+        .into();
+
+        // primary key (v2, v1)
+        let mut mem = SecondaryMemRowsetImpl::new(
+            columns.clone(),
+            &[1, 0],
+            ColumnBuilderOptions::default_for_test(),
+            0,
+        );
+        assert!(matches!(mem, SecondaryMemRowsetImpl::BTree(_)));
+        mem.append(
+            [
+                ArrayImpl::new_int32([3, 1, 2, 1, 2].into_iter().collect()),
+                ArrayImpl::new_int32([1, 2, 2, 1, 1].into_iter().collect()),
+                ArrayImpl::new_int32([0, 1, 2, 3, 4].into_iter().collect()),
+            ]
+            .into_iter()
+            .collect(),
+        )
+        .await
+        .unwrap();
+
+        let backend = IOBackend::in_memory();
+        mem.flush(backend.clone(), tempdir.path()).await.unwrap();
+        let rowset = Arc::new(
+            DiskRowset::open(
+                tempdir.path().to_path_buf(),
+                columns,
+                Cache::new(2333),
+                0,
+                backend,
+            )
+            .await
+            .unwrap(),
+        );
+
+        let mut it = rowset
+            .iter(
+                (0..3).map(StorageColumnRef::Idx).collect_vec().into(),
+                vec![],
+                ColumnSeekPosition::start(),
+                None,
+            )
+            .await
+            .unwrap();
+        let mut rows = vec![];
+        while let Some(chunk) = it.next_batch(None).await.unwrap() {
+            for i in 0..chunk.cardinality() {
+                rows.push((0..3).map(|col| chunk.array_at(col).get(i)).collect_vec());
+            }
+        }
+        let expected = [[1, 1, 3], [2, 1, 4], [3, 1, 0], [1, 2, 1], [2, 2, 2]]
+            .iter()
+            .map(|row| row.iter().map(|v| DataValue::Int32(*v)).collect_vec())
+            .collect_vec();
+        assert_eq!(rows, expected);
+    }
+}
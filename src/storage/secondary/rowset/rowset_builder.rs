@@ -4,7 +4,7 @@ use std::sync::Arc;
 
 use itertools::Itertools;
 
-use super::super::{ColumnBuilderImpl, IndexBuilder};
+use super::super::{support_bitmap_index, BitmapIndexBuilder, ColumnBuilderImpl, IndexBuilder};
 use crate::array::DataChunk;
 use crate::catalog::ColumnCatalog;
 use crate::storage::secondary::rowset::{EncodedColumn, EncodedRowset};
@@ -18,6 +18,9 @@ pub struct RowsetBuilder {
     /// Column data builders
     builders: Vec<ColumnBuilderImpl>,
 
+    /// Bitmap index builders of columns supporting it
+    bitmap_builders: Vec<Option<BitmapIndexBuilder>>,
+
     /// Count of rows in this rowset
     row_cnt: u32,
 
@@ -34,6 +37,13 @@ impl RowsetBuilder {
                     ColumnBuilderImpl::new_from_datatype(&column.datatype(), column_options.clone())
                 })
                 .collect_vec(),
+            bitmap_builders: columns
+                .iter()
+                .map(|column| {
+                    (column_options.bitmap_index && support_bitmap_index(&column.datatype().kind))
+                        .then(|| BitmapIndexBuilder::new(column_options.checksum_type))
+                })
+                .collect_vec(),
             columns,
             row_cnt: 0,
             column_options,
@@ -45,6 +55,9 @@ impl RowsetBuilder {
 
         for idx in 0..chunk.column_count() {
             self.builders[idx].append(chunk.array_at(idx));
+            if let Some(builder) = &mut self.bitmap_builders[idx] {
+                builder.append(chunk.array_at(idx));
+            }
         }
     }
 
@@ -56,7 +69,8 @@ impl RowsetBuilder {
             columns: self
                 .builders
                 .into_iter()
-                .map(|builder| {
+                .zip(self.bitmap_builders)
+                .map(|(builder, bitmap_builder)| {
                     let (block_indices, data) = builder.finish();
 
                     let mut index_builder = IndexBuilder::new(checksum_type, block_indices.len());
@@ -67,6 +81,7 @@ impl RowsetBuilder {
                     EncodedColumn {
                         index: index_builder.finish(),
                         data,
+                        bitmap_index: bitmap_builder.and_then(|builder| builder.finish()),
                     }
                 })
                 .collect_vec(),
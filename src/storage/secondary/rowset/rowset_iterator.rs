@@ -4,13 +4,16 @@ use std::ops::Bound;
 use std::sync::Arc;
 
 use bitvec::prelude::BitVec;
+use rust_decimal::Decimal;
 use smallvec::smallvec;
 
-use super::super::{ColumnIteratorImpl, ColumnSeekPosition, SecondaryIteratorImpl};
+use super::super::{ColumnIndex, ColumnIteratorImpl, ColumnSeekPosition, SecondaryIteratorImpl};
 use super::DiskRowset;
 use crate::array::ArrayImpl;
+use crate::storage::secondary::encode::PrimitiveFixedWidthEncode;
 use crate::storage::secondary::DeleteVector;
-use crate::storage::{KeyRange, PackedVec, StorageChunk, StorageColumnRef, StorageResult};
+use crate::storage::{InList, KeyRange, PackedVec, StorageChunk, StorageColumnRef, StorageResult};
+use crate::types::{DataTypeKind, DataValue, Date, Timestamp, TimestampTz, F64};
 
 /// When `expected_size` is not specified, we should limit the maximum size of the chunk.
 const ROWSET_MAX_OUTPUT: usize = 2048;
@@ -22,6 +25,16 @@ pub struct RowSetIterator {
     column_iterators: Vec<ColumnIteratorImpl>,
     /// An optional filter for the first column.
     filter: Option<KeyRange>,
+    /// The position of the first column in `column_refs`, to which `filter` is applied.
+    filter_column: Option<usize>,
+    /// Block index and data type of the first column, used to skip blocks by min/max.
+    filter_column_index: Option<(ColumnIndex, DataTypeKind)>,
+    /// An optional multi-column IN-list, used to skip blocks by min/max.
+    in_list: Option<InListIndex>,
+    /// Rows matching the equality conditions, selected by bitmap indexes.
+    selection: Option<BitVec>,
+    /// Block indexes of the columns that must not be null, used to skip all-null blocks.
+    not_null: Vec<ColumnIndex>,
     /// Indicate whether the iterator has reached the end.
     end: bool,
 }
@@ -81,15 +94,77 @@ impl RowSetIterator {
             };
         }
 
+        let filter_column = column_refs
+            .iter()
+            .position(|x| matches!(x, StorageColumnRef::Idx(0)));
+        let filter_column_index = filter_column.map(|_| {
+            let column = rowset.column(0);
+            (
+                column.index().clone(),
+                rowset.column_info(0).datatype().kind(),
+            )
+        });
+
         Ok(Self {
             column_refs,
             dvs,
             column_iterators,
             filter,
+            filter_column,
+            filter_column_index,
+            in_list: None,
+            selection: None,
+            not_null: vec![],
             end: false,
         })
     }
 
+    /// Skips blocks that can not contain any tuple of the IN-list.
+    pub fn with_in_list(mut self, rowset: &DiskRowset, in_list: Option<InList>) -> Self {
+        self.in_list = in_list.map(|in_list| InListIndex {
+            columns: (in_list.columns.iter())
+                .map(|&idx| {
+                    let column = rowset.column(idx as usize);
+                    (
+                        column.index().clone(),
+                        rowset.column_info(idx as usize).datatype().kind(),
+                    )
+                })
+                .collect(),
+            values: in_list.values,
+        });
+        self
+    }
+
+    /// Only reads rows where the columns equal to the values, if the columns have bitmap
+    /// indexes. Conditions on other columns are ignored.
+    pub fn with_equal_to(mut self, rowset: &DiskRowset, equal_to: &[(u32, DataValue)]) -> Self {
+        for (idx, value) in equal_to {
+            let Some(index) = rowset.bitmap_index(*idx as usize) else {
+                continue;
+            };
+            // only look up values of the same type
+            let kind = rowset.column_info(*idx as usize).datatype().kind();
+            if value.data_type().kind() != kind {
+                continue;
+            }
+            let rows = index.rows(value);
+            match &mut self.selection {
+                Some(selection) => *selection &= rows,
+                None => self.selection = Some(rows),
+            }
+        }
+        self
+    }
+
+    /// Skips blocks where all values of the columns are null.
+    pub fn with_not_null(mut self, rowset: &DiskRowset, not_null: &[u32]) -> Self {
+        self.not_null = (not_null.iter())
+            .map(|&idx| rowset.column(idx as usize).index().clone())
+            .collect();
+        self
+    }
+
     /// Reads the next batch.
     pub async fn next_batch(
         &mut self,
@@ -112,6 +187,13 @@ impl RowSetIterator {
         if self.end {
             return Ok(None);
         }
+        if self.skip_block_by_min_max()
+            || self.skip_block_by_in_list()
+            || self.skip_block_by_not_null()
+            || self.skip_rows_by_bitmap_index()
+        {
+            return Ok(None);
+        }
         // It's guaranteed that `expected_size` <= the number of items left
         // in the current block, if provided
         let mut fetch_size = {
@@ -188,6 +270,19 @@ impl RowSetIterator {
             visibility_map = Some(visi);
         }
 
+        // Apply the rows selected by bitmap indexes
+        if let Some(selection) = &self.selection {
+            let start_row_id = self.column_iterators[0].fetch_current_row_id() as usize;
+            let visi: BitVec = (start_row_id..start_row_id + fetch_size)
+                .map(|i| i < selection.len() && selection[i])
+                .collect();
+            if let Some(ref mut vis) = visibility_map {
+                *vis &= visi;
+            } else {
+                visibility_map = Some(visi);
+            }
+        }
+
         let mut arrays: PackedVec<ArrayImpl> = smallvec![];
         // to make sure all columns have the same chunk range
         let mut common_chunk_range = None;
@@ -220,7 +315,7 @@ impl RowSetIterator {
             }
 
             // For now, we only support range-filter scan by first column.
-            if let Some(range) = &self.filter && id == 0 {
+            if let Some(range) = &self.filter && self.filter_column == Some(id) {
                 let len = array.len();
                 let start_row_id = match &range.start {
                     Bound::Included(key) => {
@@ -250,7 +345,9 @@ impl RowSetIterator {
                         visibility_map = Some(bitmap);
                     }
                 }
-                if end_row_id == 0 {
+                // The first column is sorted, so no more rows can fall into the range once the
+                // end bound is reached in this batch.
+                if end_row_id < len {
                     self.end = true;
                 }
             }
@@ -260,6 +357,176 @@ impl RowSetIterator {
 
         Ok(StorageChunk::construct(visibility_map, arrays))
     }
+
+    /// Skips the rest of the current block if its min/max of the first column shows that no row
+    /// in it can match the filter. Returns true if the block is skipped.
+    fn skip_block_by_min_max(&mut self) -> bool {
+        let (Some(range), Some(id), Some((index, kind))) =
+            (&self.filter, self.filter_column, &self.filter_column_index)
+        else {
+            return false;
+        };
+        let row_id = self.column_iterators[id].fetch_current_row_id();
+        let block_id = (index.indexes()).partition_point(|b| b.first_rowid + b.row_count <= row_id);
+        if block_id >= index.len() {
+            return false;
+        }
+        let block = index.index(block_id as u32);
+        if !block.has_min_max {
+            return false;
+        }
+        let (Some(min), Some(max)) = (
+            decode_key(kind, &block.min_key),
+            decode_key(kind, &block.max_key),
+        ) else {
+            return false;
+        };
+        // only compare values of the same type
+        let comparable =
+            |key: &DataValue| std::mem::discriminant(key) == std::mem::discriminant(&min);
+        let before_start = match &range.start {
+            Bound::Included(key) => comparable(key) && &max < key,
+            Bound::Excluded(key) => comparable(key) && &max <= key,
+            Bound::Unbounded => false,
+        };
+        let after_end = match &range.end {
+            Bound::Included(key) => comparable(key) && &min > key,
+            Bound::Excluded(key) => comparable(key) && &min >= key,
+            Bound::Unbounded => false,
+        };
+        if after_end {
+            // the first column is sorted, so are all the following blocks
+            self.end = true;
+            return true;
+        }
+        if !before_start {
+            return false;
+        }
+        let cnt = (block.first_rowid + block.row_count - row_id) as usize;
+        for it in &mut self.column_iterators {
+            it.skip(cnt);
+        }
+        true
+    }
+
+    /// Skips rows until the end of the first block among the IN-list columns if, for every tuple,
+    /// the min/max of some column shows that it can not match. Returns true if rows are skipped.
+    fn skip_block_by_in_list(&mut self) -> bool {
+        let Some(in_list) = &self.in_list else {
+            return false;
+        };
+        let row_id = self.column_iterators[0].fetch_current_row_id();
+        // the row range covered by the current block of all columns
+        let mut end_row_id = u32::MAX;
+        let mut min_max = vec![];
+        for (index, kind) in &in_list.columns {
+            let block_id =
+                (index.indexes()).partition_point(|b| b.first_rowid + b.row_count <= row_id);
+            if block_id >= index.len() {
+                return false;
+            }
+            let block = index.index(block_id as u32);
+            end_row_id = end_row_id.min(block.first_rowid + block.row_count);
+            min_max.push(if block.has_min_max {
+                decode_key(kind, &block.min_key).zip(decode_key(kind, &block.max_key))
+            } else {
+                None
+            });
+        }
+        let may_contain = |tuple: &Vec<DataValue>| {
+            tuple.iter().zip(&min_max).all(|(value, min_max)| {
+                let Some((min, max)) = min_max else {
+                    return true;
+                };
+                if value.is_null() {
+                    return false;
+                }
+                // only compare values of the same type
+                std::mem::discriminant(value) != std::mem::discriminant(min)
+                    || (min <= value && value <= max)
+            })
+        };
+        if in_list.values.iter().any(may_contain) {
+            return false;
+        }
+        let cnt = (end_row_id - row_id) as usize;
+        for it in &mut self.column_iterators {
+            it.skip(cnt);
+        }
+        true
+    }
+
+    /// Skips rows until the end of the current block of a not-null column if all values in the
+    /// block are null. Returns true if rows are skipped.
+    fn skip_block_by_not_null(&mut self) -> bool {
+        let row_id = self.column_iterators[0].fetch_current_row_id();
+        for index in &self.not_null {
+            let block_id =
+                (index.indexes()).partition_point(|b| b.first_rowid + b.row_count <= row_id);
+            if block_id >= index.len() {
+                continue;
+            }
+            let block = index.index(block_id as u32);
+            if block.null_count < block.row_count {
+                continue;
+            }
+            let cnt = (block.first_rowid + block.row_count - row_id) as usize;
+            for it in &mut self.column_iterators {
+                it.skip(cnt);
+            }
+            return true;
+        }
+        false
+    }
+
+    /// Skips rows until the next one selected by bitmap indexes. Returns true if rows are
+    /// skipped.
+    fn skip_rows_by_bitmap_index(&mut self) -> bool {
+        let Some(selection) = &self.selection else {
+            return false;
+        };
+        let row_id = self.column_iterators[0].fetch_current_row_id() as usize;
+        let next = selection.get(row_id..).and_then(|rows| rows.first_one());
+        match next {
+            Some(0) => false,
+            Some(cnt) => {
+                for it in &mut self.column_iterators {
+                    it.skip(cnt);
+                }
+                true
+            }
+            None => {
+                // no more rows are selected
+                self.end = true;
+                true
+            }
+        }
+    }
+}
+
+/// Block indexes of the columns in an IN-list.
+struct InListIndex {
+    /// Block index and data type of each column.
+    columns: Vec<(ColumnIndex, DataTypeKind)>,
+    /// Tuples of values, in the order of `columns`.
+    values: Vec<Vec<DataValue>>,
+}
+
+/// Decodes the min/max key recorded in a block index.
+fn decode_key(kind: &DataTypeKind, mut data: &[u8]) -> Option<DataValue> {
+    Some(match kind {
+        DataTypeKind::Bool => DataValue::Bool(bool::decode(&mut data)),
+        DataTypeKind::Int16 => DataValue::Int16(i16::decode(&mut data)),
+        DataTypeKind::Int32 => DataValue::Int32(i32::decode(&mut data)),
+        DataTypeKind::Int64 => DataValue::Int64(i64::decode(&mut data)),
+        DataTypeKind::Float64 => DataValue::Float64(F64::decode(&mut data)),
+        DataTypeKind::Decimal(_, _) => DataValue::Decimal(Decimal::decode(&mut data)),
+        DataTypeKind::Date => DataValue::Date(Date::decode(&mut data)),
+        DataTypeKind::Timestamp => DataValue::Timestamp(Timestamp::decode(&mut data)),
+        DataTypeKind::TimestampTz => DataValue::TimestampTz(TimestampTz::decode(&mut data)),
+        DataTypeKind::String => DataValue::String(String::from_utf8(data.to_vec()).ok()?),
+        _ => return None,
+    })
 }
 
 impl SecondaryIteratorImpl for RowSetIterator {}
@@ -273,7 +540,8 @@ mod tests {
     use super::*;
     use crate::array::{Array, ArrayToVecExt};
     use crate::storage::secondary::rowset::tests::{
-        helper_build_rowset, helper_build_rowset_with_first_key_recorded,
+        helper_build_rowset, helper_build_rowset_with_bitmap_index,
+        helper_build_rowset_with_first_key_recorded, helper_build_rowset_with_null_blocks,
     };
     use crate::storage::secondary::SecondaryRowHandler;
     use crate::types::DataValue;
@@ -663,6 +931,235 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_rowset_iterator_range_filter_reads_fewer_blocks() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let rowset = Arc::new(helper_build_rowset_with_first_key_recorded(&tempdir).await);
+        // the sort key is not the first column to read
+        let column_refs: Arc<[StorageColumnRef]> =
+            vec![StorageColumnRef::Idx(1), StorageColumnRef::Idx(0)].into();
+
+        // counts the blocks fetched until the iterator reaches the end
+        async fn fetch_all(it: &mut RowSetIterator, column0: &mut Vec<i32>) -> usize {
+            let mut blocks = 0;
+            while !it.end {
+                if let Some(chunk) = it.next_batch_inner(None).await.unwrap() {
+                    data_from_chunk(&chunk, column0, 1).await;
+                }
+                blocks += 1;
+            }
+            blocks
+        }
+
+        let mut it = rowset
+            .iter(
+                column_refs.clone(),
+                vec![],
+                ColumnSeekPosition::RowId(0),
+                None,
+            )
+            .await
+            .unwrap();
+        let mut column0 = vec![];
+        let full_blocks = fetch_all(&mut it, &mut column0).await;
+        assert_eq!(column0, (0..280).collect_vec());
+
+        let range = KeyRange {
+            start: Bound::Included(DataValue::Int32(180)),
+            end: Bound::Included(DataValue::Int32(195)),
+        };
+        let begin_key = match &range.start {
+            Bound::Included(k) => Some(k),
+            _ => unreachable!(),
+        };
+        let start_rowid = rowset.start_rowid(begin_key).await;
+        let mut it = rowset
+            .iter(column_refs, vec![], start_rowid, Some(range))
+            .await
+            .unwrap();
+        let mut column0 = vec![];
+        let range_blocks = fetch_all(&mut it, &mut column0).await;
+        assert_eq!(column0, (180..=195).collect_vec());
+
+        // the range spans at most two blocks
+        assert!(range_blocks <= 2, "{range_blocks} blocks read");
+        assert!(range_blocks < full_blocks);
+    }
+
+    #[tokio::test]
+    async fn test_rowset_iterator_skip_blocks_by_min_max() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let rowset = Arc::new(helper_build_rowset_with_first_key_recorded(&tempdir).await);
+        // start from the beginning instead of seeking by the first key
+        let mut it = rowset
+            .iter(
+                vec![StorageColumnRef::Idx(0), StorageColumnRef::Idx(2)].into(),
+                vec![],
+                ColumnSeekPosition::RowId(0),
+                Some(KeyRange {
+                    start: Bound::Included(DataValue::Int32(180)),
+                    end: Bound::Included(DataValue::Int32(195)),
+                }),
+            )
+            .await
+            .unwrap();
+
+        let mut skipped_blocks = 0;
+        let mut column0 = vec![];
+        let mut column2 = vec![];
+        while !it.end {
+            if it.skip_block_by_min_max() {
+                skipped_blocks += 1;
+                continue;
+            }
+            if let Some(chunk) = it.next_batch_inner(None).await.unwrap() {
+                data_from_chunk(&chunk, &mut column0, 0).await;
+                data_from_chunk(&chunk, &mut column2, 1).await;
+            }
+        }
+        assert_eq!(column0, (180..=195).collect_vec());
+        assert_eq!(column2, (182..=197).collect_vec());
+
+        // all blocks before the range are skipped without being read,
+        // and the first block after the range ends the scan
+        let blocks_before_range = (rowset.column(0).index().indexes())
+            .iter()
+            .filter(|b| b.first_rowid + b.row_count <= 180)
+            .count();
+        assert!(blocks_before_range > 0);
+        assert_eq!(skipped_blocks, blocks_before_range + 1);
+    }
+
+    #[tokio::test]
+    async fn test_rowset_iterator_skip_blocks_by_in_list() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let rowset = Arc::new(helper_build_rowset_with_first_key_recorded(&tempdir).await);
+        // (v2, v3) IN ((101, 102), (201, 202), (11, 250))
+        // v2 = 11 and v3 = 250 are in different blocks, so no block can contain the last tuple.
+        let tuple = |a, b| vec![DataValue::Int32(a), DataValue::Int32(b)];
+        let in_list = InList {
+            columns: vec![1, 2],
+            values: vec![tuple(101, 102), tuple(201, 202), tuple(11, 250)],
+        };
+        let mut it = rowset
+            .iter(
+                vec![StorageColumnRef::Idx(1), StorageColumnRef::Idx(2)].into(),
+                vec![],
+                ColumnSeekPosition::RowId(0),
+                None,
+            )
+            .await
+            .unwrap()
+            .with_in_list(&rowset, Some(in_list));
+
+        let mut skipped = 0;
+        let mut column1 = vec![];
+        while !it.end {
+            if it.skip_block_by_in_list() {
+                skipped += 1;
+                continue;
+            }
+            if let Some(chunk) = it.next_batch_inner(None).await.unwrap() {
+                data_from_chunk(&chunk, &mut column1, 0).await;
+            }
+        }
+        assert!(skipped > 0);
+        assert!(column1.contains(&101));
+        assert!(column1.contains(&201));
+        assert!(!column1.contains(&11));
+        assert!(column1.len() < 280, "{} rows read", column1.len());
+    }
+
+    #[tokio::test]
+    async fn test_rowset_iterator_skip_blocks_by_not_null() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let rowset = Arc::new(helper_build_rowset_with_null_blocks(&tempdir).await);
+        let null_blocks = (rowset.column(1).index().indexes())
+            .iter()
+            .filter(|b| b.null_count == b.row_count)
+            .count();
+        assert!(null_blocks > 0);
+
+        // v2 IS NOT NULL
+        let mut it = rowset
+            .iter(
+                vec![StorageColumnRef::Idx(0)].into(),
+                vec![],
+                ColumnSeekPosition::RowId(0),
+                None,
+            )
+            .await
+            .unwrap()
+            .with_not_null(&rowset, &[1]);
+
+        let mut skipped = 0;
+        let mut column0 = vec![];
+        while !it.end {
+            if it.skip_block_by_not_null() {
+                skipped += 1;
+                continue;
+            }
+            if let Some(chunk) = it.next_batch_inner(None).await.unwrap() {
+                data_from_chunk(&chunk, &mut column0, 0).await;
+            }
+        }
+        // every all-null block is skipped, and no row with a non-null value is
+        assert_eq!(skipped, null_blocks);
+        let expected = (0..1000).filter(|i| !(200..600).contains(i)).collect_vec();
+        assert!(expected.iter().all(|i| column0.contains(i)));
+        assert!(column0.len() < 1000, "{} rows read", column0.len());
+    }
+
+    #[tokio::test]
+    async fn test_rowset_iterator_with_bitmap_index() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let rowset = Arc::new(helper_build_rowset_with_bitmap_index(&tempdir).await);
+        assert!(rowset.bitmap_index(0).is_none());
+        assert!(rowset.bitmap_index(1).is_some());
+        assert!(rowset.bitmap_index(2).is_some());
+
+        async fn scan(rowset: &DiskRowset, equal_to: &[(u32, DataValue)]) -> Vec<i32> {
+            let mut it = rowset
+                .iter(
+                    vec![StorageColumnRef::Idx(0)].into(),
+                    vec![],
+                    ColumnSeekPosition::RowId(0),
+                    None,
+                )
+                .await
+                .unwrap()
+                .with_equal_to(rowset, equal_to);
+            let mut column0 = vec![];
+            while let Some(chunk) = it.next_batch(None).await.unwrap() {
+                data_from_chunk(&chunk, &mut column0, 0).await;
+            }
+            column0
+        }
+
+        // v2 = 2 and v3 = 'b'
+        let rows = scan(
+            &rowset,
+            &[(1, DataValue::Int32(2)), (2, DataValue::String("b".into()))],
+        )
+        .await;
+        let expected = (scan(&rowset, &[]).await.into_iter())
+            .filter(|i| i % 5 == 2 && i % 3 == 1)
+            .collect_vec();
+        assert_eq!(expected.len(), 67);
+        assert_eq!(rows, expected);
+
+        // no rows match
+        assert!(scan(&rowset, &[(1, DataValue::Int32(5))]).await.is_empty());
+
+        // conditions on columns without index or of other types are ignored
+        let rows = scan(
+            &rowset,
+            &[(0, DataValue::Int32(1)), (1, DataValue::Int64(1))],
+        )
+        .await;
+        assert_eq!(rows, (0..1000).collect_vec());
+    }
+
     async fn data_from_chunk(chunk: &StorageChunk, column: &mut Vec<i32>, index: usize) {
         if let ArrayImpl::Int32(array) = chunk.array_at(index) {
             let bit_map = match chunk.visibility() {
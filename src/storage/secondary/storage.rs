@@ -68,6 +68,10 @@ impl SecondaryStorage {
         let mut table_changeset = vec![];
         for op in manifest_ops {
             match op {
+                ManifestOperation::CreateSchema(entry) => {
+                    engine.apply_create_schema(&entry)?;
+                    table_changeset.push(EpochOp::CreateSchema(entry));
+                }
                 ManifestOperation::CreateTable(entry) => {
                     engine.apply_create_table(&entry)?;
                     table_changeset.push(EpochOp::CreateTable(entry));
@@ -27,6 +27,9 @@ pub struct SecondaryTable {
     /// Mapping from [`ColumnId`] to column index in `columns`.
     pub column_map: HashMap<ColumnId, usize>,
 
+    /// Primary key columns in order. Rows are sorted by them.
+    pub ordered_pk_ids: Arc<[ColumnId]>,
+
     /// Root directory of the storage
     pub storage_options: Arc<StorageOptions>,
 
@@ -50,6 +53,7 @@ impl SecondaryTable {
         storage_options: Arc<StorageOptions>,
         table_ref_id: TableRefId,
         columns: &[ColumnCatalog],
+        ordered_pk_ids: &[ColumnId],
         next_id: Arc<(AtomicU32, AtomicU64)>,
         version: Arc<VersionManager>,
         block_cache: Cache<BlockCacheKey, Block>,
@@ -62,6 +66,7 @@ impl SecondaryTable {
                 .enumerate()
                 .map(|(idx, col)| (col.id(), idx))
                 .collect(),
+            ordered_pk_ids: ordered_pk_ids.into(),
             table_ref_id,
             storage_options,
             next_id,
@@ -68,6 +68,13 @@ impl SecondaryTransaction {
         read_only: bool,
         update: bool,
     ) -> StorageResult<Self> {
+        // take the lock before pinning the snapshot, so that changes committed by the previous
+        // holder are visible
+        let delete_lock = if update {
+            Some(table.lock_for_deletion().await)
+        } else {
+            None
+        };
         // pin a snapshot at version manager
         let pin_version = table.version.pin();
         Ok(Self {
@@ -77,11 +84,7 @@ impl SecondaryTransaction {
             table: table.clone(),
             version: table.version.clone(),
             snapshot: pin_version.snapshot.clone(),
-            delete_lock: if update {
-                Some(table.lock_for_deletion().await)
-            } else {
-                None
-            },
+            delete_lock,
             to_be_committed_rowsets: vec![],
             read_only,
             total_size: 0,
@@ -226,6 +229,14 @@ impl SecondaryTransaction {
         let mut iters: Vec<RowSetIterator> = vec![];
 
         if let Some(rowsets) = self.snapshot.get_rowsets_of(self.table.table_id()) {
+            let mut rowsets = rowsets.iter().collect_vec();
+            if let Some((index, count)) = opts.partition {
+                rowsets.sort_unstable();
+                rowsets = (rowsets.into_iter().enumerate())
+                    .filter(|(i, _)| i % count == index)
+                    .map(|(_, id)| id)
+                    .collect();
+            }
             for rowset_id in rowsets {
                 let rowset = self.version.get_rowset(self.table.table_id(), *rowset_id);
 
@@ -251,7 +262,10 @@ impl SecondaryTransaction {
                 iters.push(
                     rowset
                         .iter(col_idx.into(), dvs, start_rowid, opts.filter.clone())
-                        .await?,
+                        .await?
+                        .with_in_list(&rowset, opts.in_list.clone())
+                        .with_equal_to(&rowset, &opts.equal_to)
+                        .with_not_null(&rowset, &opts.not_null),
                 )
             }
         }
@@ -259,15 +273,22 @@ impl SecondaryTransaction {
         let final_iter = if iters.len() == 1 {
             iters.pop().unwrap().into()
         } else if opts.is_sorted {
-            let sort_key = find_sort_key_id(&self.table.columns);
-            if let Some(sort_key) = sort_key {
-                let real_col_idx = col_idx.iter().position(|x| match x {
-                    StorageColumnRef::Idx(y) => *y as usize == sort_key,
-                    _ => false,
-                });
+            let sort_key = find_sort_key_id(&self.table.columns, &self.table.ordered_pk_ids);
+            if !sort_key.is_empty() {
+                let real_col_idx = (sort_key.iter())
+                    .map(|sort_key| {
+                        col_idx
+                            .iter()
+                            .position(|x| match x {
+                                StorageColumnRef::Idx(y) => *y as usize == *sort_key,
+                                _ => false,
+                            })
+                            .expect("sort key not in column list")
+                    })
+                    .collect_vec();
                 MergeIterator::new(
                     iters.into_iter().map(|iter| iter.into()).collect_vec(),
-                    vec![real_col_idx.expect("sort key not in column list")],
+                    real_col_idx,
                 )
                 .into()
             } else {
@@ -324,6 +345,7 @@ impl SecondaryTransaction {
 
             self.mem = Some(SecondaryMemRowsetImpl::new(
                 self.table.columns.clone(),
+                &self.table.ordered_pk_ids,
                 ColumnBuilderOptions::from_storage_options(&self.table.storage_options),
                 rowset_id,
             ));
@@ -16,6 +16,7 @@ use super::{DeleteVector, DiskRowset, StorageOptions, StorageResult, MANIFEST_FI
 /// The operations sent to the version manager. Compared with manifest entries, operations
 /// like `AddRowSet` needs to be associated with a `DiskRowSet` struct.
 pub enum EpochOp {
+    CreateSchema(CreateSchemaEntry),
     CreateTable(CreateTableEntry),
     DropTable(DropTableEntry),
     AddRowSet((AddRowSetEntry, DiskRowset)),
@@ -27,6 +28,7 @@ pub enum EpochOp {
 impl std::fmt::Debug for EpochOp {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
+            Self::CreateSchema(e) => f.debug_tuple("EpochOp::CreateSchema").field(e).finish(),
             Self::CreateTable(e) => f.debug_tuple("EpochOp::CreateTable").field(e).finish(),
             Self::DropTable(e) => f.debug_tuple("EpochOp::DropTable").field(e).finish(),
             Self::AddRowSet((e, _)) => f.debug_tuple("EpochOp::AddRowSet").field(e).finish(),
@@ -254,6 +256,9 @@ impl VersionManager {
                     // For catalog operations, just leave it as-is. The version manager currently
                     // doesn't create MVCC map for catalog operations, and
                     // doesn't not provide interface to access them.
+                    EpochOp::CreateSchema(entry) => {
+                        entries.push(ManifestOperation::CreateSchema(entry))
+                    }
                     EpochOp::CreateTable(entry) => {
                         entries.push(ManifestOperation::CreateTable(entry))
                     }
@@ -47,33 +47,20 @@ impl std::ops::Add<Interval> for Date {
     type Output = Date;
 
     fn add(self, rhs: Interval) -> Self::Output {
-        // Add days
-        let days = self.0 + rhs.days();
-        let date = NaiveDate::from_num_days_from_ce_opt(days + UNIX_EPOCH_DAYS).unwrap();
-
-        // Add months and years
-        let mut day = date.day();
-        let mut month = date.month() as i32 + rhs.months();
-        let mut year = date.year() + rhs.years();
-        if month > 12 {
-            month -= 12;
-            year += 1;
-        } else if month <= 0 {
-            month += 12;
-            year -= 1;
-        }
-        assert!((1..=12).contains(&month));
+        let date = NaiveDate::from_num_days_from_ce_opt(self.0 + UNIX_EPOCH_DAYS).unwrap();
+
+        // Add months and years first
+        let months = date.year() * 12 + date.month0() as i32 + rhs.num_months();
+        let year = months.div_euclid(12);
+        let month = months.rem_euclid(12) + 1;
 
         // Fix the days after changing date.
         // For example, 1970.1.31 + 1 month = 1970.2.28
-        day = day.min(get_month_days(year, month as usize));
+        let day = date.day().min(get_month_days(year, month as usize));
+        let date = NaiveDate::from_ymd_opt(year, month as u32, day).unwrap();
 
-        Date::new(
-            NaiveDate::from_ymd_opt(year, month as u32, day)
-                .unwrap()
-                .num_days_from_ce()
-                - UNIX_EPOCH_DAYS,
-        )
+        // Then add days
+        Date::new(date.num_days_from_ce() - UNIX_EPOCH_DAYS + rhs.days())
     }
 }
 
@@ -185,3 +172,36 @@ impl FromStr for DateTimeField {
         }))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn add(date: &str, interval: &str) -> String {
+        let date: Date = date.parse().unwrap();
+        (date + interval.parse::<Interval>().unwrap()).to_string()
+    }
+
+    #[test]
+    fn add_interval() {
+        // days
+        assert_eq!(add("2020-01-01", "1 day"), "2020-01-02");
+        assert_eq!(add("2020-12-31", "1 day"), "2021-01-01");
+        assert_eq!(add("2020-02-28", "1 day"), "2020-02-29");
+        assert_eq!(add("2021-02-28", "1 day"), "2021-03-01");
+        assert_eq!(add("2020-03-01", "-1 day"), "2020-02-29");
+        // months
+        assert_eq!(add("2020-01-15", "1 month"), "2020-02-15");
+        assert_eq!(add("2020-01-31", "1 month"), "2020-02-29");
+        assert_eq!(add("2019-01-31", "1 month"), "2019-02-28");
+        assert_eq!(add("2020-03-31", "-1 month"), "2020-02-29");
+        assert_eq!(add("2020-11-15", "14 months"), "2022-01-15");
+        assert_eq!(add("2020-01-15", "-13 months"), "2018-12-15");
+        // years
+        assert_eq!(add("2020-02-29", "1 year"), "2021-02-28");
+        assert_eq!(add("2020-02-29", "4 years"), "2024-02-29");
+        assert_eq!(add("2020-02-29", "-1 year"), "2019-02-28");
+        // months are added before days
+        assert_eq!(add("2020-01-31", "1 month 1 day"), "2020-03-01");
+    }
+}
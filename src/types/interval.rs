@@ -49,6 +49,10 @@ impl Interval {
         }
     }
 
+    pub const fn from_parts(months: i32, days: i32, ms: i32) -> Self {
+        Interval { months, days, ms }
+    }
+
     pub const fn from_secs(seconds: i32) -> Self {
         Interval {
             months: 0,
@@ -85,6 +89,10 @@ impl Interval {
         self.months
     }
 
+    pub const fn num_milliseconds(&self) -> i32 {
+        self.ms
+    }
+
     pub const fn is_zero(&self) -> bool {
         matches!(
             self,
@@ -57,6 +57,10 @@ impl DataTypeKind {
         )
     }
 
+    pub const fn is_integer(&self) -> bool {
+        matches!(self, Self::Int16 | Self::Int32 | Self::Int64)
+    }
+
     /// Returns the inner types of the struct.
     pub fn as_struct(&self) -> &[DataType] {
         let Self::Struct(types) = self else { panic!("not a struct: {self}") };
@@ -73,12 +77,17 @@ impl DataTypeKind {
         }; // a <= b
         match (a, b) {
             (Null, _) => Some(b.clone()),
-            (Bool, Bool | Int32 | Int64 | Float64 | Decimal(_, _) | String) => Some(b.clone()),
+            (Bool, Bool | Int16 | Int32 | Int64 | Float64 | Decimal(_, _) | String) => {
+                Some(b.clone())
+            }
+            (Int16, Int16 | Int32 | Int64 | Float64 | Decimal(_, _) | String) => Some(b.clone()),
             (Int32, Int32 | Int64 | Float64 | Decimal(_, _) | String) => Some(b.clone()),
             (Int64, Int64 | Float64 | Decimal(_, _) | String) => Some(b.clone()),
             (Float64, Float64 | Decimal(_, _) | String) => Some(b.clone()),
             (Decimal(_, _), Decimal(_, _) | String) => Some(b.clone()),
-            (Date, Date | String) => Some(b.clone()),
+            (Date, Date | Timestamp | TimestampTz | String) => Some(b.clone()),
+            (Timestamp, Timestamp | String) => Some(b.clone()),
+            (TimestampTz, TimestampTz | String) => Some(b.clone()),
             (Interval, Interval | String) => Some(b.clone()),
             (String, String | Blob) => Some(b.clone()),
             (Blob, Blob) => Some(b.clone()),
@@ -289,6 +298,22 @@ pub enum ConvertError {
     Cast(String, &'static str),
     #[error("constant {0} overflows {1}")]
     Overflow(DataValue, DataTypeKind),
+    #[error("integer overflow: {0} {1} {2}")]
+    IntegerOverflow(i64, &'static str, i64),
+    #[error("decimal overflow: {0} {1} {2}")]
+    DecimalOverflow(Decimal, &'static str, Decimal),
+    #[error("division by zero")]
+    DivisionByZero,
+    #[error("cannot take logarithm of {0}")]
+    InvalidLogarithm(f64),
+    #[error("requested character {0} is not a valid code point")]
+    InvalidCodePoint(i64),
+    #[error("string of {0} bytes is too long")]
+    StringTooLong(usize),
+    #[error("strings of {0} bytes in total are too long")]
+    StringsTooLong(usize),
+    #[error("unsupported field {0} for date_trunc")]
+    UnsupportedTruncField(DateTimeField),
     #[error("no function {0}({1})")]
     NoUnaryOp(String, &'static str),
     #[error("no function {0}({1}, {2})")]
@@ -324,3 +349,27 @@ impl FromStr for ColumnIndex {
         Ok(Self(num))
     }
 }
+
+/// The 1-based index of a parameter in prepared statements.
+#[derive(Debug, Display, PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone)]
+#[display("?{0}")]
+pub struct ParamIndex(pub u32);
+
+#[derive(thiserror::Error, Debug, Clone)]
+#[error("parse parameter index error: {}")]
+pub enum ParseParamIndexError {
+    #[error("no leading '?'")]
+    NoLeadingSign,
+    #[error("invalid number: {0}")]
+    InvalidNum(#[from] std::num::ParseIntError),
+}
+
+impl FromStr for ParamIndex {
+    type Err = ParseParamIndexError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let body = s.strip_prefix('?').ok_or(Self::Err::NoLeadingSign)?;
+        let num = body.parse()?;
+        Ok(Self(num))
+    }
+}
@@ -2,9 +2,11 @@ use std::fmt::{Display, Formatter};
 use std::str::FromStr;
 use std::sync::OnceLock;
 
-use chrono::{DateTime, Datelike, FixedOffset, NaiveDateTime, Timelike};
+use chrono::{DateTime, Datelike, Duration, FixedOffset, NaiveDate, NaiveDateTime, Timelike, Utc};
 use serde::Serialize;
 
+use super::{Date, DateTimeField};
+
 /// unix timestamp counts from 1970-01-01 00:00:00,
 ///
 /// postgres timestamp counts from 2000-01-01 00:00:00,
@@ -12,6 +14,8 @@ use serde::Serialize;
 /// this is the difference between them
 const THIRTY_YEARS_MICROSECONDS: i64 = 946_684_800_000_000;
 
+const DAY_MICROSECONDS: i64 = 86_400_000_000;
+
 /// global timezone
 static TIME_ZONE: OnceLock<FixedOffset> = OnceLock::new();
 
@@ -51,6 +55,33 @@ impl Timestamp {
     pub fn get_inner(&self) -> i64 {
         self.0
     }
+
+    /// Creates a timestamp from microseconds since 1970-01-01 00:00:00.
+    pub const fn from_unix_micros(micros: i64) -> Self {
+        Self(micros + THIRTY_YEARS_MICROSECONDS)
+    }
+
+    /// Returns microseconds since 1970-01-01 00:00:00.
+    pub const fn unix_micros(&self) -> i64 {
+        self.0 - THIRTY_YEARS_MICROSECONDS
+    }
+
+    /// Truncates the timestamp to the precision of `field`.
+    ///
+    /// Returns `None` if the field is not supported.
+    pub fn truncate(&self, field: DateTimeField) -> Option<Self> {
+        truncate(self.0, field).map(Self)
+    }
+
+    /// Returns the timestamp at midnight of the date.
+    pub fn from_date(date: Date) -> Self {
+        Self(date_to_micros(date))
+    }
+
+    /// Returns the date of the timestamp, discarding the time part.
+    pub fn date(&self) -> Date {
+        micros_to_date(self.0)
+    }
 }
 
 impl Display for Timestamp {
@@ -83,9 +114,43 @@ impl TimestampTz {
         Self(value)
     }
 
+    /// Returns the current time.
+    pub fn now() -> Self {
+        Self(Utc::now().timestamp_micros() + THIRTY_YEARS_MICROSECONDS)
+    }
+
     pub fn get_inner(&self) -> i64 {
         self.0
     }
+
+    /// Creates a timestamp from microseconds since 1970-01-01 00:00:00.
+    pub const fn from_unix_micros(micros: i64) -> Self {
+        Self(micros + THIRTY_YEARS_MICROSECONDS)
+    }
+
+    /// Returns microseconds since 1970-01-01 00:00:00.
+    pub const fn unix_micros(&self) -> i64 {
+        self.0 - THIRTY_YEARS_MICROSECONDS
+    }
+
+    /// Truncates the timestamp to the precision of `field` in the system timezone.
+    ///
+    /// Returns `None` if the field is not supported.
+    pub fn truncate(&self, field: DateTimeField) -> Option<Self> {
+        let sys_tz = TIME_ZONE.get_or_init(|| FixedOffset::east_opt(DEFALUT_TZ * 3600).unwrap());
+        let offset = sys_tz.local_minus_utc() as i64 * 1_000_000;
+        truncate(self.0 + offset, field).map(|v| Self(v - offset))
+    }
+
+    /// Returns the instant at midnight UTC of the date.
+    pub fn from_date(date: Date) -> Self {
+        Self(date_to_micros(date))
+    }
+
+    /// Returns the UTC date of the instant.
+    pub fn date(&self) -> Date {
+        micros_to_date(self.0)
+    }
 }
 
 impl Display for TimestampTz {
@@ -120,6 +185,46 @@ impl FromStr for TimestampTz {
     }
 }
 
+/// Truncates the inner value of timestamps to the precision of `field`.
+///
+/// Weeks start on Monday.
+fn truncate(v: i64, field: DateTimeField) -> Option<i64> {
+    use sqlparser::ast::DateTimeField::*;
+    // microseconds since 1970-01-01
+    let v = v - THIRTY_YEARS_MICROSECONDS;
+    let v = match field.0 {
+        Second => v - v.rem_euclid(1_000_000),
+        Minute => v - v.rem_euclid(60_000_000),
+        Hour => v - v.rem_euclid(3_600_000_000),
+        Day => v - v.rem_euclid(DAY_MICROSECONDS),
+        // 1970-01-01 is Thursday
+        Week => v - (v + 3 * DAY_MICROSECONDS).rem_euclid(7 * DAY_MICROSECONDS),
+        Month | Quarter | Year => {
+            let epoch = NaiveDate::from_ymd_opt(1970, 1, 1)?;
+            let date = epoch + Duration::days(v.div_euclid(DAY_MICROSECONDS));
+            let month0 = match field.0 {
+                Month => date.month0(),
+                Quarter => date.month0() / 3 * 3,
+                _ => 0,
+            };
+            let date = NaiveDate::from_ymd_opt(date.year(), month0 + 1, 1)?;
+            (date - epoch).num_days() * DAY_MICROSECONDS
+        }
+        _ => return None,
+    };
+    Some(v + THIRTY_YEARS_MICROSECONDS)
+}
+
+/// Converts days since 1970-01-01 to the inner value of timestamps.
+fn date_to_micros(date: Date) -> i64 {
+    date.get_inner() as i64 * DAY_MICROSECONDS + THIRTY_YEARS_MICROSECONDS
+}
+
+/// Converts the inner value of timestamps to days since 1970-01-01.
+fn micros_to_date(v: i64) -> Date {
+    Date::new((v - THIRTY_YEARS_MICROSECONDS).div_euclid(DAY_MICROSECONDS) as i32)
+}
+
 fn naive_sys_fmt(dt: &NaiveDateTime, f: &mut Formatter<'_>) -> std::fmt::Result {
     if dt.year() < 0 {
         write!(
@@ -148,3 +253,47 @@ fn naive_utc_to_timestamp(dt: &NaiveDateTime, is_bc: bool) -> Result<i64, ParseT
     }
     Ok(dt.timestamp_micros() + THIRTY_YEARS_MICROSECONDS)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trunc(ts: &str, field: &str) -> String {
+        let ts: Timestamp = ts.parse().unwrap();
+        let field: DateTimeField = field.parse().unwrap();
+        ts.truncate(field).unwrap().to_string()
+    }
+
+    #[test]
+    fn date_trunc() {
+        let ts = "2023-05-17 12:34:56";
+        assert_eq!(trunc(ts, "YEAR"), "2023-01-01 00:00:00");
+        assert_eq!(trunc(ts, "QUARTER"), "2023-04-01 00:00:00");
+        assert_eq!(trunc(ts, "MONTH"), "2023-05-01 00:00:00");
+        assert_eq!(trunc(ts, "WEEK"), "2023-05-15 00:00:00");
+        assert_eq!(trunc(ts, "DAY"), "2023-05-17 00:00:00");
+        assert_eq!(trunc(ts, "HOUR"), "2023-05-17 12:00:00");
+        assert_eq!(trunc(ts, "MINUTE"), "2023-05-17 12:34:00");
+        assert_eq!(trunc(ts, "SECOND"), "2023-05-17 12:34:56");
+        assert_eq!(trunc("1999-12-31 23:59:59", "MONTH"), "1999-12-01 00:00:00");
+    }
+
+    #[test]
+    fn date_conversion() {
+        let ts: Timestamp = "2023-05-17 12:34:56".parse().unwrap();
+        assert_eq!(ts.date().to_string(), "2023-05-17");
+        let ts: Timestamp = "1969-12-31 23:59:59".parse().unwrap();
+        assert_eq!(ts.date().to_string(), "1969-12-31");
+
+        let date: Date = "2023-05-17".parse().unwrap();
+        assert_eq!(
+            Timestamp::from_date(date).to_string(),
+            "2023-05-17 00:00:00"
+        );
+        assert_eq!(Timestamp::from_date(date).date(), date);
+
+        let ts: TimestampTz = "2023-05-17 23:34:56 +08:00".parse().unwrap();
+        assert_eq!(ts.date().to_string(), "2023-05-17");
+        assert_eq!(TimestampTz::from_date(date).date(), date);
+    }
+}
@@ -0,0 +1,36 @@
+-- joins are ordered as a left-deep tree by default
+explain select count(*) from a, b, c, d where a2 = b1 and b2 = c1 and c2 = d1
+
+/*
+Projection { exprs: [ rowcount ], cost: 3140070.8 }
+└── Agg { aggs: [ rowcount ], cost: 3140070.5 }
+    └── Projection { exprs: [], cost: 3120070.5 }
+        └── HashJoin { type: inner, on: = { lhs: [ c2 ], rhs: [ d1 ] }, cost: 3120070.5 }
+            ├── Projection { exprs: [ c2 ], cost: 3100040.5 }
+            │   └── HashJoin { type: inner, on: = { lhs: [ b2 ], rhs: [ c1 ] }, cost: 3090040.5 }
+            │       ├── Projection { exprs: [ b2 ], cost: 3040020 }
+            │       │   └── HashJoin { type: inner, on: = { lhs: [ a2 ], rhs: [ b1 ] }, cost: 3030020 }
+            │       │       ├── Scan { table: a, list: [ a2 ], filter: null, cost: 10000 }
+            │       │       └── Scan { table: b, list: [ b1, b2 ], filter: null, cost: 20000 }
+            │       └── Scan { table: c, list: [ c1, c2 ], filter: null, cost: 20 }
+            └── Scan { table: d, list: [ d1 ], filter: null, cost: 10 }
+*/
+
+-- joining the two small tables first avoids joining the large intermediate result twice
+explain select count(*) from a, b, c, d where a2 = b1 and b2 = c1 and c2 = d1
+
+/*
+Projection { exprs: [ rowcount ], cost: 3100110.8 }
+└── Agg { aggs: [ rowcount ], cost: 3100110.5 }
+    └── Projection { exprs: [], cost: 3080110.5 }
+        └── HashJoin { type: inner, on: = { lhs: [ b2 ], rhs: [ c1 ] }, cost: 3080110.5 }
+            ├── Projection { exprs: [ b2 ], cost: 3040020 }
+            │   └── HashJoin { type: inner, on: = { lhs: [ a2 ], rhs: [ b1 ] }, cost: 3030020 }
+            │       ├── Scan { table: a, list: [ a2 ], filter: null, cost: 10000 }
+            │       └── Scan { table: b, list: [ b1, b2 ], filter: null, cost: 20000 }
+            └── Projection { exprs: [ c1 ], cost: 90 }
+                └── HashJoin { type: inner, on: = { lhs: [ c2 ], rhs: [ d1 ] }, cost: 80 }
+                    ├── Scan { table: c, list: [ c1, c2 ], filter: null, cost: 20 }
+                    └── Scan { table: d, list: [ d1 ], filter: null, cost: 10 }
+*/
+
@@ -0,0 +1,7 @@
+-- is null on a not null column is always false
+explain select v from t where v is null
+
+/*
+Empty { cost: 0 }
+*/
+
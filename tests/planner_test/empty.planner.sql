@@ -0,0 +1,35 @@
+-- a projection of an empty relation is empty
+explain select a + 1 from t where false
+
+/*
+Empty { cost: 0 }
+*/
+
+-- a hash aggregation of an empty relation is empty
+explain select b, count(*) from t where 1 = 0 group by b order by b limit 1
+
+/*
+Empty { cost: 0 }
+*/
+
+-- an inner join is empty if its filter is false
+explain select * from t join u on a = c where false
+
+/*
+Empty { cost: 0 }
+*/
+
+-- an inner join is empty if one side is empty
+explain select * from t join (select * from u where false) as v on a = v.c
+
+/*
+Empty { cost: 0 }
+*/
+
+-- a limit of 0 is empty without scanning the table
+explain select a + 1, b from t limit 0
+
+/*
+Empty { cost: 0 }
+*/
+
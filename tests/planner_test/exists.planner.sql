@@ -0,0 +1,11 @@
+-- uncorrelated EXISTS is a semi join with at most one row on the right side
+explain select 1 from a where exists (select 1 from b)
+
+/*
+Projection { exprs: [ 1 ], cost: 300 }
+└── Join { type: semi, cost: 100 }
+    ├── Scan { table: a, list: [], filter: null, cost: 0 }
+    └── Limit { limit: 1, offset: 0, cost: 0 }
+        └── Scan { table: b, list: [], filter: null, cost: 0 }
+*/
+
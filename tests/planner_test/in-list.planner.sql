@@ -0,0 +1,34 @@
+-- a short list on the primary key seeks to each key
+explain select * from t where a in (5, 1, 3)
+
+/*
+Scan
+├── table: t
+├── list: [ a, b ]
+├── filter:or
+│   ├── lhs: or { lhs: = { lhs: a, rhs: 5 }, rhs: = { lhs: a, rhs: 1 } }
+│   └── rhs: = { lhs: a, rhs: 3 }
+└── cost: 3
+*/
+
+-- a long list is filtered after scanning the whole table
+explain select * from t where a in (1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20)
+
+/*
+Filter
+├── cond:In { in: [ 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20 ] }
+│   └── a
+├── cost: 200008300
+└── Scan { table: t, list: [ a, b ], filter: null, cost: 200000000 }
+*/
+
+-- so is a list on other columns
+explain select * from t where b in (10, 30)
+
+/*
+Filter
+├── cond:or { lhs: = { lhs: b, rhs: 10 }, rhs: = { lhs: b, rhs: 30 } }
+├── cost: 200001240
+└── Scan { table: t, list: [ a, b ], filter: null, cost: 200000000 }
+*/
+
@@ -0,0 +1,12 @@
+-- a LIKE pattern with a literal prefix scans the range of the prefix
+explain select * from t where a like 'abc%'
+
+/*
+Filter { cond: like { lhs: a, rhs: 'abc%' }, cost: 2211 }
+└── Scan
+    ├── table: t
+    ├── list: [ a, b ]
+    ├── filter: and { lhs: > { lhs: 'abd', rhs: a }, rhs: >= { lhs: a, rhs: 'abc' } }
+    └── cost: 1
+*/
+
@@ -0,0 +1,20 @@
+-- both sides are ordered by the join key, so no hash table is built
+explain select a, b, d from t join u on a = c
+
+/*
+Projection { exprs: [ a, b, d ], cost: 400004130 }
+└── MergeJoin { type: inner, on: = { lhs: [ a ], rhs: [ c ] }, cost: 400004000 }
+    ├── Scan { table: t, list: [ a, b ], filter: null, cost: 200000000 }
+    └── Scan { table: u, list: [ c, d ], filter: null, cost: 200000000 }
+*/
+
+-- the right side is not ordered by the join key
+explain select a, b, c from t join u on a = d
+
+/*
+Projection { exprs: [ a, b, c ], cost: 400024064 }
+└── HashJoin { type: inner, on: = { lhs: [ a ], rhs: [ d ] }, cost: 400023940 }
+    ├── Scan { table: t, list: [ a, b ], filter: null, cost: 200000000 }
+    └── Scan { table: u, list: [ c, d ], filter: null, cost: 200000000 }
+*/
+
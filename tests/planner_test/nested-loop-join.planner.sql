@@ -0,0 +1,20 @@
+-- building a hash table does not pay off for small tables
+explain select b, d from t join u on a = c
+
+/*
+Projection { exprs: [ b, d ], cost: 1200008.5 }
+└── Join { type: inner, on: = { lhs: a, rhs: c }, cost: 1200008.3 }
+    ├── Scan { table: t, list: [ a, b ], filter: null, cost: 600000 }
+    └── Scan { table: u, list: [ c, d ], filter: null, cost: 600000 }
+*/
+
+-- but it does for the default estimation of 1000 rows
+explain select b, d from t join u on a = c
+
+/*
+Projection { exprs: [ b, d ], cost: 400024060 }
+└── HashJoin { type: inner, on: = { lhs: [ a ], rhs: [ c ] }, cost: 400023940 }
+    ├── Scan { table: t, list: [ a, b ], filter: null, cost: 200000000 }
+    └── Scan { table: u, list: [ c, d ], filter: null, cost: 200000000 }
+*/
+
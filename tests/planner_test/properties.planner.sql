@@ -0,0 +1,30 @@
+-- a primary-key scan is ordered by the key
+explain verbose select a from t
+
+/*
+Scan { table: t, list: [ a ], filter: null, cost: 100000000, ordered_by: [ a ], rows: 1000 }
+*/
+
+-- the output of hash aggregation is unordered
+explain verbose select b, count(*) from t group by b
+
+/*
+Projection { exprs: [ b, rowcount ], cost: 100010384, ordered_by: [], rows: 500 }
+└── HashAgg { aggs: [ rowcount ], group_by: [ b ], cost: 100010280, ordered_by: [], rows: 500 }
+    └── Scan { table: t, list: [ b ], filter: null, cost: 100000000, ordered_by: [], rows: 1000 }
+*/
+
+-- a composite primary-key scan is ordered by the key
+explain verbose select a, b from u
+
+/*
+Scan { table: u, list: [ a, b ], filter: null, cost: 200000000, ordered_by: [ b, a ], rows: 1000 }
+*/
+
+-- a scan without the first column of the primary key is unordered
+explain verbose select a, c from u
+
+/*
+Scan { table: u, list: [ a, c ], filter: null, cost: 200000000, ordered_by: [], rows: 1000 }
+*/
+
@@ -0,0 +1,18 @@
+-- the filter is pushed below the order
+explain select * from (select * from t order by a) as x where b = 1
+
+/*
+Order { by: [ a ], cost: 200002540 }
+└── Filter { cond: = { lhs: b, rhs: 1 }, cost: 200000610 }
+    └── Scan { table: t, list: [ a, b ], filter: null, cost: 200000000 }
+*/
+
+-- the filter is not pushed below the order in a single round
+explain select * from (select * from t order by a) as x where b = 1
+
+/*
+Filter { cond: = { lhs: b, rhs: 1 }, cost: 200012580 }
+└── Order { by: [ a ], cost: 200011970 }
+    └── Scan { table: t, list: [ a, b ], filter: null, cost: 200000000 }
+*/
+
@@ -0,0 +1,18 @@
+-- the scan is ordered by the group key, so no hash table is built
+explain verbose select a, count(*) from t group by a
+
+/*
+Projection { exprs: [ a, rowcount ], cost: 100002104, ordered_by: [ a ], rows: 1000 }
+└── SortAgg { aggs: [ rowcount ], group_by: [ a ], cost: 100001000, ordered_by: [ a ], rows: 1000 }
+    └── Scan { table: t, list: [ a ], filter: null, cost: 100000000, ordered_by: [ a ], rows: 1000 }
+*/
+
+-- the scan is not ordered by the group key
+explain verbose select b, count(*) from t group by b
+
+/*
+Projection { exprs: [ b, rowcount ], cost: 100010384, ordered_by: [], rows: 500 }
+└── HashAgg { aggs: [ rowcount ], group_by: [ b ], cost: 100010280, ordered_by: [], rows: 500 }
+    └── Scan { table: t, list: [ b ], filter: null, cost: 100000000, ordered_by: [], rows: 1000 }
+*/
+
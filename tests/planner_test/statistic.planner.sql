@@ -0,0 +1,7 @@
+-- the rows of a scan are estimated from the row count in storage
+explain select * from t
+
+/*
+Scan { table: t, list: [ v ], filter: null, cost: 300000 }
+*/
+
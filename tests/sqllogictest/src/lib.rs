@@ -27,7 +27,14 @@ impl Display for Engine {
 
 pub async fn test(filename: impl AsRef<Path>, engine: Engine) -> Result<()> {
     let db = match engine {
-        Engine::Disk => Database::new_on_disk(SecondaryStorageOptions::default_for_test()).await,
+        Engine::Disk => {
+            // bitmap indexes are opt-in, enable them to cover the index scan path
+            let options = SecondaryStorageOptions {
+                bitmap_index: true,
+                ..SecondaryStorageOptions::default_for_test()
+            };
+            Database::new_on_disk(options).await
+        }
         Engine::Mem => Database::new_in_memory(),
     };
 